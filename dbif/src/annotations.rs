@@ -0,0 +1,349 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use std::error::Error;
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    connect_to_database,
+    BoostRecord,
+    HydraError,
+    ListType,
+};
+
+//Longest tag and note that can be saved
+const MAX_TAG_LENGTH: usize = 64;
+const MAX_NOTE_LENGTH: usize = 4000;
+
+//Tags, a note and the pinned flag on a received or sent boost. Received and sent boosts
+//have their own indexes, so `sent` says which table the index is in.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct BoostAnnotation {
+    pub sent: bool,
+    pub index: u64,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+    pub pinned: bool,
+    pub updated: Option<i64>,
+}
+
+//Changes to make to an annotation. Fields left out are kept as they are.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct AnnotationUpdate {
+    pub tags: Option<Vec<String>>, // replaces all of the tags
+    pub note: Option<String>, // an empty note removes it
+    pub pinned: Option<bool>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: u64,
+}
+
+//Annotations live in their own tables so the boost rows stay as LND gave them. A boost has a row in
+//boost_annotations whenever it has a note, tags or is pinned, and none otherwise.
+pub fn create_annotation_tables(conn: &Connection) -> Result<bool, Box<dyn Error>> {
+    match conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS boost_annotations (
+             sent integer not null,
+             boost_idx integer not null,
+             note text,
+             pinned integer not null default 0,
+             updated integer not null,
+             PRIMARY KEY (sent, boost_idx)
+         );
+         CREATE TABLE IF NOT EXISTS boost_tags (
+             sent integer not null,
+             boost_idx integer not null,
+             tag text not null COLLATE NOCASE,
+             PRIMARY KEY (sent, boost_idx, tag)
+         );
+         CREATE INDEX IF NOT EXISTS boost_tags_tag ON boost_tags (tag, sent);"
+    ) {
+        Ok(_) => {
+            println!("Boost annotation tables are ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database boost annotation tables.".into())))
+        }
+    }
+
+    Ok(true)
+}
+
+//Trims the tag and checks it's usable. Tags are matched without regard to case.
+pub fn normalize_tag(tag: &str) -> Result<String, Box<dyn Error>> {
+    let tag = tag.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+    if tag.is_empty() {
+        return Err(Box::new(HydraError("Tag can't be empty.".into())));
+    }
+
+    if tag.chars().count() > MAX_TAG_LENGTH {
+        return Err(Box::new(HydraError(format!("Tag is longer than {} characters.", MAX_TAG_LENGTH))));
+    }
+
+    Ok(tag)
+}
+
+fn boost_exists(conn: &Connection, sent: bool, index: u64) -> Result<bool, Box<dyn Error>> {
+    let table = if sent { "sent_boosts" } else { "boosts" };
+
+    let exists = conn.query_row(
+        &format!("SELECT 1 FROM {} WHERE idx = ?1", table),
+        params![index],
+        |_| Ok(true),
+    ).optional()?;
+
+    Ok(exists.unwrap_or(false))
+}
+
+fn load_annotation(conn: &Connection, sent: bool, index: u64) -> Result<BoostAnnotation, Box<dyn Error>> {
+    let mut annotation = conn.query_row(
+        "SELECT note, pinned, updated FROM boost_annotations WHERE sent = ?1 AND boost_idx = ?2",
+        params![sent, index],
+        |row| Ok(BoostAnnotation {
+            sent,
+            index,
+            tags: Vec::new(),
+            note: row.get(0)?,
+            pinned: row.get(1)?,
+            updated: row.get(2)?,
+        }),
+    ).optional()?.unwrap_or(BoostAnnotation {
+        sent,
+        index,
+        ..Default::default()
+    });
+
+    let mut stmt = conn.prepare_cached("SELECT tag FROM boost_tags WHERE sent = ?1 AND boost_idx = ?2 ORDER BY tag")?;
+    let tags = stmt.query_map(params![sent, index], |row| row.get(0))?;
+
+    for tag in tags {
+        annotation.tags.push(tag?);
+    }
+
+    Ok(annotation)
+}
+
+//Saves the annotation, or removes it if there's nothing left on it
+fn save_annotation(conn: &Connection, annotation: &BoostAnnotation) -> Result<(), Box<dyn Error>> {
+    let (sent, index) = (annotation.sent, annotation.index);
+
+    conn.execute("DELETE FROM boost_tags WHERE sent = ?1 AND boost_idx = ?2", params![sent, index])?;
+
+    if annotation.tags.is_empty() && annotation.note.is_none() && !annotation.pinned {
+        conn.execute("DELETE FROM boost_annotations WHERE sent = ?1 AND boost_idx = ?2", params![sent, index])?;
+        return Ok(());
+    }
+
+    conn.execute(
+        "INSERT INTO boost_annotations (sent, boost_idx, note, pinned, updated) VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT (sent, boost_idx) DO UPDATE SET note = excluded.note, pinned = excluded.pinned, updated = excluded.updated",
+        params![sent, index, annotation.note, annotation.pinned, annotation.updated],
+    )?;
+
+    for tag in &annotation.tags {
+        conn.execute(
+            "INSERT OR IGNORE INTO boost_tags (sent, boost_idx, tag) VALUES (?1, ?2, ?3)",
+            params![sent, index, tag],
+        )?;
+    }
+
+    Ok(())
+}
+
+//Changes the annotation with the function and saves it. Returns None if there's no such boost.
+fn change_annotation(
+    filepath: &str,
+    sent: bool,
+    index: u64,
+    change: impl FnOnce(&mut BoostAnnotation) -> Result<(), Box<dyn Error>>,
+) -> Result<Option<BoostAnnotation>, Box<dyn Error>> {
+    let mut conn = connect_to_database(false, filepath)?;
+    let tx = conn.transaction()?;
+
+    if !boost_exists(&tx, sent, index)? {
+        return Ok(None);
+    }
+
+    let mut annotation = load_annotation(&tx, sent, index)?;
+    change(&mut annotation)?;
+
+    annotation.updated = Some(chrono::Utc::now().timestamp());
+
+    save_annotation(&tx, &annotation)?;
+    tx.commit()?;
+
+    //Read it back so the tags come out in the same order as everywhere else
+    let conn = connect_to_database(false, filepath)?;
+    Ok(Some(load_annotation(&conn, sent, index)?))
+}
+
+fn add_tags(annotation: &mut BoostAnnotation, tags: &[String]) -> Result<(), Box<dyn Error>> {
+    for tag in tags {
+        let tag = normalize_tag(tag)?;
+
+        if !annotation.tags.iter().any(|existing| existing.eq_ignore_ascii_case(&tag)) {
+            annotation.tags.push(tag);
+        }
+    }
+
+    Ok(())
+}
+
+//Get the annotation on a boost. Boosts without one get an empty annotation.
+pub fn get_annotation_from_db(filepath: &str, sent: bool, index: u64) -> Result<Option<BoostAnnotation>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    if !boost_exists(&conn, sent, index)? {
+        return Ok(None);
+    }
+
+    Ok(Some(load_annotation(&conn, sent, index)?))
+}
+
+//Update the tags, note or pinned flag on a boost
+pub fn update_annotation_in_db(filepath: &str, sent: bool, index: u64, update: &AnnotationUpdate) -> Result<Option<BoostAnnotation>, Box<dyn Error>> {
+    change_annotation(filepath, sent, index, |annotation| {
+        if let Some(tags) = &update.tags {
+            annotation.tags.clear();
+            add_tags(annotation, tags)?;
+        }
+
+        if let Some(note) = &update.note {
+            let note = note.trim();
+
+            if note.chars().count() > MAX_NOTE_LENGTH {
+                return Err(Box::new(HydraError(format!("Note is longer than {} characters.", MAX_NOTE_LENGTH))));
+            }
+
+            annotation.note = Some(note.to_string()).filter(|note| !note.is_empty());
+        }
+
+        if let Some(pinned) = update.pinned {
+            annotation.pinned = pinned;
+        }
+
+        Ok(())
+    })
+}
+
+//Add a tag to a boost
+pub fn add_boost_tag_in_db(filepath: &str, sent: bool, index: u64, tag: &str) -> Result<Option<BoostAnnotation>, Box<dyn Error>> {
+    change_annotation(filepath, sent, index, |annotation| add_tags(annotation, &[tag.to_string()]))
+}
+
+//Remove a tag from a boost
+pub fn remove_boost_tag_in_db(filepath: &str, sent: bool, index: u64, tag: &str) -> Result<Option<BoostAnnotation>, Box<dyn Error>> {
+    change_annotation(filepath, sent, index, |annotation| {
+        let tag = normalize_tag(tag)?;
+        annotation.tags.retain(|existing| !existing.eq_ignore_ascii_case(&tag));
+        Ok(())
+    })
+}
+
+//Remove the tags, note and pin from a boost
+pub fn delete_annotation_from_db(filepath: &str, sent: bool, index: u64) -> Result<Option<BoostAnnotation>, Box<dyn Error>> {
+    change_annotation(filepath, sent, index, |annotation| {
+        annotation.tags.clear();
+        annotation.note = None;
+        annotation.pinned = false;
+        Ok(())
+    })
+}
+
+//Get every tag in use with how many boosts have it, most used first
+pub fn get_tags_from_db(filepath: &str, sent: Option<bool>) -> Result<Vec<TagCount>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let mut stmt = conn.prepare_cached(
+        "SELECT MIN(tag), COUNT(*) FROM boost_tags WHERE ?1 IS NULL OR sent = ?1 GROUP BY tag ORDER BY COUNT(*) DESC, MIN(tag)"
+    )?;
+
+    let rows = stmt.query_map(params![sent], |row| Ok(TagCount {
+        tag: row.get(0)?,
+        count: row.get(1)?,
+    }))?;
+
+    let mut tags = Vec::new();
+
+    for tag in rows {
+        tags.push(tag?);
+    }
+
+    Ok(tags)
+}
+
+//Fill in the tags, notes and pinned flags of the boosts. Each boost's list type says whether it's a sent boost.
+pub fn attach_annotations_from_db(filepath: &str, boosts: &mut [BoostRecord], escape_html: bool) -> Result<(), Box<dyn Error>> {
+    if boosts.is_empty() {
+        return Ok(());
+    }
+
+    let conn = connect_to_database(false, filepath)?;
+
+    for sent in [false, true] {
+        let indexes: Vec<String> = boosts.iter()
+            .filter(|boost| (boost.list_type == ListType::Sent) == sent)
+            .map(|boost| boost.index.to_string())
+            .collect();
+
+        if indexes.is_empty() {
+            continue;
+        }
+
+        //Indexes are numbers so they're safe to put in the query as they are
+        let indexes = indexes.join(", ");
+        let mut annotations: HashMap<u64, BoostAnnotation> = HashMap::new();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT boost_idx, note, pinned FROM boost_annotations WHERE sent = ?1 AND boost_idx IN ({})",
+            indexes
+        ))?;
+        let mut rows = stmt.query(params![sent])?;
+
+        while let Some(row) = rows.next()? {
+            let index: u64 = row.get(0)?;
+
+            annotations.insert(index, BoostAnnotation {
+                sent,
+                index,
+                note: row.get(1)?,
+                pinned: row.get(2)?,
+                ..Default::default()
+            });
+        }
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT boost_idx, tag FROM boost_tags WHERE sent = ?1 AND boost_idx IN ({}) ORDER BY tag",
+            indexes
+        ))?;
+        let mut rows = stmt.query(params![sent])?;
+
+        while let Some(row) = rows.next()? {
+            let index: u64 = row.get(0)?;
+
+            if let Some(annotation) = annotations.get_mut(&index) {
+                annotation.tags.push(row.get(1)?);
+            }
+        }
+
+        for boost in boosts.iter_mut().filter(|boost| (boost.list_type == ListType::Sent) == sent) {
+            if let Some(annotation) = annotations.remove(&boost.index) {
+                boost.pinned = annotation.pinned;
+
+                if escape_html {
+                    boost.tags = annotation.tags.into_iter().map(BoostRecord::escape_for_html).collect();
+                    boost.note = annotation.note.map(BoostRecord::escape_for_html);
+                } else {
+                    boost.tags = annotation.tags;
+                    boost.note = annotation.note;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::error::Error;
+use serde::{Deserialize, Serialize};
+
+use crate::{connect_to_database, HydraError};
+
+//What an API token can be used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiScope {
+    Read, // read boosts, streams and stats
+    Websocket, // listen for live boosts
+    Reply, // send replies
+    Settings, // change settings and everything else
+}
+
+impl ApiScope {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "read" => Some(ApiScope::Read),
+            "websocket" => Some(ApiScope::Websocket),
+            "reply" => Some(ApiScope::Reply),
+            "settings" => Some(ApiScope::Settings),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ApiScope::Read => "read",
+            ApiScope::Websocket => "websocket",
+            ApiScope::Reply => "reply",
+            ApiScope::Settings => "settings",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiTokenRecord {
+    pub index: u64,
+    pub name: String,
+    #[serde(skip_serializing, default)]
+    pub token_hash: String,
+    pub scopes: Vec<ApiScope>,
+    pub created: i64,
+    pub expires: Option<i64>,
+    pub last_used: Option<i64>,
+}
+
+impl ApiTokenRecord {
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+}
+
+//Long lived tokens for scripts and bots. Only a hash of each token is kept.
+pub fn create_api_tokens_table(conn: &Connection) -> Result<bool, Box<dyn Error>> {
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS api_tokens (
+             idx integer primary key,
+             name text not null,
+             token_hash text not null unique,
+             scopes text not null,
+             created integer not null,
+             expires integer,
+             last_used integer
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("API tokens table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database api_tokens table.".into())))
+        }
+    }
+
+    Ok(true)
+}
+
+const TOKEN_COLUMNS: &str = "idx, name, token_hash, scopes, created, expires, last_used";
+
+fn token_from_row(row: &Row) -> Result<ApiTokenRecord, rusqlite::Error> {
+    let scopes: String = row.get(3)?;
+
+    Ok(ApiTokenRecord {
+        index: row.get(0)?,
+        name: row.get(1)?,
+        token_hash: row.get(2)?,
+        scopes: scopes.split(',').filter_map(ApiScope::from_name).collect(),
+        created: row.get(4)?,
+        expires: row.get(5)?,
+        last_used: row.get(6)?,
+    })
+}
+
+fn load_token(conn: &Connection, index: u64) -> Result<Option<ApiTokenRecord>, Box<dyn Error>> {
+    let token = conn.query_row(
+        &format!("SELECT {} FROM api_tokens WHERE idx = ?1", TOKEN_COLUMNS),
+        params![index],
+        token_from_row,
+    ).optional()?;
+
+    Ok(token)
+}
+
+//Save a new API token from its hash. Returns the saved token.
+pub fn add_api_token_to_db(filepath: &str, name: &str, token_hash: &str, scopes: &[ApiScope], expires: Option<i64>) -> Result<ApiTokenRecord, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let scopes: Vec<&str> = scopes.iter().map(|scope| scope.name()).collect();
+
+    conn.execute(
+        "INSERT INTO api_tokens (name, token_hash, scopes, created, expires) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![name, token_hash, scopes.join(","), chrono::Utc::now().timestamp(), expires],
+    )?;
+
+    match load_token(&conn, conn.last_insert_rowid() as u64)? {
+        Some(token) => Ok(token),
+        None => Err(Box::new(HydraError("Failed to save API token.".into()))),
+    }
+}
+
+//Get the API tokens, newest first
+pub fn get_api_tokens_from_db(filepath: &str) -> Result<Vec<ApiTokenRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM api_tokens ORDER BY idx DESC", TOKEN_COLUMNS))?;
+    let rows = stmt.query_map([], token_from_row)?;
+
+    let mut tokens = Vec::new();
+
+    for token in rows {
+        tokens.push(token?);
+    }
+
+    Ok(tokens)
+}
+
+pub fn get_api_token_by_hash_from_db(filepath: &str, token_hash: &str) -> Result<Option<ApiTokenRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let token = conn.query_row(
+        &format!("SELECT {} FROM api_tokens WHERE token_hash = ?1", TOKEN_COLUMNS),
+        params![token_hash],
+        token_from_row,
+    ).optional()?;
+
+    Ok(token)
+}
+
+pub fn set_api_token_used_in_db(filepath: &str, index: u64, time: i64) -> Result<(), Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    conn.execute("UPDATE api_tokens SET last_used = ?2 WHERE idx = ?1", params![index, time])?;
+
+    Ok(())
+}
+
+//Revoke an API token. It stops working straight away.
+pub fn delete_api_token_from_db(filepath: &str, index: u64) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let deleted = conn.execute("DELETE FROM api_tokens WHERE idx = ?1", params![index])?;
+
+    Ok(deleted > 0)
+}
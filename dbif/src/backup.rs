@@ -0,0 +1,98 @@
+use rusqlite::{backup::Backup, Connection, DatabaseName, OpenFlags};
+use std::error::Error;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::{
+    connect_to_database,
+    migrate_database,
+    migrations::get_schema_version,
+    latest_schema_version,
+    table_exists,
+    HydraError,
+};
+
+//Pages copied per step of an online backup, and how long to let other connections in between steps
+const BACKUP_PAGES_PER_STEP: i32 = 256;
+const BACKUP_STEP_PAUSE: Duration = Duration::from_millis(10);
+
+//Copies the live database to a standalone file using SQLite's backup API. Writers are only
+//blocked for a moment at a time, so this is safe to run while Helipad is receiving boosts.
+pub fn backup_database(filepath: &str, backup_path: &str) -> Result<(), Box<dyn Error>> {
+    if Path::new(backup_path).exists() {
+        return Err(Box::new(HydraError(format!("Backup file already exists: [{}]", backup_path))));
+    }
+
+    let conn = connect_to_database(false, filepath)?;
+    let mut dest = Connection::open(backup_path)?;
+
+    {
+        let backup = Backup::new(&conn, &mut dest)?;
+        backup.run_to_completion(BACKUP_PAGES_PER_STEP, BACKUP_STEP_PAUSE, None)?;
+    }
+
+    //The copy keeps the WAL setting of the original. Switch it back so the backup is a single file.
+    dest.execute_batch("PRAGMA journal_mode = DELETE;")?;
+
+    Ok(())
+}
+
+//Checks that a backup file is a Helipad database this version can use. Returns its schema version.
+pub fn check_backup(backup_path: &str) -> Result<i64, Box<dyn Error>> {
+    if !Path::new(backup_path).is_file() {
+        return Err(Box::new(HydraError(format!("Backup file not found: [{}]", backup_path))));
+    }
+
+    let conn = Connection::open_with_flags(backup_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+
+    let integrity: String = conn.query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .map_err(|e| HydraError(format!("Backup is not a readable database: {}", e)))?;
+
+    if integrity != "ok" {
+        return Err(Box::new(HydraError(format!("Backup integrity check failed: {}", integrity))));
+    }
+
+    if !table_exists(&conn, "boosts")? {
+        return Err(Box::new(HydraError("Backup is not a Helipad database.".into())));
+    }
+
+    let version = get_schema_version(&conn)?;
+    let latest = latest_schema_version();
+
+    if version > latest {
+        return Err(Box::new(HydraError(format!(
+            "Backup schema version {} is newer than this version of Helipad supports ({}). Upgrade Helipad before restoring it.",
+            version, latest
+        ))));
+    }
+
+    Ok(version)
+}
+
+//Replaces the database with the contents of a backup. The current database is copied aside first
+//and older backups are migrated up to the latest schema. Returns the path of the copy, if one was made.
+pub fn restore_database(backup_path: &str, filepath: &str) -> Result<Option<String>, Box<dyn Error>> {
+    let version = check_backup(backup_path)?;
+    println!("Backup schema version: {} (latest: {})", version, latest_schema_version());
+
+    let mut conn = connect_to_database(false, filepath)?;
+
+    let previous_path = if table_exists(&conn, "boosts")? {
+        let previous_path = format!("{}.pre-restore-{}.bak", filepath, chrono::Utc::now().format("%Y%m%d%H%M%S"));
+        conn.execute("VACUUM INTO ?1", [&previous_path])?;
+        println!("Current database copied to: [{}]", previous_path);
+        Some(previous_path)
+    } else {
+        None
+    };
+
+    conn.restore(DatabaseName::Main, backup_path, None::<fn(rusqlite::backup::Progress)>)?;
+
+    //Backups may have been taken with the journal off WAL
+    conn.execute_batch("PRAGMA journal_mode = WAL;")?;
+    drop(conn);
+
+    migrate_database(filepath, false)?;
+
+    Ok(previous_path)
+}
@@ -1,13 +1,14 @@
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, Row, params};
 use std::error::Error;
 use std::fmt;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
 
 use crate::HydraError;
 use crate::connect_to_database;
-use crate::bind_query_param;
+use crate::{NostrReference, NumerologyMatch};
+use crate::build_filter_clause;
+use crate::get_list_page;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BoostRecord {
@@ -30,6 +31,24 @@ pub struct BoostRecord {
     pub custom_value: Option<String>,
     pub memo: Option<String>,
     pub payment_info: Option<PaymentRecord>,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub quarantined: bool,
+    #[serde(default)]
+    pub imported: bool, // brought over by an import rather than received through this node's LND
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nostr_refs: Vec<NostrReference>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub numerology: Vec<NumerologyMatch>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supporter: Option<u64>,
 }
 
 impl BoostRecord {
@@ -57,9 +76,84 @@ impl BoostRecord {
     pub fn list_type(&self) -> String {
         self.list_type.to_string()
     }
+
+    // Returns the message with resolved nostr profile references replaced by names
+    pub fn resolved_message(&self) -> String {
+        let mut message = self.message.clone();
+
+        for nostr_ref in &self.nostr_refs {
+            if let (Some(name), "profile") = (&nostr_ref.name, nostr_ref.kind.as_str()) {
+                message = message.replace(&nostr_ref.text, &format!("@{}", name));
+            }
+        }
+
+        message
+    }
+
+    // Returns the emojis of the matched numerology
+    pub fn numerology_emojis(&self) -> String {
+        self.numerology.iter().filter_map(|num| num.emoji.clone()).collect::<Vec<String>>().join("")
+    }
+
+    //Returns the boost with all of the displayable strings html entity escaped
+    pub fn escaped_for_html(self) -> BoostRecord {
+        BoostRecord {
+            sender: BoostRecord::escape_for_html(self.sender),
+            app: BoostRecord::escape_for_html(self.app),
+            message: BoostRecord::escape_for_html(self.message),
+            podcast: BoostRecord::escape_for_html(self.podcast),
+            episode: BoostRecord::escape_for_html(self.episode),
+            tlv: BoostRecord::escape_for_html(self.tlv),
+            remote_podcast: self.remote_podcast.map(BoostRecord::escape_for_html),
+            remote_episode: self.remote_episode.map(BoostRecord::escape_for_html),
+            payment_info: self.payment_info.map(|info| PaymentRecord {
+                pubkey: BoostRecord::escape_for_html(info.pubkey),
+                custom_value: BoostRecord::escape_for_html(info.custom_value),
+                ..info
+            }),
+            ..self
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+//Columns read by boost_from_row, in order
+pub(crate) const BOOST_COLUMNS: &str = "idx, time, value_msat, value_msat_total, action, list_type, sender, app, message, podcast, episode, tlv, remote_podcast, remote_episode, reply_sent, custom_key, custom_value, memo, hidden, quarantined, imported";
+
+//Builds a boost record from a row selected with BOOST_COLUMNS
+pub(crate) fn boost_from_row(row: &Row) -> Result<BoostRecord, rusqlite::Error> {
+    Ok(BoostRecord {
+        index: row.get(0)?,
+        time: row.get(1)?,
+        value_msat: row.get(2)?,
+        value_msat_total: row.get(3)?,
+        action: ActionType::from_u8(row.get(4)?),
+        list_type: ListType::from_u8(row.get(5)?),
+        sender: row.get(6)?,
+        app: row.get(7)?,
+        message: row.get(8)?,
+        podcast: row.get(9)?,
+        episode: row.get(10)?,
+        tlv: row.get(11)?,
+        remote_podcast: row.get(12).ok(),
+        remote_episode: row.get(13).ok(),
+        reply_sent: row.get(14).unwrap_or(false),
+        custom_key: row.get(15).ok(),
+        custom_value: row.get(16).ok(),
+        memo: row.get(17).ok(),
+        payment_info: None,
+        hidden: row.get(18).unwrap_or(false),
+        quarantined: row.get(19).unwrap_or(false),
+        imported: row.get(20).unwrap_or(false),
+        nostr_refs: Vec::new(),
+        numerology: Vec::new(),
+        tags: Vec::new(),
+        note: None,
+        pinned: false,
+        supporter: None,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct PaymentRecord {
     pub payment_hash: String,
     pub pubkey: String,
@@ -75,6 +169,19 @@ pub struct BoostFilters {
     pub start_date: Option<u64>,
     pub end_date: Option<u64>,
     pub actions: Vec<ActionType>,
+    pub include_hidden: bool,
+    pub only_hidden: bool,
+    pub sender: Option<String>,
+    pub app: Option<String>,
+    pub episode: Option<String>,
+    pub min_sats: Option<u64>,
+    pub max_sats: Option<u64>,
+    pub custom_key: Option<u64>,
+    pub replied: Option<bool>,
+    pub has_reply_address: Option<bool>,
+    pub tag: Option<String>,
+    pub pinned: Option<bool>,
+    pub supporter: Option<u64>,
 }
 
 impl BoostFilters {
@@ -164,6 +271,16 @@ impl ListType {
             _ => ListType::Unknown,
         }
     }
+    //Maps the list names used by the queries ("boost", "stream", "sent") to their type
+    pub fn from_list_name(name: &str) -> Option<Self> {
+        match name {
+            "boost" => Some(ListType::Boost),
+            "stream" => Some(ListType::Stream),
+            "sent" => Some(ListType::Sent),
+            _ => None,
+        }
+    }
+
     pub fn from_action(action: ActionType) -> Self {
         match action {
             ActionType::Boost | ActionType::Auto | ActionType::Invoice => ListType::Boost,
@@ -238,48 +355,100 @@ pub fn create_boosts_table(conn: &Connection) -> Result<bool, Box<dyn Error>> {
         }
     }
 
-    //Add additional columns to existing installs
-    if conn.execute("ALTER TABLE boosts ADD COLUMN remote_podcast text", []).is_ok() {
-        println!("Boosts remote podcast column added.");
-    }
-
-    if conn.execute("ALTER TABLE boosts ADD COLUMN remote_episode text", []).is_ok() {
-        println!("Boosts remote episode column added.");
-    }
+    Ok(true)
+}
 
-    if conn.execute("ALTER TABLE boosts ADD COLUMN reply_sent integer", []).is_ok() {
-        println!("Boosts reply sent column added.");
-    }
 
-    if conn.execute_batch("ALTER TABLE boosts ADD COLUMN custom_key integer; ALTER TABLE boosts ADD COLUMN custom_value text;").is_ok() {
-        println!("Boosts custom key/value added.");
-    }
+//Add an invoice to the database
+pub fn add_invoice_to_db(filepath: &str, boost: &BoostRecord) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
 
-    if conn.execute("ALTER TABLE boosts ADD COLUMN memo text", []).is_ok() {
-        println!("Boosts memo column added.");
+    match insert_invoice(&conn, boost) {
+        Ok(_) => {
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            Err(Box::new(HydraError(format!("Failed to add boost: [{}].", boost.index))))
+        }
     }
+}
 
-    if conn.execute("ALTER TABLE boosts ADD COLUMN list_type integer", []).is_ok() {
-        println!("Boosts list type column added.");
-
-        conn.execute("UPDATE boosts SET list_type = 1 WHERE action IN (2, 4, 5)", []).unwrap();
-        conn.execute("UPDATE boosts SET list_type = 2 WHERE action NOT IN (2, 4, 5)", []).unwrap();
-    }
+pub(crate) fn insert_invoice(conn: &Connection, boost: &BoostRecord) -> Result<usize, rusqlite::Error> {
+    let mut stmt = conn.prepare_cached(
+        "INSERT INTO boosts
+            (idx, time, value_msat, value_msat_total, action, list_type, sender, app, message, podcast, episode, tlv, remote_podcast, remote_episode, reply_sent, custom_key, custom_value, memo, hidden, quarantined, imported)
+        VALUES
+            (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)
+        "
+    )?;
 
-    Ok(true)
+    stmt.execute(
+        params![
+            boost.index,
+            boost.time,
+            boost.value_msat,
+            boost.value_msat_total,
+            boost.action as u8,
+            boost.list_type as u8,
+            boost.sender,
+            boost.app,
+            boost.message,
+            boost.podcast,
+            boost.episode,
+            boost.tlv,
+            boost.remote_podcast,
+            boost.remote_episode,
+            boost.reply_sent,
+            boost.custom_key,
+            boost.custom_value,
+            boost.memo,
+            boost.hidden,
+            boost.quarantined,
+            boost.imported
+        ]
+    )
 }
 
+//Check if a received boost or stream is in the database
+pub fn invoice_exists_in_db(filepath: &str, index: u64) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut stmt = conn.prepare_cached("SELECT EXISTS(SELECT 1 FROM boosts WHERE idx = ?1)")?;
+    Ok(stmt.query_row(params![index], |row| row.get(0))?)
+}
 
-//Add an invoice to the database
-pub fn add_invoice_to_db(filepath: &str, boost: &BoostRecord) -> Result<bool, Box<dyn Error>> {
+//Add an invoice, or refresh the parsed fields of one that's already stored. Whether it was replied to,
+//hidden or quarantined is left as it was. Returns true if the invoice was new.
+pub fn upsert_invoice_in_db(filepath: &str, boost: &BoostRecord) -> Result<bool, Box<dyn Error>> {
+    let exists = invoice_exists_in_db(filepath, boost.index)?;
     let conn = connect_to_database(false, filepath)?;
 
-    match conn.execute(
+    let mut stmt = conn.prepare_cached(
         "INSERT INTO boosts
-            (idx, time, value_msat, value_msat_total, action, list_type, sender, app, message, podcast, episode, tlv, remote_podcast, remote_episode, reply_sent, custom_key, custom_value, memo)
+            (idx, time, value_msat, value_msat_total, action, list_type, sender, app, message, podcast, episode, tlv, remote_podcast, remote_episode, reply_sent, custom_key, custom_value, memo, hidden, quarantined)
         VALUES
-            (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
-        ",
+            (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)
+        ON CONFLICT(idx) DO UPDATE SET
+            time = excluded.time,
+            value_msat = excluded.value_msat,
+            value_msat_total = excluded.value_msat_total,
+            action = excluded.action,
+            list_type = excluded.list_type,
+            sender = excluded.sender,
+            app = excluded.app,
+            message = excluded.message,
+            podcast = excluded.podcast,
+            episode = excluded.episode,
+            tlv = excluded.tlv,
+            remote_podcast = excluded.remote_podcast,
+            remote_episode = excluded.remote_episode,
+            custom_key = excluded.custom_key,
+            custom_value = excluded.custom_value,
+            memo = excluded.memo
+        "
+    )?;
+
+    match stmt.execute(
         params![
             boost.index,
             boost.time,
@@ -298,15 +467,17 @@ pub fn add_invoice_to_db(filepath: &str, boost: &BoostRecord) -> Result<bool, Bo
             boost.reply_sent,
             boost.custom_key,
             boost.custom_value,
-            boost.memo
+            boost.memo,
+            boost.hidden,
+            boost.quarantined
         ]
     ) {
         Ok(_) => {
-            Ok(true)
+            Ok(!exists)
         }
         Err(e) => {
             eprintln!("{}", e);
-            Err(Box::new(HydraError(format!("Failed to add boost: [{}].", boost.index))))
+            Err(Box::new(HydraError(format!("Failed to save boost: [{}].", boost.index))))
         }
     }
 }
@@ -318,6 +489,19 @@ pub fn mark_boost_as_replied(filepath: &str, index: u64) -> Result<bool, Box<dyn
     Ok(true)
 }
 
+//Hide or unhide a boost (unhiding also releases it from quarantine)
+pub fn set_boost_hidden_in_db(filepath: &str, index: u64, hidden: bool) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    if hidden {
+        conn.execute("UPDATE boosts SET hidden = 1 WHERE idx = ?1", params![index])?;
+    } else {
+        conn.execute("UPDATE boosts SET hidden = 0, quarantined = 0 WHERE idx = ?1", params![index])?;
+    }
+
+    Ok(true)
+}
+
 //Update an existing invoice with new data (e.g., from payment metadata fetch)
 pub fn update_invoice_in_db(filepath: &str, boost: &BoostRecord) -> Result<bool, Box<dyn Error>> {
     let conn = connect_to_database(false, filepath)?;
@@ -374,155 +558,28 @@ pub fn update_invoice_in_db(filepath: &str, boost: &BoostRecord) -> Result<bool,
 pub fn get_invoices_from_db(filepath: &str, invtype: &str, index: u64, max: u64, direction: bool, escape_html: bool, filters: BoostFilters) -> Result<Vec<BoostRecord>, Box<dyn Error>> {
     let conn = connect_to_database(false, filepath)?;
 
-    let mut conditions: Vec<&str> = Vec::new();
-    let mut bindings: HashMap<&str, &str> = HashMap::new();
-
-    let cond = if direction {
-        "idx <= :idx"
-    } else {
-        "idx >= :idx"
-    };
-
-    conditions.push(cond);
-
-    let strindex = index.to_string();
-    bindings.insert(":idx", &strindex);
-
-    if invtype == "boost" {
-        conditions.push("list_type = 1");
-    }
-    else if invtype == "stream" {
-        conditions.push("list_type = 2");
-    }
-
-    let mut action_filters= HashMap::new();
-    let action_condition_string;
-
-    if !filters.actions.is_empty() {
-        for (idx, action) in filters.actions.iter().enumerate() {
-            let key = format!(":action{}", idx);
-            let value = (*action as u8).to_string();
-            action_filters.insert(key, value);
-        }
-
-        let action_condition_list = action_filters
-            .keys()
-            .map(|k| k.as_str())
-            .collect::<Vec<&str>>()
-            .join(", ");
-
-        action_condition_string = format!("action IN ({})", action_condition_list);
-        conditions.push(action_condition_string.as_str());
-
-        for (key, value) in &action_filters {
-            bindings.insert(key.as_str(), value.as_str());
-        }
-    }
-
-    if let Some(podcast) = &filters.podcast {
-        conditions.push("podcast = :podcast");
-        bindings.insert(":podcast", podcast);
-    }
-
-    let start_date = filters.start_date.unwrap_or_default().to_string();
-
-    if !start_date.is_empty() && start_date != "0" {
-        conditions.push("time >= :start_date");
-        bindings.insert(":start_date", &start_date);
-    }
-
-    let end_date = filters.end_date.unwrap_or_default().to_string();
+    let mut clause = build_filter_clause(&filters, false);
 
-    if !end_date.is_empty() && end_date != "0" {
-        conditions.push("time <= :end_date");
-        bindings.insert(":end_date", &end_date);
+    if let Some(list_type) = ListType::from_list_name(invtype) {
+        clause.conditions.push(format!("list_type = {}", list_type as u8));
     }
 
-    let conditions = conditions.join(" AND ");
-
-    let mut limit = String::new();
-    let strmax = max.to_string();
-
-    if max > 0 {
-        limit.push_str("LIMIT :max");
-        bindings.insert(":max", &strmax);
-    }
-
-    //Query for boosts and automated boosts
-    let sqltxt = format!(
-        "SELECT
-            idx, time, value_msat, value_msat_total, action, list_type, sender, app, message, podcast, episode, tlv, remote_podcast, remote_episode, reply_sent, custom_key, custom_value, memo
-        FROM
-            boosts
-        WHERE
-            {}
-        ORDER BY
-            idx DESC
-        {}
-        ",
-        conditions,
-        limit
-    );
-
-    //Prepare and execute the query
-    let mut stmt = conn.prepare(sqltxt.as_str())?;
-
-    for (name, value) in &bindings {
-        bind_query_param(&mut stmt, name, value)?;
-    }
-
-    let mut rows = stmt.raw_query();
-    let mut boosts: Vec<BoostRecord> = Vec::new();
-
-    while let Some(row) = rows.next()? {
-        let boost = BoostRecord {
-            index: row.get(0)?,
-            time: row.get(1)?,
-            value_msat: row.get(2)?,
-            value_msat_total: row.get(3)?,
-            action: ActionType::from_u8(row.get(4)?),
-            list_type: ListType::from_u8(row.get(5)?),
-            sender: row.get(6)?,
-            app: row.get(7)?,
-            message: row.get(8)?,
-            podcast: row.get(9)?,
-            episode: row.get(10)?,
-            tlv: row.get(11)?,
-            remote_podcast: row.get(12).ok(),
-            remote_episode: row.get(13).ok(),
-            reply_sent: row.get(14).unwrap_or(false),
-            custom_key: row.get(15).ok(),
-            custom_value: row.get(16).ok(),
-            memo: row.get(17).ok(),
-            payment_info: None,
-        };
-
-        //Some things like text output don't need to be html entity escaped
-        //so only do it if asked for
-        if escape_html {
-            let boost_clean = BoostRecord {
-                sender: BoostRecord::escape_for_html(boost.sender),
-                app: BoostRecord::escape_for_html(boost.app),
-                message: BoostRecord::escape_for_html(boost.message),
-                podcast: BoostRecord::escape_for_html(boost.podcast),
-                episode: BoostRecord::escape_for_html(boost.episode),
-                tlv: BoostRecord::escape_for_html(boost.tlv),
-                remote_podcast: boost.remote_podcast.map(BoostRecord::escape_for_html),
-                remote_episode: boost.remote_episode.map(BoostRecord::escape_for_html),
-                ..boost
-            };
-            boosts.push(boost_clean);
-        } else {
-            boosts.push(boost);
-        }
+    let boosts = get_list_page(&conn, false, &clause, index, max, direction)?;
 
+    //Some things like text output don't need to be html entity escaped
+    //so only do it if asked for
+    if escape_html {
+        return Ok(boosts.into_iter().map(BoostRecord::escaped_for_html).collect());
     }
 
     Ok(boosts)
 }
 
 pub fn get_single_invoice_from_db(filepath: &str, index: u64, escape_html: bool) -> Result<Option<BoostRecord>, Box<dyn Error>> {
-    let filters = BoostFilters::new();
+    let filters = BoostFilters {
+        include_hidden: true,
+        ..BoostFilters::new()
+    };
     let invoices = get_invoices_from_db(filepath, "", index, 1, true, escape_html, filters)?;
 
     if !invoices.is_empty() && invoices[0].index == index {
@@ -542,48 +599,32 @@ pub fn get_streams_from_db(filepath: &str, index: u64, max: u64, direction: bool
     get_invoices_from_db(filepath, "stream", index, max, direction, escape_html, filters)
 }
 
-//Get the last boost index number from the database
+//Get the last boost index number from the database. Imported boosts don't count since they
+//didn't come from this node's LND.
 pub fn get_last_boost_index_from_db(filepath: &str) -> Result<u64, Box<dyn Error>> {
     let conn = connect_to_database(false, filepath)?;
     let mut boosts: Vec<BoostRecord> = Vec::new();
     let max = 1;
 
     //Prepare and execute the query
-    let mut stmt = conn.prepare(
+    let sqltxt = format!(
         "SELECT
-            idx, time, value_msat, value_msat_total, action, list_type, sender, app, message, podcast, episode, tlv, remote_podcast, remote_episode, reply_sent, custom_key, custom_value, memo
+            {}
         FROM
             boosts
+        WHERE
+            imported = 0
         ORDER BY
             idx DESC
         LIMIT
             :max
-        "
-    )?;
+        ",
+        BOOST_COLUMNS
+    );
+
+    let mut stmt = conn.prepare_cached(&sqltxt)?;
 
-    let rows = stmt.query_map(&[(":max", max.to_string().as_str())], |row| {
-        Ok(BoostRecord {
-            index: row.get(0)?,
-            time: row.get(1)?,
-            value_msat: row.get(2)?,
-            value_msat_total: row.get(3)?,
-            action: ActionType::from_u8(row.get(4)?),
-            list_type: ListType::from_u8(row.get(5)?),
-            sender: row.get(6)?,
-            app: row.get(7)?,
-            message: row.get(8)?,
-            podcast: row.get(9)?,
-            episode: row.get(10)?,
-            tlv: row.get(11)?,
-            remote_podcast: row.get(12).ok(),
-            remote_episode: row.get(13).ok(),
-            reply_sent: row.get(14).unwrap_or(false),
-            custom_key: row.get(15).ok(),
-            custom_value: row.get(16).ok(),
-            memo: row.get(17).ok(),
-            payment_info: None,
-        })
-    }).unwrap();
+    let rows = stmt.query_map(&[(":max", max.to_string().as_str())], boost_from_row).unwrap();
 
     //Parse the results
     for row in rows {
@@ -604,7 +645,7 @@ pub fn get_podcasts_from_db(filepath: &str) -> Result<Vec<String>, Box<dyn Error
 
     let query = "SELECT DISTINCT podcast FROM boosts WHERE podcast <> '' ORDER BY podcast".to_string();
 
-    let mut stmt = conn.prepare(&query)?;
+    let mut stmt = conn.prepare_cached(&query)?;
     let mut rows = stmt.raw_query();
 
     //Parse the results
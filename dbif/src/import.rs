@@ -0,0 +1,300 @@
+use rusqlite::{params, Connection, OpenFlags, TransactionBehavior};
+use std::error::Error;
+use std::collections::{HashMap, HashSet};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    connect_to_database,
+    check_backup,
+    insert_invoice,
+    insert_payment,
+    migrations::migrate_database_copy,
+    pool::close_pool,
+    table_exists,
+    supporters::link_unlinked_boosts,
+    boost_from_row,
+    sent_boost_from_row,
+    BoostRecord,
+    ListType,
+    StreamRollupRecord,
+    HydraError,
+    BOOST_COLUMNS,
+    SENT_BOOST_COLUMNS,
+};
+
+//Imported boosts get indexes from here up, so they never collide with the invoice and payment
+//indexes coming from this node's LND. They're told apart by their imported column, not their index.
+pub(crate) const IMPORT_INDEX_START: u64 = 1 << 48;
+
+//How many of the boosts being imported are returned to preview
+const IMPORT_PREVIEW_SIZE: usize = 20;
+
+//Boosts, sent boosts and stream rollups read from another source, ready to be merged in
+#[derive(Debug, Clone, Default)]
+pub struct ImportBatch {
+    pub boosts: Vec<BoostRecord>, // received and sent, told apart by list_type
+    pub rollups: Vec<StreamRollupRecord>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub boosts_added: u64,
+    pub streams_added: u64,
+    pub sent_boosts_added: u64,
+    pub rollups_added: u64,
+    pub duplicates: u64,
+    pub preview: Vec<BoostRecord>, // the first boosts that were (or would be) added
+}
+
+//Reads everything out of another Helipad database. Older databases are read from a migrated copy
+//so their rows look the same as ours; the original file isn't changed.
+pub fn read_helipad_database(import_path: &str) -> Result<ImportBatch, Box<dyn Error>> {
+    check_backup(import_path)?;
+
+    let copy_path = std::env::temp_dir().join(format!(
+        "helipad-import-{}-{}.db",
+        std::process::id(),
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    ));
+    let copy_path = copy_path.to_string_lossy().to_string();
+
+    {
+        let source = Connection::open_with_flags(import_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        source.backup(rusqlite::DatabaseName::Main, &copy_path, None)?;
+    }
+
+    let result = migrate_database_copy(&copy_path).and_then(|_| read_import_copy(&copy_path));
+
+    close_pool(&copy_path);
+
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", copy_path, suffix));
+    }
+
+    result
+}
+
+fn read_import_copy(filepath: &str) -> Result<ImportBatch, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut batch = ImportBatch::default();
+
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM boosts ORDER BY idx", BOOST_COLUMNS))?;
+    let mut rows = stmt.raw_query();
+
+    while let Some(row) = rows.next()? {
+        batch.boosts.push(boost_from_row(row)?);
+    }
+
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM sent_boosts ORDER BY idx", SENT_BOOST_COLUMNS))?;
+    let mut rows = stmt.raw_query();
+
+    while let Some(row) = rows.next()? {
+        batch.boosts.push(sent_boost_from_row(row)?);
+    }
+
+    if table_exists(&conn, "stream_rollups")? {
+        let mut stmt = conn.prepare(
+            "SELECT day, podcast, episode, sender, app, stream_count, value_msat, value_msat_total, period FROM stream_rollups ORDER BY day"
+        )?;
+        let mut rows = stmt.raw_query();
+
+        while let Some(row) = rows.next()? {
+            batch.rollups.push(StreamRollupRecord {
+                day: row.get(0)?,
+                podcast: row.get(1)?,
+                episode: row.get(2)?,
+                sender: row.get(3)?,
+                app: row.get(4)?,
+                count: row.get(5)?,
+                value_msat: row.get(6)?,
+                value_msat_total: row.get(7)?,
+                period: row.get(8)?,
+            });
+        }
+    }
+
+    Ok(batch)
+}
+
+//Boosts are matched on the podcasting 2.0 uuid when they have one, and otherwise on when they
+//arrived, how much they were for, who sent them and what they said
+fn boost_uuid(tlv: &str) -> Option<String> {
+    let tlv: Value = serde_json::from_str(tlv).ok()?;
+    tlv["uuid"].as_str().filter(|uuid| !uuid.is_empty()).map(|uuid| format!("uuid:{}", uuid))
+}
+
+fn received_key(time: i64, value_msat_total: i64, sender: &str, message: &str) -> String {
+    format!("boost:{}:{}:{}:{}", time, value_msat_total, sender, message)
+}
+
+fn sent_key(time: i64, value_msat_total: i64, podcast: &str, message: &str) -> String {
+    format!("sent:{}:{}:{}:{}", time, value_msat_total, podcast, message)
+}
+
+fn load_received_keys(conn: &Connection) -> Result<HashSet<String>, Box<dyn Error>> {
+    let mut keys = HashSet::new();
+    let mut stmt = conn.prepare(
+        "SELECT time, value_msat_total, COALESCE(sender, ''), COALESCE(message, ''), CASE WHEN json_valid(tlv) THEN json_extract(tlv, '$.uuid') END FROM boosts"
+    )?;
+    let mut rows = stmt.raw_query();
+
+    while let Some(row) = rows.next()? {
+        let sender: String = row.get(2)?;
+        let message: String = row.get(3)?;
+        keys.insert(received_key(row.get(0)?, row.get(1)?, &sender, &message));
+
+        if let Some(uuid) = row.get::<_, Option<String>>(4)?.filter(|uuid| !uuid.is_empty()) {
+            keys.insert(format!("uuid:{}", uuid));
+        }
+    }
+
+    Ok(keys)
+}
+
+fn load_sent_keys(conn: &Connection) -> Result<HashSet<String>, Box<dyn Error>> {
+    let mut keys = HashSet::new();
+    let mut stmt = conn.prepare(
+        "SELECT time, value_msat_total, COALESCE(podcast, ''), COALESCE(message, ''), COALESCE(payment_hash, '') FROM sent_boosts"
+    )?;
+    let mut rows = stmt.raw_query();
+
+    while let Some(row) = rows.next()? {
+        let podcast: String = row.get(2)?;
+        let message: String = row.get(3)?;
+        let payment_hash: String = row.get(4)?;
+        keys.insert(sent_key(row.get(0)?, row.get(1)?, &podcast, &message));
+
+        if !payment_hash.is_empty() {
+            keys.insert(format!("hash:{}", payment_hash));
+        }
+    }
+
+    Ok(keys)
+}
+
+//Marks every key as seen. Returns false if any of them already had been.
+fn insert_keys(seen: &mut HashSet<String>, keys: Vec<String>) -> bool {
+    let duplicate = keys.iter().any(|key| seen.contains(key));
+    seen.extend(keys);
+    !duplicate
+}
+
+fn next_import_index(conn: &Connection, table: &str) -> Result<u64, Box<dyn Error>> {
+    let max: Option<u64> = conn.query_row(
+        &format!("SELECT MAX(idx) FROM {} WHERE idx >= ?1", table),
+        params![IMPORT_INDEX_START],
+        |row| row.get(0),
+    )?;
+
+    Ok(max.map(|max| max + 1).unwrap_or(IMPORT_INDEX_START))
+}
+
+//Merges imported boosts into the database. Duplicates of boosts already here are skipped and the rest are
+//given new indexes in time order. In dry run mode nothing is saved, but the report is the same.
+pub fn import_boosts_to_db(filepath: &str, batch: ImportBatch, dry_run: bool) -> Result<ImportReport, Box<dyn Error>> {
+    let mut conn = connect_to_database(false, filepath)?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+    let mut report = ImportReport {
+        dry_run,
+        ..Default::default()
+    };
+
+    let (mut sent, mut received): (Vec<BoostRecord>, Vec<BoostRecord>) = batch.boosts.into_iter()
+        .partition(|boost| boost.list_type == ListType::Sent);
+
+    received.sort_by_key(|boost| (boost.time, boost.index));
+    sent.sort_by_key(|boost| (boost.time, boost.index));
+
+    //Received boosts go first so replies can be pointed at their new indexes
+    let mut seen = load_received_keys(&tx)?;
+    let mut next_index = next_import_index(&tx, "boosts")?;
+    let mut remapped: HashMap<u64, u64> = HashMap::new();
+
+    for mut boost in received {
+        let mut keys = vec![received_key(boost.time, boost.value_msat_total, &boost.sender, &boost.message)];
+        keys.extend(boost_uuid(&boost.tlv));
+
+        if !insert_keys(&mut seen, keys) {
+            report.duplicates += 1;
+            continue;
+        }
+
+        remapped.insert(boost.index, next_index);
+        boost.index = next_index;
+        boost.imported = true;
+        next_index += 1;
+
+        if let Err(e) = insert_invoice(&tx, &boost) {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to import boost from {}.", boost.time))));
+        }
+
+        match boost.list_type {
+            ListType::Stream => report.streams_added += 1,
+            _ => report.boosts_added += 1,
+        }
+
+        if report.preview.len() < IMPORT_PREVIEW_SIZE {
+            report.preview.push(boost);
+        }
+    }
+
+    let mut seen = load_sent_keys(&tx)?;
+    let mut next_index = next_import_index(&tx, "sent_boosts")?;
+
+    for mut boost in sent {
+        let mut payment_info = boost.payment_info.take().unwrap_or_default();
+        let mut keys = vec![sent_key(boost.time, boost.value_msat_total, &boost.podcast, &boost.message)];
+
+        if !payment_info.payment_hash.is_empty() {
+            keys.push(format!("hash:{}", payment_info.payment_hash));
+        }
+
+        if !insert_keys(&mut seen, keys) {
+            report.duplicates += 1;
+            continue;
+        }
+
+        //Replies only stay linked if the boost they answered came along too
+        payment_info.reply_to_idx = payment_info.reply_to_idx.and_then(|idx| remapped.get(&idx).copied());
+
+        boost.index = next_index;
+        boost.imported = true;
+        next_index += 1;
+
+        if let Err(e) = insert_payment(&tx, &boost, &payment_info) {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Failed to import sent boost from {}.", boost.time))));
+        }
+
+        report.sent_boosts_added += 1;
+        boost.payment_info = Some(payment_info);
+
+        if report.preview.len() < IMPORT_PREVIEW_SIZE {
+            report.preview.push(boost);
+        }
+    }
+
+    //Rollups for periods that already have one are left alone so importing twice doesn't double them
+    for rollup in &batch.rollups {
+        report.rollups_added += tx.execute(
+            "INSERT OR IGNORE INTO stream_rollups (day, period, podcast, episode, sender, app, stream_count, value_msat, value_msat_total)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![rollup.day, rollup.period, rollup.podcast, rollup.episode, rollup.sender, rollup.app, rollup.count, rollup.value_msat, rollup.value_msat_total],
+        )? as u64;
+    }
+
+    //New senders get a supporter profile, or join the one they already have
+    link_unlinked_boosts(&tx)?;
+
+    if dry_run {
+        tx.rollback()?;
+    } else {
+        tx.commit()?;
+    }
+
+    Ok(report)
+}
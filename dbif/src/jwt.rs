@@ -29,7 +29,7 @@ pub fn create_jwt_secret_table(conn: &Connection) -> Result<bool, Box<dyn Error>
 pub fn get_or_create_jwt_secret(filepath: &str) -> Result<String, Box<dyn Error>> {
     let conn = connect_to_database(false, filepath)?;
 
-    let mut stmt = conn.prepare("SELECT secret FROM jwt_secret WHERE idx = 1")?;
+    let mut stmt = conn.prepare_cached("SELECT secret FROM jwt_secret WHERE idx = 1")?;
 
     let result = stmt.query_row([], |row| {
         row.get(0)
@@ -40,14 +40,26 @@ pub fn get_or_create_jwt_secret(filepath: &str) -> Result<String, Box<dyn Error>
     }
 
     //If no secret found, generate a new one
-    let secret: String = rand::rng()
+    let secret = new_jwt_secret();
+
+    //Set the new secret in the database
+    let _ = set_jwt_secret(filepath, &secret);
+
+    Ok(secret)
+}
+
+fn new_jwt_secret() -> String {
+    rand::rng()
         .sample_iter(&Alphanumeric)
         .take(40)
         .map(char::from)
-        .collect();
+        .collect()
+}
 
-    //Set the new secret in the database
-    let _ = set_jwt_secret(filepath, &secret);
+//Replace the JWT secret with a new one, which logs everyone out
+pub fn rotate_jwt_secret(filepath: &str) -> Result<String, Box<dyn Error>> {
+    let secret = new_jwt_secret();
+    set_jwt_secret(filepath, &secret)?;
 
     Ok(secret)
 }
@@ -3,21 +3,63 @@ use std::error::Error;
 use std::fmt;
 use std::os::unix::fs::PermissionsExt;
 
+mod annotations;
+mod api_tokens;
+mod backup;
 mod boosts;
+mod import;
 mod jwt;
+mod logins;
+mod metadata_providers;
+mod migrations;
+mod moderation;
 mod node_info;
+mod nostr;
 mod numerology;
+mod password;
+mod pool;
+mod query;
+mod retention;
+mod search;
 mod sent_boosts;
+mod sessions;
 mod settings;
+mod share_links;
+mod shows;
+mod stats;
+mod supporters;
 mod triggers;
+mod two_factor;
+mod users;
 
+pub use annotations::*;
+pub use api_tokens::*;
+pub use backup::*;
 pub use boosts::*;
+pub use import::*;
 pub use jwt::*;
+pub use logins::*;
+pub use metadata_providers::*;
+pub use migrations::{get_schema_history_from_db, latest_schema_version, migrate_database, MigrationRecord};
+pub use moderation::*;
 pub use node_info::*;
+pub use nostr::*;
 pub use numerology::*;
+pub use password::*;
+pub use pool::{run_blocking, DbConnection};
+pub use query::*;
+pub use retention::{get_stream_rollups_end_from_db, get_stream_rollups_from_db, rollup_streams_in_db, RollupResult, StreamRollupRecord};
+pub use search::*;
 pub use sent_boosts::*;
+pub use sessions::*;
 pub use settings::*;
+pub use share_links::*;
+pub use shows::*;
+pub use stats::*;
+pub use supporters::*;
 pub use triggers::*;
+pub use two_factor::*;
+pub use users::*;
 
 #[derive(Debug)]
 struct HydraError(String);
@@ -28,9 +70,9 @@ impl fmt::Display for HydraError {
 }
 impl Error for HydraError {}
 
-//Connect to the database at the given file location
-fn connect_to_database(init: bool, filepath: &str) -> Result<Connection, Box<dyn Error>> {
-    if let Ok(conn) = Connection::open(filepath) {
+//Connect to the database at the given file location. Connections come from a shared pool per file.
+fn connect_to_database(init: bool, filepath: &str) -> Result<DbConnection, Box<dyn Error>> {
+    if let Ok(conn) = pool::get_pooled_connection(filepath) {
         if init {
             match set_database_file_permissions(filepath) {
                 Ok(_) => {},
@@ -71,7 +113,7 @@ fn set_database_file_permissions(filepath: &str) -> Result<bool, Box<dyn Error>>
 
 fn table_exists(conn: &Connection, table_name: &str) -> Result<bool, Box<dyn Error>> {
     //Prepare and execute the query
-    let mut stmt = conn.prepare(r#"SELECT 1 FROM sqlite_master WHERE type='table' AND name=?1"#)?;
+    let mut stmt = conn.prepare_cached(r#"SELECT 1 FROM sqlite_master WHERE type='table' AND name=?1"#)?;
     let mut rows = stmt.query_map(params![table_name], |_| Ok(true))?;
 
     Ok(rows.next().is_some())
@@ -93,93 +135,8 @@ fn bind_query_param(stmt: &mut Statement, name: &str, value: &str) -> Result<(),
 
 //Create or update a new database file if needed
 pub fn create_database(filepath: &str) -> Result<bool, Box<dyn Error>> {
-    let conn = connect_to_database(true, filepath)?;
+    connect_to_database(true, filepath)?;
+    migrate_database(filepath, false)?;
 
-    create_boosts_table(&conn)?;
-    create_node_info_table(&conn)?;
-    create_sent_boosts_table(&conn)?;
-    create_numerology_table(&conn)?;
-    create_settings_table(&conn)?;
-    create_jwt_secret_table(&conn)?;
-    create_triggers_table(&conn)?;
-
-    // Migrate numeroloyg sounds and webhooks to triggers
-    migrate_numerology_sounds_to_triggers(&conn)?;
-    migrate_webhooks_to_triggers(&conn)?;
-
-    Ok(true)
-}
-
-pub fn migrate_numerology_sounds_to_triggers(conn: &Connection) -> Result<bool, Box<dyn Error>> {
-    // Check if sound_file column still exists
-    let has_sound_file: bool = conn
-        .query_row(
-            "SELECT COUNT(*) FROM pragma_table_info('numerology') WHERE name = 'sound_file'",
-            [],
-            |row| row.get(0),
-        )
-        .map(|count: i64| count > 0)?;
-
-    if !has_sound_file {
-        return Ok(true);
-    }
-
-    let pos: i64 = conn
-        .query_row("SELECT COALESCE(MAX(position), 0) FROM triggers", [], |row| row.get(0))
-        .unwrap_or(0);
-
-    println!("Migrating numerology sound files to triggers...");
-
-    conn.execute(
-        r#"INSERT INTO triggers (
-            position, enabled,on_boost, on_stream, on_auto, on_sent, on_invoice,
-            amount, amount_equality, sound_file, sound_name
-        )
-        SELECT
-            ?1 + ROW_NUMBER() OVER (ORDER BY position),
-            1, 1, 1, 1, 1, 1,
-            amount, equality, sound_file, sound_file
-        FROM numerology
-        WHERE sound_file IS NOT NULL AND sound_file != ''
-        ORDER BY idx"#,
-        [pos],
-    )?;
-
-    conn.execute("ALTER TABLE numerology DROP COLUMN sound_file", [])?;
-
-    println!("Migrated numerology sound files to triggers.");
     Ok(true)
 }
-
-pub fn migrate_webhooks_to_triggers(conn: &Connection) -> Result<bool, Box<dyn Error>> {
-    if !table_exists(conn, "webhooks")? {
-        return Ok(true);
-    }
-
-    let pos: i64 = conn
-        .query_row("SELECT COALESCE(MAX(position), 0) FROM triggers", [], |row| row.get(0))
-        .unwrap_or(0);
-
-    println!("Migrating webhooks to triggers...");
-
-    conn.execute(
-        r#"INSERT INTO triggers (
-            position,
-            enabled, on_boost, on_stream, on_sent, on_auto, on_invoice,
-            amount, amount_equality,
-            webhook_url, webhook_token, webhook_successful, webhook_timestamp
-        ) SELECT
-            ?1 + ROW_NUMBER() OVER (ORDER BY idx),
-            enabled, on_boost, on_stream, on_sent, 0, 0,
-            amount, equality,
-            url, token, request_successful, request_timestamp
-        FROM webhooks
-        ORDER BY idx"#,
-        [pos],
-    )?;
-
-    conn.execute("ALTER TABLE webhooks RENAME TO webhooks_archive", [])?;
-
-    println!("Migrated webhooks to triggers.");
-    Ok(true)
-}
\ No newline at end of file
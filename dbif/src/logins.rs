@@ -0,0 +1,120 @@
+use rusqlite::{params, Connection};
+use std::error::Error;
+use serde::{Deserialize, Serialize};
+
+use crate::{connect_to_database, HydraError};
+
+//Login attempts older than this are removed as new ones are saved
+const LOGIN_ATTEMPT_KEEP_DAYS: i64 = 90;
+
+//How a login attempt turned out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoginResult {
+    Success,
+    Failed, // wrong username or password
+    Locked, // refused without checking because of too many failures
+}
+
+impl LoginResult {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "success" => Some(LoginResult::Success),
+            "failed" => Some(LoginResult::Failed),
+            "locked" => Some(LoginResult::Locked),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LoginResult::Success => "success",
+            LoginResult::Failed => "failed",
+            LoginResult::Locked => "locked",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LoginAttemptRecord {
+    pub index: u64,
+    pub time: i64,
+    pub ip: String,
+    pub username: Option<String>,
+    pub result: LoginResult,
+}
+
+//An audit log of logins, successful or not
+pub fn create_login_attempts_table(conn: &Connection) -> Result<bool, Box<dyn Error>> {
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS login_attempts (
+             idx integer primary key,
+             time integer not null,
+             ip text not null,
+             username text,
+             result text not null
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Login attempts table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database login_attempts table.".into())))
+        }
+    }
+
+    conn.execute("CREATE INDEX IF NOT EXISTS login_attempts_time_idx ON login_attempts (time)", [])?;
+
+    Ok(true)
+}
+
+//Save a login attempt and clear out old ones
+pub fn add_login_attempt_to_db(filepath: &str, ip: &str, username: Option<&str>, result: LoginResult) -> Result<u64, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let now = chrono::Utc::now().timestamp();
+
+    conn.execute(
+        "INSERT INTO login_attempts (time, ip, username, result) VALUES (?1, ?2, ?3, ?4)",
+        params![now, ip, username, result.name()],
+    )?;
+
+    let index = conn.last_insert_rowid() as u64;
+
+    conn.execute("DELETE FROM login_attempts WHERE time < ?1", params![now - LOGIN_ATTEMPT_KEEP_DAYS * 86400])?;
+
+    Ok(index)
+}
+
+//Get the latest login attempts, newest first, optionally only ones with the given result
+pub fn get_login_attempts_from_db(filepath: &str, result: Option<LoginResult>, limit: u64) -> Result<Vec<LoginAttemptRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT idx, time, ip, username, result FROM login_attempts
+         WHERE ?1 IS NULL OR result = ?1
+         ORDER BY idx DESC
+         LIMIT ?2"
+    )?;
+
+    let rows = stmt.query_map(params![result.map(|result| result.name()), limit], |row| {
+        let result: String = row.get(4)?;
+
+        Ok(LoginAttemptRecord {
+            index: row.get(0)?,
+            time: row.get(1)?,
+            ip: row.get(2)?,
+            username: row.get(3)?,
+            result: LoginResult::from_name(&result).unwrap_or(LoginResult::Failed),
+        })
+    })?;
+
+    let mut attempts = Vec::new();
+
+    for attempt in rows {
+        attempts.push(attempt?);
+    }
+
+    Ok(attempts)
+}
@@ -0,0 +1,163 @@
+use rusqlite::{Connection, params};
+use std::error::Error;
+use serde::{Deserialize, Serialize};
+use crate::{connect_to_database, HydraError};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MetadataProviderRecord {
+    pub index: u64,
+    pub enabled: bool,
+    pub name: String,
+    pub memo_pattern: String,
+    pub payload_path: Option<String>,
+    pub field_map: Option<String>,
+}
+
+pub fn create_metadata_providers_table(conn: &Connection) -> Result<bool, Box<dyn Error>> {
+    //Create the metadata providers table
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS metadata_providers (
+             idx integer primary key,
+             enabled integer not null,
+             name text not null,
+             memo_pattern text not null,
+             payload_path text,
+             field_map text
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Metadata providers table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database metadata_providers table.".into())))
+        }
+    }
+
+    Ok(true)
+}
+
+pub fn get_metadata_providers_from_db(filepath: &str) -> Result<Vec<MetadataProviderRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut results: Vec<MetadataProviderRecord> = Vec::new();
+
+    let mut stmt = conn.prepare_cached(
+        r#"SELECT
+            idx,
+            enabled,
+            name,
+            memo_pattern,
+            payload_path,
+            field_map
+        FROM
+            metadata_providers
+        ORDER BY
+            idx
+        "#
+    )?;
+
+    let rows = stmt.query_map([], |row| {
+        Ok(MetadataProviderRecord {
+            index: row.get(0)?,
+            enabled: row.get(1)?,
+            name: row.get(2)?,
+            memo_pattern: row.get(3)?,
+            payload_path: row.get(4).ok(),
+            field_map: row.get(5).ok(),
+        })
+    })?;
+
+    for row in rows {
+        results.push(row?);
+    }
+
+    Ok(results)
+}
+
+pub fn load_metadata_provider_from_db(filepath: &str, index: u64) -> Result<MetadataProviderRecord, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let mut stmt = conn.prepare_cached(
+        r#"SELECT
+            idx,
+            enabled,
+            name,
+            memo_pattern,
+            payload_path,
+            field_map
+        FROM
+            metadata_providers
+        WHERE
+            idx = ?1
+        "#
+    )?;
+
+    let result = stmt.query_row(params![index], |row| {
+        Ok(MetadataProviderRecord {
+            index: row.get(0)?,
+            enabled: row.get(1)?,
+            name: row.get(2)?,
+            memo_pattern: row.get(3)?,
+            payload_path: row.get(4).ok(),
+            field_map: row.get(5).ok(),
+        })
+    })?;
+
+    Ok(result)
+}
+
+pub fn save_metadata_provider_to_db(filepath: &str, provider: &MetadataProviderRecord) -> Result<u64, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let index = if provider.index > 0 {
+        Some(provider.index)
+    } else {
+        None
+    };
+
+    let mut stmt = conn.prepare_cached(
+        r#"INSERT INTO metadata_providers (
+            idx,
+            enabled,
+            name,
+            memo_pattern,
+            payload_path,
+            field_map
+        )
+        VALUES
+            (?1, ?2, ?3, ?4, ?5, ?6)
+        ON CONFLICT(idx) DO UPDATE SET
+            enabled = excluded.enabled,
+            name = excluded.name,
+            memo_pattern = excluded.memo_pattern,
+            payload_path = excluded.payload_path,
+            field_map = excluded.field_map
+        RETURNING idx
+        "#,
+    )?;
+
+    let params = params![
+        index,
+        provider.enabled,
+        provider.name,
+        provider.memo_pattern,
+        provider.payload_path,
+        provider.field_map,
+    ];
+
+    let idx = stmt.query_row(params, |row| {
+        let idx: u64 = row.get(0)?;
+        Ok(idx)
+    })?;
+
+    Ok(idx)
+}
+
+pub fn delete_metadata_provider_from_db(filepath: &str, index: u64) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    conn.execute(r#"DELETE FROM metadata_providers WHERE idx = ?1"#, params![index])?;
+
+    Ok(true)
+}
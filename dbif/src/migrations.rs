@@ -0,0 +1,526 @@
+use rusqlite::{Connection, OptionalExtension, params};
+use std::error::Error;
+use serde::{Deserialize, Serialize};
+use crate::{
+    connect_to_database, table_exists, HydraError,
+    create_boosts_table, create_node_info_table, create_sent_boosts_table, create_numerology_table,
+    create_settings_table, create_jwt_secret_table, create_triggers_table, create_metadata_providers_table,
+    create_nostr_tables, create_moderation_rules_table, create_search_index, create_annotation_tables, create_show_tables, retention::create_stream_rollups_table,
+    supporters::migrate_supporters, create_users_table, create_api_tokens_table, create_password_table, create_login_attempts_table, create_two_factor_tables,
+    create_share_link_tables, create_sessions_table, IMPORT_INDEX_START,
+};
+
+struct Migration {
+    version: i64,
+    description: &'static str,
+    up: fn(&Connection) -> Result<(), Box<dyn Error>>,
+}
+
+//Every schema change, oldest first. Never edit or reorder an existing entry - add a new one at the end.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Create boosts, node info, sent boosts, numerology, settings, jwt secret and triggers tables",
+        up: migrate_base_tables,
+    },
+    Migration {
+        version: 2,
+        description: "Add remote item, reply, wallet, memo and list type columns to boosts",
+        up: migrate_boost_columns,
+    },
+    Migration {
+        version: 3,
+        description: "Add nostr, hosted wallet, lightning invoice and metadata settings",
+        up: migrate_settings_columns,
+    },
+    Migration {
+        version: 4,
+        description: "Move numerology sound files to triggers",
+        up: migrate_numerology_sounds_to_triggers,
+    },
+    Migration {
+        version: 5,
+        description: "Move webhooks to triggers",
+        up: migrate_webhooks_to_triggers,
+    },
+    Migration {
+        version: 6,
+        description: "Create metadata providers table",
+        up: |conn| create_metadata_providers_table(conn).map(|_| ()),
+    },
+    Migration {
+        version: 7,
+        description: "Create nostr cache tables and relay setting",
+        up: migrate_nostr,
+    },
+    Migration {
+        version: 8,
+        description: "Add numerology filter to triggers",
+        up: migrate_trigger_numerology,
+    },
+    Migration {
+        version: 9,
+        description: "Create moderation rules table and hidden boost columns",
+        up: migrate_moderation,
+    },
+    Migration {
+        version: 10,
+        description: "Create full text search index over boosts and sent boosts",
+        up: |conn| create_search_index(conn).map(|_| ()),
+    },
+    Migration {
+        version: 11,
+        description: "Create stream rollups table",
+        up: |conn| create_stream_rollups_table(conn).map(|_| ()),
+    },
+    Migration {
+        version: 12,
+        description: "Create boost tags and annotations tables",
+        up: |conn| create_annotation_tables(conn).map(|_| ()),
+    },
+    Migration {
+        version: 13,
+        description: "Create show session tables",
+        up: |conn| create_show_tables(conn).map(|_| ()),
+    },
+    Migration {
+        version: 14,
+        description: "Create supporter tables and link received boosts to them",
+        up: migrate_supporters,
+    },
+    Migration {
+        version: 15,
+        description: "Create users table",
+        up: |conn| create_users_table(conn).map(|_| ()),
+    },
+    Migration {
+        version: 16,
+        description: "Create API tokens table",
+        up: |conn| create_api_tokens_table(conn).map(|_| ()),
+    },
+    Migration {
+        version: 17,
+        description: "Create password table",
+        up: |conn| create_password_table(conn).map(|_| ()),
+    },
+    Migration {
+        version: 18,
+        description: "Create login attempts table and add lockout triggers",
+        up: migrate_login_attempts,
+    },
+    Migration {
+        version: 19,
+        description: "Create two-factor tables",
+        up: |conn| create_two_factor_tables(conn).map(|_| ()),
+    },
+    Migration {
+        version: 20,
+        description: "Create share link tables",
+        up: |conn| create_share_link_tables(conn).map(|_| ()),
+    },
+    Migration {
+        version: 21,
+        description: "Create sessions table",
+        up: |conn| create_sessions_table(conn).map(|_| ()),
+    },
+    Migration {
+        version: 22,
+        description: "Add period to stream rollups so they can cover an hour",
+        up: migrate_rollup_period,
+    },
+    Migration {
+        version: 23,
+        description: "Add imported flag to boosts and sent boosts",
+        up: migrate_imported,
+    },
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MigrationRecord {
+    pub version: i64,
+    pub description: String,
+    pub applied_at: Option<i64>,
+}
+
+pub fn latest_schema_version() -> i64 {
+    MIGRATIONS.last().map(|migration| migration.version).unwrap_or(0)
+}
+
+fn create_schema_version_table(conn: &Connection) -> Result<bool, Box<dyn Error>> {
+    //Create the schema version table
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+             version integer primary key,
+             description text not null,
+             applied_at integer not null
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Schema version table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database schema_version table.".into())))
+        }
+    }
+
+    Ok(true)
+}
+
+pub(crate) fn get_schema_version(conn: &Connection) -> Result<i64, Box<dyn Error>> {
+    if !table_exists(conn, "schema_version")? {
+        return Ok(0);
+    }
+
+    let version: Option<i64> = conn
+        .query_row("SELECT MAX(version) FROM schema_version", [], |row| row.get(0))
+        .optional()?
+        .flatten();
+
+    Ok(version.unwrap_or(0))
+}
+
+//Returns the migrations that have been applied to the database, oldest first
+pub fn get_schema_history_from_db(filepath: &str) -> Result<Vec<MigrationRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    if !table_exists(&conn, "schema_version")? {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = conn.prepare_cached("SELECT version, description, applied_at FROM schema_version ORDER BY version")?;
+
+    let rows = stmt.query_map([], |row| {
+        Ok(MigrationRecord {
+            version: row.get(0)?,
+            description: row.get(1)?,
+            applied_at: row.get(2)?,
+        })
+    })?;
+
+    let mut results: Vec<MigrationRecord> = Vec::new();
+
+    for row in rows {
+        results.push(row?);
+    }
+
+    Ok(results)
+}
+
+//Checks that the database is safe to migrate before anything is touched
+fn check_before_migrating(conn: &Connection, current: i64) -> Result<(), Box<dyn Error>> {
+    let latest = latest_schema_version();
+
+    if MIGRATIONS.windows(2).any(|pair| pair[0].version >= pair[1].version) {
+        return Err(Box::new(HydraError("Database migrations are out of order.".into())));
+    }
+
+    if current > latest {
+        return Err(Box::new(HydraError(format!(
+            "Database schema version {} is newer than this version of Helipad supports ({}). Upgrade Helipad or restore a backup.",
+            current, latest
+        ))));
+    }
+
+    let integrity: String = conn.query_row("PRAGMA quick_check", [], |row| row.get(0))?;
+
+    if integrity != "ok" {
+        return Err(Box::new(HydraError(format!("Database integrity check failed: {}", integrity))));
+    }
+
+    Ok(())
+}
+
+//Copies the database next to the original before it's migrated
+fn backup_before_migrating(conn: &Connection, filepath: &str, current: i64) -> Result<String, Box<dyn Error>> {
+    let backup_path = format!("{}.v{}-{}.bak", filepath, current, chrono::Utc::now().format("%Y%m%d%H%M%S"));
+
+    conn.execute("VACUUM INTO ?1", params![backup_path])?;
+
+    Ok(backup_path)
+}
+
+//Brings the database schema up to date. In dry run mode the pending migrations are run and rolled back
+//so they can be checked without changing anything. Returns the pending migrations.
+pub fn migrate_database(filepath: &str, dry_run: bool) -> Result<Vec<MigrationRecord>, Box<dyn Error>> {
+    run_migrations(filepath, dry_run, true)
+}
+
+//Migrates without the backup first, for scratch copies of a database
+pub(crate) fn migrate_database_copy(filepath: &str) -> Result<Vec<MigrationRecord>, Box<dyn Error>> {
+    run_migrations(filepath, false, false)
+}
+
+fn run_migrations(filepath: &str, dry_run: bool, backup: bool) -> Result<Vec<MigrationRecord>, Box<dyn Error>> {
+    let mut conn = connect_to_database(false, filepath)?;
+
+    let current = get_schema_version(&conn)?;
+    check_before_migrating(&conn, current)?;
+
+    println!("Database schema version: {} (latest: {})", current, latest_schema_version());
+
+    let pending: Vec<&Migration> = MIGRATIONS.iter().filter(|migration| migration.version > current).collect();
+
+    let mut results: Vec<MigrationRecord> = pending.iter().map(|migration| MigrationRecord {
+        version: migration.version,
+        description: migration.description.to_string(),
+        applied_at: None,
+    }).collect();
+
+    if pending.is_empty() {
+        return Ok(results);
+    }
+
+    if dry_run {
+        let tx = conn.transaction()?;
+        create_schema_version_table(&tx)?;
+
+        for migration in &pending {
+            println!("Checking migration {}: {}", migration.version, migration.description);
+
+            if let Err(e) = (migration.up)(&tx) {
+                return Err(Box::new(HydraError(format!("Migration {} would fail: {}", migration.version, e))));
+            }
+        }
+
+        tx.rollback()?;
+        println!("Dry run complete. {} migration(s) pending, nothing was changed.", pending.len());
+
+        return Ok(results);
+    }
+
+    //Existing databases get a backup first. Brand new ones have nothing to lose.
+    if backup && table_exists(&conn, "boosts")? {
+        let backup_path = backup_before_migrating(&conn, filepath, current)?;
+        println!("Database backed up to: [{}]", backup_path);
+    }
+
+    create_schema_version_table(&conn)?;
+
+    for (migration, result) in pending.iter().zip(results.iter_mut()) {
+        println!("Applying migration {}: {}", migration.version, migration.description);
+
+        let applied_at = chrono::Utc::now().timestamp();
+        let tx = conn.transaction()?;
+
+        if let Err(e) = (migration.up)(&tx) {
+            //Dropping the transaction rolls the migration back
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError(format!("Migration {} failed and was rolled back.", migration.version))));
+        }
+
+        tx.execute(
+            "INSERT INTO schema_version (version, description, applied_at) VALUES (?1, ?2, ?3)",
+            params![migration.version, migration.description, applied_at],
+        )?;
+
+        tx.commit()?;
+        result.applied_at = Some(applied_at);
+    }
+
+    println!("Database schema is now at version {}.", latest_schema_version());
+
+    Ok(results)
+}
+
+fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool, Box<dyn Error>> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2",
+        params![table, column],
+        |row| row.get(0),
+    )?;
+
+    Ok(count > 0)
+}
+
+//Adds a column unless an older version of Helipad already added it
+fn add_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<bool, Box<dyn Error>> {
+    if column_exists(conn, table, column)? {
+        return Ok(false);
+    }
+
+    conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    println!("{} {} column added.", table, column);
+
+    Ok(true)
+}
+
+fn migrate_base_tables(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    create_boosts_table(conn)?;
+    create_node_info_table(conn)?;
+    create_sent_boosts_table(conn)?;
+    create_numerology_table(conn)?;
+    create_settings_table(conn)?;
+    create_jwt_secret_table(conn)?;
+    create_triggers_table(conn)?;
+
+    Ok(())
+}
+
+fn migrate_boost_columns(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    add_column(conn, "boosts", "remote_podcast", "text")?;
+    add_column(conn, "boosts", "remote_episode", "text")?;
+    add_column(conn, "boosts", "reply_sent", "integer")?;
+    add_column(conn, "boosts", "custom_key", "integer")?;
+    add_column(conn, "boosts", "custom_value", "text")?;
+    add_column(conn, "boosts", "memo", "text")?;
+
+    if add_column(conn, "boosts", "list_type", "integer")? {
+        conn.execute("UPDATE boosts SET list_type = 1 WHERE action IN (2, 4, 5)", [])?;
+        conn.execute("UPDATE boosts SET list_type = 2 WHERE action NOT IN (2, 4, 5)", [])?;
+    }
+
+    Ok(())
+}
+
+fn migrate_settings_columns(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    add_column(conn, "settings", "resolve_nostr_refs", "integer DEFAULT 0")?;
+    add_column(conn, "settings", "show_hosted_wallet_ids", "integer DEFAULT 0")?;
+    add_column(conn, "settings", "show_lightning_invoices", "integer DEFAULT 1")?;
+    add_column(conn, "settings", "fetch_metadata", "integer DEFAULT 1")?;
+    add_column(conn, "settings", "metadata_whitelist", "text")?;
+
+    Ok(())
+}
+
+fn migrate_numerology_sounds_to_triggers(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    // Check if sound_file column still exists
+    if !column_exists(conn, "numerology", "sound_file")? {
+        return Ok(());
+    }
+
+    let pos: i64 = conn
+        .query_row("SELECT COALESCE(MAX(position), 0) FROM triggers", [], |row| row.get(0))
+        .unwrap_or(0);
+
+    println!("Migrating numerology sound files to triggers...");
+
+    conn.execute(
+        r#"INSERT INTO triggers (
+            position, enabled,on_boost, on_stream, on_auto, on_sent, on_invoice,
+            amount, amount_equality, sound_file, sound_name
+        )
+        SELECT
+            ?1 + ROW_NUMBER() OVER (ORDER BY position),
+            1, 1, 1, 1, 1, 1,
+            amount, equality, sound_file, sound_file
+        FROM numerology
+        WHERE sound_file IS NOT NULL AND sound_file != ''
+        ORDER BY idx"#,
+        [pos],
+    )?;
+
+    conn.execute("ALTER TABLE numerology DROP COLUMN sound_file", [])?;
+
+    println!("Migrated numerology sound files to triggers.");
+    Ok(())
+}
+
+fn migrate_webhooks_to_triggers(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    if !table_exists(conn, "webhooks")? {
+        return Ok(());
+    }
+
+    let pos: i64 = conn
+        .query_row("SELECT COALESCE(MAX(position), 0) FROM triggers", [], |row| row.get(0))
+        .unwrap_or(0);
+
+    println!("Migrating webhooks to triggers...");
+
+    conn.execute(
+        r#"INSERT INTO triggers (
+            position,
+            enabled, on_boost, on_stream, on_sent, on_auto, on_invoice,
+            amount, amount_equality,
+            webhook_url, webhook_token, webhook_successful, webhook_timestamp
+        ) SELECT
+            ?1 + ROW_NUMBER() OVER (ORDER BY idx),
+            enabled, on_boost, on_stream, on_sent, 0, 0,
+            amount, equality,
+            url, token, request_successful, request_timestamp
+        FROM webhooks
+        ORDER BY idx"#,
+        [pos],
+    )?;
+
+    conn.execute("ALTER TABLE webhooks RENAME TO webhooks_archive", [])?;
+
+    println!("Migrated webhooks to triggers.");
+    Ok(())
+}
+
+fn migrate_nostr(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    create_nostr_tables(conn)?;
+    add_column(conn, "settings", "nostr_relays", "text")?;
+
+    Ok(())
+}
+
+fn migrate_trigger_numerology(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    add_column(conn, "triggers", "numerology", "text")?;
+    add_column(conn, "triggers", "numerology_equality", "text")?;
+
+    Ok(())
+}
+
+fn migrate_moderation(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    create_moderation_rules_table(conn)?;
+    add_column(conn, "boosts", "hidden", "integer DEFAULT 0")?;
+    add_column(conn, "boosts", "quarantined", "integer DEFAULT 0")?;
+
+    Ok(())
+}
+
+fn migrate_login_attempts(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    create_login_attempts_table(conn)?;
+    add_column(conn, "triggers", "on_lockout", "integer DEFAULT 0")?;
+
+    Ok(())
+}
+
+//The period has to be part of the unique key, or an hourly rollup starting at midnight would add to the
+//daily rollup for that day. SQLite can't change a table's constraints, so the table is copied.
+fn migrate_rollup_period(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    if column_exists(conn, "stream_rollups", "period")? {
+        return Ok(());
+    }
+
+    conn.execute_batch(
+        "CREATE TABLE stream_rollups_period (
+             idx integer primary key,
+             day integer not null,
+             period integer not null,
+             podcast text not null,
+             episode text not null,
+             sender text not null,
+             app text not null,
+             stream_count integer not null,
+             value_msat integer not null,
+             value_msat_total integer not null,
+             UNIQUE (day, period, podcast, episode, sender, app)
+         );
+
+         INSERT INTO stream_rollups_period (idx, day, period, podcast, episode, sender, app, stream_count, value_msat, value_msat_total)
+         SELECT idx, day, 86400, podcast, episode, sender, app, stream_count, value_msat, value_msat_total FROM stream_rollups;
+
+         DROP TABLE stream_rollups;
+         ALTER TABLE stream_rollups_period RENAME TO stream_rollups;"
+    )?;
+
+    println!("stream_rollups period column added.");
+
+    Ok(())
+}
+
+fn migrate_imported(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    for table in ["boosts", "sent_boosts"] {
+        if add_column(conn, table, "imported", "integer not null DEFAULT 0")? {
+            conn.execute(&format!("UPDATE {} SET imported = 1 WHERE idx >= ?1", table), params![IMPORT_INDEX_START])?;
+        }
+
+        conn.execute(&format!("CREATE INDEX IF NOT EXISTS {}_imported_time ON {} (imported, time)", table, table), [])?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,163 @@
+use rusqlite::{Connection, params};
+use std::error::Error;
+use serde::{Deserialize, Serialize};
+use crate::{connect_to_database, HydraError};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ModerationRuleRecord {
+    pub index: u64,
+    pub enabled: bool,
+    pub field: String, // sender, app, pubkey or message
+    pub pattern: String,
+    pub is_regex: bool,
+    pub action: String, // hide or quarantine
+}
+
+pub fn create_moderation_rules_table(conn: &Connection) -> Result<bool, Box<dyn Error>> {
+    //Create the moderation rules table
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS moderation_rules (
+             idx integer primary key,
+             enabled integer not null,
+             field text not null,
+             pattern text not null,
+             is_regex integer not null,
+             action text not null
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Moderation rules table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database moderation_rules table.".into())))
+        }
+    }
+
+    Ok(true)
+}
+
+pub fn get_moderation_rules_from_db(filepath: &str) -> Result<Vec<ModerationRuleRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut results: Vec<ModerationRuleRecord> = Vec::new();
+
+    let mut stmt = conn.prepare_cached(
+        r#"SELECT
+            idx,
+            enabled,
+            field,
+            pattern,
+            is_regex,
+            action
+        FROM
+            moderation_rules
+        ORDER BY
+            idx
+        "#
+    )?;
+
+    let rows = stmt.query_map([], |row| {
+        Ok(ModerationRuleRecord {
+            index: row.get(0)?,
+            enabled: row.get(1)?,
+            field: row.get(2)?,
+            pattern: row.get(3)?,
+            is_regex: row.get(4)?,
+            action: row.get(5)?,
+        })
+    })?;
+
+    for row in rows {
+        results.push(row?);
+    }
+
+    Ok(results)
+}
+
+pub fn load_moderation_rule_from_db(filepath: &str, index: u64) -> Result<ModerationRuleRecord, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let mut stmt = conn.prepare_cached(
+        r#"SELECT
+            idx,
+            enabled,
+            field,
+            pattern,
+            is_regex,
+            action
+        FROM
+            moderation_rules
+        WHERE
+            idx = ?1
+        "#
+    )?;
+
+    let result = stmt.query_row(params![index], |row| {
+        Ok(ModerationRuleRecord {
+            index: row.get(0)?,
+            enabled: row.get(1)?,
+            field: row.get(2)?,
+            pattern: row.get(3)?,
+            is_regex: row.get(4)?,
+            action: row.get(5)?,
+        })
+    })?;
+
+    Ok(result)
+}
+
+pub fn save_moderation_rule_to_db(filepath: &str, rule: &ModerationRuleRecord) -> Result<u64, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let index = if rule.index > 0 {
+        Some(rule.index)
+    } else {
+        None
+    };
+
+    let mut stmt = conn.prepare_cached(
+        r#"INSERT INTO moderation_rules (
+            idx,
+            enabled,
+            field,
+            pattern,
+            is_regex,
+            action
+        )
+        VALUES
+            (?1, ?2, ?3, ?4, ?5, ?6)
+        ON CONFLICT(idx) DO UPDATE SET
+            enabled = excluded.enabled,
+            field = excluded.field,
+            pattern = excluded.pattern,
+            is_regex = excluded.is_regex,
+            action = excluded.action
+        RETURNING idx
+        "#,
+    )?;
+
+    let params = params![
+        index,
+        rule.enabled,
+        rule.field,
+        rule.pattern,
+        rule.is_regex,
+        rule.action,
+    ];
+
+    let idx = stmt.query_row(params, |row| {
+        let idx: u64 = row.get(0)?;
+        Ok(idx)
+    })?;
+
+    Ok(idx)
+}
+
+pub fn delete_moderation_rule_from_db(filepath: &str, index: u64) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    conn.execute(r#"DELETE FROM moderation_rules WHERE idx = ?1"#, params![index])?;
+
+    Ok(true)
+}
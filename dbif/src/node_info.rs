@@ -68,7 +68,7 @@ pub fn get_node_info_from_db(filepath: &str) -> Result<NodeInfoRecord, Box<dyn E
     let conn = connect_to_database(false, filepath)?;
 
     //Prepare and execute the query
-    let mut stmt = conn.prepare("
+    let mut stmt = conn.prepare_cached("
         SELECT
             lnd_alias,
             node_pubkey,
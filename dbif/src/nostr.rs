@@ -0,0 +1,176 @@
+use rusqlite::{Connection, OptionalExtension, params};
+use std::error::Error;
+use serde::{Deserialize, Serialize};
+use crate::{connect_to_database, HydraError};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NostrReference {
+    pub text: String, // reference as it appears in the message (nostr:npub1...)
+    pub kind: String, // "profile" or "note"
+    pub pubkey: Option<String>,
+    pub note_id: Option<String>,
+    pub name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NostrProfileRecord {
+    pub pubkey: String,
+    pub name: Option<String>,
+    pub display_name: Option<String>,
+    pub nip05: Option<String>,
+    pub picture: Option<String>,
+    pub fetched_at: i64,
+}
+
+impl NostrProfileRecord {
+    // Returns the best name to show for the profile
+    pub fn best_name(&self) -> Option<String> {
+        self.display_name.clone()
+            .filter(|name| !name.is_empty())
+            .or_else(|| self.name.clone().filter(|name| !name.is_empty()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NostrNoteRecord {
+    pub note_id: String,
+    pub pubkey: Option<String>,
+    pub content: Option<String>,
+    pub fetched_at: i64,
+}
+
+pub fn create_nostr_tables(conn: &Connection) -> Result<bool, Box<dyn Error>> {
+    //Create the nostr profile cache table
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS nostr_profiles (
+             pubkey text primary key,
+             name text,
+             display_name text,
+             nip05 text,
+             picture text,
+             fetched_at integer not null
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Nostr profiles table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database nostr_profiles table.".into())))
+        }
+    }
+
+    //Create the nostr note cache table
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS nostr_notes (
+             note_id text primary key,
+             pubkey text,
+             content text,
+             fetched_at integer not null
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Nostr notes table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database nostr_notes table.".into())))
+        }
+    }
+
+    Ok(true)
+}
+
+pub fn get_nostr_profile_from_db(filepath: &str, pubkey: &str) -> Result<Option<NostrProfileRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let profile = conn.query_row(
+        "SELECT pubkey, name, display_name, nip05, picture, fetched_at FROM nostr_profiles WHERE pubkey = ?1",
+        params![pubkey],
+        |row| {
+            Ok(NostrProfileRecord {
+                pubkey: row.get(0)?,
+                name: row.get(1)?,
+                display_name: row.get(2)?,
+                nip05: row.get(3)?,
+                picture: row.get(4)?,
+                fetched_at: row.get(5)?,
+            })
+        },
+    ).optional()?;
+
+    Ok(profile)
+}
+
+pub fn save_nostr_profile_to_db(filepath: &str, profile: &NostrProfileRecord) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    conn.execute(
+        "INSERT INTO nostr_profiles
+            (pubkey, name, display_name, nip05, picture, fetched_at)
+        VALUES
+            (?1, ?2, ?3, ?4, ?5, ?6)
+        ON CONFLICT(pubkey) DO UPDATE SET
+            name = excluded.name,
+            display_name = excluded.display_name,
+            nip05 = excluded.nip05,
+            picture = excluded.picture,
+            fetched_at = excluded.fetched_at
+        ",
+        params![
+            profile.pubkey,
+            profile.name,
+            profile.display_name,
+            profile.nip05,
+            profile.picture,
+            profile.fetched_at,
+        ]
+    )?;
+
+    Ok(true)
+}
+
+pub fn get_nostr_note_from_db(filepath: &str, note_id: &str) -> Result<Option<NostrNoteRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let note = conn.query_row(
+        "SELECT note_id, pubkey, content, fetched_at FROM nostr_notes WHERE note_id = ?1",
+        params![note_id],
+        |row| {
+            Ok(NostrNoteRecord {
+                note_id: row.get(0)?,
+                pubkey: row.get(1)?,
+                content: row.get(2)?,
+                fetched_at: row.get(3)?,
+            })
+        },
+    ).optional()?;
+
+    Ok(note)
+}
+
+pub fn save_nostr_note_to_db(filepath: &str, note: &NostrNoteRecord) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    conn.execute(
+        "INSERT INTO nostr_notes
+            (note_id, pubkey, content, fetched_at)
+        VALUES
+            (?1, ?2, ?3, ?4)
+        ON CONFLICT(note_id) DO UPDATE SET
+            pubkey = excluded.pubkey,
+            content = excluded.content,
+            fetched_at = excluded.fetched_at
+        ",
+        params![
+            note.note_id,
+            note.pubkey,
+            note.content,
+            note.fetched_at,
+        ]
+    )?;
+
+    Ok(true)
+}
@@ -13,6 +13,12 @@ pub struct NumerologyRecord {
     pub description: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NumerologyMatch {
+    pub emoji: Option<String>,
+    pub description: Option<String>,
+}
+
 pub fn create_numerology_table(conn: &Connection) -> Result<bool, Box<dyn Error>> {
     //Create the numerology table
     let numerology_exists = table_exists(&conn, "numerology")?;
@@ -48,7 +54,7 @@ pub fn get_numerology_from_db(filepath: &str) -> Result<Vec<NumerologyRecord>, B
     let conn = connect_to_database(false, filepath)?;
     let mut results: Vec<NumerologyRecord> = Vec::new();
 
-    let mut stmt = conn.prepare(
+    let mut stmt = conn.prepare_cached(
         r#"SELECT
             idx,
             position,
@@ -83,7 +89,7 @@ pub fn get_numerology_from_db(filepath: &str) -> Result<Vec<NumerologyRecord>, B
 pub fn load_numerology_from_db(filepath: &str, index: u64) -> Result<NumerologyRecord, Box<dyn Error>> {
     let conn = connect_to_database(false, filepath)?;
 
-    let mut stmt = conn.prepare(
+    let mut stmt = conn.prepare_cached(
         r#"SELECT
             idx,
             position,
@@ -123,7 +129,7 @@ pub fn save_numerology_to_db(filepath: &str, numero: &NumerologyRecord) -> Resul
 
     set_numerology_position_in_db(filepath, numero.index, numero.position)?;
 
-    let mut stmt = conn.prepare(
+    let mut stmt = conn.prepare_cached(
         r#"INSERT INTO numerology (
             idx,
             position,
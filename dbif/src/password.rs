@@ -0,0 +1,71 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use std::error::Error;
+use serde::{Deserialize, Serialize};
+
+use crate::{connect_to_database, HydraError};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PasswordRecord {
+    pub hash: String,
+    pub from_config: bool, // false once it's been changed in Helipad or with reset-password
+    pub updated: i64,
+}
+
+//The shared password, as an Argon2 hash. There's only ever one row.
+pub fn create_password_table(conn: &Connection) -> Result<bool, Box<dyn Error>> {
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS password (
+             idx integer primary key,
+             hash text not null,
+             from_config integer not null default 0,
+             updated integer not null
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Password table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database password table.".into())))
+        }
+    }
+
+    Ok(true)
+}
+
+pub fn get_password_from_db(filepath: &str) -> Result<Option<PasswordRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let password = conn.query_row(
+        "SELECT hash, from_config, updated FROM password WHERE idx = 1",
+        [],
+        |row| Ok(PasswordRecord {
+            hash: row.get(0)?,
+            from_config: row.get(1)?,
+            updated: row.get(2)?,
+        }),
+    ).optional()?;
+
+    Ok(password)
+}
+
+//Save the shared password hash, replacing any there already
+pub fn set_password_in_db(filepath: &str, hash: &str, from_config: bool) -> Result<(), Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO password (idx, hash, from_config, updated) VALUES (1, ?1, ?2, ?3)",
+        params![hash, from_config, chrono::Utc::now().timestamp()],
+    )?;
+
+    Ok(())
+}
+
+//Remove the shared password, so Helipad doesn't need a login unless there are user accounts
+pub fn delete_password_from_db(filepath: &str) -> Result<(), Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    conn.execute("DELETE FROM password WHERE idx = 1", [])?;
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+//How long a connection waits on a locked database before giving up
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+//Number of prepared statements each connection keeps around for reuse
+const STATEMENT_CACHE_CAPACITY: usize = 64;
+
+//Maximum number of open connections per database file
+const MAX_CONNECTIONS: u32 = 8;
+
+pub type DbConnection = r2d2::PooledConnection<SqliteConnectionManager>;
+
+//Opens and configures connections to a single SQLite database file for the pool
+#[derive(Debug)]
+pub struct SqliteConnectionManager {
+    filepath: String,
+}
+
+impl r2d2::ManageConnection for SqliteConnectionManager {
+    type Connection = Connection;
+    type Error = rusqlite::Error;
+
+    fn connect(&self) -> Result<Connection, rusqlite::Error> {
+        let conn = Connection::open(&self.filepath)?;
+
+        //WAL lets the web handlers read while the poller is writing
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+
+        Ok(conn)
+    }
+
+    fn is_valid(&self, conn: &mut Connection) -> Result<(), rusqlite::Error> {
+        conn.execute_batch("")
+    }
+
+    //A connection handed back still inside a transaction (a BEGIN whose COMMIT never ran) would keep holding
+    //its locks and drag the next caller's writes into it, so it's thrown away instead
+    fn has_broken(&self, conn: &mut Connection) -> bool {
+        !conn.is_autocommit()
+    }
+}
+
+type Pool = r2d2::Pool<SqliteConnectionManager>;
+
+static POOLS: OnceLock<Mutex<HashMap<String, Pool>>> = OnceLock::new();
+
+//Returns the connection pool for the database file, creating it on first use
+fn get_pool(filepath: &str) -> Result<Pool, Box<dyn Error>> {
+    let mut pools = POOLS.get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .map_err(|e| format!("Database pool lock poisoned: {}", e))?;
+
+    if let Some(pool) = pools.get(filepath) {
+        return Ok(pool.clone());
+    }
+
+    let manager = SqliteConnectionManager {
+        filepath: filepath.to_string(),
+    };
+
+    let pool = r2d2::Pool::builder()
+        .max_size(MAX_CONNECTIONS)
+        .min_idle(Some(1))
+        .build(manager)?;
+
+    pools.insert(filepath.to_string(), pool.clone());
+
+    Ok(pool)
+}
+
+//Checks out a pooled connection to the database file
+pub fn get_pooled_connection(filepath: &str) -> Result<DbConnection, Box<dyn Error>> {
+    Ok(get_pool(filepath)?.get()?)
+}
+
+//Closes the pool for a database file that's no longer needed, like a scratch copy about to be deleted
+pub(crate) fn close_pool(filepath: &str) {
+    if let Ok(mut pools) = POOLS.get_or_init(|| Mutex::new(HashMap::new())).lock() {
+        pools.remove(filepath);
+    }
+}
+
+//Runs a blocking database call on tokio's blocking thread pool so async handlers don't stall the runtime
+pub async fn run_blocking<T, F>(f: F) -> Result<T, Box<dyn Error + Send + Sync>>
+where
+    F: FnOnce() -> Result<T, Box<dyn Error>> + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(move || f().map_err(|e| e.to_string())).await {
+        Ok(Ok(result)) => Ok(result),
+        Ok(Err(e)) => Err(e.into()),
+        Err(e) => Err(format!("Database task failed: {}", e).into()),
+    }
+}
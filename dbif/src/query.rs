@@ -0,0 +1,410 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use std::error::Error;
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    connect_to_database,
+    bind_query_param,
+    HydraError,
+    BoostRecord,
+    BoostFilters,
+    ListType,
+    BOOST_COLUMNS,
+    SENT_BOOST_COLUMNS,
+    boost_from_row,
+    sent_boost_from_row,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BoostSort {
+    #[default]
+    Newest, // latest first
+    Oldest, // earliest first
+    Largest, // highest total amount first
+    Smallest, // lowest total amount first
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BoostQuery {
+    pub list: Option<ListType>, // boosts, streams or sent (all received if not set)
+    pub filters: BoostFilters,
+    pub sort: BoostSort,
+    pub cursor: Option<String>,
+    pub limit: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BoostPage {
+    pub boosts: Vec<BoostRecord>,
+    pub next_cursor: Option<String>,
+}
+
+//SQL conditions and their named parameters for a set of boost filters
+#[derive(Debug, Default)]
+pub(crate) struct FilterClause {
+    pub conditions: Vec<String>,
+    pub bindings: Vec<(String, String)>,
+}
+
+impl FilterClause {
+    fn add(&mut self, condition: &str, name: &str, value: String) {
+        self.conditions.push(condition.to_string());
+        self.bindings.push((name.to_string(), value));
+    }
+
+    //Joins the conditions for a WHERE clause
+    pub fn to_sql(&self) -> String {
+        if self.conditions.is_empty() {
+            "1".to_string()
+        } else {
+            self.conditions.join(" AND ")
+        }
+    }
+}
+
+//Sats filters compare against msat columns, capped at the largest integer SQLite holds so huge amounts can't overflow
+fn sats_to_msat(sats: u64) -> i64 {
+    sats.saturating_mul(1000).min(i64::MAX as u64) as i64
+}
+
+//Builds the conditions for the filters. The received (boosts) and sent (sent_boosts) tables
+//keep some things in different columns, so `sent` picks which one to build for.
+pub(crate) fn build_filter_clause(filters: &BoostFilters, sent: bool) -> FilterClause {
+    let mut clause = FilterClause::default();
+
+    if !filters.actions.is_empty() {
+        let names: Vec<String> = (0..filters.actions.len()).map(|idx| format!(":action{}", idx)).collect();
+        clause.conditions.push(format!("action IN ({})", names.join(", ")));
+
+        for (name, action) in names.into_iter().zip(filters.actions.iter()) {
+            clause.bindings.push((name, (*action as u8).to_string()));
+        }
+    }
+
+    if !sent {
+        if filters.only_hidden {
+            clause.conditions.push("hidden = 1".to_string());
+        }
+        else if !filters.include_hidden {
+            clause.conditions.push("COALESCE(hidden, 0) = 0".to_string());
+        }
+    }
+
+    if let Some(podcast) = &filters.podcast {
+        clause.add("podcast = :podcast", ":podcast", podcast.clone());
+    }
+
+    if let Some(sender) = &filters.sender {
+        clause.add("sender = :sender COLLATE NOCASE", ":sender", sender.clone());
+    }
+
+    if let Some(app) = &filters.app {
+        clause.add("app = :app COLLATE NOCASE", ":app", app.clone());
+    }
+
+    if let Some(episode) = &filters.episode {
+        clause.add("episode = :episode", ":episode", episode.clone());
+    }
+
+    if let Some(start_date) = filters.start_date.filter(|date| *date > 0) {
+        clause.add("time >= CAST(:start_date AS INTEGER)", ":start_date", start_date.to_string());
+    }
+
+    if let Some(end_date) = filters.end_date.filter(|date| *date > 0) {
+        clause.add("time <= CAST(:end_date AS INTEGER)", ":end_date", end_date.to_string());
+    }
+
+    if let Some(min_sats) = filters.min_sats {
+        clause.add("COALESCE(value_msat_total, 0) >= CAST(:min_msat AS INTEGER)", ":min_msat", sats_to_msat(min_sats).to_string());
+    }
+
+    if let Some(max_sats) = filters.max_sats {
+        clause.add("COALESCE(value_msat_total, 0) <= CAST(:max_msat AS INTEGER)", ":max_msat", sats_to_msat(max_sats).to_string());
+    }
+
+    if let Some(custom_key) = filters.custom_key {
+        let condition = if sent {
+            "payment_custom_key = CAST(:custom_key AS INTEGER)"
+        } else {
+            "custom_key = CAST(:custom_key AS INTEGER)"
+        };
+
+        clause.add(condition, ":custom_key", custom_key.to_string());
+    }
+
+    //Received boosts track whether we replied, sent boosts track whether they were a reply
+    if let Some(replied) = filters.replied {
+        let condition = match (sent, replied) {
+            (false, true) => "COALESCE(reply_sent, 0) = 1",
+            (false, false) => "COALESCE(reply_sent, 0) = 0",
+            (true, true) => "reply_to_idx IS NOT NULL",
+            (true, false) => "reply_to_idx IS NULL",
+        };
+
+        clause.conditions.push(condition.to_string());
+    }
+
+    if let Some(has_reply_address) = filters.has_reply_address {
+        let reply_address = "COALESCE(CASE WHEN json_valid(tlv) THEN json_extract(tlv, '$.reply_address') END, '') != ''";
+
+        if has_reply_address {
+            clause.conditions.push(reply_address.to_string());
+        } else {
+            clause.conditions.push(format!("NOT ({})", reply_address));
+        }
+    }
+
+    //Tags and pins are kept apart from the boosts, keyed by which table the boost is in
+    if let Some(tag) = &filters.tag {
+        clause.add(
+            &format!("EXISTS (SELECT 1 FROM boost_tags WHERE boost_tags.sent = {} AND boost_tags.boost_idx = idx AND boost_tags.tag = :tag)", sent as u8),
+            ":tag",
+            tag.clone(),
+        );
+    }
+
+    if let Some(pinned) = filters.pinned {
+        let condition = format!(
+            "{}EXISTS (SELECT 1 FROM boost_annotations WHERE boost_annotations.sent = {} AND boost_annotations.boost_idx = idx AND boost_annotations.pinned = 1)",
+            if pinned { "" } else { "NOT " },
+            sent as u8
+        );
+
+        clause.conditions.push(condition);
+    }
+
+    //Only received boosts are linked to supporters, so no sent boosts match
+    if let Some(supporter) = filters.supporter {
+        if sent {
+            clause.conditions.push("0".to_string());
+        } else {
+            clause.add(
+                "idx IN (SELECT boost_idx FROM supporter_boosts WHERE supporter_idx = CAST(:supporter AS INTEGER))",
+                ":supporter",
+                supporter.to_string(),
+            );
+        }
+    }
+
+    clause
+}
+
+//Cursors are the sort value and index of the last boost on the page
+fn make_cursor(sort: BoostSort, boost: &BoostRecord) -> String {
+    match sort {
+        BoostSort::Newest | BoostSort::Oldest => format!("{}:{}", boost.time, boost.index),
+        BoostSort::Largest | BoostSort::Smallest => format!("{}:{}", boost.value_msat_total, boost.index),
+    }
+}
+
+fn parse_cursor(cursor: &str) -> Result<(i64, u64), Box<dyn Error>> {
+    let invalid = || HydraError(format!("Invalid cursor: [{}].", cursor));
+
+    let (value, index) = cursor.split_once(':').ok_or_else(invalid)?;
+    Ok((value.parse().map_err(|_| invalid())?, index.parse().map_err(|_| invalid())?))
+}
+
+//Get a page of received or sent boosts matching the query. Pass the returned next_cursor back in to get the following page.
+pub fn query_boosts_in_db(filepath: &str, query: &BoostQuery, escape_html: bool) -> Result<BoostPage, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let sent = query.list == Some(ListType::Sent);
+    let mut clause = build_filter_clause(&query.filters, sent);
+
+    match query.list {
+        Some(ListType::Boost) | Some(ListType::Stream) => {
+            clause.conditions.push(format!("list_type = {}", query.list.unwrap() as u8));
+        }
+        _ => {}
+    }
+
+    if let Some(cursor) = &query.cursor {
+        let (value, index) = parse_cursor(cursor)?;
+
+        let condition = match query.sort {
+            BoostSort::Newest => "(COALESCE(time, 0) < CAST(:cursor_value AS INTEGER) OR (COALESCE(time, 0) = CAST(:cursor_value AS INTEGER) AND idx < CAST(:cursor_idx AS INTEGER)))",
+            BoostSort::Oldest => "(COALESCE(time, 0) > CAST(:cursor_value AS INTEGER) OR (COALESCE(time, 0) = CAST(:cursor_value AS INTEGER) AND idx > CAST(:cursor_idx AS INTEGER)))",
+            BoostSort::Largest => "(COALESCE(value_msat_total, 0) < CAST(:cursor_value AS INTEGER) OR (COALESCE(value_msat_total, 0) = CAST(:cursor_value AS INTEGER) AND idx < CAST(:cursor_idx AS INTEGER)))",
+            BoostSort::Smallest => "(COALESCE(value_msat_total, 0) > CAST(:cursor_value AS INTEGER) OR (COALESCE(value_msat_total, 0) = CAST(:cursor_value AS INTEGER) AND idx > CAST(:cursor_idx AS INTEGER)))",
+        };
+
+        clause.conditions.push(condition.to_string());
+        clause.bindings.push((":cursor_idx".to_string(), index.to_string()));
+        clause.bindings.push((":cursor_value".to_string(), value.to_string()));
+    }
+
+    //By time rather than index so imported boosts fall in with the rest
+    let order = match query.sort {
+        BoostSort::Newest => "COALESCE(time, 0) DESC, idx DESC",
+        BoostSort::Oldest => "COALESCE(time, 0) ASC, idx ASC",
+        BoostSort::Largest => "COALESCE(value_msat_total, 0) DESC, idx DESC",
+        BoostSort::Smallest => "COALESCE(value_msat_total, 0) ASC, idx ASC",
+    };
+
+    let (table, columns) = if sent {
+        ("sent_boosts", SENT_BOOST_COLUMNS)
+    } else {
+        ("boosts", BOOST_COLUMNS)
+    };
+
+    //Fetch one extra to know if there's another page
+    let limit = query.limit.max(1);
+    clause.bindings.push((":limit".to_string(), (limit + 1).to_string()));
+
+    let sqltxt = format!(
+        "SELECT
+            {}
+        FROM
+            {}
+        WHERE
+            {}
+        ORDER BY
+            {}
+        LIMIT CAST(:limit AS INTEGER)
+        ",
+        columns,
+        table,
+        clause.to_sql(),
+        order
+    );
+
+    //Prepare and execute the query
+    let mut stmt = conn.prepare_cached(sqltxt.as_str())?;
+
+    let bindings: HashMap<&str, &str> = clause.bindings.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+
+    for (name, value) in &bindings {
+        bind_query_param(&mut stmt, name, value)?;
+    }
+
+    let mut rows = stmt.raw_query();
+    let mut boosts: Vec<BoostRecord> = Vec::new();
+
+    while let Some(row) = rows.next()? {
+        let boost = if sent {
+            sent_boost_from_row(row)?
+        } else {
+            boost_from_row(row)?
+        };
+
+        boosts.push(boost);
+    }
+
+    let next_cursor = if boosts.len() as u64 > limit {
+        boosts.truncate(limit as usize);
+        boosts.last().map(|boost| make_cursor(query.sort, boost))
+    } else {
+        None
+    };
+
+    if escape_html {
+        boosts = boosts.into_iter().map(BoostRecord::escaped_for_html).collect();
+    }
+
+    Ok(BoostPage {
+        boosts,
+        next_cursor,
+    })
+}
+
+//Get a page of the boost or sent boost list starting at an index. This node's own boosts page by index since that's
+//the order LND hands them out in. Imported boosts have no place in that order, so they follow on after them by time.
+//Paging forwards for new boosts never returns imported ones.
+pub(crate) fn get_list_page(conn: &Connection, sent: bool, clause: &FilterClause, index: u64, max: u64, older: bool) -> Result<Vec<BoostRecord>, Box<dyn Error>> {
+    let (table, columns) = if sent {
+        ("sent_boosts", SENT_BOOST_COLUMNS)
+    } else {
+        ("boosts", BOOST_COLUMNS)
+    };
+
+    //Where the page starts decides which parts of the list it covers
+    let start: Option<(bool, i64)> = conn.query_row(
+        &format!("SELECT imported, COALESCE(time, 0) FROM {} WHERE idx = ?1", table),
+        params![index],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).optional()?;
+
+    let parts: &[(&str, &str)] = match (older, start) {
+        (false, _) => &[("imported = 0 AND idx >= :idx", "idx DESC")],
+        (true, Some((true, _))) => &[(
+            "imported = 1 AND (COALESCE(time, 0) < :time OR (COALESCE(time, 0) = :time AND idx <= :idx))",
+            "COALESCE(time, 0) DESC, idx DESC",
+        )],
+        (true, _) => &[
+            ("imported = 0 AND idx <= :idx", "idx DESC"),
+            ("imported = 1", "COALESCE(time, 0) DESC, idx DESC"),
+        ],
+    };
+
+    let strindex = index.to_string();
+    let strtime = start.map(|(_, time)| time).unwrap_or_default().to_string();
+    let mut boosts: Vec<BoostRecord> = Vec::new();
+
+    for (condition, order) in parts {
+        let remaining = max.saturating_sub(boosts.len() as u64);
+
+        if max > 0 && remaining == 0 {
+            break;
+        }
+
+        let mut bindings: HashMap<&str, &str> = clause.bindings.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+        let strmax = remaining.to_string();
+
+        if condition.contains(":idx") {
+            bindings.insert(":idx", &strindex);
+        }
+
+        if condition.contains(":time") {
+            bindings.insert(":time", &strtime);
+        }
+
+        let limit = if max > 0 {
+            bindings.insert(":max", &strmax);
+            "LIMIT :max"
+        } else {
+            ""
+        };
+
+        let sqltxt = format!(
+            "SELECT
+                {}
+            FROM
+                {}
+            WHERE
+                {} AND {}
+            ORDER BY
+                {}
+            {}
+            ",
+            columns,
+            table,
+            clause.to_sql(),
+            condition,
+            order,
+            limit
+        );
+
+        //Prepare and execute the query
+        let mut stmt = conn.prepare_cached(sqltxt.as_str())?;
+
+        for (name, value) in &bindings {
+            bind_query_param(&mut stmt, name, value)?;
+        }
+
+        let mut rows = stmt.raw_query();
+
+        while let Some(row) = rows.next()? {
+            let boost = if sent {
+                sent_boost_from_row(row)?
+            } else {
+                boost_from_row(row)?
+            };
+
+            boosts.push(boost);
+        }
+    }
+
+    Ok(boosts)
+}
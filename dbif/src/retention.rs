@@ -0,0 +1,333 @@
+use rusqlite::{params, Connection, TransactionBehavior};
+use std::error::Error;
+use std::collections::HashMap;
+use serde::Serialize;
+
+use crate::{
+    connect_to_database,
+    bind_query_param,
+    build_filter_clause,
+    ActionType,
+    BoostFilters,
+    BoostRecord,
+    HydraError,
+    ListType,
+};
+
+//Streams are rolled up by the hour so stats in other timezones still put them on the right day.
+//Rollups from before that cover a whole UTC day.
+const ROLLUP_PERIOD: i64 = 3600;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct StreamRollupRecord {
+    pub day: i64, // start of the period
+    pub period: i64, // seconds covered, an hour or a day for older rollups
+    pub podcast: String,
+    pub episode: String,
+    pub sender: String,
+    pub app: String,
+    pub count: u64,
+    pub value_msat: i64,
+    pub value_msat_total: i64,
+}
+
+impl StreamRollupRecord {
+    //A stream record holding the rollup's totals, for places that list streams
+    pub fn to_boost(&self) -> BoostRecord {
+        BoostRecord {
+            index: 0,
+            time: self.day,
+            value_msat: self.value_msat,
+            value_msat_total: self.value_msat_total,
+            action: ActionType::Stream,
+            list_type: ListType::Stream,
+            sender: self.sender.clone(),
+            app: self.app.clone(),
+            message: "".to_string(),
+            podcast: self.podcast.clone(),
+            episode: self.episode.clone(),
+            tlv: "".to_string(),
+            remote_podcast: None,
+            remote_episode: None,
+            reply_sent: false,
+            custom_key: None,
+            custom_value: None,
+            memo: None,
+            payment_info: None,
+            hidden: false,
+            quarantined: false,
+            imported: false,
+            nostr_refs: Vec::new(),
+            numerology: Vec::new(),
+            tags: Vec::new(),
+            note: None,
+            pinned: false,
+            supporter: None,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct RollupResult {
+    pub streams: u64, // raw stream rows collapsed and removed
+    pub rollups: u64, // rollup rows created or added to
+}
+
+//Rollups look like streams to the boost filters. Received queries that should include rolled up
+//streams select from this in place of the boosts table, counting rows with row_count.
+pub(crate) fn received_with_rollups() -> String {
+    format!(
+        "(SELECT
+            idx, time, podcast, episode, sender, app, value_msat, value_msat_total, action, list_type,
+            hidden, reply_sent, custom_key, tlv, 1 AS row_count
+        FROM
+            boosts
+        UNION ALL
+        {}
+        ) AS boosts",
+        stream_rollups_as_boosts("")
+    )
+}
+
+fn stream_rollups_as_boosts(extra_columns: &str) -> String {
+    format!(
+        "SELECT
+            NULL AS idx, day AS time, podcast, episode, sender, app, value_msat, value_msat_total, {} AS action, {} AS list_type,
+            0 AS hidden, 0 AS reply_sent, NULL AS custom_key, NULL AS tlv, stream_count AS row_count{}
+        FROM
+            stream_rollups",
+        ActionType::Stream as u8,
+        ListType::Stream as u8,
+        extra_columns
+    )
+}
+
+//Totals of streams that were removed by the retention policy
+pub fn create_stream_rollups_table(conn: &Connection) -> Result<bool, Box<dyn Error>> {
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS stream_rollups (
+             idx integer primary key,
+             day integer not null,
+             podcast text not null,
+             episode text not null,
+             sender text not null,
+             app text not null,
+             stream_count integer not null,
+             value_msat integer not null,
+             value_msat_total integer not null,
+             UNIQUE (day, podcast, episode, sender, app)
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Stream rollups table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database stream_rollups table.".into())))
+        }
+    }
+
+    Ok(true)
+}
+
+//Collapses streams received before the cutoff into hourly rollups per podcast, episode, sender and app,
+//then vacuums to give the space back. Hidden, quarantined, tagged and pinned streams are left alone, as is
+//the newest boost received through LND since the poller picks up from its index.
+pub fn rollup_streams_in_db(filepath: &str, older_than: i64) -> Result<RollupResult, Box<dyn Error>> {
+    let mut conn = connect_to_database(false, filepath)?;
+
+    //Only whole hours, so the newest rollup's hour has no streams left to add to it
+    let older_than = older_than - older_than.rem_euclid(ROLLUP_PERIOD);
+
+    let conditions = format!(
+        "list_type = {}
+            AND time < ?1
+            AND COALESCE(hidden, 0) = 0
+            AND COALESCE(quarantined, 0) = 0
+            AND NOT EXISTS (SELECT 1 FROM boost_annotations WHERE boost_annotations.sent = 0 AND boost_annotations.boost_idx = boosts.idx)
+            AND (imported = 1 OR idx < (SELECT MAX(idx) FROM boosts WHERE imported = 0))",
+        ListType::Stream as u8
+    );
+
+    let day = format!("(time / {secs}) * {secs}", secs = ROLLUP_PERIOD);
+
+    //Immediate so the poller can't add rows between the rollup and the delete
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+    let rollups = tx.execute(
+        &format!(
+            "INSERT INTO stream_rollups (day, period, podcast, episode, sender, app, stream_count, value_msat, value_msat_total)
+            SELECT
+                {day}, {period}, COALESCE(podcast, ''), COALESCE(episode, ''), COALESCE(sender, ''), COALESCE(app, ''),
+                COUNT(*), SUM(COALESCE(value_msat, 0)), SUM(COALESCE(value_msat_total, 0))
+            FROM
+                boosts
+            WHERE
+                {conditions}
+            GROUP BY
+                1, 3, 4, 5, 6
+            ON CONFLICT (day, period, podcast, episode, sender, app) DO UPDATE SET
+                stream_count = stream_count + excluded.stream_count,
+                value_msat = value_msat + excluded.value_msat,
+                value_msat_total = value_msat_total + excluded.value_msat_total",
+            day = day,
+            period = ROLLUP_PERIOD,
+            conditions = conditions
+        ),
+        params![older_than],
+    )?;
+
+    //Rolled up streams no longer count towards their supporter's totals
+    tx.execute(&format!("DELETE FROM supporter_boosts WHERE boost_idx IN (SELECT idx FROM boosts WHERE {})", conditions), params![older_than])?;
+
+    let streams = tx.execute(&format!("DELETE FROM boosts WHERE {}", conditions), params![older_than])?;
+
+    tx.commit()?;
+
+    if streams > 0 {
+        conn.execute_batch("VACUUM;")?;
+    }
+
+    Ok(RollupResult {
+        streams: streams as u64,
+        rollups: rollups as u64,
+    })
+}
+
+//Streams received before this time have been collapsed into rollups, if any have
+pub fn get_stream_rollups_end_from_db(filepath: &str) -> Result<Option<i64>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let end: Option<i64> = conn.query_row("SELECT MAX(day + period) FROM stream_rollups", [], |row| row.get(0))?;
+
+    Ok(end)
+}
+
+//Get the stream rollups matching the filters, newest first
+pub fn get_stream_rollups_from_db(filepath: &str, filters: &BoostFilters) -> Result<Vec<StreamRollupRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let clause = build_filter_clause(filters, false);
+
+    let sqltxt = format!(
+        "SELECT
+            time, podcast, episode, sender, app, row_count, value_msat, value_msat_total, period
+        FROM
+            ({}) AS boosts
+        WHERE
+            {}
+        ORDER BY
+            time DESC, podcast, episode, sender, app
+        ",
+        stream_rollups_as_boosts(", period"),
+        clause.to_sql()
+    );
+
+    //Prepare and execute the query
+    let mut stmt = conn.prepare_cached(sqltxt.as_str())?;
+
+    let bindings: HashMap<&str, &str> = clause.bindings.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+
+    for (name, value) in &bindings {
+        bind_query_param(&mut stmt, name, value)?;
+    }
+
+    let mut rows = stmt.raw_query();
+    let mut rollups: Vec<StreamRollupRecord> = Vec::new();
+
+    while let Some(row) = rows.next()? {
+        rollups.push(StreamRollupRecord {
+            day: row.get(0)?,
+            podcast: row.get(1)?,
+            episode: row.get(2)?,
+            sender: row.get(3)?,
+            app: row.get(4)?,
+            count: row.get(5)?,
+            value_msat: row.get(6)?,
+            value_msat_total: row.get(7)?,
+            period: row.get(8)?,
+        });
+    }
+
+    Ok(rollups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{add_invoice_to_db, create_database, get_single_invoice_from_db};
+
+    const MIDNIGHT: i64 = 1_699_920_000; // 2023-11-14 00:00 UTC
+    const HOUR: i64 = 3600;
+    const DAY: i64 = 86400;
+
+    fn stream(index: u64, time: i64, value_msat: i64) -> BoostRecord {
+        let mut boost = StreamRollupRecord {
+            day: time,
+            period: 0,
+            podcast: "Podcast".to_string(),
+            episode: "Episode".to_string(),
+            sender: "Sender".to_string(),
+            app: "App".to_string(),
+            count: 1,
+            value_msat,
+            value_msat_total: value_msat,
+        }.to_boost();
+
+        boost.index = index;
+        boost
+    }
+
+    #[test]
+    fn rollup_keeps_totals_and_newest_boost() {
+        let dir = tempfile::tempdir().unwrap();
+        let filepath = dir.path().join("database.db").to_string_lossy().to_string();
+
+        create_database(&filepath).unwrap();
+
+        //A daily rollup from before rollups were hourly
+        connect_to_database(false, &filepath).unwrap().execute(
+            "INSERT INTO stream_rollups (day, period, podcast, episode, sender, app, stream_count, value_msat, value_msat_total)
+             VALUES (?1, ?2, 'Podcast', 'Episode', 'Sender', 'App', 5, 5000, 5000)",
+            params![MIDNIGHT, DAY],
+        ).unwrap();
+
+        for boost in [
+            stream(1, MIDNIGHT + 10, 1000),
+            stream(2, MIDNIGHT + 20, 2000),
+            stream(3, MIDNIGHT + 5 * HOUR + 30, 500),
+            stream(4, MIDNIGHT + 2 * DAY, 700), // newest from LND
+        ] {
+            add_invoice_to_db(&filepath, &boost).unwrap();
+        }
+
+        let result = rollup_streams_in_db(&filepath, MIDNIGHT + 3 * DAY).unwrap();
+
+        assert_eq!(result.streams, 3);
+        assert_eq!(result.rollups, 2);
+
+        //The poller carries on from the newest boost's index, so it stays
+        assert!(get_single_invoice_from_db(&filepath, 4, false).unwrap().is_some());
+
+        for index in 1..=3 {
+            assert!(get_single_invoice_from_db(&filepath, index, false).unwrap().is_none());
+        }
+
+        let mut rollups = get_stream_rollups_from_db(&filepath, &BoostFilters::default()).unwrap();
+        rollups.sort_by_key(|rollup| (rollup.day, rollup.period));
+
+        let rollups: Vec<(i64, i64, u64, i64)> = rollups.iter()
+            .map(|rollup| (rollup.day, rollup.period, rollup.count, rollup.value_msat))
+            .collect();
+
+        //The midnight hour gets its own rollup rather than adding to the daily one
+        assert_eq!(rollups, vec![
+            (MIDNIGHT, HOUR, 2, 3000),
+            (MIDNIGHT, DAY, 5, 5000),
+            (MIDNIGHT + 5 * HOUR, HOUR, 1, 500),
+        ]);
+
+        let rolled_up: i64 = rollups.iter().map(|rollup| rollup.3).sum();
+        assert_eq!(rolled_up + 700, 5000 + 1000 + 2000 + 500 + 700);
+    }
+}
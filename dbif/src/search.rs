@@ -0,0 +1,257 @@
+use rusqlite::Connection;
+use std::error::Error;
+use std::collections::HashMap;
+use serde::Serialize;
+
+use crate::{
+    connect_to_database,
+    bind_query_param,
+    build_filter_clause,
+    BoostRecord,
+    BoostFilters,
+    ListType,
+    BOOST_COLUMNS,
+    SENT_BOOST_COLUMNS,
+    boost_from_row,
+    sent_boost_from_row,
+};
+
+//Marks the start and end of a matched term in snippets before they're escaped
+const MATCH_START: char = '\u{2}';
+const MATCH_END: char = '\u{3}';
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct SearchHighlights {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub podcast: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub episode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SearchResult {
+    pub boost: BoostRecord,
+    pub rank: f64, // bm25 score, lower is a better match
+    pub highlights: SearchHighlights,
+}
+
+//Creates the full text indexes and the triggers that keep them in sync with the boosts and sent boosts tables
+pub fn create_search_index(conn: &Connection) -> Result<bool, Box<dyn Error>> {
+    conn.execute_batch(
+        r#"CREATE VIRTUAL TABLE IF NOT EXISTS boosts_fts USING fts5(
+            message, sender, podcast, episode, memo,
+            content='boosts', content_rowid='idx', tokenize='unicode61 remove_diacritics 2'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS boosts_fts_insert AFTER INSERT ON boosts BEGIN
+            INSERT INTO boosts_fts (rowid, message, sender, podcast, episode, memo)
+            VALUES (new.idx, new.message, new.sender, new.podcast, new.episode, new.memo);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS boosts_fts_delete AFTER DELETE ON boosts BEGIN
+            INSERT INTO boosts_fts (boosts_fts, rowid, message, sender, podcast, episode, memo)
+            VALUES ('delete', old.idx, old.message, old.sender, old.podcast, old.episode, old.memo);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS boosts_fts_update AFTER UPDATE OF message, sender, podcast, episode, memo ON boosts BEGIN
+            INSERT INTO boosts_fts (boosts_fts, rowid, message, sender, podcast, episode, memo)
+            VALUES ('delete', old.idx, old.message, old.sender, old.podcast, old.episode, old.memo);
+            INSERT INTO boosts_fts (rowid, message, sender, podcast, episode, memo)
+            VALUES (new.idx, new.message, new.sender, new.podcast, new.episode, new.memo);
+        END;
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS sent_boosts_fts USING fts5(
+            message, sender, podcast, episode,
+            content='sent_boosts', content_rowid='idx', tokenize='unicode61 remove_diacritics 2'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS sent_boosts_fts_insert AFTER INSERT ON sent_boosts BEGIN
+            INSERT INTO sent_boosts_fts (rowid, message, sender, podcast, episode)
+            VALUES (new.idx, new.message, new.sender, new.podcast, new.episode);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS sent_boosts_fts_delete AFTER DELETE ON sent_boosts BEGIN
+            INSERT INTO sent_boosts_fts (sent_boosts_fts, rowid, message, sender, podcast, episode)
+            VALUES ('delete', old.idx, old.message, old.sender, old.podcast, old.episode);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS sent_boosts_fts_update AFTER UPDATE OF message, sender, podcast, episode ON sent_boosts BEGIN
+            INSERT INTO sent_boosts_fts (sent_boosts_fts, rowid, message, sender, podcast, episode)
+            VALUES ('delete', old.idx, old.message, old.sender, old.podcast, old.episode);
+            INSERT INTO sent_boosts_fts (rowid, message, sender, podcast, episode)
+            VALUES (new.idx, new.message, new.sender, new.podcast, new.episode);
+        END;
+
+        INSERT INTO boosts_fts (boosts_fts) VALUES ('rebuild');
+        INSERT INTO sent_boosts_fts (sent_boosts_fts) VALUES ('rebuild');"#,
+    )?;
+
+    println!("Search index is ready.");
+
+    Ok(true)
+}
+
+//Turns free text into an FTS5 query where every word has to match the start of a term.
+//Each word is quoted so punctuation in the search can't be read as query syntax.
+pub fn build_search_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|word| word.replace('"', ""))
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+
+    if terms.is_empty() {
+        return None;
+    }
+
+    Some(terms.join(" "))
+}
+
+//Turns the match markers into <mark> tags, escaping everything else if asked for
+fn format_highlight(text: Option<String>, escape_html: bool) -> Option<String> {
+    let text = text?;
+
+    if !text.contains(MATCH_START) {
+        return None;
+    }
+
+    let text = if escape_html {
+        BoostRecord::escape_for_html(text)
+    } else {
+        text
+    };
+
+    Some(text.replace(MATCH_START, "<mark>").replace(MATCH_END, "</mark>"))
+}
+
+//Search boosts, streams ("boost"/"stream") or sent boosts ("sent") for the text, best matches first
+pub fn search_boosts_in_db(filepath: &str, list: &str, text: &str, max: u64, offset: u64, escape_html: bool, filters: BoostFilters) -> Result<Vec<SearchResult>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let query = match build_search_query(text) {
+        Some(query) => query,
+        None => return Ok(Vec::new()),
+    };
+
+    let sent = list == "sent";
+    let (table, fts_table, columns) = if sent {
+        ("sent_boosts", "sent_boosts_fts", SENT_BOOST_COLUMNS)
+    } else {
+        ("boosts", "boosts_fts", BOOST_COLUMNS)
+    };
+
+    let filter_clause = build_filter_clause(&filters, sent);
+    let filter_conditions = filter_clause.to_sql();
+
+    let mut conditions: Vec<&str> = vec![&filter_conditions];
+    let mut bindings: HashMap<&str, &str> = filter_clause.bindings.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+
+    bindings.insert(":query", &query);
+
+    let list_condition;
+
+    if let Some(list_type @ (ListType::Boost | ListType::Stream)) = ListType::from_list_name(list) {
+        list_condition = format!("list_type = {}", list_type as u8);
+        conditions.push(&list_condition);
+    }
+
+    let conditions = conditions.join(" AND ");
+
+    let strmax = max.to_string();
+    let stroffset = offset.to_string();
+    bindings.insert(":max", &strmax);
+    bindings.insert(":offset", &stroffset);
+
+    //Sent boosts don't have a memo to highlight
+    let memo_highlight = if sent {
+        "NULL"
+    } else {
+        "highlight(boosts_fts, 4, char(2), char(3))"
+    };
+
+    let sqltxt = format!(
+        "SELECT
+            {columns},
+            fts.rank,
+            fts.message_highlight,
+            fts.sender_highlight,
+            fts.podcast_highlight,
+            fts.episode_highlight,
+            fts.memo_highlight
+        FROM
+            {table}
+        JOIN (
+            SELECT
+                rowid,
+                bm25({fts_table}) AS rank,
+                snippet({fts_table}, 0, char(2), char(3), '…', 24) AS message_highlight,
+                highlight({fts_table}, 1, char(2), char(3)) AS sender_highlight,
+                highlight({fts_table}, 2, char(2), char(3)) AS podcast_highlight,
+                highlight({fts_table}, 3, char(2), char(3)) AS episode_highlight,
+                {memo_highlight} AS memo_highlight
+            FROM
+                {fts_table}
+            WHERE
+                {fts_table} MATCH :query
+        ) AS fts ON fts.rowid = {table}.idx
+        WHERE
+            {conditions}
+        ORDER BY
+            fts.rank, idx DESC
+        LIMIT :max OFFSET :offset
+        ",
+        columns = columns,
+        table = table,
+        fts_table = fts_table,
+        memo_highlight = memo_highlight,
+        conditions = conditions,
+    );
+
+    //Prepare and execute the query
+    let mut stmt = conn.prepare_cached(sqltxt.as_str())?;
+
+    for (name, value) in &bindings {
+        bind_query_param(&mut stmt, name, value)?;
+    }
+
+    //The search columns come right after the boost columns
+    let first = columns.split(',').count();
+
+    let mut rows = stmt.raw_query();
+    let mut results: Vec<SearchResult> = Vec::new();
+
+    while let Some(row) = rows.next()? {
+        let boost = if sent {
+            sent_boost_from_row(row)?
+        } else {
+            boost_from_row(row)?
+        };
+
+        let boost = if escape_html {
+            boost.escaped_for_html()
+        } else {
+            boost
+        };
+
+        results.push(SearchResult {
+            boost,
+            rank: row.get(first)?,
+            highlights: SearchHighlights {
+                message: format_highlight(row.get(first + 1)?, escape_html),
+                sender: format_highlight(row.get(first + 2)?, escape_html),
+                podcast: format_highlight(row.get(first + 3)?, escape_html),
+                episode: format_highlight(row.get(first + 4)?, escape_html),
+                memo: format_highlight(row.get(first + 5)?, escape_html),
+            },
+        });
+    }
+
+    Ok(results)
+}
@@ -1,6 +1,5 @@
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, Row};
 use std::error::Error;
-use std::collections::HashMap;
 
 use crate::{
     connect_to_database,
@@ -9,9 +8,10 @@ use crate::{
     PaymentRecord,
     BoostFilters,
     mark_boost_as_replied,
-    bind_query_param,
+    build_filter_clause,
     ActionType,
     ListType,
+    get_list_page,
 };
 
 pub fn create_sent_boosts_table(conn: &Connection) -> Result<bool, Box<dyn Error>> {
@@ -52,56 +52,106 @@ pub fn create_sent_boosts_table(conn: &Connection) -> Result<bool, Box<dyn Error
     Ok(true)
 }
 
+//Columns read by sent_boost_from_row, in order
+pub(crate) const SENT_BOOST_COLUMNS: &str = "idx, time, value_msat, value_msat_total, action, sender, app, message, podcast, episode, tlv, remote_podcast, remote_episode, payment_hash, payment_pubkey, payment_custom_key, payment_custom_value, payment_fee_msat, reply_to_idx, imported";
+
+//Builds a sent boost record from a row selected with SENT_BOOST_COLUMNS
+pub(crate) fn sent_boost_from_row(row: &Row) -> Result<BoostRecord, rusqlite::Error> {
+    Ok(BoostRecord {
+        index: row.get(0)?,
+        time: row.get(1)?,
+        value_msat: row.get(2)?,
+        value_msat_total: row.get(3)?,
+        action: ActionType::from_u8(row.get(4)?),
+        list_type: ListType::Sent,
+        sender: row.get(5)?,
+        app: row.get(6)?,
+        message: row.get(7)?,
+        podcast: row.get(8)?,
+        episode: row.get(9)?,
+        tlv: row.get(10)?,
+        remote_podcast: row.get(11).ok(),
+        remote_episode: row.get(12).ok(),
+        reply_sent: false,
+        custom_key: None,
+        custom_value: None,
+        memo: None,
+        payment_info: Some(PaymentRecord {
+            payment_hash: row.get(13)?,
+            pubkey: row.get(14)?,
+            custom_key: row.get(15)?,
+            custom_value: row.get(16)?,
+            fee_msat: row.get(17)?,
+            reply_to_idx: row.get(18)?,
+        }),
+        hidden: false,
+        quarantined: false,
+        imported: row.get(19).unwrap_or(false),
+        nostr_refs: Vec::new(),
+        numerology: Vec::new(),
+        tags: Vec::new(),
+        note: None,
+        pinned: false,
+        supporter: None,
+    })
+}
+
 //Get all of the sent boosts from the database
 pub fn get_payments_from_db(filepath: &str, index: u64, max: u64, direction: bool, escape_html: bool, filters: BoostFilters) -> Result<Vec<BoostRecord>, Box<dyn Error>> {
     let conn = connect_to_database(false, filepath)?;
 
-    let mut conditions: Vec<&str> = Vec::new();
-    let mut bindings: HashMap<&str, &str> = HashMap::new();
+    //Sent boosts aren't narrowed by action here since reports pass the received actions along
+    let filters = BoostFilters { actions: Vec::new(), ..filters };
+    let clause = build_filter_clause(&filters, true);
 
-    let cond = if direction {
-        "idx <= :idx"
-    } else {
-        "idx >= :idx"
-    };
+    let boosts = get_list_page(&conn, true, &clause, index, max, direction)?;
 
-    conditions.push(cond);
+    //Some things like text output don't need to be html entity escaped
+    //so only do it if asked for
+    if escape_html {
+        return Ok(boosts.into_iter().map(BoostRecord::escaped_for_html).collect());
+    }
 
-    let strindex = index.to_string();
-    bindings.insert(":idx", &strindex);
+    Ok(boosts)
+}
 
-    if let Some(podcast) = &filters.podcast {
-        conditions.push("podcast = :podcast");
-        bindings.insert(":podcast", podcast);
-    }
+//Imported sent boosts don't count since they didn't come from this node's LND
+pub fn get_last_payment_index_from_db(filepath: &str) -> Result<u64, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
 
-    let start_date = filters.start_date.unwrap_or_default().to_string();
+    let mut stmt = conn.prepare_cached("SELECT MAX(idx) FROM sent_boosts WHERE imported = 0")?;
+    let index = stmt.query_row([], |row| row.get(0))?;
 
-    if !start_date.is_empty() && start_date != "0" {
-        conditions.push("time >= :start_date");
-        bindings.insert(":start_date", &start_date);
+    if let Some(idx) = index {
+        return Ok(idx);
     }
 
-    let end_date = filters.end_date.unwrap_or_default().to_string();
+    Ok(0)
+}
 
-    if !end_date.is_empty() && end_date != "0" {
-        conditions.push("time <= :end_date");
-        bindings.insert(":end_date", &end_date);
-    }
+//Add a payment (sent boost) to the database
+pub fn add_payment_to_db(filepath: &str, boost: &BoostRecord) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
 
-    let conditions = conditions.join(" AND ");
+    let payment_info = match &boost.payment_info {
+        Some(info) => info,
+        None => {
+            return Err(Box::new(HydraError(format!("Missing payment info for sent boost: [{}].", boost.index))))
+        }
+    };
 
-    let mut limit = String::new();
-    let strmax = max.to_string();
+    insert_payment(&conn, boost, payment_info)?;
 
-    if max > 0 {
-        limit.push_str("LIMIT :max");
-        bindings.insert(":max", &strmax);
+    if let Some(reply_to_idx) = payment_info.reply_to_idx {
+        mark_boost_as_replied(filepath, reply_to_idx)?;
     }
 
-    //Query for boosts and automated boosts
-    let sqltxt = format!(
-        "SELECT
+    Ok(true)
+}
+
+pub(crate) fn insert_payment(conn: &Connection, boost: &BoostRecord, payment_info: &PaymentRecord) -> Result<usize, rusqlite::Error> {
+    let mut stmt = conn.prepare_cached(
+        "INSERT INTO sent_boosts (
             idx,
             time,
             value_msat,
@@ -120,106 +170,53 @@ pub fn get_payments_from_db(filepath: &str, index: u64, max: u64, direction: boo
             payment_custom_key,
             payment_custom_value,
             payment_fee_msat,
-            reply_to_idx
-        FROM
-            sent_boosts
-        WHERE
-            {}
-        ORDER BY
-            idx DESC
-        {}
-        ",
-        conditions,
-        limit
-    );
-
-    //Prepare and execute the query
-    let mut stmt = conn.prepare(sqltxt.as_str())?;
-
-    for (name, value) in &bindings {
-        bind_query_param(&mut stmt, name, value)?;
-    }
-
-    let mut rows = stmt.raw_query();
-    let mut boosts: Vec<BoostRecord> = Vec::new();
-
-    //Parse the results
-    while let Some(row) = rows.next()? {
-        let boost = BoostRecord {
-            index: row.get(0)?,
-            time: row.get(1)?,
-            value_msat: row.get(2)?,
-            value_msat_total: row.get(3)?,
-            action: ActionType::from_u8(row.get(4)?),
-            list_type: ListType::Sent,
-            sender: row.get(5)?,
-            app: row.get(6)?,
-            message: row.get(7)?,
-            podcast: row.get(8)?,
-            episode: row.get(9)?,
-            tlv: row.get(10)?,
-            remote_podcast: row.get(11).ok(),
-            remote_episode: row.get(12).ok(),
-            reply_sent: false,
-            custom_key: None,
-            custom_value: None,
-            memo: None,
-            payment_info: Some(PaymentRecord {
-                payment_hash: row.get(13)?,
-                pubkey: row.get(14)?,
-                custom_key: row.get(15)?,
-                custom_value: row.get(16)?,
-                fee_msat: row.get(17)?,
-                reply_to_idx: row.get(18)?,
-            }),
-        };
-
-        //Some things like text output don't need to be html entity escaped
-        //so only do it if asked for
-        if escape_html {
-            let boost_clean = BoostRecord {
-                sender: BoostRecord::escape_for_html(boost.sender),
-                app: BoostRecord::escape_for_html(boost.app),
-                message: BoostRecord::escape_for_html(boost.message),
-                podcast: BoostRecord::escape_for_html(boost.podcast),
-                episode: BoostRecord::escape_for_html(boost.episode),
-                tlv: BoostRecord::escape_for_html(boost.tlv),
-                remote_podcast: boost.remote_podcast.map(BoostRecord::escape_for_html),
-                remote_episode: boost.remote_episode.map(BoostRecord::escape_for_html),
-                payment_info: match boost.payment_info {
-                    Some(info) => Some(PaymentRecord {
-                        pubkey: BoostRecord::escape_for_html(info.pubkey),
-                        custom_value: BoostRecord::escape_for_html(info.custom_value),
-                        ..info
-                    }),
-                    None => None,
-                },
-                ..boost
-            };
-            boosts.push(boost_clean);
-        } else {
-            boosts.push(boost);
-        }
-    }
+            reply_to_idx,
+            imported
+        )
+        VALUES
+            (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)
+        ON CONFLICT(idx) DO UPDATE SET
+            reply_to_idx = COALESCE(reply_to_idx, excluded.reply_to_idx)
+        "
+    )?;
 
-    Ok(boosts)
+    stmt.execute(
+        params![
+            boost.index,
+            boost.time,
+            boost.value_msat,
+            boost.value_msat_total,
+            boost.action as u8,
+            boost.sender,
+            boost.app,
+            boost.message,
+            boost.podcast,
+            boost.episode,
+            boost.tlv,
+            boost.remote_podcast,
+            boost.remote_episode,
+            payment_info.payment_hash,
+            payment_info.pubkey,
+            payment_info.custom_key,
+            payment_info.custom_value,
+            payment_info.fee_msat,
+            payment_info.reply_to_idx,
+            boost.imported,
+        ]
+    )
 }
 
-pub fn get_last_payment_index_from_db(filepath: &str) -> Result<u64, Box<dyn Error>> {
+//Check if a sent boost is in the database
+pub fn payment_exists_in_db(filepath: &str, index: u64) -> Result<bool, Box<dyn Error>> {
     let conn = connect_to_database(false, filepath)?;
-
-    let mut stmt = conn.prepare("SELECT MAX(idx) FROM sent_boosts")?;
-    let index = stmt.query_row([], |row| row.get(0))?;
-
-    if let Some(idx) = index {
-        return Ok(idx);
-    }
-
-    Ok(0)
+    let mut stmt = conn.prepare_cached("SELECT EXISTS(SELECT 1 FROM sent_boosts WHERE idx = ?1)")?;
+    Ok(stmt.query_row(params![index], |row| row.get(0))?)
 }
 
-//Add a payment (sent boost) to the database
-pub fn add_payment_to_db(filepath: &str, boost: &BoostRecord) -> Result<bool, Box<dyn Error>> {
+//Add a payment, or refresh the parsed fields of one that's already stored. The boost it replied to
+//is kept if the payment doesn't say. Returns true if the payment was new.
+pub fn upsert_payment_in_db(filepath: &str, boost: &BoostRecord) -> Result<bool, Box<dyn Error>> {
+    let exists = payment_exists_in_db(filepath, boost.index)?;
     let conn = connect_to_database(false, filepath)?;
 
     let payment_info = match &boost.payment_info {
@@ -229,7 +226,7 @@ pub fn add_payment_to_db(filepath: &str, boost: &BoostRecord) -> Result<bool, Bo
         }
     };
 
-    conn.execute(
+    let mut stmt = conn.prepare_cached(
         "INSERT INTO sent_boosts (
             idx,
             time,
@@ -254,8 +251,28 @@ pub fn add_payment_to_db(filepath: &str, boost: &BoostRecord) -> Result<bool, Bo
         VALUES
             (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)
         ON CONFLICT(idx) DO UPDATE SET
-            reply_to_idx = COALESCE(reply_to_idx, excluded.reply_to_idx)
-        ",
+            time = excluded.time,
+            value_msat = excluded.value_msat,
+            value_msat_total = excluded.value_msat_total,
+            action = excluded.action,
+            sender = excluded.sender,
+            app = excluded.app,
+            message = excluded.message,
+            podcast = excluded.podcast,
+            episode = excluded.episode,
+            tlv = excluded.tlv,
+            remote_podcast = excluded.remote_podcast,
+            remote_episode = excluded.remote_episode,
+            payment_hash = excluded.payment_hash,
+            payment_pubkey = excluded.payment_pubkey,
+            payment_custom_key = excluded.payment_custom_key,
+            payment_custom_value = excluded.payment_custom_value,
+            payment_fee_msat = excluded.payment_fee_msat,
+            reply_to_idx = COALESCE(excluded.reply_to_idx, reply_to_idx)
+        "
+    )?;
+
+    stmt.execute(
         params![
             boost.index,
             boost.time,
@@ -279,11 +296,7 @@ pub fn add_payment_to_db(filepath: &str, boost: &BoostRecord) -> Result<bool, Bo
         ]
     )?;
 
-    if let Some(reply_to_idx) = payment_info.reply_to_idx {
-        mark_boost_as_replied(filepath, reply_to_idx)?;
-    }
-
-    Ok(true)
+    Ok(!exists)
 }
 
 //Get podcasts that were send boosts from this node
@@ -292,7 +305,7 @@ pub fn get_sent_podcasts_from_db(filepath: &str) -> Result<Vec<String>, Box<dyn
 
     let query = "SELECT DISTINCT podcast FROM sent_boosts WHERE podcast <> '' ORDER BY podcast".to_string();
 
-    let mut stmt = conn.prepare(&query)?;
+    let mut stmt = conn.prepare_cached(&query)?;
     let mut rows = stmt.raw_query();
 
     //Parse the results
@@ -0,0 +1,160 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::error::Error;
+use serde::{Deserialize, Serialize};
+
+use crate::{connect_to_database, HydraError};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SessionRecord {
+    pub index: u64,
+    #[serde(skip_serializing, default)]
+    pub jti: String, // the id in the session's JWT
+    pub username: String, // empty for the shared password login
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    pub long_lived: bool, // "stay logged in"
+    pub created: i64,
+    pub last_seen: i64,
+    pub expires: i64,
+}
+
+//Logins, so a JWT stops working once its session is logged out
+pub fn create_sessions_table(conn: &Connection) -> Result<bool, Box<dyn Error>> {
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS sessions (
+             idx integer primary key,
+             jti text not null unique,
+             username text not null COLLATE NOCASE,
+             user_agent text,
+             ip text,
+             long_lived integer not null,
+             created integer not null,
+             last_seen integer not null,
+             expires integer not null
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Sessions table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database sessions table.".into())))
+        }
+    }
+
+    match conn.execute("CREATE INDEX IF NOT EXISTS sessions_username ON sessions (username)", []) {
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database sessions index.".into())))
+        }
+    }
+
+    Ok(true)
+}
+
+const SESSION_COLUMNS: &str = "idx, jti, username, user_agent, ip, long_lived, created, last_seen, expires";
+
+fn session_from_row(row: &Row) -> Result<SessionRecord, rusqlite::Error> {
+    Ok(SessionRecord {
+        index: row.get(0)?,
+        jti: row.get(1)?,
+        username: row.get(2)?,
+        user_agent: row.get(3)?,
+        ip: row.get(4)?,
+        long_lived: row.get(5)?,
+        created: row.get(6)?,
+        last_seen: row.get(7)?,
+        expires: row.get(8)?,
+    })
+}
+
+//Save a new session, clearing out expired ones while we're here. Returns the new session's index.
+pub fn add_session_to_db(
+    filepath: &str,
+    jti: &str,
+    username: &str,
+    user_agent: Option<&str>,
+    ip: Option<&str>,
+    long_lived: bool,
+    expires: i64,
+) -> Result<u64, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let now = chrono::Utc::now().timestamp();
+
+    conn.execute("DELETE FROM sessions WHERE expires <= ?1", params![now])?;
+
+    conn.execute(
+        "INSERT INTO sessions (jti, username, user_agent, ip, long_lived, created, last_seen, expires)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6, ?7)",
+        params![jti, username, user_agent, ip, long_lived, now, expires],
+    )?;
+
+    Ok(conn.last_insert_rowid() as u64)
+}
+
+pub fn get_session_from_db(filepath: &str, jti: &str) -> Result<Option<SessionRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let session = conn.query_row(
+        &format!("SELECT {} FROM sessions WHERE jti = ?1", SESSION_COLUMNS),
+        params![jti],
+        session_from_row,
+    ).optional()?;
+
+    Ok(session)
+}
+
+//Get a login's sessions that haven't expired, most recently used first
+pub fn get_sessions_from_db(filepath: &str, username: &str) -> Result<Vec<SessionRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM sessions WHERE username = ?1 AND expires > ?2 ORDER BY last_seen DESC",
+        SESSION_COLUMNS,
+    ))?;
+
+    let rows = stmt.query_map(params![username, chrono::Utc::now().timestamp()], session_from_row)?;
+
+    let mut sessions = Vec::new();
+
+    for session in rows {
+        sessions.push(session?);
+    }
+
+    Ok(sessions)
+}
+
+pub fn set_session_seen_in_db(filepath: &str, index: u64, ip: Option<&str>, last_seen: i64, expires: i64) -> Result<(), Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    conn.execute(
+        "UPDATE sessions SET ip = ?2, last_seen = ?3, expires = ?4 WHERE idx = ?1",
+        params![index, ip, last_seen, expires],
+    )?;
+
+    Ok(())
+}
+
+//Log out one of a login's sessions
+pub fn delete_session_from_db(filepath: &str, username: &str, index: u64) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let deleted = conn.execute("DELETE FROM sessions WHERE idx = ?1 AND username = ?2", params![index, username])?;
+
+    Ok(deleted > 0)
+}
+
+//Log out all of a login's sessions, except the session with this id if there is one. Returns how many there were.
+pub fn delete_sessions_from_db(filepath: &str, username: &str, keep_jti: Option<&str>) -> Result<u64, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let deleted = conn.execute("DELETE FROM sessions WHERE username = ?1 AND jti IS NOT ?2", params![username, keep_jti])?;
+
+    Ok(deleted as u64)
+}
+
+//Log out everyone, except the session with this id if there is one
+pub fn delete_all_sessions_from_db(filepath: &str, keep_jti: Option<&str>) -> Result<u64, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let deleted = conn.execute("DELETE FROM sessions WHERE jti IS NOT ?1", params![keep_jti])?;
+
+    Ok(deleted as u64)
+}
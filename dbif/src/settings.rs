@@ -3,6 +3,8 @@ use std::error::Error;
 use serde::{Deserialize, Serialize};
 use crate::{connect_to_database, HydraError};
 
+pub const DEFAULT_NOSTR_RELAYS: &str = "wss://relay.damus.io,wss://nos.lol,wss://relay.nostr.band";
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SettingsRecord {
     pub show_received_sats: bool,
@@ -16,6 +18,7 @@ pub struct SettingsRecord {
     pub show_lightning_invoices: bool,
     pub fetch_metadata: bool,
     pub metadata_whitelist: String,
+    pub nostr_relays: String,
 }
 
 pub fn create_settings_table(conn: &Connection) -> Result<bool, Box<dyn Error>> {
@@ -41,33 +44,13 @@ pub fn create_settings_table(conn: &Connection) -> Result<bool, Box<dyn Error>>
         }
     }
 
-    if conn.execute("ALTER TABLE settings ADD COLUMN resolve_nostr_refs integer DEFAULT 0", []).is_ok() {
-        println!("Nostr refs setting added.");
-    }
-
-    if conn.execute("ALTER TABLE settings ADD COLUMN show_hosted_wallet_ids integer DEFAULT 0", []).is_ok() {
-        println!("Hosted wallet id setting added.");
-    }
-
-    if conn.execute("ALTER TABLE settings ADD COLUMN show_lightning_invoices integer DEFAULT 1", []).is_ok() {
-        println!("Show lightning invoices setting added.");
-    }
-
-    if conn.execute("ALTER TABLE settings ADD COLUMN fetch_metadata integer DEFAULT 1", []).is_ok() {
-        println!("Fetch metadata setting added.");
-    }
-
-    if conn.execute("ALTER TABLE settings ADD COLUMN metadata_whitelist text", []).is_ok() {
-        println!("Metadata whitelist setting added.");
-    }
-
     Ok(true)
 }
 
 pub fn load_settings_from_db(filepath: &str) -> Result<SettingsRecord, Box<dyn Error>> {
     let conn = connect_to_database(false, filepath)?;
 
-    let mut stmt = conn.prepare(
+    let mut stmt = conn.prepare_cached(
         r#"SELECT
              show_received_sats,
              show_split_percentage,
@@ -79,7 +62,8 @@ pub fn load_settings_from_db(filepath: &str) -> Result<SettingsRecord, Box<dyn E
              show_hosted_wallet_ids,
              show_lightning_invoices,
              fetch_metadata,
-             metadata_whitelist
+             metadata_whitelist,
+             nostr_relays
         FROM
             settings
         WHERE
@@ -100,6 +84,7 @@ pub fn load_settings_from_db(filepath: &str) -> Result<SettingsRecord, Box<dyn E
             show_lightning_invoices: row.get(8)?,
             fetch_metadata: row.get(9).unwrap_or(true),
             metadata_whitelist: row.get(10).unwrap_or("".to_string()),
+            nostr_relays: row.get(11).unwrap_or(DEFAULT_NOSTR_RELAYS.to_string()),
         })
     });
 
@@ -117,6 +102,7 @@ pub fn load_settings_from_db(filepath: &str) -> Result<SettingsRecord, Box<dyn E
             show_lightning_invoices: true,
             fetch_metadata: true,
             metadata_whitelist: "".to_string(),
+            nostr_relays: DEFAULT_NOSTR_RELAYS.to_string(),
         }),
         Err(e) => Err(Box::new(e)),
     }
@@ -138,10 +124,11 @@ pub fn save_settings_to_db(filepath: &str, settings: &SettingsRecord) -> Result<
             show_hosted_wallet_ids,
             show_lightning_invoices,
             fetch_metadata,
-            metadata_whitelist
+            metadata_whitelist,
+            nostr_relays
         )
         VALUES
-            (1, ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+            (1, ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
         ON CONFLICT(idx) DO UPDATE SET
             show_received_sats = excluded.show_received_sats,
             show_split_percentage = excluded.show_split_percentage,
@@ -153,7 +140,8 @@ pub fn save_settings_to_db(filepath: &str, settings: &SettingsRecord) -> Result<
             show_hosted_wallet_ids = excluded.show_hosted_wallet_ids,
             show_lightning_invoices = excluded.show_lightning_invoices,
             fetch_metadata = excluded.fetch_metadata,
-            metadata_whitelist = excluded.metadata_whitelist
+            metadata_whitelist = excluded.metadata_whitelist,
+            nostr_relays = excluded.nostr_relays
         "#,
         params![
             settings.show_received_sats,
@@ -167,6 +155,7 @@ pub fn save_settings_to_db(filepath: &str, settings: &SettingsRecord) -> Result<
             settings.show_lightning_invoices,
             settings.fetch_metadata,
             settings.metadata_whitelist,
+            settings.nostr_relays,
         ]
     ) {
         Ok(_) => {
@@ -0,0 +1,208 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::error::Error;
+use rand::{distr::Alphanumeric, RngExt};
+use serde::{Deserialize, Serialize};
+
+use crate::{connect_to_database, HydraError};
+
+//What a share link shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareView {
+    Boosts, // the latest boosts
+    Websocket, // live boosts as they come in
+    Goal, // sats received towards a goal
+}
+
+impl ShareView {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "boosts" => Some(ShareView::Boosts),
+            "websocket" => Some(ShareView::Websocket),
+            "goal" => Some(ShareView::Goal),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShareView::Boosts => "boosts",
+            ShareView::Websocket => "websocket",
+            ShareView::Goal => "goal",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ShareLinkRecord {
+    pub index: u64,
+    pub name: String,
+    pub view: ShareView,
+    pub podcast: Option<String>, // only show this podcast
+    pub goal_sats: Option<u64>, // for goal links
+    pub created: i64,
+    pub expires: i64,
+    pub last_used: Option<i64>,
+}
+
+impl ShareLinkRecord {
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires <= now
+    }
+}
+
+//Read only links for overlays and guests, and the secret they're signed with. It's kept apart from the JWT secret so
+//changing the password doesn't break them.
+pub fn create_share_link_tables(conn: &Connection) -> Result<bool, Box<dyn Error>> {
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS share_links (
+             idx integer primary key,
+             name text not null,
+             view text not null,
+             podcast text,
+             goal_sats integer,
+             created integer not null,
+             expires integer not null,
+             last_used integer
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Share links table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database share_links table.".into())))
+        }
+    }
+
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS share_link_secret (
+             idx integer primary key,
+             secret text not null,
+             created_at integer not null
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Share link secret table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database share_link_secret table.".into())))
+        }
+    }
+
+    Ok(true)
+}
+
+//Get the secret share links are signed with, making one the first time
+pub fn get_or_create_share_link_secret(filepath: &str) -> Result<String, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let secret: Option<String> = conn.query_row("SELECT secret FROM share_link_secret WHERE idx = 1", [], |row| row.get(0)).optional()?;
+
+    if let Some(secret) = secret {
+        return Ok(secret);
+    }
+
+    let secret: String = rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(40)
+        .map(char::from)
+        .collect();
+
+    //Keep the first secret if another request made one at the same time
+    conn.execute(
+        "INSERT OR IGNORE INTO share_link_secret (idx, secret, created_at) VALUES (1, ?1, ?2)",
+        params![secret, chrono::Utc::now().timestamp()],
+    )?;
+
+    let secret = conn.query_row("SELECT secret FROM share_link_secret WHERE idx = 1", [], |row| row.get(0))?;
+
+    Ok(secret)
+}
+
+const SHARE_LINK_COLUMNS: &str = "idx, name, view, podcast, goal_sats, created, expires, last_used";
+
+fn share_link_from_row(row: &Row) -> Result<ShareLinkRecord, rusqlite::Error> {
+    let view: String = row.get(2)?;
+
+    Ok(ShareLinkRecord {
+        index: row.get(0)?,
+        name: row.get(1)?,
+        view: ShareView::from_name(&view).unwrap_or(ShareView::Boosts),
+        podcast: row.get(3)?,
+        goal_sats: row.get(4)?,
+        created: row.get(5)?,
+        expires: row.get(6)?,
+        last_used: row.get(7)?,
+    })
+}
+
+pub fn get_share_link_from_db(filepath: &str, index: u64) -> Result<Option<ShareLinkRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let link = conn.query_row(
+        &format!("SELECT {} FROM share_links WHERE idx = ?1", SHARE_LINK_COLUMNS),
+        params![index],
+        share_link_from_row,
+    ).optional()?;
+
+    Ok(link)
+}
+
+//Save a new share link. Returns the saved link.
+pub fn add_share_link_to_db(
+    filepath: &str,
+    name: &str,
+    view: ShareView,
+    podcast: Option<&str>,
+    goal_sats: Option<u64>,
+    expires: i64,
+) -> Result<ShareLinkRecord, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    conn.execute(
+        "INSERT INTO share_links (name, view, podcast, goal_sats, created, expires) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![name, view.name(), podcast, goal_sats, chrono::Utc::now().timestamp(), expires],
+    )?;
+
+    let index = conn.last_insert_rowid() as u64;
+
+    match get_share_link_from_db(filepath, index)? {
+        Some(link) => Ok(link),
+        None => Err(Box::new(HydraError("Failed to save share link.".into()))),
+    }
+}
+
+//Get all share links, newest first
+pub fn get_share_links_from_db(filepath: &str) -> Result<Vec<ShareLinkRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM share_links ORDER BY idx DESC", SHARE_LINK_COLUMNS))?;
+
+    let rows = stmt.query_map([], share_link_from_row)?;
+
+    let mut links = Vec::new();
+
+    for link in rows {
+        links.push(link?);
+    }
+
+    Ok(links)
+}
+
+pub fn set_share_link_used_in_db(filepath: &str, index: u64, time: i64) -> Result<(), Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    conn.execute("UPDATE share_links SET last_used = ?2 WHERE idx = ?1", params![index, time])?;
+
+    Ok(())
+}
+
+//Revoke a share link
+pub fn delete_share_link_from_db(filepath: &str, index: u64) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let deleted = conn.execute("DELETE FROM share_links WHERE idx = ?1", params![index])?;
+
+    Ok(deleted > 0)
+}
@@ -0,0 +1,328 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::error::Error;
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    connect_to_database,
+    bind_query_param,
+    build_filter_clause,
+    BoostFilters,
+    BoostRecord,
+    HydraError,
+    ListType,
+    BOOST_COLUMNS,
+    boost_from_row,
+};
+
+//A live show. Boosts that come in for the podcast and episode while it's running make up its queue.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ShowSessionRecord {
+    pub index: u64,
+    pub podcast: Option<String>, // any podcast if not set
+    pub episode: Option<String>, // any episode if not set
+    pub started: i64,
+    pub ended: Option<i64>,
+}
+
+impl ShowSessionRecord {
+    pub fn is_active(&self) -> bool {
+        self.ended.is_none()
+    }
+
+    //The filters that pick out the boosts and streams that came in during the show
+    pub fn filters(&self) -> BoostFilters {
+        BoostFilters {
+            podcast: self.podcast.clone(),
+            episode: self.episode.clone(),
+            start_date: Some(self.started.max(0) as u64),
+            end_date: self.ended.map(|ended| ended.max(0) as u64),
+            ..BoostFilters::new()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShowItemState {
+    #[default]
+    Unread,
+    Read, // read out on air
+    Skipped,
+}
+
+impl ShowItemState {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "unread" => Some(ShowItemState::Unread),
+            "read" => Some(ShowItemState::Read),
+            "skipped" => Some(ShowItemState::Skipped),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ShowItemState::Unread => "unread",
+            ShowItemState::Read => "read",
+            ShowItemState::Skipped => "skipped",
+        }
+    }
+}
+
+//A boost in a show's queue and whether it's been dealt with
+#[derive(Serialize, Debug, Clone)]
+pub struct ShowItem {
+    pub session: u64,
+    pub state: ShowItemState,
+    pub updated: Option<i64>,
+    pub boost: BoostRecord,
+}
+
+//Sessions and the state of their boosts. Queues aren't stored - they're the boosts in the session's
+//time window - so only boosts that have been read or skipped have a row in show_items.
+pub fn create_show_tables(conn: &Connection) -> Result<bool, Box<dyn Error>> {
+    match conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS show_sessions (
+             idx integer primary key,
+             podcast text,
+             episode text,
+             started integer not null,
+             ended integer
+         );
+         CREATE TABLE IF NOT EXISTS show_items (
+             session_idx integer not null,
+             boost_idx integer not null,
+             state text not null,
+             updated integer not null,
+             PRIMARY KEY (session_idx, boost_idx)
+         );"
+    ) {
+        Ok(_) => {
+            println!("Show session tables are ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database show session tables.".into())))
+        }
+    }
+
+    Ok(true)
+}
+
+const SESSION_COLUMNS: &str = "idx, podcast, episode, started, ended";
+
+fn session_from_row(row: &Row) -> Result<ShowSessionRecord, rusqlite::Error> {
+    Ok(ShowSessionRecord {
+        index: row.get(0)?,
+        podcast: row.get(1)?,
+        episode: row.get(2)?,
+        started: row.get(3)?,
+        ended: row.get(4)?,
+    })
+}
+
+fn load_session(conn: &Connection, index: u64) -> Result<Option<ShowSessionRecord>, Box<dyn Error>> {
+    let session = conn.query_row(
+        &format!("SELECT {} FROM show_sessions WHERE idx = ?1", SESSION_COLUMNS),
+        params![index],
+        session_from_row,
+    ).optional()?;
+
+    Ok(session)
+}
+
+fn load_active_session(conn: &Connection) -> Result<Option<ShowSessionRecord>, Box<dyn Error>> {
+    let session = conn.query_row(
+        &format!("SELECT {} FROM show_sessions WHERE ended IS NULL ORDER BY idx DESC LIMIT 1", SESSION_COLUMNS),
+        [],
+        session_from_row,
+    ).optional()?;
+
+    Ok(session)
+}
+
+//Start a show session. Only one can run at a time.
+pub fn start_show_session_in_db(filepath: &str, podcast: Option<String>, episode: Option<String>) -> Result<ShowSessionRecord, Box<dyn Error>> {
+    let mut conn = connect_to_database(false, filepath)?;
+    let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+
+    if let Some(active) = load_active_session(&tx)? {
+        return Err(Box::new(HydraError(format!("Show session {} is still running.", active.index))));
+    }
+
+    let podcast = podcast.map(|podcast| podcast.trim().to_string()).filter(|podcast| !podcast.is_empty());
+    let episode = episode.map(|episode| episode.trim().to_string()).filter(|episode| !episode.is_empty());
+    let started = chrono::Utc::now().timestamp();
+
+    tx.execute(
+        "INSERT INTO show_sessions (podcast, episode, started) VALUES (?1, ?2, ?3)",
+        params![podcast, episode, started],
+    )?;
+
+    let index = tx.last_insert_rowid() as u64;
+    tx.commit()?;
+
+    Ok(ShowSessionRecord {
+        index,
+        podcast,
+        episode,
+        started,
+        ended: None,
+    })
+}
+
+//Stop a show session. Stopping one that has already ended leaves it as it was.
+pub fn stop_show_session_in_db(filepath: &str, index: u64) -> Result<Option<ShowSessionRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    conn.execute(
+        "UPDATE show_sessions SET ended = ?2 WHERE idx = ?1 AND ended IS NULL",
+        params![index, chrono::Utc::now().timestamp()],
+    )?;
+
+    load_session(&conn, index)
+}
+
+//Remove a show session and the state of its queue. The boosts themselves are kept.
+pub fn delete_show_session_from_db(filepath: &str, index: u64) -> Result<bool, Box<dyn Error>> {
+    let mut conn = connect_to_database(false, filepath)?;
+    let tx = conn.transaction()?;
+
+    tx.execute("DELETE FROM show_items WHERE session_idx = ?1", params![index])?;
+    let deleted = tx.execute("DELETE FROM show_sessions WHERE idx = ?1", params![index])?;
+
+    tx.commit()?;
+
+    Ok(deleted > 0)
+}
+
+pub fn get_show_session_from_db(filepath: &str, index: u64) -> Result<Option<ShowSessionRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    load_session(&conn, index)
+}
+
+pub fn get_active_show_session_from_db(filepath: &str) -> Result<Option<ShowSessionRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    load_active_session(&conn)
+}
+
+//Get the show sessions, newest first
+pub fn get_show_sessions_from_db(filepath: &str, max: u64) -> Result<Vec<ShowSessionRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT {} FROM show_sessions ORDER BY idx DESC LIMIT ?1",
+        SESSION_COLUMNS
+    ))?;
+
+    let rows = stmt.query_map(params![max], session_from_row)?;
+    let mut sessions = Vec::new();
+
+    for session in rows {
+        sessions.push(session?);
+    }
+
+    Ok(sessions)
+}
+
+//Reads the boosts in a session's queue, oldest first, or just the one boost if an index is given
+fn load_items(conn: &Connection, session: &ShowSessionRecord, boost_index: Option<u64>, escape_html: bool) -> Result<Vec<ShowItem>, Box<dyn Error>> {
+    let mut clause = build_filter_clause(&session.filters(), false);
+
+    //Only boosts go in the queue, and quarantined ones wait until they're let through
+    clause.conditions.push(format!("list_type = {}", ListType::Boost as u8));
+    clause.conditions.push("COALESCE(quarantined, 0) = 0".to_string());
+
+    if let Some(boost_index) = boost_index {
+        clause.conditions.push("idx = CAST(:boost_idx AS INTEGER)".to_string());
+        clause.bindings.push((":boost_idx".to_string(), boost_index.to_string()));
+    }
+
+    clause.bindings.push((":session".to_string(), session.index.to_string()));
+
+    let sqltxt = format!(
+        "SELECT
+            {},
+            items.state,
+            items.updated
+        FROM
+            boosts
+        LEFT JOIN
+            show_items AS items ON items.session_idx = CAST(:session AS INTEGER) AND items.boost_idx = boosts.idx
+        WHERE
+            {}
+        ORDER BY
+            time, idx
+        ",
+        BOOST_COLUMNS,
+        clause.to_sql()
+    );
+
+    let mut stmt = conn.prepare_cached(&sqltxt)?;
+
+    let bindings: HashMap<&str, &str> = clause.bindings.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+
+    for (name, value) in &bindings {
+        bind_query_param(&mut stmt, name, value)?;
+    }
+
+    let mut rows = stmt.raw_query();
+    let mut items = Vec::new();
+
+    while let Some(row) = rows.next()? {
+        let boost = boost_from_row(row)?;
+        let state: Option<String> = row.get(20)?;
+
+        items.push(ShowItem {
+            session: session.index,
+            state: state.as_deref().and_then(ShowItemState::from_name).unwrap_or_default(),
+            updated: row.get(21)?,
+            boost: if escape_html { boost.escaped_for_html() } else { boost },
+        });
+    }
+
+    Ok(items)
+}
+
+//Get the boosts in a session's queue, optionally only those in one state
+pub fn get_show_items_from_db(filepath: &str, session: &ShowSessionRecord, state: Option<ShowItemState>, escape_html: bool) -> Result<Vec<ShowItem>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut items = load_items(&conn, session, None, escape_html)?;
+
+    if let Some(state) = state {
+        items.retain(|item| item.state == state);
+    }
+
+    Ok(items)
+}
+
+//Get a boost from a session's queue. Returns None if the boost isn't in the queue.
+pub fn get_show_item_from_db(filepath: &str, session: &ShowSessionRecord, boost_index: u64, escape_html: bool) -> Result<Option<ShowItem>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    Ok(load_items(&conn, session, Some(boost_index), escape_html)?.pop())
+}
+
+//Mark a boost in a session's queue as read, skipped or back to unread. Returns None if the boost isn't in the queue.
+pub fn set_show_item_state_in_db(filepath: &str, session: &ShowSessionRecord, boost_index: u64, state: ShowItemState, escape_html: bool) -> Result<Option<ShowItem>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    if load_items(&conn, session, Some(boost_index), false)?.is_empty() {
+        return Ok(None);
+    }
+
+    if state == ShowItemState::Unread {
+        conn.execute(
+            "DELETE FROM show_items WHERE session_idx = ?1 AND boost_idx = ?2",
+            params![session.index, boost_index],
+        )?;
+    } else {
+        conn.execute(
+            "INSERT INTO show_items (session_idx, boost_idx, state, updated) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (session_idx, boost_idx) DO UPDATE SET state = excluded.state, updated = excluded.updated",
+            params![session.index, boost_index, state.name(), chrono::Utc::now().timestamp()],
+        )?;
+    }
+
+    Ok(load_items(&conn, session, Some(boost_index), escape_html)?.pop())
+}
@@ -0,0 +1,218 @@
+use std::error::Error;
+use std::collections::{BTreeMap, HashMap};
+use chrono::{DateTime, Datelike, Duration, TimeZone};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    connect_to_database,
+    bind_query_param,
+    build_filter_clause,
+    retention::received_with_rollups,
+    BoostFilters,
+    ListType,
+};
+
+//Width of the slots amounts are summed into before they're grouped into local days, weeks or months.
+//Every timezone offset is a multiple of 15 minutes so slots never straddle a local midnight.
+const TIME_SLOT_SECONDS: i64 = 15 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsGroup {
+    Podcast,
+    Episode,
+    App,
+    Sender,
+    Hour,
+    Day,
+    Week, // weeks start on Monday
+    Month,
+    Year,
+}
+
+impl StatsGroup {
+    fn is_time(&self) -> bool {
+        matches!(self, StatsGroup::Hour | StatsGroup::Day | StatsGroup::Week | StatsGroup::Month | StatsGroup::Year)
+    }
+
+    //Returns the name of the local time bucket the timestamp falls in
+    fn time_bucket(&self, time: DateTime<Tz>) -> String {
+        match self {
+            StatsGroup::Hour => time.format("%Y-%m-%dT%H:00").to_string(),
+            StatsGroup::Week => {
+                let monday = time.date_naive() - Duration::days(time.weekday().num_days_from_monday() as i64);
+                monday.format("%Y-%m-%d").to_string()
+            }
+            StatsGroup::Month => time.format("%Y-%m").to_string(),
+            StatsGroup::Year => time.format("%Y").to_string(),
+            _ => time.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct StatsRecord {
+    pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub podcast: Option<String>, // set when grouping by episode
+    pub count: u64,
+    pub value_msat: i64,
+    pub value_msat_total: i64,
+    pub split_percentage: Option<f64>, // value_msat as a percentage of value_msat_total, for items that have a total
+    #[serde(skip)]
+    split_msat: i64,
+    #[serde(skip)]
+    split_msat_total: i64,
+}
+
+impl StatsRecord {
+    fn add(&mut self, other: &StatsRecord) {
+        self.count += other.count;
+        self.value_msat += other.value_msat;
+        self.value_msat_total += other.value_msat_total;
+        self.split_msat += other.split_msat;
+        self.split_msat_total += other.split_msat_total;
+    }
+
+    fn finish(mut self) -> Self {
+        if self.split_msat_total > 0 {
+            let percentage = self.split_msat as f64 / self.split_msat_total as f64 * 100.0;
+            self.split_percentage = Some((percentage * 100.0).round() / 100.0);
+        }
+
+        self
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct StatsSummary {
+    pub group: StatsGroup,
+    pub timezone: String,
+    pub total: StatsRecord,
+    pub groups: Vec<StatsRecord>,
+}
+
+//Totals the boosts, streams or sent boosts matching the filters, grouped by a field or by local time buckets in the timezone.
+//Received boosts and streams are totalled together when no list is given.
+pub fn get_stats_from_db(filepath: &str, list: Option<ListType>, group: StatsGroup, timezone: Tz, filters: &BoostFilters) -> Result<StatsSummary, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let sent = list == Some(ListType::Sent);
+
+    //Streams removed by the retention policy still count through their rollups
+    let (table, row_count) = if sent {
+        ("sent_boosts".to_string(), "COUNT(*)")
+    } else {
+        (received_with_rollups(), "SUM(row_count)")
+    };
+
+    let mut clause = build_filter_clause(filters, sent);
+
+    match list {
+        Some(ListType::Boost) | Some(ListType::Stream) => {
+            clause.conditions.push(format!("list_type = {}", list.unwrap() as u8));
+        }
+        _ => {}
+    }
+
+    let time_slot = format!("CAST(COALESCE(time, 0) / {} AS TEXT)", TIME_SLOT_SECONDS);
+
+    let (key, podcast) = match group {
+        StatsGroup::Podcast => ("COALESCE(podcast, '')", "NULL"),
+        StatsGroup::Episode => ("COALESCE(episode, '')", "COALESCE(podcast, '')"),
+        StatsGroup::App => ("COALESCE(app, '')", "NULL"),
+        StatsGroup::Sender => ("COALESCE(sender, '')", "NULL"),
+        _ => (time_slot.as_str(), "NULL"),
+    };
+
+    let sqltxt = format!(
+        "SELECT
+            {key} AS stats_key,
+            {podcast} AS stats_podcast,
+            {row_count},
+            SUM(COALESCE(value_msat, 0)),
+            SUM(COALESCE(value_msat_total, 0)),
+            SUM(CASE WHEN value_msat_total > 0 THEN COALESCE(value_msat, 0) ELSE 0 END),
+            SUM(CASE WHEN value_msat_total > 0 THEN value_msat_total ELSE 0 END)
+        FROM
+            {table}
+        WHERE
+            {conditions}
+        GROUP BY
+            stats_key, stats_podcast
+        ",
+        key = key,
+        podcast = podcast,
+        row_count = row_count,
+        table = table,
+        conditions = clause.to_sql(),
+    );
+
+    //Prepare and execute the query
+    let mut stmt = conn.prepare_cached(sqltxt.as_str())?;
+
+    let bindings: HashMap<&str, &str> = clause.bindings.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+
+    for (name, value) in &bindings {
+        bind_query_param(&mut stmt, name, value)?;
+    }
+
+    let mut rows = stmt.raw_query();
+    let mut records: Vec<StatsRecord> = Vec::new();
+
+    while let Some(row) = rows.next()? {
+        records.push(StatsRecord {
+            key: row.get(0)?,
+            podcast: row.get(1)?,
+            count: row.get(2)?,
+            value_msat: row.get(3)?,
+            value_msat_total: row.get(4)?,
+            split_percentage: None,
+            split_msat: row.get(5)?,
+            split_msat_total: row.get(6)?,
+        });
+    }
+
+    let mut total = StatsRecord {
+        key: "total".to_string(),
+        ..Default::default()
+    };
+
+    for record in &records {
+        total.add(record);
+    }
+
+    let groups: Vec<StatsRecord> = if group.is_time() {
+        //Fold the time slots into local time buckets, which sort chronologically by name
+        let mut buckets: BTreeMap<String, StatsRecord> = BTreeMap::new();
+
+        for record in records {
+            let slot: i64 = record.key.parse().unwrap_or_default();
+
+            let time = match timezone.timestamp_opt(slot * TIME_SLOT_SECONDS, 0).single() {
+                Some(time) => time,
+                None => continue,
+            };
+
+            let key = group.time_bucket(time);
+
+            buckets.entry(key.clone())
+                .or_insert_with(|| StatsRecord { key, ..Default::default() })
+                .add(&record);
+        }
+
+        buckets.into_values().map(StatsRecord::finish).collect()
+    } else {
+        //Biggest totals first
+        records.sort_by(|a, b| b.value_msat_total.cmp(&a.value_msat_total).then(b.count.cmp(&a.count)));
+        records.into_iter().map(StatsRecord::finish).collect()
+    };
+
+    Ok(StatsSummary {
+        group,
+        timezone: timezone.name().to_string(),
+        total: total.finish(),
+        groups,
+    })
+}
@@ -0,0 +1,579 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use std::error::Error;
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    connect_to_database,
+    bind_query_param,
+    build_filter_clause,
+    BoostFilters,
+    BoostRecord,
+    HydraError,
+    ListType,
+};
+
+//Longest supporter name and note that can be saved
+const MAX_NAME_LENGTH: usize = 100;
+const MAX_NOTE_LENGTH: usize = 4000;
+
+//Sender names apps use when the listener didn't give one. These don't say who sent the boost.
+const ANONYMOUS_SENDERS: [&str; 4] = ["anonymous", "anon", "unknown", "someone"];
+
+//Something that identifies who sent a boost
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SupporterIdentity {
+    pub kind: String, // sender, node or wallet
+    pub value: String,
+}
+
+//A listener who sends boosts and streams, with their totals across every identity they've used
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SupporterRecord {
+    pub index: u64,
+    pub name: String,
+    pub note: Option<String>,
+    pub thanked: bool,
+    pub identities: Vec<SupporterIdentity>,
+    pub first_seen: Option<i64>,
+    pub last_seen: Option<i64>,
+    pub boosts: u64,
+    pub streams: u64,
+    pub value_msat: i64,
+    pub value_msat_total: i64,
+    pub replied: u64, // boosts a reply was sent to
+    pub favourite_app: Option<String>,
+}
+
+//Changes to make to a supporter. Fields left out are kept as they are.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct SupporterUpdate {
+    pub name: Option<String>,
+    pub note: Option<String>, // an empty note removes it
+    pub thanked: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SupporterSort {
+    #[default]
+    Recent, // most recently seen first
+    Total, // most sats first
+    Boosts, // most boosts first
+    Name,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SupporterQuery {
+    pub list: Option<ListType>, // only count boosts or streams (both if not set)
+    pub filters: BoostFilters, // only count the boosts matching these
+    pub search: Option<String>, // name or identity containing this
+    pub sort: SupporterSort,
+    pub limit: u64,
+    pub offset: u64,
+}
+
+//Supporters, the identities that point to them and which supporter sent each received boost. The boost rows
+//stay as LND gave them, so merging supporters only has to move identities and links.
+pub fn create_supporter_tables(conn: &Connection) -> Result<bool, Box<dyn Error>> {
+    match conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS supporters (
+             idx integer primary key,
+             name text not null,
+             note text,
+             thanked integer not null default 0,
+             created integer not null
+         );
+         CREATE TABLE IF NOT EXISTS supporter_identities (
+             kind text not null,
+             value text not null COLLATE NOCASE,
+             supporter_idx integer not null,
+             PRIMARY KEY (kind, value)
+         );
+         CREATE INDEX IF NOT EXISTS supporter_identities_supporter ON supporter_identities (supporter_idx);
+         CREATE TABLE IF NOT EXISTS supporter_boosts (
+             boost_idx integer primary key,
+             supporter_idx integer not null
+         );
+         CREATE INDEX IF NOT EXISTS supporter_boosts_supporter ON supporter_boosts (supporter_idx);"
+    ) {
+        Ok(_) => {
+            println!("Supporter tables are ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database supporter tables.".into())))
+        }
+    }
+
+    Ok(true)
+}
+
+//Creates the supporter tables and links every boost already received
+pub(crate) fn migrate_supporters(conn: &Connection) -> Result<(), Box<dyn Error>> {
+    create_supporter_tables(conn)?;
+
+    let linked = link_unlinked_boosts(conn)?;
+    println!("Linked {} boost(s) to supporters.", linked);
+
+    Ok(())
+}
+
+//Works out who sent a boost, strongest identity first. The sender's wallet comes from the reply address and
+//reply custom records their app includes - the boost's own custom key and value are the wallet it was sent to.
+fn boost_identities(sender: &str, tlv: &str) -> Vec<SupporterIdentity> {
+    let mut identities = Vec::new();
+    let tlv: Value = serde_json::from_str(tlv).unwrap_or_default();
+
+    let text = |field: &str| match &tlv[field] {
+        Value::String(text) => text.trim().to_string(),
+        Value::Number(number) => number.to_string(),
+        _ => "".to_string(),
+    };
+
+    let (address, custom_key, custom_value) = (text("reply_address"), text("reply_custom_key"), text("reply_custom_value"));
+
+    if !address.is_empty() {
+        //Custodial wallets share one node, so the node only identifies the sender when there's no wallet id
+        if !custom_key.is_empty() && !custom_value.is_empty() {
+            identities.push(SupporterIdentity {
+                kind: "wallet".to_string(),
+                value: format!("{}/{}/{}", address, custom_key, custom_value),
+            });
+        } else {
+            identities.push(SupporterIdentity {
+                kind: "node".to_string(),
+                value: address,
+            });
+        }
+    }
+
+    let sender = sender.trim();
+
+    if !sender.is_empty() && !ANONYMOUS_SENDERS.contains(&sender.to_lowercase().as_str()) {
+        identities.push(SupporterIdentity {
+            kind: "sender".to_string(),
+            value: sender.to_string(),
+        });
+    }
+
+    identities
+}
+
+//Links a boost to the supporter with the strongest matching identity, creating one if none match. The boost's
+//other identities are added to the supporter unless they already belong to someone else.
+fn link_boost(conn: &Connection, boost_index: u64, sender: &str, tlv: &str) -> Result<Option<u64>, Box<dyn Error>> {
+    let linked: Option<u64> = conn.query_row(
+        "SELECT supporter_idx FROM supporter_boosts WHERE boost_idx = ?1",
+        params![boost_index],
+        |row| row.get(0),
+    ).optional()?;
+
+    if linked.is_some() {
+        return Ok(linked);
+    }
+
+    let identities = boost_identities(sender, tlv);
+
+    if identities.is_empty() {
+        return Ok(None);
+    }
+
+    let mut supporter: Option<u64> = None;
+
+    for identity in &identities {
+        supporter = conn.query_row(
+            "SELECT supporter_idx FROM supporter_identities WHERE kind = ?1 AND value = ?2",
+            params![identity.kind, identity.value],
+            |row| row.get(0),
+        ).optional()?;
+
+        if supporter.is_some() {
+            break;
+        }
+    }
+
+    let supporter = match supporter {
+        Some(supporter) => supporter,
+        None => {
+            //Named after the sender if they gave a name, otherwise the start of their node or wallet
+            let name = identities.iter()
+                .find(|identity| identity.kind == "sender")
+                .map(|identity| identity.value.clone())
+                .unwrap_or_else(|| format!("{}…", identities[0].value.chars().take(16).collect::<String>()));
+
+            conn.execute(
+                "INSERT INTO supporters (name, created) VALUES (?1, ?2)",
+                params![name, chrono::Utc::now().timestamp()],
+            )?;
+
+            conn.last_insert_rowid() as u64
+        }
+    };
+
+    for identity in &identities {
+        conn.execute(
+            "INSERT OR IGNORE INTO supporter_identities (kind, value, supporter_idx) VALUES (?1, ?2, ?3)",
+            params![identity.kind, identity.value, supporter],
+        )?;
+    }
+
+    conn.execute(
+        "INSERT INTO supporter_boosts (boost_idx, supporter_idx) VALUES (?1, ?2)",
+        params![boost_index, supporter],
+    )?;
+
+    Ok(Some(supporter))
+}
+
+//Links every received boost that isn't linked to a supporter yet
+pub(crate) fn link_unlinked_boosts(conn: &Connection) -> Result<u64, Box<dyn Error>> {
+    let mut stmt = conn.prepare(
+        "SELECT idx, COALESCE(sender, ''), COALESCE(tlv, '') FROM boosts
+         WHERE NOT EXISTS (SELECT 1 FROM supporter_boosts WHERE supporter_boosts.boost_idx = boosts.idx)
+         ORDER BY idx"
+    )?;
+
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, u64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?;
+    let mut linked = 0;
+
+    for row in rows {
+        let (index, sender, tlv) = row?;
+
+        if link_boost(conn, index, &sender, &tlv)?.is_some() {
+            linked += 1;
+        }
+    }
+
+    Ok(linked)
+}
+
+//Link a received boost to its supporter. Returns the supporter's index, or None if the boost doesn't say who sent it.
+pub fn link_boost_to_supporter_in_db(filepath: &str, boost: &BoostRecord) -> Result<Option<u64>, Box<dyn Error>> {
+    let mut conn = connect_to_database(false, filepath)?;
+    let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+
+    let supporter = link_boost(&tx, boost.index, &boost.sender, &boost.tlv)?;
+    tx.commit()?;
+
+    Ok(supporter)
+}
+
+//Link every received boost that isn't linked to a supporter yet, like ones added by a rescan
+pub fn link_supporters_in_db(filepath: &str) -> Result<u64, Box<dyn Error>> {
+    let mut conn = connect_to_database(false, filepath)?;
+    let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+
+    let linked = link_unlinked_boosts(&tx)?;
+    tx.commit()?;
+
+    Ok(linked)
+}
+
+//Totals the supporters' boosts matching the query. Supporters with no matching boosts are left out.
+fn query_supporters(conn: &Connection, query: &SupporterQuery, supporter: Option<u64>) -> Result<Vec<SupporterRecord>, Box<dyn Error>> {
+    let mut clause = build_filter_clause(&query.filters, false);
+
+    match query.list {
+        Some(ListType::Boost) | Some(ListType::Stream) => {
+            clause.conditions.push(format!("list_type = {}", query.list.unwrap() as u8));
+        }
+        _ => {}
+    }
+
+    //The filters name the boost columns, so they're applied before joining the supporters
+    let mut conditions = vec!["1".to_string()];
+
+    if let Some(supporter) = supporter {
+        conditions.push("supporters.idx = CAST(:supporter_idx AS INTEGER)".to_string());
+        clause.bindings.push((":supporter_idx".to_string(), supporter.to_string()));
+    }
+
+    if let Some(search) = query.search.as_ref().map(|search| search.trim()).filter(|search| !search.is_empty()) {
+        conditions.push(
+            "(supporters.name LIKE :search OR EXISTS (SELECT 1 FROM supporter_identities AS identities
+                WHERE identities.supporter_idx = supporters.idx AND identities.value LIKE :search))".to_string()
+        );
+        clause.bindings.push((":search".to_string(), format!("%{}%", search)));
+    }
+
+    let order = match query.sort {
+        SupporterSort::Recent => "MAX(boosts.time) DESC",
+        SupporterSort::Total => "SUM(COALESCE(boosts.value_msat_total, 0)) DESC",
+        SupporterSort::Boosts => "SUM(boosts.list_type = 1) DESC, SUM(COALESCE(boosts.value_msat_total, 0)) DESC",
+        SupporterSort::Name => "supporters.name COLLATE NOCASE",
+    };
+
+    let limit = if query.limit > 0 { query.limit.to_string() } else { "-1".to_string() };
+
+    let sqltxt = format!(
+        "SELECT
+            supporters.idx,
+            supporters.name,
+            supporters.note,
+            supporters.thanked,
+            MIN(boosts.time),
+            MAX(boosts.time),
+            SUM(boosts.list_type = {boost}),
+            SUM(boosts.list_type = {stream}),
+            SUM(COALESCE(boosts.value_msat, 0)),
+            SUM(COALESCE(boosts.value_msat_total, 0)),
+            SUM(COALESCE(boosts.reply_sent, 0))
+        FROM
+            (SELECT idx, time, list_type, value_msat, value_msat_total, reply_sent FROM boosts WHERE {filters}) AS boosts
+        JOIN
+            supporter_boosts ON supporter_boosts.boost_idx = boosts.idx
+        JOIN
+            supporters ON supporters.idx = supporter_boosts.supporter_idx
+        WHERE
+            {conditions}
+        GROUP BY
+            supporters.idx
+        ORDER BY
+            {order}, supporters.idx
+        LIMIT {limit} OFFSET {offset}
+        ",
+        boost = ListType::Boost as u8,
+        stream = ListType::Stream as u8,
+        filters = clause.to_sql(),
+        conditions = conditions.join(" AND "),
+        order = order,
+        limit = limit,
+        offset = query.offset,
+    );
+
+    let mut stmt = conn.prepare_cached(&sqltxt)?;
+
+    let bindings: HashMap<&str, &str> = clause.bindings.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+
+    for (name, value) in &bindings {
+        bind_query_param(&mut stmt, name, value)?;
+    }
+
+    let mut rows = stmt.raw_query();
+    let mut supporters = Vec::new();
+
+    while let Some(row) = rows.next()? {
+        supporters.push(SupporterRecord {
+            index: row.get(0)?,
+            name: row.get(1)?,
+            note: row.get(2)?,
+            thanked: row.get(3)?,
+            identities: Vec::new(),
+            first_seen: row.get(4)?,
+            last_seen: row.get(5)?,
+            boosts: row.get(6)?,
+            streams: row.get(7)?,
+            value_msat: row.get(8)?,
+            value_msat_total: row.get(9)?,
+            replied: row.get(10)?,
+            favourite_app: None,
+        });
+    }
+
+    attach_details(conn, &mut supporters)?;
+
+    Ok(supporters)
+}
+
+//Fills in the identities and the app each supporter has sent the most boosts and streams from
+fn attach_details(conn: &Connection, supporters: &mut [SupporterRecord]) -> Result<(), Box<dyn Error>> {
+    if supporters.is_empty() {
+        return Ok(());
+    }
+
+    //Indexes are numbers so they're safe to put in the query as they are
+    let indexes = supporters.iter().map(|supporter| supporter.index.to_string()).collect::<Vec<String>>().join(", ");
+    let mut identities: HashMap<u64, Vec<SupporterIdentity>> = HashMap::new();
+    let mut apps: HashMap<u64, String> = HashMap::new();
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT supporter_idx, kind, value FROM supporter_identities WHERE supporter_idx IN ({}) ORDER BY kind, value",
+        indexes
+    ))?;
+    let mut rows = stmt.query([])?;
+
+    while let Some(row) = rows.next()? {
+        identities.entry(row.get(0)?).or_default().push(SupporterIdentity {
+            kind: row.get(1)?,
+            value: row.get(2)?,
+        });
+    }
+
+    //Most used app first, so the first one seen for each supporter is their favourite
+    let mut stmt = conn.prepare(&format!(
+        "SELECT supporter_boosts.supporter_idx, boosts.app
+         FROM supporter_boosts JOIN boosts ON boosts.idx = supporter_boosts.boost_idx
+         WHERE supporter_boosts.supporter_idx IN ({}) AND COALESCE(boosts.hidden, 0) = 0 AND COALESCE(boosts.app, '') != ''
+         GROUP BY 1, 2
+         ORDER BY COUNT(*) DESC, MAX(boosts.time) DESC",
+        indexes
+    ))?;
+    let mut rows = stmt.query([])?;
+
+    while let Some(row) = rows.next()? {
+        apps.entry(row.get(0)?).or_insert(row.get(1)?);
+    }
+
+    for supporter in supporters.iter_mut() {
+        supporter.identities = identities.remove(&supporter.index).unwrap_or_default();
+        supporter.favourite_app = apps.remove(&supporter.index);
+    }
+
+    Ok(())
+}
+
+//Get the supporters and their totals for the boosts matching the query. Hidden boosts aren't counted.
+pub fn get_supporters_from_db(filepath: &str, query: &SupporterQuery) -> Result<Vec<SupporterRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    query_supporters(&conn, query, None)
+}
+
+fn load_supporter(conn: &Connection, index: u64) -> Result<Option<SupporterRecord>, Box<dyn Error>> {
+    let supporter = conn.query_row(
+        "SELECT idx, name, note, thanked FROM supporters WHERE idx = ?1",
+        params![index],
+        |row| Ok(SupporterRecord {
+            index: row.get(0)?,
+            name: row.get(1)?,
+            note: row.get(2)?,
+            thanked: row.get(3)?,
+            ..Default::default()
+        }),
+    ).optional()?;
+
+    let supporter = match supporter {
+        Some(supporter) => supporter,
+        None => return Ok(None),
+    };
+
+    //Supporters whose boosts have all been hidden still have a profile
+    match query_supporters(conn, &SupporterQuery::default(), Some(index))?.pop() {
+        Some(totals) => Ok(Some(totals)),
+        None => {
+            let mut supporters = vec![supporter];
+            attach_details(conn, &mut supporters)?;
+            Ok(supporters.pop())
+        }
+    }
+}
+
+//Get a supporter's profile
+pub fn get_supporter_from_db(filepath: &str, index: u64) -> Result<Option<SupporterRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    load_supporter(&conn, index)
+}
+
+//Change a supporter's name, note or thanked flag. Returns None if there's no such supporter.
+pub fn update_supporter_in_db(filepath: &str, index: u64, update: &SupporterUpdate) -> Result<Option<SupporterRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    if let Some(name) = &update.name {
+        let name = name.trim();
+
+        if name.is_empty() {
+            return Err(Box::new(HydraError("Name can't be empty.".into())));
+        }
+
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return Err(Box::new(HydraError(format!("Name is longer than {} characters.", MAX_NAME_LENGTH))));
+        }
+
+        conn.execute("UPDATE supporters SET name = ?2 WHERE idx = ?1", params![index, name])?;
+    }
+
+    if let Some(note) = &update.note {
+        let note = note.trim();
+
+        if note.chars().count() > MAX_NOTE_LENGTH {
+            return Err(Box::new(HydraError(format!("Note is longer than {} characters.", MAX_NOTE_LENGTH))));
+        }
+
+        let note = Some(note).filter(|note| !note.is_empty());
+        conn.execute("UPDATE supporters SET note = ?2 WHERE idx = ?1", params![index, note])?;
+    }
+
+    if let Some(thanked) = update.thanked {
+        conn.execute("UPDATE supporters SET thanked = ?2 WHERE idx = ?1", params![index, thanked])?;
+    }
+
+    load_supporter(&conn, index)
+}
+
+//Merge other supporters into one, for listeners who've boosted from more than one app. Their identities, boosts
+//and notes move over and the merged supporters are removed. Returns None if the supporter to merge into doesn't exist.
+pub fn merge_supporters_in_db(filepath: &str, index: u64, others: &[u64]) -> Result<Option<SupporterRecord>, Box<dyn Error>> {
+    let mut conn = connect_to_database(false, filepath)?;
+    let tx = conn.transaction()?;
+
+    let (mut note, mut thanked) = match tx.query_row(
+        "SELECT note, thanked FROM supporters WHERE idx = ?1",
+        params![index],
+        |row| Ok((row.get::<_, Option<String>>(0)?, row.get::<_, bool>(1)?)),
+    ).optional()? {
+        Some(supporter) => supporter,
+        None => return Ok(None),
+    };
+
+    for &other in others.iter().filter(|&&other| other != index) {
+        let (other_note, other_thanked) = match tx.query_row(
+            "SELECT note, thanked FROM supporters WHERE idx = ?1",
+            params![other],
+            |row| Ok((row.get::<_, Option<String>>(0)?, row.get::<_, bool>(1)?)),
+        ).optional()? {
+            Some(supporter) => supporter,
+            None => return Err(Box::new(HydraError(format!("Supporter {} not found.", other)))),
+        };
+
+        note = match (note, other_note) {
+            (Some(note), Some(other_note)) => Some(format!("{}\n\n{}", note, other_note)),
+            (note, other_note) => note.or(other_note),
+        };
+        thanked = thanked || other_thanked;
+
+        tx.execute("UPDATE supporter_identities SET supporter_idx = ?1 WHERE supporter_idx = ?2", params![index, other])?;
+        tx.execute("UPDATE supporter_boosts SET supporter_idx = ?1 WHERE supporter_idx = ?2", params![index, other])?;
+        tx.execute("DELETE FROM supporters WHERE idx = ?1", params![other])?;
+    }
+
+    tx.execute("UPDATE supporters SET note = ?2, thanked = ?3 WHERE idx = ?1", params![index, note, thanked])?;
+    tx.commit()?;
+
+    let conn = connect_to_database(false, filepath)?;
+    load_supporter(&conn, index)
+}
+
+//Fill in the supporter of each received boost
+pub fn attach_supporters_from_db(filepath: &str, boosts: &mut [BoostRecord]) -> Result<(), Box<dyn Error>> {
+    let indexes: Vec<String> = boosts.iter()
+        .filter(|boost| boost.list_type != ListType::Sent)
+        .map(|boost| boost.index.to_string())
+        .collect();
+
+    if indexes.is_empty() {
+        return Ok(());
+    }
+
+    let conn = connect_to_database(false, filepath)?;
+
+    //Indexes are numbers so they're safe to put in the query as they are
+    let mut stmt = conn.prepare(&format!(
+        "SELECT boost_idx, supporter_idx FROM supporter_boosts WHERE boost_idx IN ({})",
+        indexes.join(", ")
+    ))?;
+
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, u64>(0)?, row.get::<_, u64>(1)?)))?;
+    let mut supporters: HashMap<u64, u64> = HashMap::new();
+
+    for row in rows {
+        let (boost, supporter) = row?;
+        supporters.insert(boost, supporter);
+    }
+
+    for boost in boosts.iter_mut().filter(|boost| boost.list_type != ListType::Sent) {
+        boost.supporter = supporters.get(&boost.index).copied();
+    }
+
+    Ok(())
+}
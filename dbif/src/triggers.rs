@@ -13,6 +13,7 @@ pub struct TriggerRecord {
     pub on_auto: bool,
     pub on_sent: bool,
     pub on_invoice: bool,
+    pub on_lockout: bool,
     pub amount: Option<u64>,
     pub amount_equality: Option<String>,
     pub sender: Option<String>,
@@ -21,6 +22,8 @@ pub struct TriggerRecord {
     pub app_equality: Option<String>,
     pub podcast: Option<String>,
     pub podcast_equality: Option<String>,
+    pub numerology: Option<String>,
+    pub numerology_equality: Option<String>,
     pub sound_file: Option<String>,
     pub sound_name: Option<String>,
     pub webhook_url: Option<String>,
@@ -50,6 +53,7 @@ impl Default for TriggerRecord {
             on_auto: false,
             on_sent: false,
             on_invoice: false,
+            on_lockout: false,
             amount: None,
             amount_equality: None,
             sender: None,
@@ -58,6 +62,8 @@ impl Default for TriggerRecord {
             app_equality: None,
             podcast: None,
             podcast_equality: None,
+            numerology: None,
+            numerology_equality: None,
             sound_file: None,
             sound_name: None,
             webhook_url: None,
@@ -128,7 +134,7 @@ pub fn get_triggers_from_db(filepath: &str) -> Result<Vec<TriggerRecord>, Box<dy
     let conn = connect_to_database(false, filepath)?;
     let mut results: Vec<TriggerRecord> = Vec::new();
 
-    let mut stmt = conn.prepare(
+    let mut stmt = conn.prepare_cached(
         r#"SELECT
              idx,
              position,
@@ -161,7 +167,10 @@ pub fn get_triggers_from_db(filepath: &str) -> Result<Vec<TriggerRecord>, Box<dy
              midi_note,
              midi_velocity,
              midi_channel,
-             midi_duration
+             midi_duration,
+             numerology,
+             numerology_equality,
+             on_lockout
         FROM
             triggers
         ORDER BY
@@ -202,6 +211,9 @@ pub fn get_triggers_from_db(filepath: &str) -> Result<Vec<TriggerRecord>, Box<dy
             midi_velocity: row.get(29).ok(),
             midi_channel: row.get(30).ok(),
             midi_duration: row.get(31).ok(),
+            numerology: row.get(32).ok(),
+            numerology_equality: row.get(33).ok(),
+            on_lockout: row.get(34).unwrap_or(false),
         })
     }).unwrap();
 
@@ -215,7 +227,7 @@ pub fn get_triggers_from_db(filepath: &str) -> Result<Vec<TriggerRecord>, Box<dy
 pub fn load_trigger_from_db(filepath: &str, index: u64) -> Result<TriggerRecord, Box<dyn Error>> {
     let conn = connect_to_database(false, filepath)?;
 
-    let mut stmt = conn.prepare(
+    let mut stmt = conn.prepare_cached(
         r#"SELECT
             idx,
             position,
@@ -248,7 +260,10 @@ pub fn load_trigger_from_db(filepath: &str, index: u64) -> Result<TriggerRecord,
             midi_note,
             midi_velocity,
             midi_channel,
-            midi_duration
+            midi_duration,
+            numerology,
+            numerology_equality,
+            on_lockout
         FROM
             triggers
         WHERE
@@ -290,6 +305,9 @@ pub fn load_trigger_from_db(filepath: &str, index: u64) -> Result<TriggerRecord,
             midi_velocity: row.get(29).ok(),
             midi_channel: row.get(30).ok(),
             midi_duration: row.get(31).ok(),
+            numerology: row.get(32).ok(),
+            numerology_equality: row.get(33).ok(),
+            on_lockout: row.get(34).unwrap_or(false),
         })
     })?;
 
@@ -307,7 +325,7 @@ pub fn save_trigger_to_db(filepath: &str, trigger: &TriggerRecord) -> Result<u64
 
     set_trigger_position_in_db(filepath, trigger.index, trigger.position)?;
 
-    let mut stmt = conn.prepare(
+    let mut stmt = conn.prepare_cached(
         r#"INSERT INTO triggers (
             idx,
             position,
@@ -340,10 +358,13 @@ pub fn save_trigger_to_db(filepath: &str, trigger: &TriggerRecord) -> Result<u64
             midi_note,
             midi_velocity,
             midi_channel,
-            midi_duration
+            midi_duration,
+            numerology,
+            numerology_equality,
+            on_lockout
         )
         VALUES
-            (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32)
+            (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32, ?33, ?34, ?35)
         ON CONFLICT(idx) DO UPDATE SET
             position = excluded.position,
             enabled = excluded.enabled,
@@ -375,7 +396,10 @@ pub fn save_trigger_to_db(filepath: &str, trigger: &TriggerRecord) -> Result<u64
             midi_note = excluded.midi_note,
             midi_velocity = excluded.midi_velocity,
             midi_channel = excluded.midi_channel,
-            midi_duration = excluded.midi_duration
+            midi_duration = excluded.midi_duration,
+            numerology = excluded.numerology,
+            numerology_equality = excluded.numerology_equality,
+            on_lockout = excluded.on_lockout
         RETURNING idx
         "#,
     )?;
@@ -412,7 +436,10 @@ pub fn save_trigger_to_db(filepath: &str, trigger: &TriggerRecord) -> Result<u64
         trigger.midi_note,
         trigger.midi_velocity,
         trigger.midi_channel,
-        trigger.midi_duration
+        trigger.midi_duration,
+        trigger.numerology,
+        trigger.numerology_equality,
+        trigger.on_lockout
     ];
 
     let idx = stmt.query_row(params, |row| {
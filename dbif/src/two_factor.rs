@@ -0,0 +1,169 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use std::error::Error;
+use serde::{Deserialize, Serialize};
+
+use crate::{connect_to_database, HydraError};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TwoFactorRecord {
+    pub username: String, // empty for the shared password login
+    #[serde(skip_serializing, default)]
+    pub secret: String,
+    pub enabled: bool, // false until the first code is confirmed
+    pub created: i64,
+    pub last_step: Option<i64>, // last TOTP time step used, so a code can't be used twice
+    pub recovery_codes: u64, // unused recovery codes left
+}
+
+//TOTP secrets and hashed recovery codes for each login
+pub fn create_two_factor_tables(conn: &Connection) -> Result<bool, Box<dyn Error>> {
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS two_factor (
+             username text primary key COLLATE NOCASE,
+             secret text not null,
+             enabled integer not null,
+             created integer not null,
+             last_step integer
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Two-factor table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database two_factor table.".into())))
+        }
+    }
+
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS two_factor_recovery_codes (
+             idx integer primary key,
+             username text not null COLLATE NOCASE,
+             code_hash text not null,
+             used integer
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Two-factor recovery codes table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database two_factor_recovery_codes table.".into())))
+        }
+    }
+
+    Ok(true)
+}
+
+pub fn get_two_factor_from_db(filepath: &str, username: &str) -> Result<Option<TwoFactorRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let two_factor = conn.query_row(
+        "SELECT
+             username, secret, enabled, created, last_step,
+             (SELECT COUNT(*) FROM two_factor_recovery_codes codes WHERE codes.username = two_factor.username AND used IS NULL)
+         FROM two_factor
+         WHERE username = ?1",
+        params![username],
+        |row| Ok(TwoFactorRecord {
+            username: row.get(0)?,
+            secret: row.get(1)?,
+            enabled: row.get(2)?,
+            created: row.get(3)?,
+            last_step: row.get(4)?,
+            recovery_codes: row.get(5)?,
+        }),
+    ).optional()?;
+
+    Ok(two_factor)
+}
+
+//Save a new secret that isn't turned on until a code from it is confirmed
+pub fn start_two_factor_in_db(filepath: &str, username: &str, secret: &str) -> Result<(), Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let changed = conn.execute(
+        "INSERT INTO two_factor (username, secret, enabled, created) VALUES (?1, ?2, 0, ?3)
+         ON CONFLICT(username) DO UPDATE SET secret = excluded.secret, created = excluded.created, last_step = NULL
+         WHERE enabled = 0",
+        params![username, secret, chrono::Utc::now().timestamp()],
+    )?;
+
+    if changed == 0 {
+        return Err(Box::new(HydraError("Two-factor authentication is already on.".into())));
+    }
+
+    Ok(())
+}
+
+//Turn two-factor on with its first recovery codes
+pub fn enable_two_factor_in_db(filepath: &str, username: &str, step: i64, code_hashes: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut conn = connect_to_database(false, filepath)?;
+    let tx = conn.transaction()?;
+
+    tx.execute("UPDATE two_factor SET enabled = 1, last_step = ?2 WHERE username = ?1", params![username, step])?;
+    replace_recovery_codes(&tx, username, code_hashes)?;
+
+    tx.commit()?;
+
+    Ok(())
+}
+
+fn replace_recovery_codes(conn: &Connection, username: &str, code_hashes: &[String]) -> Result<(), Box<dyn Error>> {
+    conn.execute("DELETE FROM two_factor_recovery_codes WHERE username = ?1", params![username])?;
+
+    for code_hash in code_hashes {
+        conn.execute(
+            "INSERT INTO two_factor_recovery_codes (username, code_hash) VALUES (?1, ?2)",
+            params![username, code_hash],
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn set_recovery_codes_in_db(filepath: &str, username: &str, code_hashes: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut conn = connect_to_database(false, filepath)?;
+    let tx = conn.transaction()?;
+
+    replace_recovery_codes(&tx, username, code_hashes)?;
+
+    tx.commit()?;
+
+    Ok(())
+}
+
+//Record that a TOTP time step was used. Returns false if it, or a later one, already was.
+pub fn use_two_factor_step_in_db(filepath: &str, username: &str, step: i64) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let changed = conn.execute(
+        "UPDATE two_factor SET last_step = ?2 WHERE username = ?1 AND (last_step IS NULL OR last_step < ?2)",
+        params![username, step],
+    )?;
+
+    Ok(changed > 0)
+}
+
+//Use up a recovery code. Returns false if there's no unused code with that hash.
+pub fn use_recovery_code_in_db(filepath: &str, username: &str, code_hash: &str) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let changed = conn.execute(
+        "UPDATE two_factor_recovery_codes SET used = ?3 WHERE username = ?1 AND code_hash = ?2 AND used IS NULL",
+        params![username, code_hash, chrono::Utc::now().timestamp()],
+    )?;
+
+    Ok(changed > 0)
+}
+
+pub fn delete_two_factor_from_db(filepath: &str, username: &str) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    conn.execute("DELETE FROM two_factor_recovery_codes WHERE username = ?1", params![username])?;
+    let deleted = conn.execute("DELETE FROM two_factor WHERE username = ?1", params![username])?;
+
+    Ok(deleted > 0)
+}
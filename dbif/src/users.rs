@@ -0,0 +1,219 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::error::Error;
+use serde::{Deserialize, Serialize};
+
+use crate::{connect_to_database, HydraError};
+
+//What a user is allowed to do. Each role can do everything the ones before it can.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UserRole {
+    #[default]
+    Viewer, // read boosts and stats
+    Operator, // also reply, annotate and run shows
+    Admin, // also change settings, triggers and users
+}
+
+impl UserRole {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "viewer" => Some(UserRole::Viewer),
+            "operator" => Some(UserRole::Operator),
+            "admin" => Some(UserRole::Admin),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            UserRole::Viewer => "viewer",
+            UserRole::Operator => "operator",
+            UserRole::Admin => "admin",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UserRecord {
+    pub index: u64,
+    pub username: String,
+    #[serde(skip_serializing, default)]
+    pub password_hash: String,
+    pub role: UserRole,
+    pub created: i64,
+    pub last_login: Option<i64>,
+}
+
+//User accounts. Passwords are hashed before they get here.
+pub fn create_users_table(conn: &Connection) -> Result<bool, Box<dyn Error>> {
+    match conn.execute(
+        "CREATE TABLE IF NOT EXISTS users (
+             idx integer primary key,
+             username text not null unique COLLATE NOCASE,
+             password_hash text not null,
+             role text not null,
+             created integer not null,
+             last_login integer
+         )",
+        [],
+    ) {
+        Ok(_) => {
+            println!("Users table is ready.");
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(Box::new(HydraError("Failed to create database users table.".into())))
+        }
+    }
+
+    Ok(true)
+}
+
+const USER_COLUMNS: &str = "idx, username, password_hash, role, created, last_login";
+
+fn user_from_row(row: &Row) -> Result<UserRecord, rusqlite::Error> {
+    let role: String = row.get(3)?;
+
+    Ok(UserRecord {
+        index: row.get(0)?,
+        username: row.get(1)?,
+        password_hash: row.get(2)?,
+        role: UserRole::from_name(&role).unwrap_or_default(),
+        created: row.get(4)?,
+        last_login: row.get(5)?,
+    })
+}
+
+fn count_admins(conn: &Connection) -> Result<u64, Box<dyn Error>> {
+    let count = conn.query_row(
+        "SELECT COUNT(*) FROM users WHERE role = ?1",
+        params![UserRole::Admin.name()],
+        |row| row.get(0),
+    )?;
+
+    Ok(count)
+}
+
+//Whether any accounts have been set up. Once they have, everyone logs in with one.
+pub fn users_exist_in_db(filepath: &str) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let exists = conn.query_row("SELECT EXISTS (SELECT 1 FROM users)", [], |row| row.get(0))?;
+
+    Ok(exists)
+}
+
+//Get the user accounts, sorted by name
+pub fn get_users_from_db(filepath: &str) -> Result<Vec<UserRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM users ORDER BY username", USER_COLUMNS))?;
+    let rows = stmt.query_map([], user_from_row)?;
+
+    let mut users = Vec::new();
+
+    for user in rows {
+        users.push(user?);
+    }
+
+    Ok(users)
+}
+
+pub fn get_user_from_db(filepath: &str, index: u64) -> Result<Option<UserRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let user = conn.query_row(
+        &format!("SELECT {} FROM users WHERE idx = ?1", USER_COLUMNS),
+        params![index],
+        user_from_row,
+    ).optional()?;
+
+    Ok(user)
+}
+
+//Look up a user by name, ignoring case
+pub fn get_user_by_name_from_db(filepath: &str, username: &str) -> Result<Option<UserRecord>, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+
+    let user = conn.query_row(
+        &format!("SELECT {} FROM users WHERE username = ?1", USER_COLUMNS),
+        params![username.trim()],
+        user_from_row,
+    ).optional()?;
+
+    Ok(user)
+}
+
+//Add a user account. The first account has to be an admin so nobody gets locked out.
+pub fn add_user_to_db(filepath: &str, username: &str, password_hash: &str, role: UserRole) -> Result<u64, Box<dyn Error>> {
+    let mut conn = connect_to_database(false, filepath)?;
+    let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+
+    if role != UserRole::Admin && count_admins(&tx)? == 0 {
+        return Err(Box::new(HydraError("The first account has to be an admin.".into())));
+    }
+
+    let exists: bool = tx.query_row("SELECT EXISTS (SELECT 1 FROM users WHERE username = ?1)", params![username], |row| row.get(0))?;
+
+    if exists {
+        return Err(Box::new(HydraError(format!("There's already a user named {}.", username))));
+    }
+
+    tx.execute(
+        "INSERT INTO users (username, password_hash, role, created) VALUES (?1, ?2, ?3, ?4)",
+        params![username, password_hash, role.name(), chrono::Utc::now().timestamp()],
+    )?;
+
+    let index = tx.last_insert_rowid() as u64;
+    tx.commit()?;
+
+    Ok(index)
+}
+
+//Change a user's role. The last admin can't be demoted.
+pub fn set_user_role_in_db(filepath: &str, index: u64, role: UserRole) -> Result<bool, Box<dyn Error>> {
+    let mut conn = connect_to_database(false, filepath)?;
+    let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+
+    let changed = tx.execute("UPDATE users SET role = ?2 WHERE idx = ?1", params![index, role.name()])?;
+
+    if count_admins(&tx)? == 0 {
+        return Err(Box::new(HydraError("There has to be at least one admin.".into())));
+    }
+
+    tx.commit()?;
+
+    Ok(changed > 0)
+}
+
+pub fn set_user_password_in_db(filepath: &str, index: u64, password_hash: &str) -> Result<bool, Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    let changed = conn.execute("UPDATE users SET password_hash = ?2 WHERE idx = ?1", params![index, password_hash])?;
+
+    Ok(changed > 0)
+}
+
+pub fn set_user_last_login_in_db(filepath: &str, index: u64) -> Result<(), Box<dyn Error>> {
+    let conn = connect_to_database(false, filepath)?;
+    conn.execute("UPDATE users SET last_login = ?2 WHERE idx = ?1", params![index, chrono::Utc::now().timestamp()])?;
+
+    Ok(())
+}
+
+//Remove a user account, its two-factor setup and its sessions. The last admin can't be removed.
+pub fn delete_user_from_db(filepath: &str, index: u64) -> Result<bool, Box<dyn Error>> {
+    let mut conn = connect_to_database(false, filepath)?;
+    let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+
+    for table in ["two_factor_recovery_codes", "two_factor", "sessions"].iter() {
+        tx.execute(&format!("DELETE FROM {} WHERE username = (SELECT username FROM users WHERE idx = ?1)", table), params![index])?;
+    }
+
+    let deleted = tx.execute("DELETE FROM users WHERE idx = ?1", params![index])?;
+
+    if deleted > 0 && count_admins(&tx)? == 0 {
+        return Err(Box::new(HydraError("There has to be at least one admin.".into())));
+    }
+
+    tx.commit()?;
+
+    Ok(deleted > 0)
+}
@@ -0,0 +1,72 @@
+use anyhow::{anyhow, Result};
+use data_encoding::HEXLOWER;
+use rand::{distr::Alphanumeric, RngExt};
+use sha2::{Digest, Sha256};
+
+/// API tokens start with this so they can be told apart from login JWTs
+pub const TOKEN_PREFIX: &str = "hpd_";
+
+/// Random characters after the prefix
+const TOKEN_LENGTH: usize = 40;
+
+/// Longest name a token can have
+const MAX_NAME_LENGTH: usize = 100;
+
+/// How often the last used time is saved, so busy scripts don't write to the database on every call
+const LAST_USED_RESOLUTION: i64 = 60;
+
+/// Tokens are long and random, so a plain SHA-256 is enough to keep them safe at rest
+pub fn hash_token(token: &str) -> String {
+    HEXLOWER.encode(&Sha256::digest(token.as_bytes()))
+}
+
+/// Makes a new token and saves its hash. The token itself is only returned here and can't be shown again.
+pub fn create_token(db_filepath: &str, name: &str, scopes: &[dbif::ApiScope], expires_days: Option<u64>) -> Result<(String, dbif::ApiTokenRecord)> {
+    let name = name.trim();
+
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err(anyhow!("Token names have to be 1 to {} characters long", MAX_NAME_LENGTH));
+    }
+
+    if scopes.is_empty() {
+        return Err(anyhow!("Pick at least one scope"));
+    }
+
+    let random: String = rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect();
+
+    let token = format!("{}{}", TOKEN_PREFIX, random);
+
+    let expires = expires_days
+        .filter(|days| *days > 0)
+        .map(|days| chrono::Utc::now().timestamp() + days as i64 * 86400);
+
+    let record = dbif::add_api_token_to_db(db_filepath, name, &hash_token(&token), scopes, expires)
+        .map_err(|e| anyhow!("Unable to save token: {}", e))?;
+
+    Ok((token, record))
+}
+
+/// Looks up a token, returning it if it exists and hasn't expired
+pub fn check_token(db_filepath: &str, token: &str) -> Result<Option<dbif::ApiTokenRecord>> {
+    let record = dbif::get_api_token_by_hash_from_db(db_filepath, &hash_token(token))
+        .map_err(|e| anyhow!("Unable to look up token: {}", e))?;
+
+    let now = chrono::Utc::now().timestamp();
+
+    let record = match record {
+        Some(record) if !record.is_expired(now) => record,
+        _ => return Ok(None),
+    };
+
+    if record.last_used.is_none_or(|last_used| now - last_used >= LAST_USED_RESOLUTION) {
+        if let Err(e) = dbif::set_api_token_used_in_db(db_filepath, record.index, now) {
+            eprintln!("** Unable to save token last used time: {}", e);
+        }
+    }
+
+    Ok(Some(record))
+}
@@ -0,0 +1,250 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Utc;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use crate::HelipadConfig;
+
+/// Prefix of the snapshot file names, followed by the time they were taken
+const SNAPSHOT_PREFIX: &str = "helipad-";
+
+/// Name of the database inside a backup archive
+const ARCHIVE_DATABASE_NAME: &str = "database.db";
+
+/// Folder the sounds are kept under inside a backup archive
+const ARCHIVE_SOUNDS_DIR: &str = "sounds/";
+
+/// Takes a snapshot of the database into the directory. When a sound directory is given, the database
+/// and sounds are bundled into a tar archive instead. Returns the path of the new snapshot.
+pub fn create_snapshot(database_path: &str, sound_path: Option<&str>, backup_dir: &Path) -> Result<PathBuf> {
+    let name = format!("{}{}", SNAPSHOT_PREFIX, Utc::now().format("%Y%m%d-%H%M%S"));
+    let db_backup_path = backup_dir.join(format!("{}.db", name));
+
+    dbif::backup_database(database_path, &db_backup_path.to_string_lossy())
+        .map_err(|e| anyhow!("Database backup failed: {}", e))?;
+
+    let sound_path = match sound_path {
+        Some(sound_path) => sound_path,
+        None => return Ok(db_backup_path),
+    };
+
+    let archive_path = backup_dir.join(format!("{}.tar", name));
+    let result = write_archive(&archive_path, &db_backup_path, Path::new(sound_path));
+
+    fs::remove_file(&db_backup_path).context("Unable to remove database backup after archiving it")?;
+
+    if result.is_err() {
+        let _ = fs::remove_file(&archive_path);
+    }
+
+    result.map(|_| archive_path)
+}
+
+/// Deletes the oldest snapshots in the directory so only `keep` are left
+pub fn rotate_snapshots(backup_dir: &Path, keep: usize) -> Result<()> {
+    let mut snapshots = list_snapshots(backup_dir)?;
+
+    //Names contain the time so they sort oldest first
+    snapshots.sort();
+
+    let remove = snapshots.len().saturating_sub(keep);
+
+    for path in snapshots.into_iter().take(remove) {
+        println!("Removing old backup: [{}]", path.display());
+        fs::remove_file(&path).with_context(|| format!("Unable to remove old backup {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+fn list_snapshots(backup_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut snapshots = Vec::new();
+
+    for entry in fs::read_dir(backup_dir).context("Unable to read backup directory")? {
+        let path = entry?.path();
+
+        let is_snapshot = path.file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.starts_with(SNAPSHOT_PREFIX) && (name.ends_with(".db") || name.ends_with(".tar")))
+            .unwrap_or(false);
+
+        if is_snapshot && path.is_file() {
+            snapshots.push(path);
+        }
+    }
+
+    Ok(snapshots)
+}
+
+/// How long until the next scheduled snapshot is due, going by the newest one in the directory
+fn time_until_next_snapshot(backup_dir: &Path, interval: Duration) -> Duration {
+    let newest = list_snapshots(backup_dir).unwrap_or_default()
+        .iter()
+        .filter_map(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok())
+        .max();
+
+    match newest {
+        Some(modified) => {
+            let age = SystemTime::now().duration_since(modified).unwrap_or_default();
+            interval.saturating_sub(age)
+        }
+        None => Duration::ZERO,
+    }
+}
+
+/// Takes rotating snapshots of the database on the configured schedule
+pub async fn backup_scheduler(helipad_config: HelipadConfig) {
+    if helipad_config.backup_dir.is_empty() {
+        return;
+    }
+
+    let backup_dir = PathBuf::from(&helipad_config.backup_dir);
+
+    if let Err(e) = fs::create_dir_all(&backup_dir) {
+        eprintln!("Unable to create backup directory: {}", e);
+        return;
+    }
+
+    let interval = Duration::from_secs(helipad_config.backup_interval.max(1) * 3600);
+
+    println!("Backing up every {} hour(s) to: [{}]", helipad_config.backup_interval.max(1), backup_dir.display());
+
+    loop {
+        tokio::time::sleep(time_until_next_snapshot(&backup_dir, interval)).await;
+
+        let config = helipad_config.clone();
+        let dir = backup_dir.clone();
+
+        let result = tokio::task::spawn_blocking(move || {
+            let sound_path = config.backup_sounds.then_some(config.sound_path.as_str());
+            let snapshot = create_snapshot(&config.database_file_path, sound_path, &dir)?;
+            rotate_snapshots(&dir, config.backup_keep.max(1))?;
+            Ok::<PathBuf, anyhow::Error>(snapshot)
+        }).await;
+
+        match result {
+            Ok(Ok(snapshot)) => println!("Backup saved to: [{}]", snapshot.display()),
+            Ok(Err(e)) => eprintln!("** Error backing up database: {:#}", e),
+            Err(e) => eprintln!("** Backup task failed: {}", e),
+        }
+
+        //Don't spin if the snapshot couldn't be written
+        tokio::time::sleep(Duration::from_secs(60)).await;
+    }
+}
+
+/// Restores a database backup (.db) or backup archive (.tar) over the current database.
+/// Sounds in an archive are put back in the sound directory.
+pub fn restore_backup(backup_path: &str, database_path: &str, sound_path: &str) -> Result<()> {
+    if !backup_path.ends_with(".tar") {
+        restore_database(backup_path, database_path)?;
+        println!("Database restored from: [{}]", backup_path);
+        return Ok(());
+    }
+
+    let temp_dir = tempfile::tempdir().context("Unable to create temporary directory")?;
+    let db_backup_path = temp_dir.path().join(ARCHIVE_DATABASE_NAME);
+    let sounds_dir = temp_dir.path().join(ARCHIVE_SOUNDS_DIR);
+    let mut found_database = false;
+    let mut sounds: Vec<String> = Vec::new();
+
+    fs::create_dir(&sounds_dir).context("Unable to create temporary directory")?;
+
+    let file = File::open(backup_path).with_context(|| format!("Unable to open {}", backup_path))?;
+    let mut archive = tar::Archive::new(BufReader::new(file));
+
+    for entry in archive.entries().context("Unable to read backup archive")? {
+        let mut entry = entry.context("Unable to read backup archive")?;
+
+        //Only regular files are restored
+        if entry.header().entry_type() != tar::EntryType::Regular {
+            continue;
+        }
+
+        let name = entry.path().context("Invalid file name in backup archive")?.to_string_lossy().to_string();
+
+        if name == ARCHIVE_DATABASE_NAME {
+            copy_entry(&mut entry, &db_backup_path).context("Unable to extract database from archive")?;
+            found_database = true;
+        }
+        else if let Some(filename) = name.strip_prefix(ARCHIVE_SOUNDS_DIR) {
+            //Only plain file names, so an archive can't write outside the sound directory
+            if !filename.is_empty() && Path::new(filename).file_name() == Some(filename.as_ref()) {
+                copy_entry(&mut entry, &sounds_dir.join(filename))
+                    .with_context(|| format!("Unable to extract sound {}", filename))?;
+                sounds.push(filename.to_string());
+            }
+        }
+    }
+
+    if !found_database {
+        bail!("Archive does not contain a database");
+    }
+
+    //Check and restore the database before touching the sounds
+    restore_database(&db_backup_path.to_string_lossy(), database_path)?;
+    println!("Database restored from: [{}]", backup_path);
+
+    fs::create_dir_all(sound_path).context("Unable to create sound directory")?;
+
+    for filename in &sounds {
+        fs::copy(sounds_dir.join(filename), Path::new(sound_path).join(filename))
+            .with_context(|| format!("Unable to restore sound {}", filename))?;
+    }
+
+    println!("Restored {} sound(s) to: [{}]", sounds.len(), sound_path);
+
+    Ok(())
+}
+
+/// Streams a file out of an archive to the path
+fn copy_entry(entry: &mut impl Read, path: &Path) -> Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    std::io::copy(entry, &mut file)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+fn restore_database(backup_path: &str, database_path: &str) -> Result<()> {
+    match dbif::restore_database(backup_path, database_path) {
+        Ok(Some(previous_path)) => println!("Previous database saved to: [{}]", previous_path),
+        Ok(None) => {}
+        Err(e) => bail!("Database restore failed: {}", e),
+    }
+
+    Ok(())
+}
+
+/// Writes the database backup and the files in the sound directory to a tar archive.
+/// Long sound file names are kept with GNU long name entries.
+fn write_archive(archive_path: &Path, db_backup_path: &Path, sound_path: &Path) -> Result<()> {
+    let file = File::create(archive_path).context("Unable to create backup archive")?;
+    let mut archive = tar::Builder::new(BufWriter::new(file));
+
+    archive.append_path_with_name(db_backup_path, ARCHIVE_DATABASE_NAME)
+        .context("Unable to add database to backup archive")?;
+
+    if sound_path.is_dir() {
+        for entry in fs::read_dir(sound_path).context("Unable to read sound directory")? {
+            let path = entry?.path();
+
+            if !path.is_file() {
+                continue;
+            }
+
+            let name = format!("{}{}", ARCHIVE_SOUNDS_DIR, path.file_name().unwrap_or_default().to_string_lossy());
+
+            archive.append_path_with_name(&path, &name)
+                .with_context(|| format!("Unable to add {} to backup archive", path.display()))?;
+        }
+    }
+
+    archive.into_inner()
+        .context("Unable to finish backup archive")?
+        .flush()
+        .context("Unable to finish backup archive")?;
+
+    Ok(())
+}
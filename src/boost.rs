@@ -1,9 +1,8 @@
-use regex::Regex;
 use serde::Deserialize;
 use std::error::Error;
 use std::collections::HashMap;
 use crate::podcastindex;
-use crate::metadata;
+use crate::metadata::{self, MetadataProvider};
 use lnd::lnrpc::lnrpc::{Payment, Invoice, invoice::InvoiceState};
 use crate::deserializers::{d_action, d_blank, d_zero, de_optional_string_or_number};
 
@@ -66,7 +65,7 @@ impl RawBoost {
     }
 }
 
-pub async fn parse_boost_from_invoice(invoice: Invoice, remote_cache: &mut podcastindex::GuidCache, fetch_metadata: bool, metadata_whitelist: &str) -> Option<dbif::BoostRecord> {
+pub async fn parse_boost_from_invoice(invoice: Invoice, remote_cache: &mut podcastindex::GuidCache, metadata_providers: &[Box<dyn MetadataProvider>]) -> Option<dbif::BoostRecord> {
     if invoice.state != InvoiceState::Settled as i32 {
         return None; // invoice hasn't been fulfilled yet
     }
@@ -92,6 +91,15 @@ pub async fn parse_boost_from_invoice(invoice: Invoice, remote_cache: &mut podca
         custom_key: None,
         custom_value: None,
         payment_info: None,
+        hidden: false,
+        quarantined: false,
+        imported: false,
+        nostr_refs: Vec::new(),
+        numerology: Vec::new(),
+        tags: Vec::new(),
+        note: None,
+        pinned: false,
+        supporter: None,
     };
 
     for htlc in &invoice.htlcs {
@@ -106,11 +114,10 @@ pub async fn parse_boost_from_invoice(invoice: Invoice, remote_cache: &mut podca
         return None; // unrelated keysend/amp payment
     }
 
-    // Fetch any RSS payment or Podcast Guru payment metadata from the invoice memo
-    if fetch_metadata &&
+    // Fetch any payment metadata referenced by the invoice memo from a trusted provider
+    if !metadata_providers.is_empty() &&
        !invoice.memo.is_empty() &&
-       should_fetch_metadata(&invoice.memo, metadata_whitelist) &&
-       fetch_boost_metadata(&mut boost, &invoice.memo, remote_cache).await
+       fetch_boost_metadata(&mut boost, &invoice.memo, metadata_providers, true, remote_cache).await
     {
         return Some(boost);
     }
@@ -172,6 +179,15 @@ pub async fn parse_boost_from_payment(payment: Payment, remote_cache: &mut podca
                 fee_msat: payment.fee_msat,
                 reply_to_idx: None,
             }),
+            hidden: false,
+            quarantined: false,
+            imported: false,
+            nostr_refs: Vec::new(),
+            numerology: Vec::new(),
+            tags: Vec::new(),
+            note: None,
+            pinned: false,
+            supporter: None,
         };
 
         // Parse boost and custodial wallet TLVs
@@ -207,30 +223,14 @@ async fn parse_custom_records(boost: &mut dbif::BoostRecord, custom_records: &Ha
     }
 }
 
-fn should_fetch_metadata(comment: &str, additional_domains: &str) -> bool {
-    let mut domains = vec!["fountain.fm", "castamatic.com", "tardbox.com"];
-
-    let extra: Vec<&str> = additional_domains
-        .split(',')
-        .map(str::trim)
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    domains.extend(extra);
-
-    let domain_match = domains.iter().any(|d| {
-        Regex::new(&format!(r"rss::payment::\w+ https://{}/", d))
-            .map(|re| re.is_match(comment))
-            .unwrap_or(false)
-    });
-
-    domain_match || Regex::new(r"V4V: https://boost\.podcastguru\.io/")
-        .map(|re| re.is_match(comment))
-        .unwrap_or(false)
-}
-
-pub async fn fetch_boost_metadata(boost: &mut dbif::BoostRecord, comment: &str, remote_cache: &mut podcastindex::GuidCache) -> bool {
-    let metadata = match metadata::fetch_payment_metadata(comment).await {
+pub async fn fetch_boost_metadata(
+    boost: &mut dbif::BoostRecord,
+    comment: &str,
+    metadata_providers: &[Box<dyn MetadataProvider>],
+    trusted_only: bool,
+    remote_cache: &mut podcastindex::GuidCache,
+) -> bool {
+    let metadata = match metadata::fetch_payment_metadata(comment, metadata_providers, trusted_only).await {
         Ok(Some(metadata)) => metadata,
         Ok(None) => {
             eprintln!("** No payment metadata found for boost: {}", boost.index);
@@ -0,0 +1,76 @@
+use axum::http::Method;
+use axum_extra::extract::cookie::{Cookie, SameSite};
+use data_encoding::BASE64URL_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::{distr::Alphanumeric, RngExt};
+use sha2::Sha256;
+
+/// Cookie the token is kept in. Scripts read it to send the token back, so it can't be HttpOnly.
+pub const COOKIE_NAME: &str = "HELIPAD_CSRF";
+
+/// Header htmx and jQuery send the token back in
+pub const HEADER_NAME: &str = "x-csrf-token";
+
+/// Form field plain HTML forms send the token back in
+pub const FORM_FIELD: &str = "csrf_token";
+
+/// Largest form body read looking for the token
+pub const MAX_FORM_BYTES: usize = 1024 * 1024;
+
+// the nonce is alphanumeric, so it can't run into the binding
+fn mac(secret: &str, binding: &str, nonce: &str) -> Hmac<Sha256> {
+    let key = format!("{}:csrf", secret);
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(nonce.as_bytes());
+    mac.update(b":");
+    mac.update(binding.as_bytes());
+    mac
+}
+
+/// A new token for one login: a random nonce and its signature over the nonce and the login's `binding`. Signing it
+/// means a cookie planted by another site on the same domain isn't accepted, a token given to one login doesn't work
+/// for another, and changing the password makes old tokens stop working.
+pub fn new_token(secret: &str, binding: &str) -> String {
+    let nonce: String = rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect();
+
+    format!("{}.{}", nonce, BASE64URL_NOPAD.encode(&mac(secret, binding, &nonce).finalize().into_bytes()))
+}
+
+/// Whether a token was signed with this secret for this login
+pub fn is_valid(secret: &str, binding: &str, token: &str) -> bool {
+    let (nonce, signature) = match token.split_once('.') {
+        Some((nonce, signature)) => (nonce, signature),
+        None => return false,
+    };
+
+    let signature = match BASE64URL_NOPAD.decode(signature.as_bytes()) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+
+    mac(secret, binding, nonce).verify_slice(&signature).is_ok()
+}
+
+/// Whether the token sent with a request matches the one in its cookie, and both belong to this login
+pub fn matches(secret: &str, binding: &str, cookie: &str, sent: &str) -> bool {
+    is_valid(secret, binding, cookie) && is_valid(secret, binding, sent) && cookie == sent
+}
+
+/// Methods that don't change anything, so don't need a token
+pub fn is_safe_method(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// The cookie holding a token. It lasts as long as the browser session and is sent again whenever it's missing.
+pub fn cookie(token: String, secure: bool) -> Cookie<'static> {
+    Cookie::build((COOKIE_NAME, token))
+        .path("/")
+        .secure(secure)
+        .http_only(false)
+        .same_site(SameSite::Strict)
+        .build()
+}
@@ -1,7 +1,8 @@
 // use crate::{Context, Request, Body, Response};
 use axum::{
-    extract::{Form, Path, Query, Request, State},
-    http::{header, StatusCode},
+    body::Body,
+    extract::{ConnectInfo, Extension, Form, Path, Query, Request, State},
+    http::{header, HeaderMap, Method, StatusCode},
     middleware::Next,
     response::{Html, Json, Redirect, IntoResponse, Response},
 };
@@ -14,8 +15,9 @@ use axum_extra::{
 use axum_typed_multipart::{FieldData, TryFromMultipart, TypedMultipart};
 
 use chrono::{DateTime, TimeDelta, Utc};
-use crate::{AppState, lightning, podcastindex, boost, triggers, WebSocketEvent};
-use dbif::{BoostRecord, BoostFilters, NumerologyRecord, TriggerRecord, ActionType};
+use chrono_tz::Tz;
+use crate::{AppState, api_tokens, csrf, login_throttle, sessions, trusted_proxy::TrustedProxy, password, share_links, two_factor, users, backup, import, lightning, rescan, podcastindex, boost, metadata, moderation, nostr, numerology, shows, triggers, WebSocketEvent};
+use dbif::{BoostRecord, BoostFilters, BoostSort, NumerologyRecord, StatsGroup, TriggerRecord, ActionType, ListType, UserRole};
 use handlebars::Handlebars;
 use jsonwebtoken::{Algorithm, Header, DecodingKey, EncodingKey, Validation};
 use reqwest::header::USER_AGENT;
@@ -24,21 +26,79 @@ use serde_json::json;
 use std::{fs, str};
 use std::string::String;
 use tempfile::NamedTempFile;
+use tokio_util::io::ReaderStream;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::net::SocketAddr;
 use uuid::Uuid;
 
 // JWT session times
 const JWT_SESSION_HOURS: i64 = 1;             // 1 hour for normal login
 const JWT_LONG_SESSION_HOURS: i64 = 30 * 24;  // 30 days for "stay logged in"
+const TWO_FACTOR_CHALLENGE_MINUTES: i64 = 5;  // time to enter a two-factor code after the password
 
 //Structs and Enums ------------------------------------------------------------------------------------------
 #[derive(Debug, Serialize, Deserialize)]
 struct JwtClaims {
-   // sub: String,
+   sub: Option<String>, // username, or none when logged in with the shared password
+   role: Option<UserRole>,
    iat: usize,
    exp: usize,
    long_lived: Option<bool>, // true if "stay logged in" was selected
+   #[serde(rename = "2fa", default)]
+   two_factor: Option<bool>, // true if a two-factor code was given at login
+   #[serde(default)]
+   jti: Option<String>, // the session it belongs to
+}
+
+//A login with the right password that still needs a two-factor code. Signed with a different key so it can't be
+//used as a session.
+#[derive(Debug, Serialize, Deserialize)]
+struct TwoFactorChallenge {
+   sub: Option<String>,
+   exp: usize,
+}
+
+//Who a session belongs to. The shared password logs in as an admin with no username.
+#[derive(Debug, Clone)]
+pub struct SessionUser {
+    username: Option<String>,
+    role: UserRole,
+    two_factor: bool, // logged in with a two-factor code
+    session: Option<String>, // jti of its session, none behind a login proxy
+}
+
+impl SessionUser {
+    fn shared() -> Self {
+        Self {
+            username: None,
+            role: UserRole::Admin,
+            two_factor: false,
+            session: None,
+        }
+    }
+
+    fn from_account(user: &dbif::UserRecord) -> Self {
+        Self {
+            username: Some(user.username.clone()),
+            role: user.role,
+            two_factor: false,
+            session: None,
+        }
+    }
+
+    fn two_factor_key(&self) -> String {
+        two_factor::login_key(self.username.as_deref()).to_string()
+    }
+
+    //What CSRF tokens are tied to, so one login's token can't be used with another's cookie.
+    //Logins behind a proxy have no session, so theirs are tied to the account.
+    fn csrf_binding(&self) -> String {
+        match &self.session {
+            Some(jti) => format!("session:{}", jti),
+            None => format!("login:{}", self.two_factor_key()),
+        }
+    }
 }
 
 fn verify_jwt_cookie(jar: &CookieJar, secret: &String) -> Option<JwtClaims> {
@@ -112,7 +172,7 @@ fn verify_jwt_header(req: &Request, secret: &String) -> Option<JwtClaims> {
     Some(token.claims)
 }
 
-fn new_jwt_token(secret: String, long_lived: bool, duration_hours: i64) -> String {
+fn new_jwt_token(secret: String, long_lived: bool, duration_hours: i64, user: &SessionUser) -> String {
     let iat = Utc::now().timestamp();
     let exp = Utc::now()
         .checked_add_signed(TimeDelta::try_hours(duration_hours).unwrap())
@@ -120,774 +180,4247 @@ fn new_jwt_token(secret: String, long_lived: bool, duration_hours: i64) -> Strin
         .timestamp();
 
     let my_claims = JwtClaims {
+        sub: user.username.clone(),
+        role: Some(user.role),
         iat: iat as usize,
         exp: exp as usize,
         long_lived: Some(long_lived),
+        two_factor: Some(user.two_factor),
+        jti: user.session.clone(),
     };
 
     return jsonwebtoken::encode(&Header::default(), &my_claims, &EncodingKey::from_secret(secret.as_ref())).unwrap();
 }
 
-fn new_jwt_cookie(secret: String, long_lived: bool) -> Cookie<'static> {
-    let duration_hours = if long_lived {
+fn session_hours(long_lived: bool) -> i64 {
+    if long_lived {
         JWT_LONG_SESSION_HOURS
     } else {
         JWT_SESSION_HOURS
-    };
+    }
+}
+
+fn new_jwt_cookie(secret: String, long_lived: bool, user: &SessionUser, secure: bool) -> Cookie<'static> {
+    let duration_hours = session_hours(long_lived);
 
-    let jwt = new_jwt_token(secret, long_lived, duration_hours);
+    let jwt = new_jwt_token(secret, long_lived, duration_hours, user);
 
     return Cookie::build(("HELIPAD_JWT", jwt))
         .path("/")
         .max_age(cookie::time::Duration::seconds(duration_hours * 3600))
-        .secure(false) // Do not require HTTPS.
+        .secure(secure) // only over HTTPS if the secure_cookies option is on
         .http_only(true)
         .same_site(cookie::SameSite::Lax)
         .build();
 }
 
-pub async fn auth_middleware(
-    State(state): State<AppState>,
-    jar: CookieJar,
-    req: Request,
-    next: Next,
-) -> Response {
-
-    if state.helipad_config.password.is_empty() {
-        return next.run(req).await; // no password required
-    }
-
-    let path = req.uri().path();
+fn two_factor_challenge_secret(secret: &str) -> String {
+    format!("{}:2fa-challenge", secret)
+}
 
-    if path == "/login" || path == "/api/v1/login" || path.starts_with("/script") || path.starts_with("/style") {
-        return next.run(req).await; // no password required for certain paths
-    }
+fn new_two_factor_challenge(secret: &str, user: &SessionUser) -> String {
+    let exp = Utc::now()
+        .checked_add_signed(TimeDelta::try_minutes(TWO_FACTOR_CHALLENGE_MINUTES).unwrap())
+        .expect("invalid timestamp")
+        .timestamp();
 
-    if let Some(claims) = verify_jwt_cookie(&jar, &state.helipad_config.secret) {
-        // valid jwt: refresh and add to response
-        let resp = next.run(req).await;
-        let long_lived = claims.long_lived.unwrap_or(false);
-        let cookie = new_jwt_cookie(state.helipad_config.secret, long_lived);
-        let jar = jar.add(cookie);
-        return (jar, resp).into_response();
-    }
+    let challenge = TwoFactorChallenge {
+        sub: user.username.clone(),
+        exp: exp as usize,
+    };
 
-    if let Some(_) = verify_jwt_header(&req, &state.helipad_config.secret) {
-        // valid jwt
-        return next.run(req).await.into_response();
-    }
+    jsonwebtoken::encode(&Header::default(), &challenge, &EncodingKey::from_secret(two_factor_challenge_secret(secret).as_ref())).unwrap()
+}
 
-    let ctype = match req.headers().get(header::CONTENT_TYPE) {
-        Some(val) => val.to_str().unwrap_or(""),
-        None => "",
-    };
+//Gets the username from a two-factor challenge, or none for the shared password
+fn verify_two_factor_challenge(secret: &str, challenge: &str) -> Option<Option<String>> {
+    let key = DecodingKey::from_secret(two_factor_challenge_secret(secret).as_ref());
 
-    // login required
-    if path.starts_with("/api/v1") || ctype.starts_with("application/json") {
-        return (StatusCode::FORBIDDEN, "Not logged in").into_response(); // json response
+    match jsonwebtoken::decode::<TwoFactorChallenge>(challenge, &key, &Validation::new(Algorithm::HS256)) {
+        Ok(token) => Some(token.claims.sub), // expiry is checked by the validation
+        Err(_) => {
+            eprintln!("Invalid or expired two-factor challenge");
+            None
+        }
     }
-
-    Redirect::to("/login").into_response() // redirect to login
 }
 
-//Login html
-#[derive(Debug, Serialize, Deserialize)]
-pub struct LoginForm {
-    password: String,
-    stay_logged_in: Option<String>,
+//Whether the shared password is set
+async fn password_enabled(state: &AppState) -> bool {
+    state.auth.read().await.password_hash.is_some()
 }
 
-pub async fn login(State(state): State<AppState>) -> Response {
-    if state.helipad_config.password.is_empty() {
-        return Redirect::to("/").into_response(); // no password required
-    }
-
-    HtmlTemplate("webroot/html/login.html", json!({"message": ""})).into_response()
+async fn jwt_secret(state: &AppState) -> String {
+    state.auth.read().await.jwt_secret.clone()
 }
 
-pub async fn handle_login(
-    State(state): State<AppState>,
-    jar: CookieJar,
-    Form(post_vars): Form<LoginForm>,
-) -> Response {
+//Whether a login has two-factor authentication turned on
+async fn two_factor_enabled(state: &AppState, user: &SessionUser) -> bool {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let key = user.two_factor_key();
 
-    if state.helipad_config.password == *post_vars.password {
-        // valid password: set cookie and redirect
-        let stay_logged_in = post_vars.stay_logged_in.is_some();
-        let cookie = new_jwt_cookie(state.helipad_config.secret, stay_logged_in);
-        let jar = jar.add(cookie);
-        let resp = Redirect::to("/");
-        return (jar, resp).into_response();
+    match dbif::run_blocking(move || two_factor::is_enabled(&db_filepath, &key).map_err(Into::into)).await {
+        Ok(enabled) => enabled,
+        Err(e) => {
+            eprintln!("** Error checking two-factor: {}", e);
+            true // fail closed
+        }
     }
-
-    HtmlTemplate("webroot/html/login.html", json!({
-        "version": state.version,
-        "message": "Bad password",
-    })).into_response()
 }
 
-//API - authenticate and return JWT token for API clients
-pub async fn api_v1_login(
-    State(state): State<AppState>,
-    Json(post_vars): Json<LoginForm>,
-) -> Response {
-
-    if state.helipad_config.password.is_empty() {
-        let resp = json!({
-            "success": true,
-            "message": "No password required",
-        });
-        return (StatusCode::OK, Json(resp)).into_response();
-    }
+//Whether user accounts have been set up. Once they have, the shared password no longer logs in.
+async fn accounts_enabled(state: &AppState) -> bool {
+    let db_filepath = state.helipad_config.database_file_path.clone();
 
-    if state.helipad_config.password == *post_vars.password {
-        // valid password: set cookie and redirect
-        let stay_logged_in = post_vars.stay_logged_in.is_some();
-        let cookie = new_jwt_cookie(state.helipad_config.secret, stay_logged_in);
-        let resp = json!({
-            "success": true,
-            "message": "Login successful",
-            "token": cookie.clone().value().to_string(),
-        });
-        return (StatusCode::OK, Json(resp)).into_response();
+    match dbif::run_blocking(move || dbif::users_exist_in_db(&db_filepath)).await {
+        Ok(exists) => exists,
+        Err(e) => {
+            eprintln!("** Error checking for user accounts: {}", e);
+            true // fail closed
+        }
     }
-
-    let resp = json!({
-        "success": false,
-        "message": "Invalid password",
-    });
-    return (StatusCode::UNAUTHORIZED, Json(resp)).into_response();
 }
 
-struct HtmlTemplate<'a, T>(&'a str, T);
+//Finds who a session belongs to. Accounts are looked up again so role changes and removals apply straight away, and
+//the session too so logging it out does. Cookies are refreshed with each request, so they expire later than the JWT.
+async fn session_user(state: &AppState, claims: &JwtClaims, accounts: bool, addr: SocketAddr, from_cookie: bool) -> Option<SessionUser> {
+    let mut user = find_user(state, claims.sub.clone(), accounts).await?;
+    user.two_factor = claims.two_factor.unwrap_or(false);
 
-impl<T> IntoResponse for HtmlTemplate<'_, T>
-where
-    T: Serialize,
-{
-    fn into_response(self) -> Response {
-        let reg = Handlebars::new();
+    let jti = claims.jti.clone()?; // from before sessions were saved
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let key = two_factor::login_key(user.username.as_deref()).to_string();
+    let ip = addr.ip().to_string();
 
-        let doc = match fs::read_to_string(self.0) {
-            Ok(doc) => doc,
-            Err(e) => {
-                eprintln!("** Unable to open template file {}: {}.\n", self.0, e);
-                return (StatusCode::BAD_REQUEST, "Unable to open template file").into_response();
-            }
-        };
+    let expires = if from_cookie {
+        Utc::now().timestamp() + session_hours(claims.long_lived.unwrap_or(false)) * 3600
+    } else {
+        claims.exp as i64
+    };
 
-        let doc_rendered = match reg.render_template(&doc, &self.1) {
-            Ok(rendered) => rendered,
-            Err(e) => {
-                eprintln!("** Unable to render template {}: {}.\n", self.0, e);
-                return (StatusCode::BAD_REQUEST, "Unable to render template").into_response();
-            }
-        };
+    let lookup = jti.clone();
 
-        Html(doc_rendered).into_response()
+    match dbif::run_blocking(move || sessions::check_session(&db_filepath, &lookup, &key, &ip, expires).map_err(Into::into)).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return None, // logged out
+        Err(e) => {
+            eprintln!("** Error checking session: {}", e);
+            return None;
+        }
     }
-}
-
-//Homepage html
-pub async fn home(State(state): State<AppState>) -> Response {
-    HtmlTemplate("webroot/html/home.html", &json!({"version": state.version})).into_response()
-}
 
-//Streams html
-pub async fn streams(State(state): State<AppState>) -> Response {
-    HtmlTemplate("webroot/html/streams.html", &json!({"version": state.version})).into_response()
-}
+    user.session = Some(jti);
 
-//Sent html
-pub async fn sent(State(state): State<AppState>) -> Response {
-    HtmlTemplate("webroot/html/sent.html", &json!({"version": state.version})).into_response()
+    Some(user)
 }
 
-//Streams html
-pub async fn settings(State(state): State<AppState>) -> Response {
-    HtmlTemplate("webroot/html/settings.html", &json!({"version": state.version})).into_response()
+//Starts a session for someone who just logged in, so its JWT can be logged out later
+async fn start_session(state: &AppState, user: &mut SessionUser, headers: &HeaderMap, addr: SocketAddr, long_lived: bool) -> Result<(), String> {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let key = two_factor::login_key(user.username.as_deref()).to_string();
+    let user_agent = headers.get(header::USER_AGENT).and_then(|val| val.to_str().ok()).map(str::to_string);
+    let ip = addr.ip().to_string();
+    let expires = Utc::now().timestamp() + session_hours(long_lived) * 3600;
+
+    let result = dbif::run_blocking(move || {
+        sessions::start_session(&db_filepath, &key, user_agent.as_deref(), &ip, long_lived, expires).map_err(Into::into)
+    }).await;
+
+    match result {
+        Ok(jti) => {
+            user.session = Some(jti);
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("** Error starting session: {}", e);
+            Err("Unable to start a session".to_string())
+        }
+    }
 }
 
+async fn find_user(state: &AppState, username: Option<String>, accounts: bool) -> Option<SessionUser> {
+    let username = match username {
+        Some(username) => username,
+        None if !accounts => return Some(SessionUser::shared()),
+        None => return None, // shared password sessions end when accounts are set up
+    };
 
-//Numerology definitions file
-pub async fn numerology_json(State(state): State<AppState>) -> Response {
-    let results = dbif::get_numerology_from_db(&state.helipad_config.database_file_path).unwrap();
-    Json(results).into_response()
-}
+    let db_filepath = state.helipad_config.database_file_path.clone();
 
-//API - give back node info
-pub async fn api_v1_node_info(State(state): State<AppState>) -> Response {
-    match dbif::get_node_info_from_db(&state.helipad_config.database_file_path) {
-        Ok(info) => {
-            Json(info).into_response()
-        }
+    match dbif::run_blocking(move || dbif::get_user_by_name_from_db(&db_filepath, &username)).await {
+        Ok(user) => user.map(|user| SessionUser::from_account(&user)),
         Err(e) => {
-            eprintln!("** Error getting node info: {}.\n", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting node info.").into_response()
+            eprintln!("** Error getting user: {}", e);
+            None
         }
     }
 }
 
-pub async fn api_v1_settings(State(state): State<AppState>) -> Response {
-    match dbif::load_settings_from_db(&state.helipad_config.database_file_path) {
-        Ok(settings) => {
-            Json(settings).into_response()
-        }
+//Finds who the trusted proxy says is logged in: their account if they have one, otherwise the proxy role if one is set.
+//The proxy does its own two-factor, so it counts as given.
+async fn proxy_user(state: &AppState, proxy: &TrustedProxy, addr: SocketAddr, headers: &HeaderMap) -> Option<SessionUser> {
+    let username = proxy.username(addr.ip(), headers)?;
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let lookup = username.clone();
+
+    let account = match dbif::run_blocking(move || dbif::get_user_by_name_from_db(&db_filepath, &lookup)).await {
+        Ok(account) => account,
         Err(e) => {
-            eprintln!("** Error getting settings: {}.\n", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting settings.").into_response()
+            eprintln!("** Error getting user: {}", e);
+            return None;
         }
+    };
+
+    let mut user = match (account, proxy.default_role) {
+        (Some(account), _) => SessionUser::from_account(&account),
+        (None, Some(role)) => SessionUser {
+            username: Some(username),
+            role,
+            two_factor: false,
+            session: None,
+        },
+        (None, None) => {
+            eprintln!("** Proxy user {} has no Helipad account", username);
+            return None;
+        }
+    };
+
+    user.two_factor = true;
+
+    Some(user)
+}
+
+//Pages where a login changes its own password, sets up its own two-factor or logs out its own sessions.
+//A login proxy looks after these instead, and API tokens can't use them since they aren't a login.
+fn manages_own_login(path: &str) -> bool {
+    if path == "/password" || path == "/api/v1/password" {
+        return true;
     }
+
+    ["/settings/2fa", "/api/v1/2fa", "/settings/sessions", "/api/v1/sessions"].iter()
+        .any(|prefix| path == *prefix || path.starts_with(&format!("{}/", prefix)))
 }
 
-//API - give back the node balance
-pub async fn api_v1_balance(State(state): State<AppState>) -> Response {
+//The role needed for a request. Reading is open to everyone logged in, changing boosts (replies, tags,
+//shows and so on) needs an operator and settings, triggers, users and database tools need an admin.
+fn required_role(method: &Method, path: &str) -> UserRole {
+    if manages_own_login(path) {
+        return UserRole::Viewer; // everyone can change their own password, two-factor and sessions
+    }
 
-    //Get the boosts from db for returning
-    match dbif::get_wallet_balance_from_db(&state.helipad_config.database_file_path) {
-        Ok(balance) => {
-            Json(balance).into_response()
-        }
-        Err(e) => {
-            eprintln!("** Error getting balance: {}.\n", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting balance.").into_response()
-        }
+    let admin_paths = ["/settings", "/api/v1/users", "/api/v1/tokens", "/api/v1/share_links", "/api/v1/logins", "/api/v1/backup", "/api/v1/rescan", "/api/v1/import"];
+
+    if admin_paths.iter().any(|prefix| path == *prefix || path.starts_with(&format!("{}/", prefix))) {
+        return UserRole::Admin;
+    }
+
+    match *method {
+        Method::GET | Method::HEAD | Method::OPTIONS => UserRole::Viewer,
+        _ => UserRole::Operator,
     }
 }
 
-//API - serve boosts as JSON either in ascending or descending order
-#[derive(Debug, Serialize, Deserialize)]
-pub struct BoostParams {
-    index: u64,
-    count: u64,
-    #[serde(default)]
-    old: bool,
-    #[serde(default)]
-    triggers: bool,
-    #[serde(default)]
-    podcast: Option<String>,
+//Whether a request needs a session that logged in with a two-factor code, if the login has two-factor on.
+//Spending sats and anything needing an admin does.
+fn requires_two_factor(method: &Method, path: &str) -> bool {
+    path == "/api/v1/reply" || required_role(method, path) == UserRole::Admin
 }
 
-impl Default for BoostParams {
-    fn default() -> Self {
-        Self {
-            index: 0,
-            count: 0,
-            old: false,
-            triggers: false,
-            podcast: None,
-        }
+//The scope an API token needs for a request. Anything a viewer can't do that isn't a reply needs the settings scope.
+fn required_scope(method: &Method, path: &str) -> dbif::ApiScope {
+    if path == "/api/v1/ws" {
+        dbif::ApiScope::Websocket
+    } else if path == "/api/v1/reply" || path == "/api/v1/mark_replied" {
+        dbif::ApiScope::Reply
+    } else if required_role(method, path) == UserRole::Viewer {
+        dbif::ApiScope::Read
+    } else {
+        dbif::ApiScope::Settings
     }
 }
 
-pub async fn api_v1_boosts(
-    Query(params): Query<BoostParams>,
-    State(state): State<AppState>,
-) -> Response {
+//Gets an API token from the authorization header, or the `token` parameter for websocket clients that can't set headers
+fn api_token_from_request(req: &Request) -> Option<String> {
+    let is_token = |token: &str| token.starts_with(api_tokens::TOKEN_PREFIX);
 
-    let index = params.index;
-    let boostcount = params.count;
+    let bearer = req.headers().get(header::AUTHORIZATION)
+        .and_then(|val| val.to_str().ok())
+        .and_then(|val| val.strip_prefix("Bearer "))
+        .map(|val| val.trim().to_string());
 
-    //Was the "old" flag used?
-    let old = params.old;
+    if let Some(token) = bearer.filter(|token| is_token(token)) {
+        return Some(token);
+    }
 
-    println!("** Supplied index from call: [{}]", index);
-    println!("** Supplied boost count from call: [{}]", boostcount);
+    if req.uri().path() != "/api/v1/ws" {
+        return None;
+    }
 
-    let mut filters = BoostFilters::new();
-    filters.podcast = params.podcast;
+    url::form_urlencoded::parse(req.uri().query().unwrap_or("").as_bytes())
+        .find(|(name, _)| name == "token")
+        .map(|(_, token)| token.to_string())
+        .filter(|token| is_token(token))
+}
 
-    //Get the boosts from db for returning
-    let boosts = match dbif::get_boosts_from_db(&state.helipad_config.database_file_path, index, boostcount, old, true, filters) {
-        Ok(boosts) => boosts,
-        Err(e) => {
-            eprintln!("** Error getting boosts: {}.\n", e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting boosts.").into_response()
-        }
+//Checks the CSRF token sent with a cookie login's request matches the one in its cookie. Scripts send it in a header,
+//plain HTML forms as a form field, so the form is read to find it and then put back for the handler.
+async fn check_csrf_token(req: Request, secret: &str, binding: &str, cookie: Option<&str>) -> Result<Request, Response> {
+    let forbidden = |req_line: String| {
+        eprintln!("** Missing or invalid CSRF token: {}", req_line);
+        (StatusCode::FORBIDDEN, "Missing or invalid CSRF token. Reload the page and try again.").into_response()
     };
 
-    if params.triggers {
-        let boosts_with_triggers = match triggers::get_boosts_with_triggers(&state.helipad_config.database_file_path, boosts).await {
-            Ok(boosts) => boosts,
-            Err(e) => {
-                eprintln!("** Error getting boosts with triggers: {}.\n", e);
-                return (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting boosts with triggers.").into_response()
-            }
-        };
+    let req_line = format!("{} {}", req.method(), req.uri().path());
 
-        Json(boosts_with_triggers).into_response()
+    let cookie = match cookie {
+        Some(cookie) => cookie,
+        None => return Err(forbidden(req_line)),
+    };
+
+    if let Some(sent) = req.headers().get(csrf::HEADER_NAME) {
+        if !csrf::matches(secret, binding, cookie, sent.to_str().unwrap_or("")) {
+            return Err(forbidden(req_line));
+        }
+
+        return Ok(req);
     }
-    else {
-        Json(boosts).into_response()
+
+    let is_form = req.headers().get(header::CONTENT_TYPE)
+        .and_then(|val| val.to_str().ok())
+        .is_some_and(|ctype| ctype.starts_with("application/x-www-form-urlencoded"));
+
+    if !is_form {
+        return Err(forbidden(req_line));
     }
-}
 
-//API - serve streams as JSON either in ascending or descending order
-pub async fn api_v1_streams(
-    Query(params): Query<BoostParams>,
-    State(state): State<AppState>
-) -> Response {
+    let (parts, body) = req.into_parts();
 
-    let index = params.index;
-    let boostcount = params.count;
+    let bytes = match axum::body::to_bytes(body, csrf::MAX_FORM_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => return Err(forbidden(req_line)),
+    };
 
-    //Was the "old" flag used?
-    let old = params.old;
+    let sent = url::form_urlencoded::parse(&bytes)
+        .find(|(name, _)| name == csrf::FORM_FIELD)
+        .map(|(_, token)| token.to_string());
 
-    println!("** Supplied index from call: [{}]", index);
-    println!("** Supplied stream count from call: [{}]", boostcount);
+    if !sent.is_some_and(|sent| csrf::matches(secret, binding, cookie, &sent)) {
+        return Err(forbidden(req_line));
+    }
 
-    let mut filters = BoostFilters::new();
-    filters.podcast = params.podcast;
+    Ok(Request::from_parts(parts, Body::from(bytes)))
+}
 
-    //Get the boosts from db for returning
-    let streams = match dbif::get_streams_from_db(&state.helipad_config.database_file_path, index, boostcount, old, true, filters) {
-        Ok(streams) => streams,
-        Err(e) => {
-            eprintln!("** Error getting streams: {}.\n", e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting streams.").into_response()
+pub async fn auth_middleware(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    jar: CookieJar,
+    req: Request,
+    next: Next,
+) -> Response {
+
+    let accounts = accounts_enabled(&state).await;
+    let proxy = state.helipad_config.trusted_proxy.clone();
+
+    if proxy.is_none() && !password_enabled(&state).await && !accounts {
+        return next.run(req).await; // no password required
+    }
+
+    let path = req.uri().path().to_string();
+
+    if path == "/login" || path == "/api/v1/login" || path.starts_with("/script") || path.starts_with("/style") {
+        return next.run(req).await; // no password required for certain paths
+    }
+
+    if proxy.is_some() && manages_own_login(&path) {
+        return (StatusCode::NOT_FOUND, "Passwords and two-factor are handled by the login proxy.").into_response();
+    }
+
+    if let Some(token) = api_token_from_request(&req) {
+        if manages_own_login(&path) {
+            return (StatusCode::FORBIDDEN, "API tokens can't change passwords, two-factor or sessions.").into_response();
         }
-    };
 
-    if params.triggers {
-        let streams_with_triggers = match triggers::get_boosts_with_triggers(&state.helipad_config.database_file_path, streams).await {
-            Ok(boosts) => boosts,
+        let db_filepath = state.helipad_config.database_file_path.clone();
+
+        let record = match dbif::run_blocking(move || api_tokens::check_token(&db_filepath, &token).map_err(Into::into)).await {
+            Ok(record) => record,
             Err(e) => {
-                eprintln!("** Error getting streams with triggers: {}.\n", e);
-                return (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting streams with triggers.").into_response()
+                eprintln!("** Error checking API token: {}", e);
+                None
             }
         };
 
-        Json(streams_with_triggers).into_response()
-    }
-    else {
-        Json(streams).into_response()
+        let scope = required_scope(req.method(), &path);
+
+        return match record {
+            Some(record) if record.scopes.contains(&scope) => next.run(req).await,
+            Some(record) => {
+                eprintln!("** API token {} can't {} {}", record.name, req.method(), path);
+                (StatusCode::FORBIDDEN, format!("This needs a token with the {} scope.", scope.name())).into_response()
+            }
+            None => (StatusCode::FORBIDDEN, "Not logged in").into_response(), // revoked, expired or unknown
+        };
     }
-}
 
-//API - get the current invoice index number
-pub async fn api_v1_index(State(state): State<AppState>) -> Response {
+    let secret = jwt_secret(&state).await;
 
-    //Get the last known invoice index from the database
-    match dbif::get_last_boost_index_from_db(&state.helipad_config.database_file_path) {
-        Ok(index) => {
-            println!("** get_last_boost_index_from_db() -> [{}]", index);
-            Json(index).into_response()
-        }
-        Err(e) => {
-            eprintln!("** Error getting current db index: {}.\n", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting current db index.").into_response()
+    // behind a trusted proxy only its header logs in
+    let (claims, from_cookie) = match (&proxy, verify_jwt_cookie(&jar, &secret)) {
+        (Some(_), _) => (None, false),
+        (None, Some(claims)) => (Some(claims), true),
+        (None, None) => (verify_jwt_header(&req, &secret), false),
+    };
+
+    let user = match (&proxy, &claims) {
+        (Some(proxy), _) => proxy_user(&state, proxy, addr, req.headers()).await,
+        (None, Some(claims)) => session_user(&state, claims, accounts, addr, from_cookie).await,
+        (None, None) => None,
+    };
+
+    let user = match user {
+        Some(user) => user,
+        None => {
+            let ctype = match req.headers().get(header::CONTENT_TYPE) {
+                Some(val) => val.to_str().unwrap_or(""),
+                None => "",
+            };
+
+            // login required
+            if proxy.is_some() {
+                return (StatusCode::FORBIDDEN, "Not logged in through the login proxy").into_response(); // no login page to send them to
+            }
+
+            if path.starts_with("/api/v1") || ctype.starts_with("application/json") {
+                return (StatusCode::FORBIDDEN, "Not logged in").into_response(); // json response
+            }
+
+            return Redirect::to("/login").into_response(); // redirect to login
         }
+    };
+
+    let required = required_role(req.method(), &path);
+
+    if user.role < required {
+        eprintln!("** {} ({}) can't {} {}", user.username.as_deref().unwrap_or("-"), user.role.name(), req.method(), path);
+        return (StatusCode::FORBIDDEN, format!("This needs the {} role.", required.name())).into_response();
     }
-}
 
-//API - get the current payment index number
-pub async fn api_v1_sent_index(State(state): State<AppState>) -> Response {
-    //Get the last known payment index from the database
-    match dbif::get_last_payment_index_from_db(&state.helipad_config.database_file_path) {
-        Ok(index) => {
-            println!("** get_last_payment_index_from_db() -> [{}]", index);
-            Json(index).into_response()
-        }
-        Err(e) => {
-            eprintln!("** Error getting current db index: {}.\n", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting current sent index.").into_response()
-        }
+    if !user.two_factor && requires_two_factor(req.method(), &path) && two_factor_enabled(&state, &user).await {
+        return (StatusCode::FORBIDDEN, "This needs two-factor authentication. Log in again with your code.").into_response();
     }
-}
 
-//API - serve sent as JSON either in ascending or descending order
-pub async fn api_v1_sent(
-    Query(params): Query<BoostParams>,
-    State(state): State<AppState>
-) -> Response {
+    // cookie and proxy logins are sent by the browser on their own, so changes need the CSRF token too
+    let csrf_binding = user.csrf_binding();
+    let csrf_cookie = jar.get(csrf::COOKIE_NAME)
+        .map(|cookie| cookie.value_trimmed().to_string())
+        .filter(|token| csrf::is_valid(&secret, &csrf_binding, token));
 
-    let index = params.index;
-    let boostcount = params.count;
+    let mut req = req;
 
-    //Was the "old" flag used?
-    let old = params.old;
+    if (from_cookie || proxy.is_some()) && !csrf::is_safe_method(req.method()) {
+        req = match check_csrf_token(req, &secret, &csrf_binding, csrf_cookie.as_deref()).await {
+            Ok(req) => req,
+            Err(resp) => return resp,
+        };
+    }
 
-    println!("** Supplied index from call: [{}]", index);
-    println!("** Supplied sent boost count from call: [{}]", boostcount);
+    req.extensions_mut().insert(user.clone());
 
-    let mut filters = BoostFilters::new();
-    filters.podcast = params.podcast;
+    if from_cookie {
+        // valid jwt: refresh and add to response
+        let resp = next.run(req).await;
 
-    //Get sent boosts from db for returning
-    let sent_boosts = match dbif::get_payments_from_db(&state.helipad_config.database_file_path, index, boostcount, old, true, filters) {
-        Ok(sent_boosts) => sent_boosts,
-        Err(e) => {
-            eprintln!("** Error getting sent boosts: {}.\n", e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting sent boosts.").into_response()
+        if resp.headers().contains_key(header::SET_COOKIE) {
+            return resp; // the handler logged in again, e.g. after a password change
         }
-    };
 
-    if params.triggers {
-        let sent_boosts_with_triggers = match triggers::get_boosts_with_triggers(&state.helipad_config.database_file_path, sent_boosts).await {
-            Ok(boosts) => boosts,
-            Err(e) => {
-                eprintln!("** Error getting sent boosts with triggers: {}.\n", e);
-                return (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting sent boosts with triggers.").into_response()
-            }
-        };
+        let long_lived = claims.and_then(|claims| claims.long_lived).unwrap_or(false);
+        let cookie = new_jwt_cookie(secret.clone(), long_lived, &user, state.helipad_config.secure_cookies);
+        let mut jar = jar.add(cookie);
 
-        Json(sent_boosts_with_triggers).into_response()
+        if csrf_cookie.is_none() {
+            jar = jar.add(csrf::cookie(csrf::new_token(&secret, &csrf_binding), state.helipad_config.secure_cookies));
+        }
+
+        return (jar, resp).into_response();
     }
-    else {
-        Json(sent_boosts).into_response()
+
+    if proxy.is_some() && csrf_cookie.is_none() {
+        let resp = next.run(req).await;
+        let jar = jar.add(csrf::cookie(csrf::new_token(&secret, &csrf_binding), state.helipad_config.secure_cookies));
+        return (jar, resp).into_response();
     }
+
+    // valid jwt
+    next.run(req).await.into_response()
 }
 
-pub async fn api_v1_podcasts(
-    State(state): State<AppState>
-) -> Response {
+//Login html
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoginForm {
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    password: String,
+    stay_logged_in: Option<String>,
+    code: Option<String>, // two-factor code or recovery code
+    challenge: Option<String>, // from the first step of a two-factor login
+}
 
-    //Get the podcasts from db for returning
-    match dbif::get_podcasts_from_db(&state.helipad_config.database_file_path) {
-        Ok(podcasts) => {
-            Json(podcasts).into_response()
-        }
+//Checks the login form against the accounts, or the shared password if there aren't any
+async fn check_login(state: &AppState, post_vars: &LoginForm) -> Option<SessionUser> {
+    if !accounts_enabled(state).await {
+        let hash = state.auth.read().await.password_hash.clone()?;
+        let password = post_vars.password.clone();
+
+        return match dbif::run_blocking(move || Ok(users::verify_password(&hash, &password))).await {
+            Ok(true) => Some(SessionUser::shared()),
+            _ => None,
+        };
+    }
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let username = post_vars.username.clone().unwrap_or_default();
+    let password = post_vars.password.clone();
+
+    match dbif::run_blocking(move || users::authenticate(&db_filepath, &username, &password).map_err(Into::into)).await {
+        Ok(user) => user.map(|user| SessionUser::from_account(&user)),
         Err(e) => {
-            eprintln!("** Error getting podcasts: {}.\n", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting podcasts.").into_response()
+            eprintln!("** Error checking login: {}", e);
+            None
         }
     }
 }
 
-pub async fn api_v1_sent_podcasts(
-    State(state): State<AppState>
-) -> Response {
+//Why a login was refused
+enum LoginError {
+    Invalid,
+    Locked(i64), // seconds until logins are allowed again
+    TwoFactorNeeded(String), // the password was right; the challenge carries it to the second step
+    TwoFactorInvalid(String),
+}
 
-    //Get the podcasts from db for returning
-    match dbif::get_sent_podcasts_from_db(&state.helipad_config.database_file_path) {
-        Ok(podcasts) => {
-            Json(podcasts).into_response()
+impl LoginError {
+    fn message(&self, accounts: bool) -> String {
+        match self {
+            LoginError::Invalid if accounts => "Bad username or password".to_string(),
+            LoginError::Invalid => "Bad password".to_string(),
+            LoginError::Locked(secs) => format!("Too many failed logins. Try again in {} seconds.", secs),
+            LoginError::TwoFactorNeeded(_) => "Enter the code from your authenticator app, or a recovery code".to_string(),
+            LoginError::TwoFactorInvalid(_) => "Bad two-factor code".to_string(),
         }
-        Err(e) => {
-            eprintln!("** Error getting sent podcasts: {}.\n", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting sent podcasts.").into_response()
+    }
+
+    fn challenge(&self) -> Option<&str> {
+        match self {
+            LoginError::TwoFactorNeeded(challenge) | LoginError::TwoFactorInvalid(challenge) => Some(challenge),
+            _ => None,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ReplyForm {
-    index: u64,
-    sats: u64,
-    sender: Option<String>,
-    message: Option<String>,
-}
+async fn record_login_attempt(state: &AppState, ip: &str, username: Option<String>, result: dbif::LoginResult) {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let ip = ip.to_string();
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ReplyResponse {
-    success: bool,
-    data: BoostRecord,
+    if let Err(e) = dbif::run_blocking(move || dbif::add_login_attempt_to_db(&db_filepath, &ip, username.as_deref(), result)).await {
+        eprintln!("** Error saving login attempt: {}", e);
+    }
 }
 
-pub async fn api_v1_reply(
-    State(state): State<AppState>,
-    Form(params): Form<ReplyForm>,
-) -> Response {
-    let index = params.index;
-    let sats = params.sats;
-    let sender = params.sender.unwrap_or("Anonymous".into());
-    let message = params.message.unwrap_or("".into());
+//Checks a login unless there have been too many failures, saving it to the login audit log. Lockouts run the lockout triggers.
+async fn attempt_login(state: &AppState, ip: &str, post_vars: &LoginForm) -> Result<SessionUser, LoginError> {
+    let mut username = post_vars.username.clone().filter(|username| !username.trim().is_empty());
 
-    let boost = match dbif::get_single_invoice_from_db(&state.helipad_config.database_file_path, index, true) {
-        Ok(Some(boost)) => boost,
-        Ok(None) => {
-            return (StatusCode::INTERNAL_SERVER_ERROR, "** Unknown boost index.").into_response();
+    let retry_after = state.login_throttle.lock().await.retry_after(ip, Utc::now().timestamp());
+
+    if let Some(secs) = retry_after {
+        record_login_attempt(state, ip, username, dbif::LoginResult::Locked).await;
+        return Err(LoginError::Locked(secs));
+    }
+
+    let challenge = post_vars.challenge.clone().filter(|challenge| !challenge.is_empty());
+
+    let user = match &challenge {
+        Some(challenge) => match verify_two_factor_challenge(&jwt_secret(state).await, challenge) {
+            Some(username) => find_user(state, username, accounts_enabled(state).await).await,
+            None => None,
         },
-        Err(_) => {
-            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error finding boost index.").into_response();
-        }
+        None => check_login(state, post_vars).await,
     };
 
-    let tlv = boost.parse_tlv().unwrap();
+    let mut error = LoginError::Invalid;
 
-    let pub_key = tlv["reply_address"].as_str().unwrap_or_default().to_string();
+    if let Some(mut user) = user {
+        let code = post_vars.code.clone().filter(|code| !code.trim().is_empty());
 
-    let custom_key = match tlv["reply_custom_key"].as_u64() {
-        None => None,
-        Some(0) => None,
-        Some(rck) => Some(rck),
-    };
+        let passed = match (two_factor_enabled(state, &user).await, code) {
+            (false, _) => true,
+            (true, None) => {
+                return Err(LoginError::TwoFactorNeeded(new_two_factor_challenge(&jwt_secret(state).await, &user)));
+            }
+            (true, Some(code)) => {
+                let db_filepath = state.helipad_config.database_file_path.clone();
+                let key = user.two_factor_key();
+
+                user.two_factor = match dbif::run_blocking(move || two_factor::verify_code(&db_filepath, &key, &code).map_err(Into::into)).await {
+                    Ok(valid) => valid,
+                    Err(e) => {
+                        eprintln!("** Error checking two-factor code: {}", e);
+                        false
+                    }
+                };
+
+                user.two_factor
+            }
+        };
 
-    let custom_value = match tlv["reply_custom_value"].as_str() {
-        None => None,
-        Some("") => None,
-        Some(rcv) => Some(rcv.to_string()),
-    };
+        if passed {
+            state.login_throttle.lock().await.succeed(ip);
+            record_login_attempt(state, ip, user.username.clone(), dbif::LoginResult::Success).await;
+            return Ok(user);
+        }
 
-    if pub_key.is_empty() {
-        return (StatusCode::BAD_REQUEST, "** No reply_address found in boost").into_response();
+        // keep the first challenge so retrying doesn't extend the time to enter a code
+        let challenge = match challenge {
+            Some(challenge) => challenge,
+            None => new_two_factor_challenge(&jwt_secret(state).await, &user),
+        };
+
+        username = user.username.clone();
+        error = LoginError::TwoFactorInvalid(challenge);
     }
 
-    if custom_key.is_none() && custom_value.is_some() {
-        return (StatusCode::BAD_REQUEST, "** No reply_custom_key found in boost").into_response();
+    let lockouts = state.login_throttle.lock().await.fail(ip, username.as_deref(), Utc::now().timestamp());
+    record_login_attempt(state, ip, username, dbif::LoginResult::Failed).await;
+
+    for lockout in lockouts {
+        let locked = match lockout.scope {
+            login_throttle::LockoutScope::Ip => lockout.ip.clone(),
+            login_throttle::LockoutScope::Global => "all logins".to_string(),
+        };
+
+        eprintln!("** Too many failed logins: locked out {} for {} seconds", locked, lockout.locked_seconds);
+
+        let db_filepath = state.helipad_config.database_file_path.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = triggers::process_lockout_triggers(&db_filepath, &lockout).await {
+                eprintln!("** Error running lockout triggers: {:#}", e);
+            }
+        });
     }
 
-    if custom_key.is_some() && custom_value.is_none() {
-        return (StatusCode::BAD_REQUEST, "** No reply_custom_value found in boost").into_response();
+    Err(error)
+}
+
+pub async fn login(State(state): State<AppState>) -> Response {
+    let accounts = accounts_enabled(&state).await;
+
+    if state.helipad_config.trusted_proxy.is_some() || (!password_enabled(&state).await && !accounts) {
+        return Redirect::to("/").into_response(); // no password required, or the proxy logs in
     }
 
-    let reply_tlv = json!({
-        "app_name": "Helipad",
-        "app_version": state.version,
-        "podcast": tlv["podcast"].as_str().unwrap_or_default(),
-        "episode": tlv["episode"].as_str().unwrap_or_default(),
-        "name": tlv["sender_name"].as_str().unwrap_or_default(),
-        "sender_name": sender,
-        "message": message,
-        "action": "boost",
-        "value_msat": sats * 1000,
-        "value_msat_total": sats * 1000,
-    });
+    HtmlTemplate("webroot/html/login.html", json!({"message": "", "accounts": accounts})).into_response()
+}
 
-    let helipad_config = state.helipad_config.clone();
-    let lightning = match lightning::connect_to_lnd(&helipad_config.node_address, &helipad_config.cert_path, &helipad_config.macaroon_path).await {
-        Some(lndconn) => lndconn,
-        None => {
-            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error connecting to LND.").into_response();
-        }
-    };
+pub async fn handle_login(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    jar: CookieJar,
+    Form(post_vars): Form<LoginForm>,
+) -> Response {
 
-    let payment = match lightning::send_boost(lightning, pub_key, custom_key, custom_value, sats, reply_tlv.clone()).await {
-        Ok(payment) => payment,
-        Err(e) => {
-            eprintln!("** Error sending boost: {}", e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, format!("** Error sending boost: {}", e)).into_response();
+    if state.helipad_config.trusted_proxy.is_some() {
+        return Redirect::to("/").into_response(); // the proxy logs in
+    }
+
+    let error = match attempt_login(&state, &addr.ip().to_string(), &post_vars).await {
+        Ok(mut user) => {
+            // valid password: set cookie and redirect
+            let stay_logged_in = post_vars.stay_logged_in.is_some();
+
+            if let Err(e) = start_session(&state, &mut user, &headers, addr, stay_logged_in).await {
+                return (StatusCode::INTERNAL_SERVER_ERROR, e).into_response();
+            }
+
+            let secret = jwt_secret(&state).await;
+            let secure = state.helipad_config.secure_cookies;
+            let jar = jar
+                .add(new_jwt_cookie(secret.clone(), stay_logged_in, &user, secure))
+                .add(csrf::cookie(csrf::new_token(&secret, &user.csrf_binding()), secure));
+            let resp = Redirect::to("/");
+            return (jar, resp).into_response();
+        }
+        Err(e) => e,
+    };
+
+    let accounts = accounts_enabled(&state).await;
+
+    HtmlTemplate("webroot/html/login.html", json!({
+        "version": state.version,
+        "message": error.message(accounts),
+        "accounts": accounts,
+        "username": post_vars.username,
+        "challenge": error.challenge(),
+        "stay_logged_in": post_vars.stay_logged_in.is_some(),
+    })).into_response()
+}
+
+//API - authenticate and return JWT token for API clients
+pub async fn api_v1_login(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(post_vars): Json<LoginForm>,
+) -> Response {
+
+    if state.helipad_config.trusted_proxy.is_some() {
+        let resp = json!({
+            "success": false,
+            "message": "Logins are handled by the login proxy. Use an API token.",
+        });
+        return (StatusCode::FORBIDDEN, Json(resp)).into_response();
+    }
+
+    if !password_enabled(&state).await && !accounts_enabled(&state).await {
+        let resp = json!({
+            "success": true,
+            "message": "No password required",
+        });
+        return (StatusCode::OK, Json(resp)).into_response();
+    }
+
+    match attempt_login(&state, &addr.ip().to_string(), &post_vars).await {
+        Ok(mut user) => {
+            // valid password: return a token
+            let stay_logged_in = post_vars.stay_logged_in.is_some();
+
+            if let Err(e) = start_session(&state, &mut user, &headers, addr, stay_logged_in).await {
+                let resp = json!({
+                    "success": false,
+                    "message": e,
+                });
+                return (StatusCode::INTERNAL_SERVER_ERROR, Json(resp)).into_response();
+            }
+
+            let cookie = new_jwt_cookie(jwt_secret(&state).await, stay_logged_in, &user, state.helipad_config.secure_cookies);
+            let resp = json!({
+                "success": true,
+                "message": "Login successful",
+                "token": cookie.clone().value().to_string(),
+                "username": user.username,
+                "role": user.role,
+            });
+            (StatusCode::OK, Json(resp)).into_response()
+        }
+        Err(LoginError::Locked(secs)) => {
+            let resp = json!({
+                "success": false,
+                "message": LoginError::Locked(secs).message(true),
+                "retry_after": secs,
+            });
+            (StatusCode::TOO_MANY_REQUESTS, [(header::RETRY_AFTER, secs.to_string())], Json(resp)).into_response()
+        }
+        Err(LoginError::Invalid) => {
+            let resp = json!({
+                "success": false,
+                "message": "Invalid password",
+            });
+            (StatusCode::UNAUTHORIZED, Json(resp)).into_response()
+        }
+        Err(error) => {
+            let resp = json!({
+                "success": false,
+                "message": error.message(true),
+                "two_factor_required": true,
+                "challenge": error.challenge(),
+            });
+            (StatusCode::UNAUTHORIZED, Json(resp)).into_response()
+        }
+    }
+}
+
+//Change password html
+#[derive(Debug, Deserialize)]
+pub struct PasswordForm {
+    #[serde(default)]
+    current_password: String,
+    new_password: String,
+    #[serde(default)]
+    confirm_password: Option<String>,
+}
+
+//Changes the logged in user's own password and logs out their other sessions, or changes the shared password and
+//logs everyone else out. Returns a new login so whoever changed it stays logged in, in the same session if they have
+//one, along with who was logged out.
+async fn change_own_password(
+    state: &AppState,
+    user: Option<SessionUser>,
+    headers: &HeaderMap,
+    addr: SocketAddr,
+    form: PasswordForm,
+) -> Result<(Cookie<'static>, &'static str), (StatusCode, String)> {
+    //Only when there's no login at all can the first shared password be set without one
+    let auth_enabled = state.helipad_config.trusted_proxy.is_some() || password_enabled(state).await || accounts_enabled(state).await;
+
+    let mut user = match user {
+        Some(user) => user,
+        None if auth_enabled => return Err((StatusCode::FORBIDDEN, "Not logged in".to_string())),
+        None => SessionUser::shared(),
+    };
+
+    if form.confirm_password.as_ref().is_some_and(|confirm| *confirm != form.new_password) {
+        return Err((StatusCode::BAD_REQUEST, "The new passwords don't match".to_string()));
+    }
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let username = user.username.clone();
+
+    let result = dbif::run_blocking(move || {
+        password::change_password(&db_filepath, username.as_deref(), &form.current_password, &form.new_password).map_err(Into::into)
+    }).await;
+
+    let secrets = match result {
+        Ok(secrets) => secrets,
+        Err(e) => {
+            eprintln!("** Error changing password: {}", e);
+            return Err((StatusCode::BAD_REQUEST, e.to_string()));
+        }
+    };
+
+    //Only the shared password's secret is shared by everyone, so an account just logs out its own sessions
+    let logged_out = if secrets.is_some() {
+        "Everyone else has been logged out."
+    } else {
+        "Your other sessions have been logged out."
+    };
+
+    println!("** Password changed for {}. {}", user.username.as_deref().unwrap_or("the shared login"), logged_out);
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let keep = user.session.clone();
+    let key = user.two_factor_key();
+    let shared = secrets.is_some();
+
+    let result = dbif::run_blocking(move || {
+        if shared {
+            dbif::delete_all_sessions_from_db(&db_filepath, keep.as_deref())
+        } else {
+            sessions::end_sessions(&db_filepath, &key, keep.as_deref()).map_err(Into::into)
+        }
+    }).await;
+
+    if let Err(e) = result {
+        eprintln!("** Error logging out sessions: {}", e);
+    }
+
+    if user.session.is_none() {
+        start_session(state, &mut user, headers, addr, false).await.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    }
+
+    let jwt_secret = match secrets {
+        Some(secrets) => {
+            let jwt_secret = secrets.jwt_secret.clone();
+            *state.auth.write().await = secrets;
+            jwt_secret
+        }
+        None => state.auth.read().await.jwt_secret.clone(),
+    };
+
+    Ok((new_jwt_cookie(jwt_secret, false, &user, state.helipad_config.secure_cookies), logged_out))
+}
+
+async fn password_page(state: &AppState, user: Option<&SessionUser>, message: &str, error: &str) -> Response {
+    let username = user.and_then(|user| user.username.clone());
+    let current = username.is_some() || password_enabled(state).await;
+
+    HtmlTemplate("webroot/html/password.html", json!({
+        "version": state.version,
+        "username": username,
+        "current": current,
+        "message": message,
+        "error": error,
+    })).into_response()
+}
+
+pub async fn password(
+    State(state): State<AppState>,
+    user: Option<Extension<SessionUser>>,
+) -> Response {
+    password_page(&state, user.as_ref().map(|user| &user.0), "", "").await
+}
+
+pub async fn handle_password(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    user: Option<Extension<SessionUser>>,
+    jar: CookieJar,
+    Form(form): Form<PasswordForm>,
+) -> Response {
+    let user = user.map(|user| user.0);
+
+    match change_own_password(&state, user.clone(), &headers, addr, form).await {
+        Ok((cookie, logged_out)) => {
+            let message = format!("Password changed. {}", logged_out);
+            let page = password_page(&state, user.as_ref(), &message, "").await;
+            (jar.add(cookie), page).into_response()
+        }
+        Err((status, e)) => (status, password_page(&state, user.as_ref(), "", &e).await).into_response(),
+    }
+}
+
+//API - change the logged in user's password, or the shared password, and get a new token
+pub async fn api_v1_password(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    user: Option<Extension<SessionUser>>,
+    Json(form): Json<PasswordForm>,
+) -> Response {
+    match change_own_password(&state, user.map(|user| user.0), &headers, addr, form).await {
+        Ok((cookie, _)) => (StatusCode::OK, Json(json!({
+            "success": true,
+            "message": "Password changed",
+            "token": cookie.value().to_string(),
+        }))).into_response(),
+        Err((status, e)) => (status, Json(json!({
+            "success": false,
+            "message": e,
+        }))).into_response(),
+    }
+}
+
+//Two-factor code from the settings forms and the API
+#[derive(Debug, Deserialize)]
+pub struct TwoFactorForm {
+    #[serde(default)]
+    code: String,
+}
+
+//Two-factor needs someone logged in with a password. API tokens can't change it.
+fn two_factor_user(user: Option<Extension<SessionUser>>) -> Result<SessionUser, String> {
+    user.map(|user| user.0).ok_or_else(|| "Log in with a password to set up two-factor authentication".to_string())
+}
+
+//Runs a two-factor change for the logged in user, or the shared login
+async fn run_two_factor<T, F>(state: &AppState, user: &SessionUser, f: F) -> Result<T, String>
+where
+    F: FnOnce(&str, &str) -> anyhow::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let key = user.two_factor_key();
+
+    dbif::run_blocking(move || f(&db_filepath, &key).map_err(Into::into)).await.map_err(|e| {
+        eprintln!("** Two-factor error: {}", e);
+        e.to_string()
+    })
+}
+
+async fn two_factor_fragment(state: &AppState, user: Option<&SessionUser>, extra: serde_json::Value) -> Response {
+    let record = match user {
+        Some(user) => {
+            let db_filepath = state.helipad_config.database_file_path.clone();
+            let key = user.two_factor_key();
+
+            dbif::run_blocking(move || dbif::get_two_factor_from_db(&db_filepath, &key)).await.unwrap_or_else(|e| {
+                eprintln!("** Error getting two-factor: {}", e);
+                None
+            })
+        }
+        None => None,
+    };
+
+    let mut params = json!({
+        "available": user.is_some(),
+        "username": user.and_then(|user| user.username.clone()),
+        "enabled": record.as_ref().is_some_and(|record| record.enabled),
+        "recovery_codes_left": record.as_ref().map(|record| record.recovery_codes).unwrap_or(0),
+    });
+
+    if let (Some(params), serde_json::Value::Object(extra)) = (params.as_object_mut(), extra) {
+        params.extend(extra);
+    }
+
+    HtmlTemplate("webroot/template/two-factor.hbs", params).into_response()
+}
+
+pub async fn two_factor_settings(
+    State(state): State<AppState>,
+    user: Option<Extension<SessionUser>>,
+) -> Response {
+    two_factor_fragment(&state, user.as_ref().map(|user| &user.0), json!({})).await
+}
+
+pub async fn two_factor_settings_start(
+    State(state): State<AppState>,
+    user: Option<Extension<SessionUser>>,
+) -> Response {
+    let user = match two_factor_user(user) {
+        Ok(user) => user,
+        Err(e) => return two_factor_fragment(&state, None, json!({"error": e})).await,
+    };
+
+    let extra = match run_two_factor(&state, &user, two_factor::start_enrolment).await {
+        Ok(enrolment) => json!({"enrolment": {"secret": enrolment.secret, "qr_svg": enrolment.qr_svg}}),
+        Err(e) => json!({"error": e}),
+    };
+
+    two_factor_fragment(&state, Some(&user), extra).await
+}
+
+pub async fn two_factor_settings_confirm(
+    State(state): State<AppState>,
+    user: Option<Extension<SessionUser>>,
+    jar: CookieJar,
+    Form(form): Form<TwoFactorForm>,
+) -> Response {
+    let mut user = match two_factor_user(user) {
+        Ok(user) => user,
+        Err(e) => return two_factor_fragment(&state, None, json!({"error": e})).await,
+    };
+
+    match run_two_factor(&state, &user, move |db, key| two_factor::confirm_enrolment(db, key, &form.code)).await {
+        Ok(codes) => {
+            println!("** Two-factor turned on for {}", user.username.as_deref().unwrap_or("the shared login"));
+
+            // this session just gave a code, so it can carry on sending boosts and changing settings
+            user.two_factor = true;
+            let cookie = new_jwt_cookie(jwt_secret(&state).await, false, &user, state.helipad_config.secure_cookies);
+            let page = two_factor_fragment(&state, Some(&user), json!({"recovery_codes": codes})).await;
+
+            (jar.add(cookie), page).into_response()
+        }
+        Err(e) => two_factor_fragment(&state, Some(&user), json!({"error": e})).await,
+    }
+}
+
+pub async fn two_factor_settings_recovery(
+    State(state): State<AppState>,
+    user: Option<Extension<SessionUser>>,
+    Form(form): Form<TwoFactorForm>,
+) -> Response {
+    let user = match two_factor_user(user) {
+        Ok(user) => user,
+        Err(e) => return two_factor_fragment(&state, None, json!({"error": e})).await,
+    };
+
+    let extra = match run_two_factor(&state, &user, move |db, key| two_factor::regenerate_recovery_codes(db, key, &form.code)).await {
+        Ok(codes) => json!({"recovery_codes": codes}),
+        Err(e) => json!({"error": e}),
+    };
+
+    two_factor_fragment(&state, Some(&user), extra).await
+}
+
+pub async fn two_factor_settings_disable(
+    State(state): State<AppState>,
+    user: Option<Extension<SessionUser>>,
+    Form(form): Form<TwoFactorForm>,
+) -> Response {
+    let user = match two_factor_user(user) {
+        Ok(user) => user,
+        Err(e) => return two_factor_fragment(&state, None, json!({"error": e})).await,
+    };
+
+    let extra = match run_two_factor(&state, &user, move |db, key| two_factor::disable(db, key, &form.code)).await {
+        Ok(()) => {
+            println!("** Two-factor turned off for {}", user.username.as_deref().unwrap_or("the shared login"));
+            json!({})
+        }
+        Err(e) => json!({"error": e}),
+    };
+
+    two_factor_fragment(&state, Some(&user), extra).await
+}
+
+fn two_factor_error(status: StatusCode, message: String) -> Response {
+    (status, Json(json!({
+        "success": false,
+        "message": message,
+    }))).into_response()
+}
+
+//API - two-factor status for the logged in user
+pub async fn api_v1_two_factor(
+    State(state): State<AppState>,
+    user: Option<Extension<SessionUser>>,
+) -> Response {
+    let user = match two_factor_user(user) {
+        Ok(user) => user,
+        Err(e) => return two_factor_error(StatusCode::FORBIDDEN, e),
+    };
+
+    match run_two_factor(&state, &user, |db, key| dbif::get_two_factor_from_db(db, key).map_err(|e| anyhow::anyhow!("{}", e))).await {
+        Ok(record) => Json(json!({
+            "enabled": record.as_ref().is_some_and(|record| record.enabled),
+            "pending": record.as_ref().is_some_and(|record| !record.enabled),
+            "recovery_codes": record.as_ref().map(|record| record.recovery_codes).unwrap_or(0),
+        })).into_response(),
+        Err(e) => two_factor_error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+//API - make a new two-factor secret. It's turned on by confirming a code from it.
+pub async fn api_v1_two_factor_start(
+    State(state): State<AppState>,
+    user: Option<Extension<SessionUser>>,
+) -> Response {
+    let user = match two_factor_user(user) {
+        Ok(user) => user,
+        Err(e) => return two_factor_error(StatusCode::FORBIDDEN, e),
+    };
+
+    match run_two_factor(&state, &user, two_factor::start_enrolment).await {
+        Ok(enrolment) => Json(json!({
+            "secret": enrolment.secret,
+            "url": enrolment.url,
+            "qr_svg": enrolment.qr_svg,
+        })).into_response(),
+        Err(e) => two_factor_error(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+//API - turn two-factor on, returning the recovery codes and a token that counts as a two-factor login
+pub async fn api_v1_two_factor_confirm(
+    State(state): State<AppState>,
+    user: Option<Extension<SessionUser>>,
+    Json(form): Json<TwoFactorForm>,
+) -> Response {
+    let mut user = match two_factor_user(user) {
+        Ok(user) => user,
+        Err(e) => return two_factor_error(StatusCode::FORBIDDEN, e),
+    };
+
+    match run_two_factor(&state, &user, move |db, key| two_factor::confirm_enrolment(db, key, &form.code)).await {
+        Ok(codes) => {
+            println!("** Two-factor turned on for {}", user.username.as_deref().unwrap_or("the shared login"));
+
+            user.two_factor = true;
+            let token = new_jwt_token(jwt_secret(&state).await, false, JWT_SESSION_HOURS, &user);
+
+            Json(json!({
+                "success": true,
+                "recovery_codes": codes,
+                "token": token,
+            })).into_response()
+        }
+        Err(e) => two_factor_error(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+//API - replace the recovery codes
+pub async fn api_v1_two_factor_recovery(
+    State(state): State<AppState>,
+    user: Option<Extension<SessionUser>>,
+    Json(form): Json<TwoFactorForm>,
+) -> Response {
+    let user = match two_factor_user(user) {
+        Ok(user) => user,
+        Err(e) => return two_factor_error(StatusCode::FORBIDDEN, e),
+    };
+
+    match run_two_factor(&state, &user, move |db, key| two_factor::regenerate_recovery_codes(db, key, &form.code)).await {
+        Ok(codes) => Json(json!({
+            "success": true,
+            "recovery_codes": codes,
+        })).into_response(),
+        Err(e) => two_factor_error(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+//API - turn two-factor off
+pub async fn api_v1_two_factor_disable(
+    State(state): State<AppState>,
+    user: Option<Extension<SessionUser>>,
+    Json(form): Json<TwoFactorForm>,
+) -> Response {
+    let user = match two_factor_user(user) {
+        Ok(user) => user,
+        Err(e) => return two_factor_error(StatusCode::FORBIDDEN, e),
+    };
+
+    match run_two_factor(&state, &user, move |db, key| two_factor::disable(db, key, &form.code)).await {
+        Ok(()) => {
+            println!("** Two-factor turned off for {}", user.username.as_deref().unwrap_or("the shared login"));
+
+            Json(json!({
+                "success": true,
+                "message": "Two-factor authentication is off",
+            })).into_response()
+        }
+        Err(e) => two_factor_error(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+//Sessions belong to someone logged in with a password. API tokens and the login proxy don't have them.
+fn session_owner(user: Option<Extension<SessionUser>>) -> Result<SessionUser, String> {
+    user.map(|user| user.0)
+        .filter(|user| user.session.is_some())
+        .ok_or_else(|| "Log in with a password to see your sessions".to_string())
+}
+
+//Gets the logged in user's sessions, with the one they're using marked
+async fn user_sessions(state: &AppState, user: &SessionUser) -> Result<Vec<(dbif::SessionRecord, bool)>, String> {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let key = user.two_factor_key();
+
+    match dbif::run_blocking(move || dbif::get_sessions_from_db(&db_filepath, &key)).await {
+        Ok(sessions) => Ok(sessions.into_iter().map(|session| {
+            let current = user.session.as_deref() == Some(session.jti.as_str());
+            (session, current)
+        }).collect()),
+        Err(e) => {
+            eprintln!("** Error getting sessions: {}", e);
+            Err("Unable to get sessions".to_string())
+        }
+    }
+}
+
+//Logs out one of the logged in user's sessions, or all of them if there's no index. Returns how many were logged out.
+async fn end_user_sessions(state: &AppState, user: &SessionUser, index: Option<u64>) -> Result<u64, String> {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let key = user.two_factor_key();
+
+    let result = dbif::run_blocking(move || match index {
+        Some(index) => sessions::end_session(&db_filepath, &key, index).map(u64::from).map_err(Into::into),
+        None => sessions::end_sessions(&db_filepath, &key, None).map_err(Into::into),
+    }).await;
+
+    match result {
+        Ok(count) => {
+            println!("** Logged out {} session(s) for {}", count, user.username.as_deref().unwrap_or("the shared login"));
+            Ok(count)
+        }
+        Err(e) => {
+            eprintln!("** Error logging out sessions: {}", e);
+            Err("Unable to log out".to_string())
+        }
+    }
+}
+
+//Whether the session with this index is the one being used
+async fn is_current_session(state: &AppState, user: &SessionUser, index: u64) -> bool {
+    user_sessions(state, user).await
+        .map(|sessions| sessions.iter().any(|(session, current)| *current && session.index == index))
+        .unwrap_or(false)
+}
+
+//Clears the login cookie and sends htmx to the login page
+fn logged_out(jar: CookieJar) -> Response {
+    let jar = jar.remove(Cookie::build("HELIPAD_JWT").path("/"));
+    (jar, [("HX-Redirect", "/login")]).into_response()
+}
+
+async fn sessions_fragment(state: &AppState, user: Option<&SessionUser>, error: Option<String>) -> Response {
+    let date = |time: i64| DateTime::from_timestamp(time, 0).map(|dt| dt.format("%Y-%m-%d %H:%M").to_string());
+
+    let (sessions, error) = match user {
+        Some(user) => match user_sessions(state, user).await {
+            Ok(sessions) => (sessions, error),
+            Err(e) => (Vec::new(), Some(e)),
+        },
+        None => (Vec::new(), error),
+    };
+
+    let rows: Vec<_> = sessions.iter().map(|(session, current)| json!({
+        "session": session,
+        "current": current,
+        "created": date(session.created),
+        "last_seen": date(session.last_seen),
+        "expires": date(session.expires),
+    })).collect();
+
+    HtmlTemplate("webroot/template/sessions.hbs", json!({
+        "available": user.is_some(),
+        "sessions": rows,
+        "error": error,
+    })).into_response()
+}
+
+pub async fn session_settings(
+    State(state): State<AppState>,
+    user: Option<Extension<SessionUser>>,
+) -> Response {
+    let user = session_owner(user).ok();
+    sessions_fragment(&state, user.as_ref(), None).await
+}
+
+pub async fn session_settings_delete(
+    State(state): State<AppState>,
+    user: Option<Extension<SessionUser>>,
+    jar: CookieJar,
+    Path(idx): Path<String>,
+) -> Response {
+    let user = match session_owner(user) {
+        Ok(user) => user,
+        Err(e) => return sessions_fragment(&state, None, Some(e)).await,
+    };
+
+    let index: u64 = match idx.parse() {
+        Ok(index) => index,
+        Err(_) => return (StatusCode::BAD_REQUEST, "** Invalid session index.").into_response(),
+    };
+
+    let current = is_current_session(&state, &user, index).await;
+
+    match end_user_sessions(&state, &user, Some(index)).await {
+        Ok(_) if current => logged_out(jar),
+        Ok(_) => sessions_fragment(&state, Some(&user), None).await,
+        Err(e) => sessions_fragment(&state, Some(&user), Some(e)).await,
+    }
+}
+
+pub async fn session_settings_delete_all(
+    State(state): State<AppState>,
+    user: Option<Extension<SessionUser>>,
+    jar: CookieJar,
+) -> Response {
+    let user = match session_owner(user) {
+        Ok(user) => user,
+        Err(e) => return sessions_fragment(&state, None, Some(e)).await,
+    };
+
+    match end_user_sessions(&state, &user, None).await {
+        Ok(_) => logged_out(jar),
+        Err(e) => sessions_fragment(&state, Some(&user), Some(e)).await,
+    }
+}
+
+fn session_error(status: StatusCode, message: String) -> Response {
+    (status, Json(json!({
+        "success": false,
+        "message": message,
+    }))).into_response()
+}
+
+//API - the logged in user's sessions
+pub async fn api_v1_sessions(
+    State(state): State<AppState>,
+    user: Option<Extension<SessionUser>>,
+) -> Response {
+    let user = match session_owner(user) {
+        Ok(user) => user,
+        Err(e) => return session_error(StatusCode::FORBIDDEN, e),
+    };
+
+    match user_sessions(&state, &user).await {
+        Ok(sessions) => {
+            let sessions: Vec<_> = sessions.into_iter().map(|(session, current)| {
+                let mut value = json!(session);
+
+                if let Some(value) = value.as_object_mut() {
+                    value.insert("current".to_string(), json!(current));
+                }
+
+                value
+            }).collect();
+
+            Json(sessions).into_response()
+        }
+        Err(e) => session_error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+//API - log out one of the logged in user's sessions
+pub async fn api_v1_session_delete(
+    State(state): State<AppState>,
+    user: Option<Extension<SessionUser>>,
+    Path(idx): Path<String>,
+) -> Response {
+    let user = match session_owner(user) {
+        Ok(user) => user,
+        Err(e) => return session_error(StatusCode::FORBIDDEN, e),
+    };
+
+    let index: u64 = match idx.parse() {
+        Ok(index) => index,
+        Err(_) => return session_error(StatusCode::BAD_REQUEST, "Invalid session index".to_string()),
+    };
+
+    match end_user_sessions(&state, &user, Some(index)).await {
+        Ok(0) => session_error(StatusCode::NOT_FOUND, "Session not found".to_string()),
+        Ok(_) => Json(json!({
+            "success": true,
+            "message": "Logged out",
+        })).into_response(),
+        Err(e) => session_error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+//API - log out all of the logged in user's sessions, this one included
+pub async fn api_v1_sessions_delete(
+    State(state): State<AppState>,
+    user: Option<Extension<SessionUser>>,
+) -> Response {
+    let user = match session_owner(user) {
+        Ok(user) => user,
+        Err(e) => return session_error(StatusCode::FORBIDDEN, e),
+    };
+
+    match end_user_sessions(&state, &user, None).await {
+        Ok(count) => Json(json!({
+            "success": true,
+            "message": "Logged out everywhere",
+            "sessions": count,
+        })).into_response(),
+        Err(e) => session_error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+struct HtmlTemplate<'a, T>(&'a str, T);
+
+impl<T> IntoResponse for HtmlTemplate<'_, T>
+where
+    T: Serialize,
+{
+    fn into_response(self) -> Response {
+        let reg = Handlebars::new();
+
+        let doc = match fs::read_to_string(self.0) {
+            Ok(doc) => doc,
+            Err(e) => {
+                eprintln!("** Unable to open template file {}: {}.\n", self.0, e);
+                return (StatusCode::BAD_REQUEST, "Unable to open template file").into_response();
+            }
+        };
+
+        let doc_rendered = match reg.render_template(&doc, &self.1) {
+            Ok(rendered) => rendered,
+            Err(e) => {
+                eprintln!("** Unable to render template {}: {}.\n", self.0, e);
+                return (StatusCode::BAD_REQUEST, "Unable to render template").into_response();
+            }
+        };
+
+        Html(doc_rendered).into_response()
+    }
+}
+
+//Homepage html
+pub async fn home(State(state): State<AppState>) -> Response {
+    HtmlTemplate("webroot/html/home.html", &json!({"version": state.version, "proxy": state.helipad_config.trusted_proxy.is_some()})).into_response()
+}
+
+//Streams html
+pub async fn streams(State(state): State<AppState>) -> Response {
+    HtmlTemplate("webroot/html/streams.html", &json!({"version": state.version, "proxy": state.helipad_config.trusted_proxy.is_some()})).into_response()
+}
+
+//Sent html
+pub async fn sent(State(state): State<AppState>) -> Response {
+    HtmlTemplate("webroot/html/sent.html", &json!({"version": state.version, "proxy": state.helipad_config.trusted_proxy.is_some()})).into_response()
+}
+
+//Streams html
+pub async fn settings(State(state): State<AppState>) -> Response {
+    HtmlTemplate("webroot/html/settings.html", &json!({"version": state.version, "proxy": state.helipad_config.trusted_proxy.is_some()})).into_response()
+}
+
+
+//Numerology definitions file
+pub async fn numerology_json(State(state): State<AppState>) -> Response {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let results = dbif::run_blocking(move || dbif::get_numerology_from_db(&db_filepath)).await.unwrap();
+    Json(results).into_response()
+}
+
+//API - give back node info
+pub async fn api_v1_node_info(State(state): State<AppState>) -> Response {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    match dbif::run_blocking(move || dbif::get_node_info_from_db(&db_filepath)).await {
+        Ok(info) => {
+            Json(info).into_response()
+        }
+        Err(e) => {
+            eprintln!("** Error getting node info: {}.\n", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting node info.").into_response()
+        }
+    }
+}
+
+pub async fn api_v1_settings(State(state): State<AppState>) -> Response {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    match dbif::run_blocking(move || dbif::load_settings_from_db(&db_filepath)).await {
+        Ok(settings) => {
+            Json(settings).into_response()
+        }
+        Err(e) => {
+            eprintln!("** Error getting settings: {}.\n", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting settings.").into_response()
+        }
+    }
+}
+
+//API - give back the node balance
+pub async fn api_v1_balance(State(state): State<AppState>) -> Response {
+
+    //Get the boosts from db for returning
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    match dbif::run_blocking(move || dbif::get_wallet_balance_from_db(&db_filepath)).await {
+        Ok(balance) => {
+            Json(balance).into_response()
+        }
+        Err(e) => {
+            eprintln!("** Error getting balance: {}.\n", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting balance.").into_response()
+        }
+    }
+}
+
+//API - serve boosts as JSON either in ascending or descending order
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BoostParams {
+    index: u64,
+    count: u64,
+    #[serde(default)]
+    old: bool,
+    #[serde(default)]
+    triggers: bool,
+    #[serde(default)]
+    podcast: Option<String>,
+    #[serde(default)]
+    hidden: bool,
+    #[serde(default)]
+    tag: Option<String>,
+    #[serde(default)]
+    pinned: Option<bool>,
+    #[serde(default)]
+    supporter: Option<u64>,
+}
+
+impl Default for BoostParams {
+    fn default() -> Self {
+        Self {
+            index: 0,
+            count: 0,
+            old: false,
+            triggers: false,
+            podcast: None,
+            hidden: false,
+            tag: None,
+            pinned: None,
+            supporter: None,
+        }
+    }
+}
+
+pub async fn api_v1_boosts(
+    Query(params): Query<BoostParams>,
+    State(state): State<AppState>,
+) -> Response {
+
+    let index = params.index;
+    let boostcount = params.count;
+
+    //Was the "old" flag used?
+    let old = params.old;
+
+    println!("** Supplied index from call: [{}]", index);
+    println!("** Supplied boost count from call: [{}]", boostcount);
+
+    let mut filters = BoostFilters::new();
+    filters.podcast = params.podcast;
+    filters.include_hidden = params.hidden;
+    filters.tag = params.tag.filter(|tag| !tag.is_empty());
+    filters.pinned = params.pinned;
+    filters.supporter = params.supporter;
+
+    //Get the boosts from db for returning
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let mut boosts = match dbif::run_blocking(move || dbif::get_boosts_from_db(&db_filepath, index, boostcount, old, true, filters)).await {
+        Ok(boosts) => boosts,
+        Err(e) => {
+            eprintln!("** Error getting boosts: {}.\n", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting boosts.").into_response()
+        }
+    };
+
+    numerology::attach_numerology(&state.helipad_config.database_file_path, &mut boosts).await;
+    attach_annotations(&state, &mut boosts, true).await;
+    resolve_nostr_refs(&state, &mut boosts).await;
+
+    if params.triggers {
+        let boosts_with_triggers = match triggers::get_boosts_with_triggers(&state.helipad_config.database_file_path, boosts).await {
+            Ok(boosts) => boosts,
+            Err(e) => {
+                eprintln!("** Error getting boosts with triggers: {}.\n", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting boosts with triggers.").into_response()
+            }
+        };
+
+        Json(boosts_with_triggers).into_response()
+    }
+    else {
+        Json(boosts).into_response()
+    }
+}
+
+//Attach the tags, notes, pins and supporters to the boosts
+async fn attach_annotations(state: &AppState, boosts: &mut [BoostRecord], escape_html: bool) {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let mut annotated = boosts.to_vec();
+
+    let result = dbif::run_blocking(move || {
+        if let Err(e) = dbif::attach_annotations_from_db(&db_filepath, &mut annotated, escape_html) {
+            eprintln!("** Error getting boost annotations: {}", e);
+        }
+
+        if let Err(e) = dbif::attach_supporters_from_db(&db_filepath, &mut annotated) {
+            eprintln!("** Error getting boost supporters: {}", e);
+        }
+
+        Ok(annotated)
+    }).await;
+
+    match result {
+        Ok(annotated) => boosts.clone_from_slice(&annotated),
+        Err(e) => eprintln!("** Error getting boost annotations: {}", e),
+    }
+}
+
+//Attach cached nostr references to the boosts when enabled
+async fn resolve_nostr_refs(state: &AppState, boosts: &mut [BoostRecord]) {
+    let settings = state.settings.read().await.clone();
+
+    if settings.resolve_nostr_refs {
+        nostr::attach_boost_refs(&state.helipad_config.database_file_path, &settings.nostr_relays, boosts).await;
+    }
+}
+
+//API - serve streams as JSON either in ascending or descending order
+pub async fn api_v1_streams(
+    Query(params): Query<BoostParams>,
+    State(state): State<AppState>
+) -> Response {
+
+    let index = params.index;
+    let boostcount = params.count;
+
+    //Was the "old" flag used?
+    let old = params.old;
+
+    println!("** Supplied index from call: [{}]", index);
+    println!("** Supplied stream count from call: [{}]", boostcount);
+
+    let mut filters = BoostFilters::new();
+    filters.podcast = params.podcast;
+    filters.include_hidden = params.hidden;
+    filters.tag = params.tag.filter(|tag| !tag.is_empty());
+    filters.pinned = params.pinned;
+    filters.supporter = params.supporter;
+
+    //Get the boosts from db for returning
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let mut streams = match dbif::run_blocking(move || dbif::get_streams_from_db(&db_filepath, index, boostcount, old, true, filters)).await {
+        Ok(streams) => streams,
+        Err(e) => {
+            eprintln!("** Error getting streams: {}.\n", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting streams.").into_response()
+        }
+    };
+
+    numerology::attach_numerology(&state.helipad_config.database_file_path, &mut streams).await;
+    attach_annotations(&state, &mut streams, true).await;
+    resolve_nostr_refs(&state, &mut streams).await;
+
+    if params.triggers {
+        let streams_with_triggers = match triggers::get_boosts_with_triggers(&state.helipad_config.database_file_path, streams).await {
+            Ok(boosts) => boosts,
+            Err(e) => {
+                eprintln!("** Error getting streams with triggers: {}.\n", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting streams with triggers.").into_response()
+            }
+        };
+
+        Json(streams_with_triggers).into_response()
+    }
+    else {
+        Json(streams).into_response()
+    }
+}
+
+//API - get the current invoice index number
+pub async fn api_v1_index(State(state): State<AppState>) -> Response {
+
+    //Get the last known invoice index from the database
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    match dbif::run_blocking(move || dbif::get_last_boost_index_from_db(&db_filepath)).await {
+        Ok(index) => {
+            println!("** get_last_boost_index_from_db() -> [{}]", index);
+            Json(index).into_response()
+        }
+        Err(e) => {
+            eprintln!("** Error getting current db index: {}.\n", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting current db index.").into_response()
+        }
+    }
+}
+
+//API - get the current payment index number
+pub async fn api_v1_sent_index(State(state): State<AppState>) -> Response {
+    //Get the last known payment index from the database
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    match dbif::run_blocking(move || dbif::get_last_payment_index_from_db(&db_filepath)).await {
+        Ok(index) => {
+            println!("** get_last_payment_index_from_db() -> [{}]", index);
+            Json(index).into_response()
+        }
+        Err(e) => {
+            eprintln!("** Error getting current db index: {}.\n", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting current sent index.").into_response()
+        }
+    }
+}
+
+//API - serve sent as JSON either in ascending or descending order
+pub async fn api_v1_sent(
+    Query(params): Query<BoostParams>,
+    State(state): State<AppState>
+) -> Response {
+
+    let index = params.index;
+    let boostcount = params.count;
+
+    //Was the "old" flag used?
+    let old = params.old;
+
+    println!("** Supplied index from call: [{}]", index);
+    println!("** Supplied sent boost count from call: [{}]", boostcount);
+
+    let mut filters = BoostFilters::new();
+    filters.podcast = params.podcast;
+    filters.tag = params.tag.filter(|tag| !tag.is_empty());
+    filters.pinned = params.pinned;
+
+    //Get sent boosts from db for returning
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let mut sent_boosts = match dbif::run_blocking(move || dbif::get_payments_from_db(&db_filepath, index, boostcount, old, true, filters)).await {
+        Ok(sent_boosts) => sent_boosts,
+        Err(e) => {
+            eprintln!("** Error getting sent boosts: {}.\n", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting sent boosts.").into_response()
+        }
+    };
+
+    numerology::attach_numerology(&state.helipad_config.database_file_path, &mut sent_boosts).await;
+    attach_annotations(&state, &mut sent_boosts, true).await;
+    resolve_nostr_refs(&state, &mut sent_boosts).await;
+
+    if params.triggers {
+        let sent_boosts_with_triggers = match triggers::get_boosts_with_triggers(&state.helipad_config.database_file_path, sent_boosts).await {
+            Ok(boosts) => boosts,
+            Err(e) => {
+                eprintln!("** Error getting sent boosts with triggers: {}.\n", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting sent boosts with triggers.").into_response()
+            }
+        };
+
+        Json(sent_boosts_with_triggers).into_response()
+    }
+    else {
+        Json(sent_boosts).into_response()
+    }
+}
+
+pub async fn api_v1_podcasts(
+    State(state): State<AppState>
+) -> Response {
+
+    //Get the podcasts from db for returning
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    match dbif::run_blocking(move || dbif::get_podcasts_from_db(&db_filepath)).await {
+        Ok(podcasts) => {
+            Json(podcasts).into_response()
+        }
+        Err(e) => {
+            eprintln!("** Error getting podcasts: {}.\n", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting podcasts.").into_response()
+        }
+    }
+}
+
+pub async fn api_v1_sent_podcasts(
+    State(state): State<AppState>
+) -> Response {
+
+    //Get the podcasts from db for returning
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    match dbif::run_blocking(move || dbif::get_sent_podcasts_from_db(&db_filepath)).await {
+        Ok(podcasts) => {
+            Json(podcasts).into_response()
+        }
+        Err(e) => {
+            eprintln!("** Error getting sent podcasts: {}.\n", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting sent podcasts.").into_response()
+        }
+    }
+}
+
+//Parses comma separated action names, e.g. boost,auto
+fn parse_actions(actions: &str) -> Vec<ActionType> {
+    actions.split(',')
+        .map(|action| action.trim())
+        .filter(|action| !action.is_empty())
+        .map(ActionType::from_str)
+        .collect()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QueryParams {
+    #[serde(default)]
+    list: Option<String>,
+    #[serde(default)]
+    sender: Option<String>,
+    #[serde(default)]
+    app: Option<String>,
+    #[serde(default)]
+    podcast: Option<String>,
+    #[serde(default)]
+    episode: Option<String>,
+    #[serde(default)]
+    start_date: Option<u64>,
+    #[serde(default)]
+    end_date: Option<u64>,
+    #[serde(default)]
+    actions: Option<String>,
+    #[serde(default)]
+    min_sats: Option<u64>,
+    #[serde(default)]
+    max_sats: Option<u64>,
+    #[serde(default)]
+    custom_key: Option<u64>,
+    #[serde(default)]
+    replied: Option<bool>,
+    #[serde(default)]
+    has_reply_address: Option<bool>,
+    #[serde(default)]
+    tag: Option<String>,
+    #[serde(default)]
+    pinned: Option<bool>,
+    #[serde(default)]
+    supporter: Option<u64>,
+    #[serde(default)]
+    sort: BoostSort,
+    #[serde(default)]
+    cursor: Option<String>,
+    #[serde(default)]
+    count: Option<u64>,
+    #[serde(default)]
+    hidden: bool,
+}
+
+impl QueryParams {
+    //Boosts and streams are used together unless a list is given
+    fn list(&self) -> Result<Option<ListType>, String> {
+        match self.list.as_deref() {
+            Some("boosts") => Ok(Some(ListType::Boost)),
+            Some("streams") => Ok(Some(ListType::Stream)),
+            Some("sent") => Ok(Some(ListType::Sent)),
+            Some("") | Some("all") | None => Ok(None),
+            Some(other) => Err(format!("** Unknown list: {}", other)),
+        }
+    }
+
+    fn filters(&self) -> BoostFilters {
+        let text = |val: &Option<String>| val.clone().filter(|val| !val.is_empty());
+
+        let mut filters = BoostFilters::new();
+        filters.podcast = text(&self.podcast);
+        filters.sender = text(&self.sender);
+        filters.app = text(&self.app);
+        filters.episode = text(&self.episode);
+        filters.start_date = self.start_date;
+        filters.end_date = self.end_date;
+        filters.min_sats = self.min_sats;
+        filters.max_sats = self.max_sats;
+        filters.custom_key = self.custom_key;
+        filters.replied = self.replied;
+        filters.has_reply_address = self.has_reply_address;
+        filters.tag = text(&self.tag);
+        filters.pinned = self.pinned;
+        filters.supporter = self.supporter;
+        filters.include_hidden = self.hidden;
+
+        if let Some(actions) = &self.actions {
+            filters.actions = parse_actions(actions);
+        }
+
+        filters
+    }
+}
+
+//API - filtered boosts, streams or sent boosts with cursor pagination
+pub async fn api_v1_query(
+    Query(params): Query<QueryParams>,
+    State(state): State<AppState>
+) -> Response {
+
+    let list = match params.list() {
+        Ok(list) => list,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+
+    let query = dbif::BoostQuery {
+        list,
+        filters: params.filters(),
+        sort: params.sort,
+        cursor: params.cursor.clone().filter(|val| !val.is_empty()),
+        limit: params.count.unwrap_or(50).clamp(1, 500),
+    };
+
+    println!("** Supplied query from call: [{:?}]", query);
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    let mut page = match dbif::run_blocking(move || dbif::query_boosts_in_db(&db_filepath, &query, true)).await {
+        Ok(page) => page,
+        Err(e) => {
+            eprintln!("** Error querying boosts: {}.\n", e);
+            return (StatusCode::BAD_REQUEST, "** Error querying boosts.").into_response()
+        }
+    };
+
+    numerology::attach_numerology(&state.helipad_config.database_file_path, &mut page.boosts).await;
+    attach_annotations(&state, &mut page.boosts, true).await;
+    resolve_nostr_refs(&state, &mut page.boosts).await;
+
+    Json(page).into_response()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatsParams {
+    group: StatsGroup,
+    #[serde(default)]
+    tz: Option<String>,
+}
+
+//API - totals grouped by podcast, episode, app, sender or local time. Accepts the same filters as /api/v1/query.
+pub async fn api_v1_stats(
+    Query(stats): Query<StatsParams>,
+    Query(params): Query<QueryParams>,
+    State(state): State<AppState>
+) -> Response {
+
+    let list = match params.list() {
+        Ok(list) => list,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+
+    let timezone: Tz = match stats.tz.as_deref().filter(|tz| !tz.is_empty()).unwrap_or("UTC").parse() {
+        Ok(tz) => tz,
+        Err(_) => return (StatusCode::BAD_REQUEST, format!("** Unknown timezone: {}", stats.tz.unwrap_or_default())).into_response(),
+    };
+
+    let group = stats.group;
+    let filters = params.filters();
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::get_stats_from_db(&db_filepath, list, group, timezone, &filters)).await {
+        Ok(summary) => Json(summary).into_response(),
+        Err(e) => {
+            eprintln!("** Error getting stats: {}.\n", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting stats.").into_response()
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchParams {
+    q: String,
+    #[serde(default)]
+    list: Option<String>,
+    #[serde(default)]
+    podcast: Option<String>,
+    #[serde(default)]
+    start_date: Option<u64>,
+    #[serde(default)]
+    end_date: Option<u64>,
+    #[serde(default)]
+    actions: Option<String>,
+    #[serde(default)]
+    count: Option<u64>,
+    #[serde(default)]
+    offset: Option<u64>,
+    #[serde(default)]
+    hidden: bool,
+    #[serde(default)]
+    tag: Option<String>,
+}
+
+//API - full text search over boost messages, senders, podcasts, episodes and memos
+pub async fn api_v1_search(
+    Query(params): Query<SearchParams>,
+    State(state): State<AppState>
+) -> Response {
+
+    println!("** Supplied search from call: [{}]", params.q);
+
+    if dbif::build_search_query(&params.q).is_none() {
+        return (StatusCode::BAD_REQUEST, "** Search text is required.").into_response();
+    }
+
+    //Boosts and streams are searched together unless a list is given
+    let list = match params.list.as_deref() {
+        Some("boosts") => "boost",
+        Some("streams") => "stream",
+        Some("sent") => "sent",
+        _ => "",
+    };
+
+    let mut filters = BoostFilters::new();
+    filters.podcast = params.podcast;
+    filters.start_date = params.start_date;
+    filters.end_date = params.end_date;
+    filters.include_hidden = params.hidden;
+    filters.tag = params.tag.filter(|tag| !tag.is_empty());
+
+    if let Some(actions) = &params.actions {
+        filters.actions = parse_actions(actions);
+    }
+
+    let count = params.count.unwrap_or(50).clamp(1, 200);
+    let offset = params.offset.unwrap_or(0);
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let text = params.q.clone();
+
+    let results = match dbif::run_blocking(move || dbif::search_boosts_in_db(&db_filepath, list, &text, count, offset, true, filters)).await {
+        Ok(results) => results,
+        Err(e) => {
+            eprintln!("** Error searching boosts: {}.\n", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error searching boosts.").into_response()
+        }
+    };
+
+    let (mut boosts, matches): (Vec<BoostRecord>, Vec<(f64, dbif::SearchHighlights)>) = results
+        .into_iter()
+        .map(|result| (result.boost, (result.rank, result.highlights)))
+        .unzip();
+
+    numerology::attach_numerology(&state.helipad_config.database_file_path, &mut boosts).await;
+    attach_annotations(&state, &mut boosts, true).await;
+    resolve_nostr_refs(&state, &mut boosts).await;
+
+    let results: Vec<dbif::SearchResult> = boosts
+        .into_iter()
+        .zip(matches)
+        .map(|(boost, (rank, highlights))| dbif::SearchResult { boost, rank, highlights })
+        .collect();
+
+    Json(results).into_response()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReplyForm {
+    index: u64,
+    sats: u64,
+    sender: Option<String>,
+    message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReplyResponse {
+    success: bool,
+    data: BoostRecord,
+}
+
+pub async fn api_v1_reply(
+    State(state): State<AppState>,
+    Form(params): Form<ReplyForm>,
+) -> Response {
+    let index = params.index;
+    let sats = params.sats;
+    let sender = params.sender.unwrap_or("Anonymous".into());
+    let message = params.message.unwrap_or("".into());
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    let boost = match dbif::run_blocking(move || dbif::get_single_invoice_from_db(&db_filepath, index, true)).await {
+        Ok(Some(boost)) => boost,
+        Ok(None) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Unknown boost index.").into_response();
+        },
+        Err(_) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error finding boost index.").into_response();
+        }
+    };
+
+    let tlv = boost.parse_tlv().unwrap();
+
+    let pub_key = tlv["reply_address"].as_str().unwrap_or_default().to_string();
+
+    let custom_key = match tlv["reply_custom_key"].as_u64() {
+        None => None,
+        Some(0) => None,
+        Some(rck) => Some(rck),
+    };
+
+    let custom_value = match tlv["reply_custom_value"].as_str() {
+        None => None,
+        Some("") => None,
+        Some(rcv) => Some(rcv.to_string()),
+    };
+
+    if pub_key.is_empty() {
+        return (StatusCode::BAD_REQUEST, "** No reply_address found in boost").into_response();
+    }
+
+    if custom_key.is_none() && custom_value.is_some() {
+        return (StatusCode::BAD_REQUEST, "** No reply_custom_key found in boost").into_response();
+    }
+
+    if custom_key.is_some() && custom_value.is_none() {
+        return (StatusCode::BAD_REQUEST, "** No reply_custom_value found in boost").into_response();
+    }
+
+    let reply_tlv = json!({
+        "app_name": "Helipad",
+        "app_version": state.version,
+        "podcast": tlv["podcast"].as_str().unwrap_or_default(),
+        "episode": tlv["episode"].as_str().unwrap_or_default(),
+        "name": tlv["sender_name"].as_str().unwrap_or_default(),
+        "sender_name": sender,
+        "message": message,
+        "action": "boost",
+        "value_msat": sats * 1000,
+        "value_msat_total": sats * 1000,
+    });
+
+    let helipad_config = state.helipad_config.clone();
+    let lightning = match lightning::connect_to_lnd(&helipad_config.node_address, &helipad_config.cert_path, &helipad_config.macaroon_path).await {
+        Some(lndconn) => lndconn,
+        None => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error connecting to LND.").into_response();
+        }
+    };
+
+    let payment = match lightning::send_boost(lightning, pub_key, custom_key, custom_value, sats, reply_tlv.clone()).await {
+        Ok(payment) => payment,
+        Err(e) => {
+            eprintln!("** Error sending boost: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, format!("** Error sending boost: {}", e)).into_response();
         }
     };
 
     let mut cache = podcastindex::GuidCache::new(1);
 
-    let mut boost = match boost::parse_boost_from_payment(payment, &mut cache).await {
-        Some(boost) => boost,
-        None => {
-            eprintln!("** Error parsing sent boost");
-            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error parsing sent boost").into_response();
+    let mut boost = match boost::parse_boost_from_payment(payment, &mut cache).await {
+        Some(boost) => boost,
+        None => {
+            eprintln!("** Error parsing sent boost");
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error parsing sent boost").into_response();
+        }
+    };
+
+    if let Some(pay_info) = boost.payment_info {
+        boost.payment_info = Some(dbif::PaymentRecord {
+            reply_to_idx: Some(index),
+            ..pay_info
+        });
+    }
+
+    //Give some output
+    println!("Sent Boost: {:#?}", boost);
+
+    //Store in the database
+    let (db_filepath, payment) = (state.helipad_config.database_file_path.clone(), boost.clone());
+
+    match dbif::run_blocking(move || dbif::add_payment_to_db(&db_filepath, &payment)).await {
+        Ok(_) => println!("New sent boost added."),
+        Err(e) => eprintln!("Error adding sent boost: {:#?}", e)
+    }
+
+    Json(ReplyResponse {
+        success: true,
+        data: boost,
+    }).into_response()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MarkRepliedForm {
+    index: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MarkRepliedResponse {
+    success: bool,
+}
+
+pub async fn api_v1_mark_replied(
+    State(state): State<AppState>,
+    Form(params): Form<MarkRepliedForm>,
+) -> Response {
+
+    //Parameter - index (unsigned int)
+    let index = params.index;
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    let result = dbif::run_blocking(move || dbif::mark_boost_as_replied(&db_filepath, index)).await;
+
+    if let Err(e) = result {
+        eprintln!("** Error marking boost as replied: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, format!("** Error marking boost as replied: {}", e)).into_response();
+    }
+
+    Json(MarkRepliedResponse {
+        success: true,
+    }).into_response()
+}
+
+pub async fn api_v1_hide(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+) -> Response {
+    set_boost_hidden(&state, &idx, true).await
+}
+
+pub async fn api_v1_unhide(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+) -> Response {
+    set_boost_hidden(&state, &idx, false).await
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BackupParams {
+    sounds: Option<bool>,
+}
+
+//API - download a fresh backup of the database, or an archive with the sounds as well
+pub async fn api_v1_backup(
+    State(state): State<AppState>,
+    Query(params): Query<BackupParams>,
+) -> Response {
+    let include_sounds = params.sounds.unwrap_or(false);
+    let config = state.helipad_config.clone();
+
+    let result = tokio::task::spawn_blocking(move || {
+        let temp_dir = tempfile::tempdir()?;
+        let sound_path = include_sounds.then_some(config.sound_path.as_str());
+        let snapshot = backup::create_snapshot(&config.database_file_path, sound_path, temp_dir.path())?;
+
+        //The open file outlives the temporary directory it was written to
+        let file = fs::File::open(&snapshot)?;
+        let filename = snapshot.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+        Ok::<(fs::File, String), anyhow::Error>((file, filename))
+    }).await;
+
+    let (file, filename) = match result {
+        Ok(Ok(snapshot)) => snapshot,
+        Ok(Err(e)) => {
+            eprintln!("** Error creating backup: {:#}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error creating backup.").into_response();
+        }
+        Err(e) => {
+            eprintln!("** Backup task failed: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error creating backup.").into_response();
+        }
+    };
+
+    let content_type = if include_sounds { "application/x-tar" } else { "application/vnd.sqlite3" };
+    let stream = ReaderStream::new(tokio::fs::File::from_std(file));
+
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename))
+        ],
+        Body::from_stream(stream)
+    ).into_response()
+}
+
+//API - re-read invoices and payments from LND and report the ones Helipad was missing
+pub async fn api_v1_rescan(
+    State(state): State<AppState>,
+    Json(options): Json<rescan::RescanOptions>,
+) -> Response {
+    let _guard = match rescan::RescanGuard::acquire() {
+        Some(guard) => guard,
+        None => {
+            return (StatusCode::CONFLICT, "** A rescan is already running.").into_response();
+        }
+    };
+
+    let helipad_config = state.helipad_config.clone();
+    let mut lightning = match lightning::connect_to_lnd(&helipad_config.node_address, &helipad_config.cert_path, &helipad_config.macaroon_path).await {
+        Some(lndconn) => lndconn,
+        None => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error connecting to LND.").into_response();
+        }
+    };
+
+    let settings = state.settings.read().await.clone();
+
+    match rescan::rescan(&mut lightning, &helipad_config.database_file_path, &settings, &state.metadata_providers, &options).await {
+        Ok(report) => {
+            println!("Rescan complete: {} scanned, {} missing.", report.invoices_scanned + report.payments_scanned, report.gaps.len());
+            Json(report).into_response()
+        }
+        Err(e) => {
+            eprintln!("** Error rescanning LND: {:#}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("** Error rescanning LND: {}", e)).into_response()
+        }
+    }
+}
+
+#[derive(Debug, TryFromMultipart)]
+pub struct ImportMultipart {
+    #[form_data(limit = "512MiB")]
+    file: FieldData<NamedTempFile>,
+    format: Option<String>, // helipad, csv or json, guessed from the file name if not given
+    mapping: Option<String>, // column mapping JSON for csv and json
+    dry_run: Option<bool>,
+}
+
+//Import boosts from another Helipad database, a CSV export or a JSON file
+pub async fn api_v1_import(
+    State(state): State<AppState>,
+    TypedMultipart(parts): TypedMultipart<ImportMultipart>,
+) -> Response {
+    let filename = parts.file.metadata.file_name.clone().unwrap_or_default();
+
+    let format = match parts.format.as_deref().filter(|format| !format.is_empty()) {
+        Some(format) => import::ImportFormat::from_name(format),
+        None => Ok(import::ImportFormat::from_filename(&filename)),
+    };
+
+    let mapping = match parts.mapping.as_deref().filter(|mapping| !mapping.is_empty()) {
+        Some(mapping) => serde_json::from_str(mapping).map_err(|e| anyhow::anyhow!("Invalid column mapping: {}", e)),
+        None => Ok(import::ImportMapping::default()),
+    };
+
+    let (format, mapping) = match (format, mapping) {
+        (Ok(format), Ok(mapping)) => (format, mapping),
+        (Err(e), _) | (_, Err(e)) => {
+            return (StatusCode::BAD_REQUEST, format!("** {}", e)).into_response();
+        }
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let dry_run = parts.dry_run.unwrap_or(false);
+    let upload = parts.file.contents;
+
+    let result = tokio::task::spawn_blocking(move || {
+        let batch = import::read_import_file(&upload.path().to_string_lossy(), format, &mapping)
+            .map_err(|e| (StatusCode::BAD_REQUEST, format!("{:#}", e)))?;
+
+        dbif::import_boosts_to_db(&db_filepath, batch, dry_run)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+    }).await;
+
+    match result {
+        Ok(Ok(report)) => {
+            println!("Imported boosts from [{}]: {} added, {} duplicates.", filename, report.boosts_added + report.streams_added + report.sent_boosts_added, report.duplicates);
+            Json(report).into_response()
+        }
+        Ok(Err((status, e))) => {
+            eprintln!("** Error importing boosts: {}", e);
+            (status, format!("** Error importing boosts: {}", e)).into_response()
+        }
+        Err(e) => {
+            eprintln!("** Import task failed: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error importing boosts.").into_response()
+        }
+    }
+}
+
+async fn set_boost_hidden(state: &AppState, idx: &str, hidden: bool) -> Response {
+    let index: u64 = match idx.parse() {
+        Ok(i) => i,
+        Err(e) => {
+            eprintln!("** Invalid boost index: {}", e);
+            return (StatusCode::BAD_REQUEST, "** Invalid boost index.").into_response();
+        }
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    if let Err(e) = dbif::run_blocking(move || dbif::set_boost_hidden_in_db(&db_filepath, index, hidden)).await {
+        eprintln!("** Error setting boost hidden: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, format!("** Error setting boost hidden: {}", e)).into_response();
+    }
+
+    println!("** set_boost_hidden({}, {})", index, hidden);
+
+    Json(MarkRepliedResponse {
+        success: true,
+    }).into_response()
+}
+
+//Received boosts and streams share one set of indexes and sent boosts have their own
+fn parse_annotation_target(list: &str, idx: &str) -> Result<(bool, u64), (StatusCode, &'static str)> {
+    let sent = match list {
+        "received" | "boosts" | "streams" => false,
+        "sent" => true,
+        _ => {
+            return Err((StatusCode::BAD_REQUEST, "** Unknown list, use received or sent."));
+        }
+    };
+
+    match idx.parse() {
+        Ok(index) => Ok((sent, index)),
+        Err(e) => {
+            eprintln!("** Invalid boost index: {}", e);
+            Err((StatusCode::BAD_REQUEST, "** Invalid boost index."))
+        }
+    }
+}
+
+//Sends the changed annotation to every open browser and returns it
+fn annotation_changed(state: &AppState, result: Result<Option<dbif::BoostAnnotation>, String>) -> Response {
+    let annotation = match result {
+        Ok(Some(annotation)) => annotation,
+        Ok(None) => {
+            return (StatusCode::NOT_FOUND, "** Boost not found.").into_response();
+        }
+        Err(e) => {
+            eprintln!("** Error saving boost annotation: {}", e);
+            return (StatusCode::BAD_REQUEST, format!("** Error saving boost annotation: {}", e)).into_response();
+        }
+    };
+
+    //Browsers put the event straight into the page, the same as the boost lists
+    let escaped = dbif::BoostAnnotation {
+        tags: annotation.tags.iter().cloned().map(BoostRecord::escape_for_html).collect(),
+        note: annotation.note.clone().map(BoostRecord::escape_for_html),
+        ..annotation.clone()
+    };
+
+    if let Err(e) = state.ws_tx.send(WebSocketEvent("annotation".to_string(), serde_json::to_value(&escaped).unwrap())) {
+        eprintln!("Error sending WebSocket event: {:#?}", e);
+    }
+
+    Json(annotation).into_response()
+}
+
+//API - get the tags, note and pin on a boost
+pub async fn api_v1_annotation(
+    State(state): State<AppState>,
+    Path((list, idx)): Path<(String, String)>,
+) -> Response {
+    let (sent, index) = match parse_annotation_target(&list, &idx) {
+        Ok(target) => target,
+        Err(response) => return response.into_response(),
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::get_annotation_from_db(&db_filepath, sent, index)).await {
+        Ok(Some(annotation)) => Json(annotation).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "** Boost not found.").into_response(),
+        Err(e) => {
+            eprintln!("** Error getting boost annotation: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting boost annotation.").into_response()
+        }
+    }
+}
+
+//API - change the tags, note or pin on a boost
+pub async fn api_v1_annotation_update(
+    State(state): State<AppState>,
+    Path((list, idx)): Path<(String, String)>,
+    Json(update): Json<dbif::AnnotationUpdate>,
+) -> Response {
+    let (sent, index) = match parse_annotation_target(&list, &idx) {
+        Ok(target) => target,
+        Err(response) => return response.into_response(),
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let result = dbif::run_blocking(move || dbif::update_annotation_in_db(&db_filepath, sent, index, &update)).await;
+
+    println!("** api_v1_annotation_update({}, {})", list, index);
+
+    annotation_changed(&state, result.map_err(|e| e.to_string()))
+}
+
+//API - remove the tags, note and pin from a boost
+pub async fn api_v1_annotation_delete(
+    State(state): State<AppState>,
+    Path((list, idx)): Path<(String, String)>,
+) -> Response {
+    let (sent, index) = match parse_annotation_target(&list, &idx) {
+        Ok(target) => target,
+        Err(response) => return response.into_response(),
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let result = dbif::run_blocking(move || dbif::delete_annotation_from_db(&db_filepath, sent, index)).await;
+
+    println!("** api_v1_annotation_delete({}, {})", list, index);
+
+    annotation_changed(&state, result.map_err(|e| e.to_string()))
+}
+
+//API - add a tag to a boost
+pub async fn api_v1_tag_add(
+    State(state): State<AppState>,
+    Path((list, idx, tag)): Path<(String, String, String)>,
+) -> Response {
+    let (sent, index) = match parse_annotation_target(&list, &idx) {
+        Ok(target) => target,
+        Err(response) => return response.into_response(),
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let result = dbif::run_blocking(move || dbif::add_boost_tag_in_db(&db_filepath, sent, index, &tag)).await;
+
+    annotation_changed(&state, result.map_err(|e| e.to_string()))
+}
+
+//API - remove a tag from a boost
+pub async fn api_v1_tag_remove(
+    State(state): State<AppState>,
+    Path((list, idx, tag)): Path<(String, String, String)>,
+) -> Response {
+    let (sent, index) = match parse_annotation_target(&list, &idx) {
+        Ok(target) => target,
+        Err(response) => return response.into_response(),
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let result = dbif::run_blocking(move || dbif::remove_boost_tag_in_db(&db_filepath, sent, index, &tag)).await;
+
+    annotation_changed(&state, result.map_err(|e| e.to_string()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TagsParams {
+    #[serde(default)]
+    list: Option<String>,
+}
+
+//API - every tag in use and how many boosts have it
+pub async fn api_v1_tags(
+    State(state): State<AppState>,
+    Query(params): Query<TagsParams>,
+) -> Response {
+    let sent = match params.list.as_deref() {
+        Some("received") => Some(false),
+        Some("sent") => Some(true),
+        Some("") | Some("all") | None => None,
+        Some(other) => {
+            return (StatusCode::BAD_REQUEST, format!("** Unknown list: {}", other)).into_response();
+        }
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::get_tags_from_db(&db_filepath, sent)).await {
+        Ok(tags) => Json(tags).into_response(),
+        Err(e) => {
+            eprintln!("** Error getting tags: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting tags.").into_response()
+        }
+    }
+}
+
+//Loads a show session from its index in the path
+async fn load_show_session(state: &AppState, idx: &str) -> Result<dbif::ShowSessionRecord, (StatusCode, &'static str)> {
+    let index: u64 = match idx.parse() {
+        Ok(index) => index,
+        Err(e) => {
+            eprintln!("** Invalid show session index: {}", e);
+            return Err((StatusCode::BAD_REQUEST, "** Invalid show session index."));
+        }
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::get_show_session_from_db(&db_filepath, index)).await {
+        Ok(Some(session)) => Ok(session),
+        Ok(None) => Err((StatusCode::NOT_FOUND, "** Show session not found.")),
+        Err(e) => {
+            eprintln!("** Error getting show session: {}", e);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, "** Error getting show session."))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShowsParams {
+    #[serde(default)]
+    count: Option<u64>,
+}
+
+//API - the show sessions, newest first
+pub async fn api_v1_shows(
+    State(state): State<AppState>,
+    Query(params): Query<ShowsParams>,
+) -> Response {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let count = params.count.unwrap_or(50);
+
+    match dbif::run_blocking(move || dbif::get_show_sessions_from_db(&db_filepath, count)).await {
+        Ok(sessions) => Json(sessions).into_response(),
+        Err(e) => {
+            eprintln!("** Error getting show sessions: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting show sessions.").into_response()
+        }
+    }
+}
+
+//API - the running show session, or null if there isn't one
+pub async fn api_v1_show_active(
+    State(state): State<AppState>,
+) -> Response {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::get_active_show_session_from_db(&db_filepath)).await {
+        Ok(session) => Json(session).into_response(),
+        Err(e) => {
+            eprintln!("** Error getting show session: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting show session.").into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShowQueueParams {
+    #[serde(default)]
+    state: Option<String>,
+}
+
+//API - a show session and the boosts in its queue
+pub async fn api_v1_show(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+    Query(params): Query<ShowQueueParams>,
+) -> Response {
+    let session = match load_show_session(&state, &idx).await {
+        Ok(session) => session,
+        Err(response) => return response.into_response(),
+    };
+
+    let item_state = match params.state.as_deref().filter(|item_state| !item_state.is_empty()) {
+        Some(name) => match dbif::ShowItemState::from_name(name) {
+            Some(item_state) => Some(item_state),
+            None => return (StatusCode::BAD_REQUEST, "** Unknown state, use unread, read or skipped.").into_response(),
+        },
+        None => None,
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let queue_session = session.clone();
+
+    let result = dbif::run_blocking(move || {
+        let mut items = dbif::get_show_items_from_db(&db_filepath, &queue_session, item_state, true)?;
+        shows::attach_numerology(&db_filepath, &mut items);
+        Ok(items)
+    }).await;
+
+    match result {
+        Ok(items) => Json(json!({
+            "session": session,
+            "items": items,
+        })).into_response(),
+        Err(e) => {
+            eprintln!("** Error getting show queue: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting show queue.").into_response()
+        }
+    }
+}
+
+//API - the totals, top boosters and numerology of a show session
+pub async fn api_v1_show_summary(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+) -> Response {
+    let session = match load_show_session(&state, &idx).await {
+        Ok(session) => session,
+        Err(response) => return response.into_response(),
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || shows::session_summary(&db_filepath, &session).map_err(Into::into)).await {
+        Ok(summary) => Json(summary).into_response(),
+        Err(e) => {
+            eprintln!("** Error getting show summary: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting show summary.").into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShowStartParams {
+    #[serde(default)]
+    podcast: Option<String>,
+    #[serde(default)]
+    episode: Option<String>,
+}
+
+//API - start a show session
+pub async fn api_v1_show_start(
+    State(state): State<AppState>,
+    Json(params): Json<ShowStartParams>,
+) -> Response {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::start_show_session_in_db(&db_filepath, params.podcast, params.episode)).await {
+        Ok(session) => {
+            println!("** api_v1_show_start({})", session.index);
+            shows::send_session(&state.ws_tx, &session);
+            Json(session).into_response()
+        }
+        Err(e) => {
+            eprintln!("** Error starting show session: {}", e);
+            (StatusCode::BAD_REQUEST, format!("** Error starting show session: {}", e)).into_response()
+        }
+    }
+}
+
+//API - stop a show session and get its summary
+pub async fn api_v1_show_stop(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+) -> Response {
+    let session = match load_show_session(&state, &idx).await {
+        Ok(session) => session,
+        Err(response) => return response.into_response(),
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    let result = dbif::run_blocking(move || {
+        let session = dbif::stop_show_session_in_db(&db_filepath, session.index)?.unwrap_or(session);
+        shows::session_summary(&db_filepath, &session).map_err(Into::into)
+    }).await;
+
+    match result {
+        Ok(summary) => {
+            println!("** api_v1_show_stop({})", summary.session.index);
+            shows::send_session(&state.ws_tx, &summary.session);
+            Json(summary).into_response()
+        }
+        Err(e) => {
+            eprintln!("** Error stopping show session: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error stopping show session.").into_response()
+        }
+    }
+}
+
+//API - delete a show session that has ended
+pub async fn api_v1_show_delete(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+) -> Response {
+    let session = match load_show_session(&state, &idx).await {
+        Ok(session) => session,
+        Err(response) => return response.into_response(),
+    };
+
+    if session.is_active() {
+        return (StatusCode::BAD_REQUEST, "** Stop the show session before deleting it.").into_response();
+    }
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::delete_show_session_from_db(&db_filepath, session.index)).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => {
+            eprintln!("** Error deleting show session: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error deleting show session.").into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShowItemUpdate {
+    state: dbif::ShowItemState,
+}
+
+//API - mark a boost in a show's queue as read, skipped or unread
+pub async fn api_v1_show_item_update(
+    State(state): State<AppState>,
+    Path((idx, boost_idx)): Path<(String, String)>,
+    Json(update): Json<ShowItemUpdate>,
+) -> Response {
+    let session = match load_show_session(&state, &idx).await {
+        Ok(session) => session,
+        Err(response) => return response.into_response(),
+    };
+
+    let boost_index: u64 = match boost_idx.parse() {
+        Ok(index) => index,
+        Err(e) => {
+            eprintln!("** Invalid boost index: {}", e);
+            return (StatusCode::BAD_REQUEST, "** Invalid boost index.").into_response();
+        }
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    let result = dbif::run_blocking(move || {
+        let item = dbif::set_show_item_state_in_db(&db_filepath, &session, boost_index, update.state, true)?;
+
+        Ok(item.map(|mut item| {
+            shows::attach_numerology(&db_filepath, std::slice::from_mut(&mut item));
+            item
+        }))
+    }).await;
+
+    match result {
+        Ok(Some(item)) => {
+            shows::send_item(&state.ws_tx, &item);
+            Json(item).into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, "** Boost is not in the show queue.").into_response(),
+        Err(e) => {
+            eprintln!("** Error updating show queue: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error updating show queue.").into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewApiToken {
+    name: String,
+    scopes: Vec<dbif::ApiScope>,
+    #[serde(default)]
+    expires_days: Option<u64>,
+}
+
+//API - the API tokens, without the tokens themselves
+pub async fn api_v1_tokens(
+    State(state): State<AppState>,
+) -> Response {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::get_api_tokens_from_db(&db_filepath)).await {
+        Ok(tokens) => Json(tokens).into_response(),
+        Err(e) => {
+            eprintln!("** Error getting API tokens: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting API tokens.").into_response()
+        }
+    }
+}
+
+//API - make an API token. The response is the only time the token is shown.
+pub async fn api_v1_token_add(
+    State(state): State<AppState>,
+    Json(new_token): Json<NewApiToken>,
+) -> Response {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    let result = dbif::run_blocking(move || {
+        api_tokens::create_token(&db_filepath, &new_token.name, &new_token.scopes, new_token.expires_days).map_err(Into::into)
+    }).await;
+
+    match result {
+        Ok((token, record)) => {
+            println!("** api_v1_token_add({})", record.name);
+            (StatusCode::CREATED, Json(json!({
+                "token": token,
+                "api_token": record,
+            }))).into_response()
+        }
+        Err(e) => {
+            eprintln!("** Error adding API token: {}", e);
+            (StatusCode::BAD_REQUEST, format!("** Error adding API token: {}", e)).into_response()
+        }
+    }
+}
+
+//API - revoke an API token
+pub async fn api_v1_token_delete(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+) -> Response {
+    let index: u64 = match idx.parse() {
+        Ok(index) => index,
+        Err(_) => return (StatusCode::BAD_REQUEST, "** Invalid token index.").into_response(),
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::delete_api_token_from_db(&db_filepath, index)).await {
+        Ok(true) => {
+            println!("** api_v1_token_delete({})", index);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => (StatusCode::NOT_FOUND, "** API token not found.").into_response(),
+        Err(e) => {
+            eprintln!("** Error deleting API token: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error deleting API token.").into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewShareLink {
+    name: String,
+    view: dbif::ShareView,
+    #[serde(default)]
+    podcast: Option<String>,
+    #[serde(default)]
+    goal_sats: Option<u64>,
+    #[serde(default)]
+    expires_days: Option<u64>,
+}
+
+//Share links with the path to each one. Links are signed rather than stored, so they can be shown again.
+fn share_links_with_paths(db_filepath: &str) -> Result<Vec<(dbif::ShareLinkRecord, String)>, Box<dyn std::error::Error>> {
+    let secret = share_links::secret(db_filepath)?;
+    let mut links = Vec::new();
+
+    for link in dbif::get_share_links_from_db(db_filepath)? {
+        let path = format!("/share/{}", share_links::link_token(&secret, &link)?);
+        links.push((link, path));
+    }
+
+    Ok(links)
+}
+
+//API - list share links
+pub async fn api_v1_share_links(
+    State(state): State<AppState>,
+) -> Response {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || share_links_with_paths(&db_filepath)).await {
+        Ok(links) => {
+            let links: Vec<_> = links.into_iter().map(|(link, path)| json!({
+                "share_link": link,
+                "path": path,
+            })).collect();
+
+            Json(links).into_response()
+        }
+        Err(e) => {
+            eprintln!("** Error getting share links: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting share links.").into_response()
+        }
+    }
+}
+
+//API - make a share link
+pub async fn api_v1_share_link_add(
+    State(state): State<AppState>,
+    Json(new_link): Json<NewShareLink>,
+) -> Response {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    let result = dbif::run_blocking(move || {
+        share_links::create_link(
+            &db_filepath,
+            &new_link.name,
+            new_link.view,
+            new_link.podcast.as_deref(),
+            new_link.goal_sats,
+            new_link.expires_days,
+        ).map_err(Into::into)
+    }).await;
+
+    match result {
+        Ok((token, link)) => {
+            println!("** api_v1_share_link_add({})", link.name);
+            (StatusCode::CREATED, Json(json!({
+                "path": format!("/share/{}", token),
+                "share_link": link,
+            }))).into_response()
+        }
+        Err(e) => {
+            eprintln!("** Error adding share link: {}", e);
+            (StatusCode::BAD_REQUEST, format!("** Error adding share link: {}", e)).into_response()
+        }
+    }
+}
+
+//API - revoke a share link
+pub async fn api_v1_share_link_delete(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+) -> Response {
+    let index: u64 = match idx.parse() {
+        Ok(index) => index,
+        Err(_) => return (StatusCode::BAD_REQUEST, "** Invalid share link index.").into_response(),
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::delete_share_link_from_db(&db_filepath, index)).await {
+        Ok(true) => {
+            println!("** api_v1_share_link_delete({})", index);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => (StatusCode::NOT_FOUND, "** Share link not found.").into_response(),
+        Err(e) => {
+            eprintln!("** Error deleting share link: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error deleting share link.").into_response()
+        }
+    }
+}
+
+//Checks a share link for a view. Links that don't exist, have expired, were revoked or are for another view are
+//all just not found.
+pub async fn check_share_link(state: &AppState, token: &str, view: Option<dbif::ShareView>) -> Result<dbif::ShareLinkRecord, Response> {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let token = token.to_string();
+
+    let link = match dbif::run_blocking(move || share_links::check_link(&db_filepath, &token).map_err(Into::into)).await {
+        Ok(link) => link,
+        Err(e) => {
+            eprintln!("** Error checking share link: {}", e);
+            None
+        }
+    };
+
+    match link {
+        Some(link) if view.is_none_or(|view| view == link.view) => Ok(link),
+        _ => Err((StatusCode::NOT_FOUND, "This link has expired or been revoked.").into_response()),
+    }
+}
+
+//Share link page
+pub async fn share_page(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Response {
+    let link = match check_share_link(&state, &token, None).await {
+        Ok(link) => link,
+        Err(resp) => return resp,
+    };
+
+    HtmlTemplate("webroot/html/share.html", json!({
+        "version": state.version,
+        "token": token,
+        "name": link.name,
+        "podcast": link.podcast,
+        "view": link.view,
+    })).into_response()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShareBoostParams {
+    #[serde(default)]
+    index: Option<u64>,
+    #[serde(default)]
+    count: Option<u64>,
+    #[serde(default)]
+    old: Option<bool>,
+}
+
+//Boosts for a boost feed link. Only public boost details are included, not tags, notes or supporters.
+pub async fn share_boosts(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    Query(params): Query<ShareBoostParams>,
+) -> Response {
+    let link = match check_share_link(&state, &token, Some(dbif::ShareView::Boosts)).await {
+        Ok(link) => link,
+        Err(resp) => return resp,
+    };
+
+    let mut filters = BoostFilters::new();
+    filters.podcast = link.podcast;
+
+    let index = params.index.unwrap_or(i64::MAX as u64); // latest boosts by default
+    let count = params.count.unwrap_or(20).clamp(1, share_links::MAX_BOOSTS);
+    let old = params.old.unwrap_or(params.index.is_none());
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    let mut boosts = match dbif::run_blocking(move || dbif::get_boosts_from_db(&db_filepath, index, count, old, true, filters)).await {
+        Ok(boosts) => boosts,
+        Err(e) => {
+            eprintln!("** Error getting boosts: {}.\n", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting boosts.").into_response()
+        }
+    };
+
+    numerology::attach_numerology(&state.helipad_config.database_file_path, &mut boosts).await;
+
+    let boosts: Vec<share_links::SharedBoost> = boosts.into_iter().map(Into::into).collect();
+
+    Json(boosts).into_response()
+}
+
+//Progress towards the goal for a goal link, counting boosts and streams since the link was made
+pub async fn share_goal(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Response {
+    let link = match check_share_link(&state, &token, Some(dbif::ShareView::Goal)).await {
+        Ok(link) => link,
+        Err(resp) => return resp,
+    };
+
+    let mut filters = BoostFilters::new();
+    filters.podcast = link.podcast.clone();
+    filters.start_date = Some(link.created as u64);
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    let summary = match dbif::run_blocking(move || dbif::get_stats_from_db(&db_filepath, None, StatsGroup::Podcast, Tz::UTC, &filters)).await {
+        Ok(summary) => summary,
+        Err(e) => {
+            eprintln!("** Error getting goal progress: {}.\n", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting goal progress.").into_response()
+        }
+    };
+
+    let goal_sats = link.goal_sats.unwrap_or(0);
+    let sats = summary.total.value_msat_total.max(0) as u64 / 1000;
+    let percent = if goal_sats > 0 { (sats as f64 / goal_sats as f64 * 100.0).min(100.0) } else { 0.0 };
+
+    Json(json!({
+        "name": link.name,
+        "podcast": link.podcast,
+        "since": link.created,
+        "goal_sats": goal_sats,
+        "sats": sats,
+        "count": summary.total.count,
+        "percent": (percent * 10.0).round() / 10.0,
+    })).into_response()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewUser {
+    username: String,
+    password: String,
+    role: UserRole,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserUpdate {
+    #[serde(default)]
+    role: Option<UserRole>,
+    #[serde(default)]
+    password: Option<String>,
+}
+
+//API - the user accounts
+pub async fn api_v1_users(
+    State(state): State<AppState>,
+) -> Response {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::get_users_from_db(&db_filepath)).await {
+        Ok(users) => Json(users).into_response(),
+        Err(e) => {
+            eprintln!("** Error getting users: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting users.").into_response()
+        }
+    }
+}
+
+//API - add a user account
+pub async fn api_v1_user_add(
+    State(state): State<AppState>,
+    Json(user): Json<NewUser>,
+) -> Response {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    let result = dbif::run_blocking(move || {
+        let index = users::add_user(&db_filepath, &user.username, &user.password, user.role)?;
+        dbif::get_user_from_db(&db_filepath, index)
+    }).await;
+
+    match result {
+        Ok(Some(user)) => {
+            println!("** api_v1_user_add({})", user.username);
+            (StatusCode::CREATED, Json(user)).into_response()
+        }
+        Ok(None) => (StatusCode::INTERNAL_SERVER_ERROR, "** Error adding user.").into_response(),
+        Err(e) => {
+            eprintln!("** Error adding user: {}", e);
+            (StatusCode::BAD_REQUEST, format!("** Error adding user: {}", e)).into_response()
+        }
+    }
+}
+
+//API - change a user's role or password
+pub async fn api_v1_user_update(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+    Json(update): Json<UserUpdate>,
+) -> Response {
+    let index: u64 = match idx.parse() {
+        Ok(index) => index,
+        Err(_) => return (StatusCode::BAD_REQUEST, "** Invalid user index.").into_response(),
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    let result = dbif::run_blocking(move || {
+        match users::update_user(&db_filepath, index, update.role, update.password.as_deref())? {
+            true => dbif::get_user_from_db(&db_filepath, index),
+            false => Ok(None),
+        }
+    }).await;
+
+    match result {
+        Ok(Some(user)) => {
+            println!("** api_v1_user_update({})", user.username);
+            Json(user).into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, "** User not found.").into_response(),
+        Err(e) => {
+            eprintln!("** Error updating user: {}", e);
+            (StatusCode::BAD_REQUEST, format!("** Error updating user: {}", e)).into_response()
+        }
+    }
+}
+
+//API - remove a user account
+pub async fn api_v1_user_delete(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+) -> Response {
+    let index: u64 = match idx.parse() {
+        Ok(index) => index,
+        Err(_) => return (StatusCode::BAD_REQUEST, "** Invalid user index.").into_response(),
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::delete_user_from_db(&db_filepath, index)).await {
+        Ok(true) => {
+            println!("** api_v1_user_delete({})", index);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => (StatusCode::NOT_FOUND, "** User not found.").into_response(),
+        Err(e) => {
+            eprintln!("** Error deleting user: {}", e);
+            (StatusCode::BAD_REQUEST, format!("** Error deleting user: {}", e)).into_response()
+        }
+    }
+}
+
+//Loads a supporter's index from the path
+fn parse_supporter_index(idx: &str) -> Result<u64, (StatusCode, &'static str)> {
+    idx.parse().map_err(|e| {
+        eprintln!("** Invalid supporter index: {}", e);
+        (StatusCode::BAD_REQUEST, "** Invalid supporter index.")
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SupportersParams {
+    #[serde(default)]
+    q: Option<String>,
+    #[serde(default)]
+    sort: dbif::SupporterSort,
+    #[serde(default)]
+    count: Option<u64>,
+    #[serde(default)]
+    offset: u64,
+}
+
+//API - the supporter directory, most recently seen first unless a sort is given
+pub async fn api_v1_supporters(
+    State(state): State<AppState>,
+    Query(params): Query<SupportersParams>,
+) -> Response {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    let query = dbif::SupporterQuery {
+        search: params.q,
+        sort: params.sort,
+        limit: params.count.unwrap_or(100),
+        offset: params.offset,
+        ..Default::default()
+    };
+
+    match dbif::run_blocking(move || dbif::get_supporters_from_db(&db_filepath, &query)).await {
+        Ok(supporters) => Json(supporters).into_response(),
+        Err(e) => {
+            eprintln!("** Error getting supporters: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting supporters.").into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LeaderboardParams {
+    #[serde(default)]
+    podcast: Option<String>,
+    #[serde(default)]
+    episode: Option<String>,
+    #[serde(default)]
+    start_date: Option<u64>,
+    #[serde(default)]
+    end_date: Option<u64>,
+    #[serde(default)]
+    list: Option<String>,
+    #[serde(default)]
+    count: Option<u64>,
+}
+
+//API - the supporters who sent the most sats to a podcast or episode over a time range
+pub async fn api_v1_leaderboard(
+    State(state): State<AppState>,
+    Query(params): Query<LeaderboardParams>,
+) -> Response {
+    let list = match params.list.as_deref() {
+        Some("boosts") => Some(ListType::Boost),
+        Some("streams") => Some(ListType::Stream),
+        Some("") | Some("all") | None => None,
+        Some(other) => {
+            return (StatusCode::BAD_REQUEST, format!("** Unknown list: {}", other)).into_response();
+        }
+    };
+
+    let text = |val: Option<String>| val.filter(|val| !val.is_empty());
+
+    let mut filters = BoostFilters::new();
+    filters.podcast = text(params.podcast);
+    filters.episode = text(params.episode);
+    filters.start_date = params.start_date;
+    filters.end_date = params.end_date;
+
+    let query = dbif::SupporterQuery {
+        list,
+        filters,
+        sort: dbif::SupporterSort::Total,
+        limit: params.count.unwrap_or(10),
+        ..Default::default()
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::get_supporters_from_db(&db_filepath, &query)).await {
+        Ok(supporters) => Json(supporters).into_response(),
+        Err(e) => {
+            eprintln!("** Error getting leaderboard: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting leaderboard.").into_response()
+        }
+    }
+}
+
+//API - a supporter's profile
+pub async fn api_v1_supporter(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+) -> Response {
+    let index = match parse_supporter_index(&idx) {
+        Ok(index) => index,
+        Err(response) => return response.into_response(),
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::get_supporter_from_db(&db_filepath, index)).await {
+        Ok(Some(supporter)) => Json(supporter).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "** Supporter not found.").into_response(),
+        Err(e) => {
+            eprintln!("** Error getting supporter: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting supporter.").into_response()
+        }
+    }
+}
+
+//API - change a supporter's name, note or thanked flag
+pub async fn api_v1_supporter_update(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+    Json(update): Json<dbif::SupporterUpdate>,
+) -> Response {
+    let index = match parse_supporter_index(&idx) {
+        Ok(index) => index,
+        Err(response) => return response.into_response(),
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::update_supporter_in_db(&db_filepath, index, &update)).await {
+        Ok(Some(supporter)) => {
+            println!("** api_v1_supporter_update({})", index);
+            Json(supporter).into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, "** Supporter not found.").into_response(),
+        Err(e) => {
+            eprintln!("** Error updating supporter: {}", e);
+            (StatusCode::BAD_REQUEST, format!("** Error updating supporter: {}", e)).into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SupporterMerge {
+    supporters: Vec<u64>,
+}
+
+//API - merge other supporters into this one
+pub async fn api_v1_supporter_merge(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+    Json(merge): Json<SupporterMerge>,
+) -> Response {
+    let index = match parse_supporter_index(&idx) {
+        Ok(index) => index,
+        Err(response) => return response.into_response(),
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::merge_supporters_in_db(&db_filepath, index, &merge.supporters)).await {
+        Ok(Some(supporter)) => {
+            println!("** api_v1_supporter_merge({})", index);
+            Json(supporter).into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, "** Supporter not found.").into_response(),
+        Err(e) => {
+            eprintln!("** Error merging supporters: {}", e);
+            (StatusCode::BAD_REQUEST, format!("** Error merging supporters: {}", e)).into_response()
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FetchMetadataResponse {
+    success: bool,
+    boost: Option<BoostRecord>,
+    message: String,
+}
+
+pub async fn api_v1_fetch_metadata(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+) -> Response {
+    let index: u64 = match idx.parse() {
+        Ok(i) => i,
+        Err(e) => {
+            eprintln!("** Invalid boost index: {}", e);
+            return (StatusCode::BAD_REQUEST, Json(FetchMetadataResponse {
+                success: false,
+                boost: None,
+                message: "Invalid boost index".to_string(),
+            })).into_response();
+        }
+    };
+
+    // Get the boost from database
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    let mut boost = match dbif::run_blocking(move || dbif::get_single_invoice_from_db(&db_filepath, index, true)).await {
+        Ok(Some(boost)) => boost,
+        Ok(None) => {
+            return (StatusCode::NOT_FOUND, Json(FetchMetadataResponse {
+                success: false,
+                boost: None,
+                message: "Boost not found".to_string(),
+            })).into_response();
+        },
+        Err(e) => {
+            eprintln!("** Error finding boost: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(FetchMetadataResponse {
+                success: false,
+                boost: None,
+                message: format!("Error finding boost: {}", e),
+            })).into_response();
+        }
+    };
+
+    // Fetch payment metadata
+    let mut remote_cache = podcastindex::GuidCache::new(1);
+    let comment = boost.message.clone();
+
+    let metadata_whitelist = state.settings.read().await.metadata_whitelist.clone();
+    let metadata_providers = state.metadata_providers.get(&state.helipad_config.database_file_path, &metadata_whitelist).await;
+
+    if !boost::fetch_boost_metadata(&mut boost, &comment, &metadata_providers, false, &mut remote_cache).await {
+        return (StatusCode::BAD_REQUEST, Json(FetchMetadataResponse {
+            success: false,
+            boost: None,
+            message: "No payment metadata found for boost".to_string(),
+        })).into_response();
+    }
+
+    // Update the boost in the database
+    let (db_filepath, updated) = (state.helipad_config.database_file_path.clone(), boost.clone());
+
+    match dbif::run_blocking(move || dbif::update_invoice_in_db(&db_filepath, &updated)).await {
+        Ok(_) => {
+            println!("** Successfully fetched and updated payment metadata for boost {}", index);
+            (StatusCode::OK, Json(FetchMetadataResponse {
+                success: true,
+                boost: Some(boost),
+                message: "Payment metadata fetched successfully".to_string(),
+            })).into_response()
+        }
+        Err(e) => {
+            eprintln!("** Error updating boost in database: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(FetchMetadataResponse {
+                success: false,
+                boost: None,
+                message: format!("Error updating boost: {}", e),
+            })).into_response()
+        }
+    }
+}
+
+pub async fn general_settings_load(State(state): State<AppState>) -> impl IntoResponse {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let settings = dbif::run_blocking(move || dbif::load_settings_from_db(&db_filepath)).await.unwrap();
+    HtmlTemplate("webroot/template/general-settings.hbs", json!({"settings": settings}))
+}
+
+#[derive(Debug, TryFromMultipart)]
+pub struct GeneralSettingsMultipart {
+    show_received_sats: Option<bool>,
+    show_split_percentage: Option<bool>,
+    hide_boosts: Option<bool>,
+    hide_boosts_below: Option<String>,
+    play_pew: Option<bool>,
+    resolve_nostr_refs: Option<bool>,
+    show_hosted_wallet_ids: Option<bool>,
+    show_lightning_invoices: Option<bool>,
+    fetch_metadata: Option<bool>,
+    metadata_whitelist: Option<String>,
+    nostr_relays: Option<String>,
+
+    // The `unlimited arguments` means that this field will be limited to the
+    // total size of the request body. If you want to limit the size of this
+    // field to a specific value you can also specify a limit in bytes, like
+    // '5MiB' or '1GiB'.
+    #[form_data(limit = "5MiB")]
+    custom_pew_file: Option<FieldData<NamedTempFile>>,
+    custom_pew_existing: Option<bool>,
+}
+
+pub async fn general_settings_save(
+    State(state): State<AppState>,
+    TypedMultipart(parts): TypedMultipart<GeneralSettingsMultipart>,
+) -> impl IntoResponse {
+
+    let hide_boosts_below = match parts.hide_boosts_below {
+        Some(s) => match s.is_empty() {
+            false => Some(s.parse::<u64>().unwrap_or(0)),
+            true => None,
+        },
+        None => None,
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let mut settings = dbif::run_blocking(move || dbif::load_settings_from_db(&db_filepath)).await.unwrap();
+
+    settings.show_received_sats = parts.show_received_sats.unwrap_or(false);
+    settings.show_split_percentage = parts.show_split_percentage.unwrap_or(false);
+    settings.hide_boosts = parts.hide_boosts.unwrap_or(false);
+    settings.hide_boosts_below = hide_boosts_below;
+    settings.play_pew = parts.play_pew.unwrap_or(false);
+    settings.resolve_nostr_refs = parts.resolve_nostr_refs.unwrap_or(false);
+    settings.show_hosted_wallet_ids = parts.show_hosted_wallet_ids.unwrap_or(false);
+    settings.show_lightning_invoices = parts.show_lightning_invoices.unwrap_or(false);
+    settings.fetch_metadata = parts.fetch_metadata.unwrap_or(false);
+    settings.metadata_whitelist = parts.metadata_whitelist.unwrap_or("".to_string());
+
+    if let Some(relays) = parts.nostr_relays {
+        settings.nostr_relays = relays.trim().to_string();
+    }
+
+    if !settings.hide_boosts {
+        settings.hide_boosts_below = None;
+    }
+
+    if let Some(field) = parts.custom_pew_file {
+        let from_path = field.contents.path();
+        let to_path =  format!("{}/custom_pew.mp3", state.helipad_config.sound_path);
+        let bytes = std::fs::copy(from_path, &to_path).unwrap_or(0);
+
+        if bytes > 0 {
+            println!("** Wrote custom pew to: {}", to_path);
+            settings.custom_pew_file = Some("custom_pew.mp3".to_string())
+        } else {
+            settings.custom_pew_file = None;
+        }
+    } else if parts.custom_pew_existing.is_none() {
+        settings.custom_pew_file = None;
+    }
+
+    let (db_filepath, saving) = (state.helipad_config.database_file_path.clone(), settings.clone());
+    dbif::run_blocking(move || dbif::save_settings_to_db(&db_filepath, &saving)).await.unwrap();
+
+    *state.settings.write().await = settings.clone();
+
+    HtmlTemplate("webroot/template/general-settings.hbs", json!({"settings": settings, "saved": true}))
+}
+
+pub async fn numerology_list(db_filepath: &str) -> impl IntoResponse {
+    let db_filepath = db_filepath.to_string();
+    let results = dbif::run_blocking(move || dbif::get_numerology_from_db(&db_filepath)).await.unwrap();
+    HtmlTemplate("webroot/template/numerology-list.hbs", json!({"numerology": results}))
+}
+
+pub async fn numerology_settings_list(State(state): State<AppState>) -> impl IntoResponse {
+    numerology_list(&state.helipad_config.database_file_path).await
+}
+
+pub async fn numerology_settings_load(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+) -> impl IntoResponse {
+
+    let index = match idx.as_str() {
+        "add" => 0,
+        idx => idx.parse().unwrap(),
+    };
+
+    let result = if index > 0 {
+        let db_filepath = state.helipad_config.database_file_path.clone();
+        dbif::run_blocking(move || dbif::load_numerology_from_db(&db_filepath, index)).await.ok()
+    } else {
+        None
+    };
+
+    let equality = match &result {
+        Some(eq) => eq.equality.clone(),
+        None => "".to_string(),
+    };
+
+    let params = json!({
+        "numerology": result,
+        "equality": json!({
+            "eq": equality == "=",
+            "in": equality == "=~",
+            "lt": equality == "<",
+            "gte": equality == ">=",
+        }),
+    });
+
+    HtmlTemplate("webroot/template/numerology-edit.hbs", params)
+}
+
+#[derive(Debug, TryFromMultipart)]
+pub struct NumerologyMultipart {
+    position: u64,
+    amount: u64,
+    equality: String,
+    emoji: Option<String>,
+    description: Option<String>,
+}
+
+pub async fn numerology_settings_save(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+    TypedMultipart(parts): TypedMultipart<NumerologyMultipart>,
+) -> Response {
+    let db_filepath = state.helipad_config.database_file_path;
+
+    let index = match idx.as_str() {
+        "add" => 0,
+        idx => idx.parse().unwrap(),
+    };
+
+    let numero = NumerologyRecord {
+        index,
+        position: parts.position,
+        amount: parts.amount,
+        equality: parts.equality,
+        emoji: parts.emoji,
+        description: parts.description,
+    };
+
+    let filepath = db_filepath.clone();
+
+    let idx = match dbif::run_blocking(move || dbif::save_numerology_to_db(&filepath, &numero)).await {
+        Ok(idx) => idx,
+        Err(e) => {
+            eprintln!("** Error saving numerology: {}.\n", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error saving numerology.").into_response();
+        }
+    };
+
+    println!("** numerology_settings_save({})", idx);
+
+    numerology_list(&db_filepath).await.into_response()
+}
+
+pub async fn numerology_settings_delete(
+    State(state): State<AppState>,
+    Path(idx): Path<String>
+) -> impl IntoResponse {
+
+    let index = idx.parse().unwrap();
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    if let Err(e) = dbif::run_blocking(move || dbif::delete_numerology_from_db(&db_filepath, index)).await {
+        eprintln!("** Error deleting numerology: {}.\n", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "** Error deleting numerology.");
+    }
+
+    println!("** numerology_settings_delete({})", index);
+
+    (StatusCode::OK, "")
+}
+
+pub async fn numerology_settings_reset() -> impl IntoResponse {
+    HtmlTemplate("webroot/template/numerology-reset.hbs", "")
+}
+
+pub async fn numerology_settings_do_reset(State(state): State<AppState>) -> Response {
+    let db_filepath = state.helipad_config.database_file_path;
+
+    let filepath = db_filepath.clone();
+
+    if let Err(e) = dbif::run_blocking(move || dbif::reset_numerology_in_db(&filepath)).await {
+        eprintln!("** Error resetting numerology: {}.\n", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "** Error resetting numerology.").into_response()
+    }
+
+    numerology_list(&db_filepath).await.into_response()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NumerologyPatchForm {
+    position: u64,
+}
+
+pub async fn numerology_settings_patch(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+    Form(params): Form<NumerologyPatchForm>,
+) -> Result<impl IntoResponse, (StatusCode, &'static str)> {
+    let db_filepath = state.helipad_config.database_file_path;
+
+    let index = idx.parse().unwrap();
+
+    let filepath = db_filepath.clone();
+
+    let mut numero = match dbif::run_blocking(move || dbif::load_numerology_from_db(&filepath, index)).await {
+        Ok(num) => num,
+        Err(e) => {
+            eprintln!("** Error loading numerology item: {}.\n", e);
+            return Err((StatusCode::INTERNAL_SERVER_ERROR, "** Error loading numerology item."));
+        }
+    };
+
+    numero.position = params.position;
+
+    let filepath = db_filepath.clone();
+
+    match dbif::run_blocking(move || dbif::save_numerology_to_db(&filepath, &numero)).await {
+        Ok(num) => num,
+        Err(e) => {
+            eprintln!("** Error saving numerology item: {}.\n", e);
+            return Err((StatusCode::INTERNAL_SERVER_ERROR, "** Error saving numerology item."));
+        }
+    };
+
+    println!("** numerology_settings_patch({})", index);
+
+    Ok(numerology_list(&db_filepath).await)
+}
+
+
+pub async fn triggers_list(db_filepath: &str) -> impl IntoResponse {
+    let db_filepath = db_filepath.to_string();
+    let results = dbif::run_blocking(move || dbif::get_triggers_from_db(&db_filepath)).await.unwrap();
+    HtmlTemplate("webroot/template/trigger-list.hbs", json!({"trigger": results}))
+}
+
+pub async fn trigger_settings_list(State(state): State<AppState>) -> impl IntoResponse {
+    triggers_list(&state.helipad_config.database_file_path).await
+}
+
+pub async fn trigger_settings_load(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+) -> impl IntoResponse {
+
+    let index = match idx.as_str() {
+        "add" => 0,
+        idx => idx.parse().unwrap(),
+    };
+
+    let result = if index > 0 {
+        let db_filepath = state.helipad_config.database_file_path.clone();
+        dbif::run_blocking(move || dbif::load_trigger_from_db(&db_filepath, index)).await.ok()
+    } else {
+        None
+    };
+
+    let amount_equality = match &result {
+        Some(trigger) => trigger.amount_equality.clone().unwrap_or_default(),
+        None => "".to_string(),
+    };
+
+    let sender_equality = match &result {
+        Some(trigger) => trigger.sender_equality.clone().unwrap_or_default(),
+        None => "".to_string(),
+    };
+
+    let app_equality = match &result {
+        Some(trigger) => trigger.app_equality.clone().unwrap_or_default(),
+        None => "".to_string(),
+    };
+
+    let podcast_equality = match &result {
+        Some(trigger) => trigger.podcast_equality.clone().unwrap_or_default(),
+        None => "".to_string(),
+    };
+
+    let numerology_equality = match &result {
+        Some(trigger) => trigger.numerology_equality.clone().unwrap_or_default(),
+        None => "".to_string(),
+    };
+
+    let params = json!({
+        "trigger": result,
+        "equality": json!({
+            "eq": amount_equality == "=",
+            "in": amount_equality == "=~",
+            "lt": amount_equality == "<",
+            "gte": amount_equality == ">=",
+            "starts_with": amount_equality == "^=",
+            "ends_with": amount_equality == "$=",
+        }),
+        "sender_equality": json!({
+            "eq": sender_equality == "=",
+            "not_eq": sender_equality == "!=",
+            "in": sender_equality == "=~",
+            "starts_with": sender_equality == "^=",
+            "ends_with": sender_equality == "$=",
+        }),
+        "app_equality": json!({
+            "eq": app_equality == "=",
+            "not_eq": app_equality == "!=",
+            "in": app_equality == "=~",
+            "starts_with": app_equality == "^=",
+            "ends_with": app_equality == "$=",
+        }),
+        "podcast_equality": json!({
+            "eq": podcast_equality == "=",
+            "not_eq": podcast_equality == "!=",
+            "in": podcast_equality == "=~",
+            "starts_with": podcast_equality == "^=",
+            "ends_with": podcast_equality == "$=",
+        }),
+        "numerology_equality": json!({
+            "eq": numerology_equality == "=",
+            "not_eq": numerology_equality == "!=",
+            "in": numerology_equality == "=~",
+        }),
+    });
+
+    HtmlTemplate("webroot/template/trigger-edit.hbs", params)
+}
+
+#[derive(Debug, TryFromMultipart)]
+pub struct TriggerMultipart {
+    enabled: Option<bool>,
+
+    on_boost: Option<bool>,
+    on_stream: Option<bool>,
+    on_auto: Option<bool>,
+    on_sent: Option<bool>,
+    on_invoice: Option<bool>,
+    on_lockout: Option<bool>,
+
+    amount: Option<String>,
+    amount_equality: Option<String>,
+    sender: Option<String>,
+    sender_equality: Option<String>,
+    app: Option<String>,
+    app_equality: Option<String>,
+    podcast: Option<String>,
+    podcast_equality: Option<String>,
+    numerology: Option<String>,
+    numerology_equality: Option<String>,
+
+    webhook_url: Option<String>,
+    webhook_token: Option<String>,
+
+    osc_address: Option<String>,
+    osc_port: Option<String>,
+    osc_path: Option<String>,
+    osc_args: Option<String>,
+
+    midi_note: Option<String>,
+    midi_velocity: Option<String>,
+    midi_channel: Option<String>,
+    midi_duration: Option<String>,
+
+    #[form_data(limit = "5MiB")]
+    sound_file: Option<FieldData<NamedTempFile>>,
+    sound_file_existing: Option<bool>,
+}
+
+pub async fn trigger_settings_save(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+    TypedMultipart(parts): TypedMultipart<TriggerMultipart>,
+) -> Response {
+    println!("** trigger_settings_save({:?})", parts);
+    let db_filepath = state.helipad_config.database_file_path;
+
+    let index = match idx.as_str() {
+        "add" => 0,
+        idx => idx.parse().unwrap(),
+    };
+
+    let mut trigger = TriggerRecord {
+        index: index,
+        position: index,
+        enabled: parts.enabled.unwrap_or(false),
+        on_boost: parts.on_boost.unwrap_or(false),
+        on_stream: parts.on_stream.unwrap_or(false),
+        on_auto: parts.on_auto.unwrap_or(false),
+        on_sent: parts.on_sent.unwrap_or(false),
+        on_invoice: parts.on_invoice.unwrap_or(false),
+        on_lockout: parts.on_lockout.unwrap_or(false),
+        amount: parts.amount.as_ref().and_then(|s| if s.is_empty() { None } else { s.parse::<u64>().ok() }),
+        amount_equality: parts.amount_equality.filter(|equality| !equality.is_empty()),
+        sender: parts.sender.filter(|sender| !sender.is_empty()),
+        sender_equality: parts.sender_equality.filter(|equality| !equality.is_empty()),
+        app: parts.app.filter(|app| !app.is_empty()),
+        app_equality: parts.app_equality.filter(|equality| !equality.is_empty()),
+        podcast: parts.podcast.filter(|podcast| !podcast.is_empty()),
+        podcast_equality: parts.podcast_equality.filter(|equality| !equality.is_empty()),
+        numerology: parts.numerology.filter(|numerology| !numerology.is_empty()),
+        numerology_equality: parts.numerology_equality.filter(|equality| !equality.is_empty()),
+        webhook_url: parts.webhook_url.filter(|url| !url.is_empty()),
+        webhook_token: parts.webhook_token.clone().filter(|token| !token.is_empty()),
+        webhook_successful: None,
+        webhook_timestamp: None,
+        osc_address: parts.osc_address.filter(|address| !address.is_empty()),
+        osc_port: parts.osc_port.as_ref().and_then(|s| s.parse::<u16>().ok()),
+        osc_path: parts.osc_path.filter(|path| !path.is_empty()),
+        osc_args: parts.osc_args.filter(|args| !args.is_empty()),
+        osc_successful: None,
+        osc_timestamp: None,
+        midi_note: parts.midi_note.as_ref().and_then(|s| if s.is_empty() { None } else { s.parse::<u8>().ok() }),
+        midi_velocity: parts.midi_velocity.as_ref().and_then(|s| if s.is_empty() { None } else { s.parse::<u8>().ok() }),
+        midi_channel: parts.midi_channel.as_ref().and_then(|s| if s.is_empty() { None } else { s.parse::<u8>().ok() }),
+        midi_duration: parts.midi_duration.as_ref().and_then(|s| if s.is_empty() { None } else { s.parse::<u16>().ok() }),
+        sound_file: None,
+        sound_name: None,
+    };
+
+    if let Some(0) = trigger.amount {
+        trigger.amount = None;
+    }
+
+    if trigger.amount.is_none() {
+        trigger.amount_equality = None;
+    }
+
+    if trigger.sender.is_none() {
+        trigger.sender_equality = None;
+    }
+
+    if trigger.app.is_none() {
+        trigger.app_equality = None;
+    }
+
+    if trigger.podcast.is_none() {
+        trigger.podcast_equality = None;
+    }
+
+    if trigger.numerology.is_none() {
+        trigger.numerology_equality = None;
+    }
+
+    if index > 0 {
+        let filepath = db_filepath.clone();
+
+        let existing = match dbif::run_blocking(move || dbif::load_trigger_from_db(&filepath, index)).await {
+            Ok(exist) => exist,
+            Err(e) => {
+                eprintln!("** Error loading trigger: {}.\n", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, "** Error loading trigger.".to_string()).into_response();
+            }
+        };
+
+        trigger.position = existing.position;
+
+        trigger.sound_file = existing.sound_file;
+        trigger.sound_name = existing.sound_name;
+
+        trigger.webhook_successful = existing.webhook_successful;
+        trigger.webhook_timestamp = existing.webhook_timestamp;
+
+        trigger.osc_successful = existing.osc_successful;
+        trigger.osc_timestamp = existing.osc_timestamp;
+    };
+
+    let existing_filename = trigger.sound_file.clone();
+
+    let new_filename = match existing_filename.clone() {
+        Some(filename) => filename,
+        None => format!("{}.mp3", Uuid::new_v4()),
+    };
+
+    let new_path = format!("{}/{}", state.helipad_config.sound_path, new_filename);
+
+    if let Some(field) = parts.sound_file {
+        // Upload new file
+        let bytes = std::fs::copy(field.contents.path(), &new_path).unwrap_or(0);
+
+        trigger.sound_file = if bytes > 0 {
+            println!("** Wrote sound file to: {}", new_path);
+            Some(new_filename)
+        } else {
+            None
+        };
+
+        trigger.sound_name = field.metadata.file_name.or(trigger.sound_file.clone());
+
+    } else if parts.sound_file_existing.is_none() {
+        // Remove existing file
+        if let Some(filename) = existing_filename {
+            let full_path = format!("{}/{}", state.helipad_config.sound_path, filename);
+
+            match std::fs::remove_file(&full_path) {
+                Ok(_) => println!("** Removed sound file: {}", full_path),
+                Err(e) => eprintln!("** Error removing sound file: {}.", e),
+            };
+        }
+
+        trigger.sound_file = None;
+        trigger.sound_name = None;
+    }
+
+    let filepath = db_filepath.clone();
+
+    let idx = match dbif::run_blocking(move || dbif::save_trigger_to_db(&filepath, &trigger)).await {
+        Ok(idx) => idx,
+        Err(e) => {
+            eprintln!("** Error saving trigger: {}.\n", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error saving trigger.").into_response();
         }
     };
 
-    if let Some(pay_info) = boost.payment_info {
-        boost.payment_info = Some(dbif::PaymentRecord {
-            reply_to_idx: Some(index),
-            ..pay_info
-        });
+    println!("** trigger_settings_save({})", idx);
+
+    triggers_list(&db_filepath).await.into_response()
+}
+
+pub async fn trigger_settings_delete(
+    State(state): State<AppState>,
+    Path(idx): Path<String>
+) -> impl IntoResponse {
+
+    let index = idx.parse().unwrap();
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    if let Err(e) = dbif::run_blocking(move || dbif::delete_trigger_from_db(&db_filepath, index)).await {
+        eprintln!("** Error deleting trigger: {}.\n", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "** Error deleting trigger.");
     }
 
-    //Give some output
-    println!("Sent Boost: {:#?}", boost);
+    println!("** trigger_settings_delete({})", index);
+
+    (StatusCode::OK, "")
+}
+
+pub async fn trigger_settings_test(
+    State(state): State<AppState>,
+    Path(idx): Path<String>
+) -> Response {
+    let index: u64 = match idx.parse() {
+        Ok(i) => i,
+        Err(e) => {
+            eprintln!("** Invalid trigger index: {}.\n", e);
+            return (StatusCode::BAD_REQUEST, "Invalid trigger index").into_response();
+        }
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    let trigger = match dbif::run_blocking(move || dbif::load_trigger_from_db(&db_filepath, index)).await {
+        Ok(trg) => trg,
+        Err(e) => {
+            eprintln!("** Error loading trigger: {}.\n", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Error loading trigger").into_response();
+        }
+    };
+
+    println!("** Test trigger {}: Executing actions...", index);
+
+    let boost_with_effects = match triggers::test_trigger(&state.helipad_config.database_file_path, &trigger).await {
+        Ok(boost_with_effects) => boost_with_effects,
+        Err(e) => {
+            eprintln!("** Error testing trigger: {}.\n", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Error testing trigger").into_response();
+        }
+    };
+
+    match state.ws_tx.send(WebSocketEvent(
+        "boost".to_string(),
+        serde_json::to_value(&boost_with_effects).unwrap()
+    )) {
+        Ok(_) => println!("WebSocket event sent."),
+        Err(e) => eprintln!("Error sending WebSocket event: {:#?}", e)
+    }
+
+    triggers_list(&state.helipad_config.database_file_path).await.into_response()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TriggerPatchForm {
+    position: u64,
+}
+
+pub async fn trigger_settings_patch(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+    Form(params): Form<TriggerPatchForm>,
+) -> Result<impl IntoResponse, (StatusCode, &'static str)> {
+    let db_filepath = state.helipad_config.database_file_path;
+
+    let index = idx.parse().unwrap();
+
+    let filepath = db_filepath.clone();
+
+    let mut trigger = match dbif::run_blocking(move || dbif::load_trigger_from_db(&filepath, index)).await {
+        Ok(num) => num,
+        Err(e) => {
+            eprintln!("** Error loading trigger item: {}.\n", e);
+            return Err((StatusCode::INTERNAL_SERVER_ERROR, "** Error loading trigger item."));
+        }
+    };
+
+        trigger.position = params.position;
+
+    let filepath = db_filepath.clone();
+
+    match dbif::run_blocking(move || dbif::save_trigger_to_db(&filepath, &trigger)).await {
+        Ok(num) => num,
+        Err(e) => {
+            eprintln!("** Error saving trigger item: {}.\n", e);
+            return Err((StatusCode::INTERNAL_SERVER_ERROR, "** Error saving trigger item."));
+        }
+    };
 
-    //Store in the database
-    match dbif::add_payment_to_db(&state.helipad_config.database_file_path, &boost) {
-        Ok(_) => println!("New sent boost added."),
-        Err(e) => eprintln!("Error adding sent boost: {:#?}", e)
-    }
+    println!("** trigger_settings_patch({})", index);
 
-    Json(ReplyResponse {
-        success: true,
-        data: boost,
-    }).into_response()
+    Ok(triggers_list(&db_filepath).await)
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct MarkRepliedForm {
-    index: u64,
+
+pub async fn metadata_providers_list(db_filepath: &str) -> impl IntoResponse {
+    let db_filepath = db_filepath.to_string();
+    let results = dbif::run_blocking(move || dbif::get_metadata_providers_from_db(&db_filepath)).await.unwrap();
+    HtmlTemplate("webroot/template/metadata-provider-list.hbs", json!({"provider": results}))
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct MarkRepliedResponse {
-    success: bool,
+pub async fn metadata_provider_settings_list(State(state): State<AppState>) -> impl IntoResponse {
+    metadata_providers_list(&state.helipad_config.database_file_path).await
 }
 
-pub async fn api_v1_mark_replied(
+pub async fn metadata_provider_settings_load(
     State(state): State<AppState>,
-    Form(params): Form<MarkRepliedForm>,
-) -> Response {
+    Path(idx): Path<String>,
+) -> impl IntoResponse {
 
-    //Parameter - index (unsigned int)
-    let index = params.index;
+    let index = match idx.as_str() {
+        "add" => 0,
+        idx => idx.parse().unwrap(),
+    };
 
-    let result = dbif::mark_boost_as_replied(&state.helipad_config.database_file_path, index);
+    let result = if index > 0 {
+        let db_filepath = state.helipad_config.database_file_path.clone();
+        dbif::run_blocking(move || dbif::load_metadata_provider_from_db(&db_filepath, index)).await.ok()
+    } else {
+        None
+    };
 
-    if let Err(e) = result {
-        eprintln!("** Error marking boost as replied: {}", e);
-        return (StatusCode::INTERNAL_SERVER_ERROR, format!("** Error marking boost as replied: {}", e)).into_response();
-    }
+    let params = json!({
+        "provider": result,
+        "fields": metadata::RAWBOOST_FIELDS,
+    });
 
-    Json(MarkRepliedResponse {
-        success: true,
-    }).into_response()
+    HtmlTemplate("webroot/template/metadata-provider-edit.hbs", params)
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct FetchMetadataResponse {
-    success: bool,
-    boost: Option<BoostRecord>,
-    message: String,
+#[derive(Debug, TryFromMultipart)]
+pub struct MetadataProviderMultipart {
+    enabled: Option<bool>,
+    name: String,
+    memo_pattern: String,
+    payload_path: Option<String>,
+    field_map: Option<String>,
 }
 
-pub async fn api_v1_fetch_metadata(
+pub async fn metadata_provider_settings_save(
     State(state): State<AppState>,
     Path(idx): Path<String>,
+    TypedMultipart(parts): TypedMultipart<MetadataProviderMultipart>,
 ) -> Response {
-    let index: u64 = match idx.parse() {
-        Ok(i) => i,
-        Err(e) => {
-            eprintln!("** Invalid boost index: {}", e);
-            return (StatusCode::BAD_REQUEST, Json(FetchMetadataResponse {
-                success: false,
-                boost: None,
-                message: "Invalid boost index".to_string(),
-            })).into_response();
-        }
-    };
+    let db_filepath = state.helipad_config.database_file_path;
 
-    // Get the boost from database
-    let mut boost = match dbif::get_single_invoice_from_db(&state.helipad_config.database_file_path, index, true) {
-        Ok(Some(boost)) => boost,
-        Ok(None) => {
-            return (StatusCode::NOT_FOUND, Json(FetchMetadataResponse {
-                success: false,
-                boost: None,
-                message: "Boost not found".to_string(),
-            })).into_response();
-        },
-        Err(e) => {
-            eprintln!("** Error finding boost: {}", e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, Json(FetchMetadataResponse {
-                success: false,
-                boost: None,
-                message: format!("Error finding boost: {}", e),
-            })).into_response();
-        }
+    let index = match idx.as_str() {
+        "add" => 0,
+        idx => idx.parse().unwrap(),
     };
 
-    // Fetch payment metadata
-    let mut remote_cache = podcastindex::GuidCache::new(1);
-    let comment = boost.message.clone();
+    let provider = dbif::MetadataProviderRecord {
+        index,
+        enabled: parts.enabled.unwrap_or(false),
+        name: parts.name,
+        memo_pattern: parts.memo_pattern,
+        payload_path: parts.payload_path.filter(|path| !path.is_empty()),
+        field_map: parts.field_map.filter(|map| !map.trim().is_empty()),
+    };
 
-    if !boost::fetch_boost_metadata(&mut boost, &comment, &mut remote_cache).await {
-        return (StatusCode::BAD_REQUEST, Json(FetchMetadataResponse {
-            success: false,
-            boost: None,
-            message: "No payment metadata found for boost".to_string(),
-        })).into_response();
+    // Make sure the pattern and field map are usable before saving
+    if let Err(e) = metadata::GenericProvider::from_record(&provider, "") {
+        eprintln!("** Invalid metadata provider: {}.\n", e);
+        return (StatusCode::BAD_REQUEST, format!("Invalid metadata provider: {}", e)).into_response();
     }
 
-    // Update the boost in the database
-    match dbif::update_invoice_in_db(&state.helipad_config.database_file_path, &boost) {
-        Ok(_) => {
-            println!("** Successfully fetched and updated payment metadata for boost {}", index);
-            (StatusCode::OK, Json(FetchMetadataResponse {
-                success: true,
-                boost: Some(boost),
-                message: "Payment metadata fetched successfully".to_string(),
-            })).into_response()
-        }
+    let filepath = db_filepath.clone();
+
+    let idx = match dbif::run_blocking(move || dbif::save_metadata_provider_to_db(&filepath, &provider)).await {
+        Ok(idx) => idx,
         Err(e) => {
-            eprintln!("** Error updating boost in database: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(FetchMetadataResponse {
-                success: false,
-                boost: None,
-                message: format!("Error updating boost: {}", e),
-            })).into_response()
+            eprintln!("** Error saving metadata provider: {}.\n", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error saving metadata provider.").into_response();
         }
-    }
-}
+    };
 
-pub async fn general_settings_load(State(state): State<AppState>) -> impl IntoResponse {
-    let settings = dbif::load_settings_from_db(&state.helipad_config.database_file_path).unwrap();
-    HtmlTemplate("webroot/template/general-settings.hbs", json!({"settings": settings}))
-}
+    state.metadata_providers.invalidate().await;
 
-#[derive(Debug, TryFromMultipart)]
-pub struct GeneralSettingsMultipart {
-    show_received_sats: Option<bool>,
-    show_split_percentage: Option<bool>,
-    hide_boosts: Option<bool>,
-    hide_boosts_below: Option<String>,
-    play_pew: Option<bool>,
-    resolve_nostr_refs: Option<bool>,
-    show_hosted_wallet_ids: Option<bool>,
-    show_lightning_invoices: Option<bool>,
-    fetch_metadata: Option<bool>,
-    metadata_whitelist: Option<String>,
+    println!("** metadata_provider_settings_save({})", idx);
 
-    // The `unlimited arguments` means that this field will be limited to the
-    // total size of the request body. If you want to limit the size of this
-    // field to a specific value you can also specify a limit in bytes, like
-    // '5MiB' or '1GiB'.
-    #[form_data(limit = "5MiB")]
-    custom_pew_file: Option<FieldData<NamedTempFile>>,
-    custom_pew_existing: Option<bool>,
+    metadata_providers_list(&db_filepath).await.into_response()
 }
 
-pub async fn general_settings_save(
+pub async fn metadata_provider_settings_delete(
     State(state): State<AppState>,
-    TypedMultipart(parts): TypedMultipart<GeneralSettingsMultipart>,
+    Path(idx): Path<String>
 ) -> impl IntoResponse {
 
-    let hide_boosts_below = match parts.hide_boosts_below {
-        Some(s) => match s.is_empty() {
-            false => Some(s.parse::<u64>().unwrap_or(0)),
-            true => None,
-        },
-        None => None,
-    };
-
-    let mut settings = dbif::load_settings_from_db(&state.helipad_config.database_file_path).unwrap();
-
-    settings.show_received_sats = parts.show_received_sats.unwrap_or(false);
-    settings.show_split_percentage = parts.show_split_percentage.unwrap_or(false);
-    settings.hide_boosts = parts.hide_boosts.unwrap_or(false);
-    settings.hide_boosts_below = hide_boosts_below;
-    settings.play_pew = parts.play_pew.unwrap_or(false);
-    settings.resolve_nostr_refs = parts.resolve_nostr_refs.unwrap_or(false);
-    settings.show_hosted_wallet_ids = parts.show_hosted_wallet_ids.unwrap_or(false);
-    settings.show_lightning_invoices = parts.show_lightning_invoices.unwrap_or(false);
-    settings.fetch_metadata = parts.fetch_metadata.unwrap_or(false);
-    settings.metadata_whitelist = parts.metadata_whitelist.unwrap_or("".to_string());
-
-    if !settings.hide_boosts {
-        settings.hide_boosts_below = None;
-    }
+    let index = idx.parse().unwrap();
 
-    if let Some(field) = parts.custom_pew_file {
-        let from_path = field.contents.path();
-        let to_path =  format!("{}/custom_pew.mp3", state.helipad_config.sound_path);
-        let bytes = std::fs::copy(from_path, &to_path).unwrap_or(0);
+    let db_filepath = state.helipad_config.database_file_path.clone();
 
-        if bytes > 0 {
-            println!("** Wrote custom pew to: {}", to_path);
-            settings.custom_pew_file = Some("custom_pew.mp3".to_string())
-        } else {
-            settings.custom_pew_file = None;
-        }
-    } else if parts.custom_pew_existing.is_none() {
-        settings.custom_pew_file = None;
+    if let Err(e) = dbif::run_blocking(move || dbif::delete_metadata_provider_from_db(&db_filepath, index)).await {
+        eprintln!("** Error deleting metadata provider: {}.\n", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "** Error deleting metadata provider.");
     }
 
-    dbif::save_settings_to_db(&state.helipad_config.database_file_path, &settings).unwrap();
+    state.metadata_providers.invalidate().await;
 
-    *state.settings.write().await = settings.clone();
+    println!("** metadata_provider_settings_delete({})", index);
 
-    HtmlTemplate("webroot/template/general-settings.hbs", json!({"settings": settings, "saved": true}))
+    (StatusCode::OK, "")
 }
 
-pub fn numerology_list(db_filepath: &String) -> impl IntoResponse {
-    let results = dbif::get_numerology_from_db(db_filepath).unwrap();
-    HtmlTemplate("webroot/template/numerology-list.hbs", json!({"numerology": results}))
+pub async fn moderation_rules_list(db_filepath: &str) -> impl IntoResponse {
+    let db_filepath = db_filepath.to_string();
+    let results = dbif::run_blocking(move || dbif::get_moderation_rules_from_db(&db_filepath)).await.unwrap();
+    HtmlTemplate("webroot/template/moderation-rule-list.hbs", json!({"rule": results}))
 }
 
-pub async fn numerology_settings_list(State(state): State<AppState>) -> impl IntoResponse {
-    numerology_list(&state.helipad_config.database_file_path)
+pub async fn moderation_settings_list(State(state): State<AppState>) -> impl IntoResponse {
+    moderation_rules_list(&state.helipad_config.database_file_path).await
 }
 
-pub async fn numerology_settings_load(
+pub async fn moderation_settings_load(
     State(state): State<AppState>,
     Path(idx): Path<String>,
 ) -> impl IntoResponse {
@@ -898,495 +4431,607 @@ pub async fn numerology_settings_load(
     };
 
     let result = if index > 0 {
-        dbif::load_numerology_from_db(&state.helipad_config.database_file_path, index).ok()
+        let db_filepath = state.helipad_config.database_file_path.clone();
+        dbif::run_blocking(move || dbif::load_moderation_rule_from_db(&db_filepath, index)).await.ok()
     } else {
         None
     };
 
-    let equality = match &result {
-        Some(eq) => eq.equality.clone(),
-        None => "".to_string(),
-    };
+    let field = result.as_ref().map(|rule| rule.field.clone()).unwrap_or("sender".to_string());
+    let action = result.as_ref().map(|rule| rule.action.clone()).unwrap_or("hide".to_string());
 
     let params = json!({
-        "numerology": result,
-        "equality": json!({
-            "eq": equality == "=",
-            "in": equality == "=~",
-            "lt": equality == "<",
-            "gte": equality == ">=",
+        "rule": result,
+        "field": json!({
+            "sender": field == "sender",
+            "app": field == "app",
+            "pubkey": field == "pubkey",
+            "message": field == "message",
+        }),
+        "action": json!({
+            "hide": action == "hide",
+            "quarantine": action == "quarantine",
         }),
     });
 
-    HtmlTemplate("webroot/template/numerology-edit.hbs", params)
+    HtmlTemplate("webroot/template/moderation-rule-edit.hbs", params)
+}
+
+#[derive(Debug, TryFromMultipart)]
+pub struct ModerationRuleMultipart {
+    enabled: Option<bool>,
+    field: String,
+    pattern: String,
+    is_regex: Option<bool>,
+    action: String,
+}
+
+pub async fn moderation_settings_save(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+    TypedMultipart(parts): TypedMultipart<ModerationRuleMultipart>,
+) -> Response {
+    let db_filepath = state.helipad_config.database_file_path;
+
+    let index = match idx.as_str() {
+        "add" => 0,
+        idx => idx.parse().unwrap(),
+    };
+
+    let rule = dbif::ModerationRuleRecord {
+        index,
+        enabled: parts.enabled.unwrap_or(false),
+        field: parts.field,
+        pattern: parts.pattern.trim().to_string(),
+        is_regex: parts.is_regex.unwrap_or(false),
+        action: parts.action,
+    };
+
+    if let Err(e) = moderation::validate_rule(&rule) {
+        eprintln!("** Invalid moderation rule: {}.\n", e);
+        return (StatusCode::BAD_REQUEST, format!("Invalid moderation rule: {}", e)).into_response();
+    }
+
+    let filepath = db_filepath.clone();
+
+    let idx = match dbif::run_blocking(move || dbif::save_moderation_rule_to_db(&filepath, &rule)).await {
+        Ok(idx) => idx,
+        Err(e) => {
+            eprintln!("** Error saving moderation rule: {}.\n", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error saving moderation rule.").into_response();
+        }
+    };
+
+    println!("** moderation_settings_save({})", idx);
+
+    moderation_rules_list(&db_filepath).await.into_response()
+}
+
+pub async fn moderation_settings_delete(
+    State(state): State<AppState>,
+    Path(idx): Path<String>
+) -> impl IntoResponse {
+
+    let index = idx.parse().unwrap();
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    if let Err(e) = dbif::run_blocking(move || dbif::delete_moderation_rule_from_db(&db_filepath, index)).await {
+        eprintln!("** Error deleting moderation rule: {}.\n", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "** Error deleting moderation rule.");
+    }
+
+    println!("** moderation_settings_delete({})", index);
+
+    (StatusCode::OK, "")
+}
+
+pub async fn moderation_hidden_list(State(state): State<AppState>) -> impl IntoResponse {
+    let filters = BoostFilters {
+        only_hidden: true,
+        ..BoostFilters::new()
+    };
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::get_invoices_from_db(&db_filepath, "", 0, 100, false, false, filters)).await {
+        Ok(boosts) => {
+            HtmlTemplate("webroot/template/moderation-hidden-list.hbs", json!({"boosts": boosts}))
+        },
+        Err(err) => {
+            HtmlTemplate("webroot/template/moderation-hidden-list.hbs", json!({"error": err.to_string()}))
+        }
+    }
+}
+
+pub async fn moderation_hidden_unhide(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+) -> impl IntoResponse {
+    let index = idx.parse().unwrap();
+
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    if let Err(e) = dbif::run_blocking(move || dbif::set_boost_hidden_in_db(&db_filepath, index, false)).await {
+        eprintln!("** Error unhiding boost: {}.\n", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "** Error unhiding boost.");
+    }
+
+    println!("** moderation_hidden_unhide({})", index);
+
+    (StatusCode::OK, "")
+}
+
+pub async fn api_tokens_list(db_filepath: &str, new_token: Option<(String, String)>) -> impl IntoResponse {
+    let db_filepath = db_filepath.to_string();
+    let tokens = dbif::run_blocking(move || dbif::get_api_tokens_from_db(&db_filepath)).await.unwrap();
+    let now = Utc::now().timestamp();
+
+    let date = |time: Option<i64>| time
+        .and_then(|time| DateTime::from_timestamp(time, 0))
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string());
+
+    let rows: Vec<_> = tokens.iter().map(|token| json!({
+        "token": token,
+        "scopes": token.scopes.iter().map(|scope| scope.name()).collect::<Vec<&str>>().join(", "),
+        "expired": token.is_expired(now),
+        "expires": date(token.expires),
+        "last_used": date(token.last_used),
+        "created": date(Some(token.created)),
+    })).collect();
+
+    HtmlTemplate("webroot/template/api-token-list.hbs", json!({
+        "tokens": rows,
+        "new_token": new_token.map(|(name, token)| json!({"name": name, "token": token})),
+    }))
+}
+
+pub async fn api_token_settings_list(State(state): State<AppState>) -> impl IntoResponse {
+    api_tokens_list(&state.helipad_config.database_file_path, None).await
+}
+
+pub async fn api_token_settings_load() -> impl IntoResponse {
+    HtmlTemplate("webroot/template/api-token-edit.hbs", json!({}))
 }
 
 #[derive(Debug, TryFromMultipart)]
-pub struct NumerologyMultipart {
-    position: u64,
-    amount: u64,
-    equality: String,
-    emoji: Option<String>,
-    description: Option<String>,
+pub struct ApiTokenMultipart {
+    name: String,
+    scopes: Vec<String>,
+    expires_days: Option<String>,
 }
 
-pub async fn numerology_settings_save(
+pub async fn api_token_settings_save(
     State(state): State<AppState>,
-    Path(idx): Path<String>,
-    TypedMultipart(parts): TypedMultipart<NumerologyMultipart>,
+    TypedMultipart(parts): TypedMultipart<ApiTokenMultipart>,
 ) -> Response {
     let db_filepath = state.helipad_config.database_file_path;
+    let scopes: Vec<dbif::ApiScope> = parts.scopes.iter().filter_map(|scope| dbif::ApiScope::from_name(scope)).collect();
 
-    let index = match idx.as_str() {
-        "add" => 0,
-        idx => idx.parse().unwrap(),
-    };
+    let expires_days = parts.expires_days.as_ref().and_then(|s| if s.is_empty() { None } else { s.parse::<u64>().ok() });
 
-    let numero = NumerologyRecord {
-        index,
-        position: parts.position,
-        amount: parts.amount,
-        equality: parts.equality,
-        emoji: parts.emoji,
-        description: parts.description,
-    };
+    let filepath = db_filepath.clone();
 
-    let idx = match dbif::save_numerology_to_db(&db_filepath, &numero) {
-        Ok(idx) => idx,
+    let result = dbif::run_blocking(move || {
+        api_tokens::create_token(&filepath, &parts.name, &scopes, expires_days).map_err(Into::into)
+    }).await;
+
+    let (token, record) = match result {
+        Ok(created) => created,
         Err(e) => {
-            eprintln!("** Error saving numerology: {}.\n", e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error saving numerology.").into_response();
+            eprintln!("** Error adding API token: {}.\n", e);
+            return (StatusCode::BAD_REQUEST, format!("Unable to add API token: {}", e)).into_response();
         }
     };
 
-    println!("** numerology_settings_save({})", idx);
+    println!("** api_token_settings_save({})", record.index);
 
-    numerology_list(&db_filepath).into_response()
+    api_tokens_list(&db_filepath, Some((record.name, token))).await.into_response()
 }
 
-pub async fn numerology_settings_delete(
+pub async fn api_token_settings_delete(
     State(state): State<AppState>,
     Path(idx): Path<String>
 ) -> impl IntoResponse {
 
     let index = idx.parse().unwrap();
 
-    if let Err(e) = dbif::delete_numerology_from_db(&state.helipad_config.database_file_path, index) {
-        eprintln!("** Error deleting numerology: {}.\n", e);
-        return (StatusCode::INTERNAL_SERVER_ERROR, "** Error deleting numerology.");
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    if let Err(e) = dbif::run_blocking(move || dbif::delete_api_token_from_db(&db_filepath, index)).await {
+        eprintln!("** Error deleting API token: {}.\n", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "** Error deleting API token.");
     }
 
-    println!("** numerology_settings_delete({})", index);
+    println!("** api_token_settings_delete({})", index);
 
     (StatusCode::OK, "")
 }
 
-pub async fn numerology_settings_reset() -> impl IntoResponse {
-    HtmlTemplate("webroot/template/numerology-reset.hbs", "")
+pub async fn share_links_list(db_filepath: &str) -> impl IntoResponse {
+    let db_filepath = db_filepath.to_string();
+    let links = dbif::run_blocking(move || share_links_with_paths(&db_filepath)).await.unwrap();
+    let now = Utc::now().timestamp();
+
+    let date = |time: Option<i64>| time
+        .and_then(|time| DateTime::from_timestamp(time, 0))
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string());
+
+    let rows: Vec<_> = links.iter().map(|(link, path)| json!({
+        "link": link,
+        "path": path,
+        "view": match link.view {
+            dbif::ShareView::Boosts => "Boost feed",
+            dbif::ShareView::Websocket => "Live boosts",
+            dbif::ShareView::Goal => "Goal",
+        },
+        "expired": link.is_expired(now),
+        "expires": date(Some(link.expires)),
+        "last_used": date(link.last_used),
+    })).collect();
+
+    HtmlTemplate("webroot/template/share-link-list.hbs", json!({"links": rows}))
 }
 
-pub async fn numerology_settings_do_reset(State(state): State<AppState>) -> Response {
-    let db_filepath = state.helipad_config.database_file_path;
+pub async fn share_link_settings_list(State(state): State<AppState>) -> impl IntoResponse {
+    share_links_list(&state.helipad_config.database_file_path).await
+}
 
-    if let Err(e) = dbif::reset_numerology_in_db(&db_filepath) {
-        eprintln!("** Error resetting numerology: {}.\n", e);
-        return (StatusCode::INTERNAL_SERVER_ERROR, "** Error resetting numerology.").into_response()
-    }
+pub async fn share_link_settings_load(State(state): State<AppState>) -> impl IntoResponse {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let podcasts = dbif::run_blocking(move || dbif::get_podcasts_from_db(&db_filepath)).await.unwrap_or_default();
 
-    numerology_list(&db_filepath).into_response()
+    HtmlTemplate("webroot/template/share-link-edit.hbs", json!({"podcasts": podcasts}))
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct NumerologyPatchForm {
-    position: u64,
+#[derive(Debug, TryFromMultipart)]
+pub struct ShareLinkMultipart {
+    name: String,
+    view: String,
+    podcast: Option<String>,
+    goal_sats: Option<String>,
+    expires_days: Option<String>,
 }
 
-pub async fn numerology_settings_patch(
+pub async fn share_link_settings_save(
     State(state): State<AppState>,
-    Path(idx): Path<String>,
-    Form(params): Form<NumerologyPatchForm>,
-) -> Result<impl IntoResponse, (StatusCode, &'static str)> {
+    TypedMultipart(parts): TypedMultipart<ShareLinkMultipart>,
+) -> Response {
     let db_filepath = state.helipad_config.database_file_path;
 
-    let index = idx.parse().unwrap();
-
-    let mut numero = match dbif::load_numerology_from_db(&db_filepath, index) {
-        Ok(num) => num,
-        Err(e) => {
-            eprintln!("** Error loading numerology item: {}.\n", e);
-            return Err((StatusCode::INTERNAL_SERVER_ERROR, "** Error loading numerology item."));
-        }
+    let view = match dbif::ShareView::from_name(&parts.view) {
+        Some(view) => view,
+        None => return (StatusCode::BAD_REQUEST, "Unknown share link view").into_response(),
     };
 
-    numero.position = params.position;
+    let number = |value: &Option<String>| value.as_ref().and_then(|s| if s.is_empty() { None } else { s.parse::<u64>().ok() });
 
-    match dbif::save_numerology_to_db(&db_filepath, &numero) {
-        Ok(num) => num,
+    let (filepath, goal_sats, expires_days) = (db_filepath.clone(), number(&parts.goal_sats), number(&parts.expires_days));
+
+    let result = dbif::run_blocking(move || {
+        share_links::create_link(&filepath, &parts.name, view, parts.podcast.as_deref(), goal_sats, expires_days).map_err(Into::into)
+    }).await;
+
+    let link = match result {
+        Ok((_, link)) => link,
         Err(e) => {
-            eprintln!("** Error saving numerology item: {}.\n", e);
-            return Err((StatusCode::INTERNAL_SERVER_ERROR, "** Error saving numerology item."));
+            eprintln!("** Error adding share link: {}.\n", e);
+            return (StatusCode::BAD_REQUEST, format!("Unable to add share link: {}", e)).into_response();
         }
     };
 
-    println!("** numerology_settings_patch({})", index);
-
-    Ok(numerology_list(&db_filepath))
-}
-
+    println!("** share_link_settings_save({})", link.index);
 
-pub fn triggers_list(db_filepath: &String) -> impl IntoResponse {
-    let results = dbif::get_triggers_from_db(db_filepath).unwrap();
-    HtmlTemplate("webroot/template/trigger-list.hbs", json!({"trigger": results}))
-}
-
-pub async fn trigger_settings_list(State(state): State<AppState>) -> impl IntoResponse {
-    triggers_list(&state.helipad_config.database_file_path)
+    share_links_list(&db_filepath).await.into_response()
 }
 
-pub async fn trigger_settings_load(
+pub async fn share_link_settings_delete(
     State(state): State<AppState>,
-    Path(idx): Path<String>,
+    Path(idx): Path<String>
 ) -> impl IntoResponse {
 
-    let index = match idx.as_str() {
-        "add" => 0,
-        idx => idx.parse().unwrap(),
-    };
+    let index = idx.parse().unwrap();
 
-    let result = if index > 0 {
-        dbif::load_trigger_from_db(&state.helipad_config.database_file_path, index).ok()
-    } else {
-        None
-    };
+    let db_filepath = state.helipad_config.database_file_path.clone();
 
-    let amount_equality = match &result {
-        Some(trigger) => trigger.amount_equality.clone().unwrap_or_default(),
-        None => "".to_string(),
-    };
+    if let Err(e) = dbif::run_blocking(move || dbif::delete_share_link_from_db(&db_filepath, index)).await {
+        eprintln!("** Error deleting share link: {}.\n", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "** Error deleting share link.");
+    }
 
-    let sender_equality = match &result {
-        Some(trigger) => trigger.sender_equality.clone().unwrap_or_default(),
-        None => "".to_string(),
-    };
+    println!("** share_link_settings_delete({})", index);
 
-    let app_equality = match &result {
-        Some(trigger) => trigger.app_equality.clone().unwrap_or_default(),
-        None => "".to_string(),
-    };
+    (StatusCode::OK, "")
+}
 
-    let podcast_equality = match &result {
-        Some(trigger) => trigger.podcast_equality.clone().unwrap_or_default(),
-        None => "".to_string(),
-    };
+#[derive(Debug, Deserialize)]
+pub struct LoginAttemptParams {
+    result: Option<String>,
+    limit: Option<u64>,
+}
 
-    let params = json!({
-        "trigger": result,
-        "equality": json!({
-            "eq": amount_equality == "=",
-            "in": amount_equality == "=~",
-            "lt": amount_equality == "<",
-            "gte": amount_equality == ">=",
-            "starts_with": amount_equality == "^=",
-            "ends_with": amount_equality == "$=",
-        }),
-        "sender_equality": json!({
-            "eq": sender_equality == "=",
-            "not_eq": sender_equality == "!=",
-            "in": sender_equality == "=~",
-            "starts_with": sender_equality == "^=",
-            "ends_with": sender_equality == "$=",
-        }),
-        "app_equality": json!({
-            "eq": app_equality == "=",
-            "not_eq": app_equality == "!=",
-            "in": app_equality == "=~",
-            "starts_with": app_equality == "^=",
-            "ends_with": app_equality == "$=",
-        }),
-        "podcast_equality": json!({
-            "eq": podcast_equality == "=",
-            "not_eq": podcast_equality == "!=",
-            "in": podcast_equality == "=~",
-            "starts_with": podcast_equality == "^=",
-            "ends_with": podcast_equality == "$=",
-        }),
-    });
+impl LoginAttemptParams {
+    fn result(&self) -> Option<dbif::LoginResult> {
+        self.result.as_deref().and_then(dbif::LoginResult::from_name)
+    }
 
-    HtmlTemplate("webroot/template/trigger-edit.hbs", params)
+    fn limit(&self) -> u64 {
+        self.limit.unwrap_or(100).clamp(1, 1000)
+    }
 }
 
-#[derive(Debug, TryFromMultipart)]
-pub struct TriggerMultipart {
-    enabled: Option<bool>,
+//API - the login audit log, newest first
+pub async fn api_v1_logins(
+    State(state): State<AppState>,
+    Query(params): Query<LoginAttemptParams>,
+) -> Response {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let (result, limit) = (params.result(), params.limit());
 
-    on_boost: Option<bool>,
-    on_stream: Option<bool>,
-    on_auto: Option<bool>,
-    on_sent: Option<bool>,
-    on_invoice: Option<bool>,
+    match dbif::run_blocking(move || dbif::get_login_attempts_from_db(&db_filepath, result, limit)).await {
+        Ok(attempts) => Json(attempts).into_response(),
+        Err(e) => {
+            eprintln!("** Error getting login attempts: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting login attempts.").into_response()
+        }
+    }
+}
 
-    amount: Option<String>,
-    amount_equality: Option<String>,
-    sender: Option<String>,
-    sender_equality: Option<String>,
-    app: Option<String>,
-    app_equality: Option<String>,
-    podcast: Option<String>,
-    podcast_equality: Option<String>,
+pub async fn login_settings_list(
+    State(state): State<AppState>,
+    Query(params): Query<LoginAttemptParams>,
+) -> impl IntoResponse {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let (result, limit) = (params.result(), params.limit());
+    let attempts = dbif::run_blocking(move || dbif::get_login_attempts_from_db(&db_filepath, result, limit)).await.unwrap();
+
+    let rows: Vec<_> = attempts.iter().map(|attempt| json!({
+        "attempt": attempt,
+        "time": DateTime::from_timestamp(attempt.time, 0).map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string()),
+        "success": attempt.result == dbif::LoginResult::Success,
+        "failed": attempt.result == dbif::LoginResult::Failed,
+        "locked": attempt.result == dbif::LoginResult::Locked,
+    })).collect();
+
+    HtmlTemplate("webroot/template/login-list.hbs", json!({"attempts": rows}))
+}
 
-    webhook_url: Option<String>,
-    webhook_token: Option<String>,
+pub async fn users_list(db_filepath: &str) -> impl IntoResponse {
+    let db_filepath = db_filepath.to_string();
+    let users = dbif::run_blocking(move || dbif::get_users_from_db(&db_filepath)).await.unwrap();
 
-    osc_address: Option<String>,
-    osc_port: Option<String>,
-    osc_path: Option<String>,
-    osc_args: Option<String>,
+    let rows: Vec<_> = users.iter().map(|user| json!({
+        "user": user,
+        "role": user.role.name(),
+        "last_login": user.last_login
+            .and_then(|time| DateTime::from_timestamp(time, 0))
+            .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string()),
+    })).collect();
 
-    midi_note: Option<String>,
-    midi_velocity: Option<String>,
-    midi_channel: Option<String>,
-    midi_duration: Option<String>,
+    HtmlTemplate("webroot/template/user-list.hbs", json!({"users": rows}))
+}
 
-    #[form_data(limit = "5MiB")]
-    sound_file: Option<FieldData<NamedTempFile>>,
-    sound_file_existing: Option<bool>,
+pub async fn user_settings_list(State(state): State<AppState>) -> impl IntoResponse {
+    users_list(&state.helipad_config.database_file_path).await
 }
 
-pub async fn trigger_settings_save(
+pub async fn user_settings_load(
     State(state): State<AppState>,
     Path(idx): Path<String>,
-    TypedMultipart(parts): TypedMultipart<TriggerMultipart>,
-) -> Response {
-    println!("** trigger_settings_save({:?})", parts);
-    let db_filepath = state.helipad_config.database_file_path;
+) -> impl IntoResponse {
+    let db_filepath = state.helipad_config.database_file_path.clone();
 
     let index = match idx.as_str() {
         "add" => 0,
         idx => idx.parse().unwrap(),
     };
 
-    let mut trigger = TriggerRecord {
-        index: index,
-        position: index,
-        enabled: parts.enabled.unwrap_or(false),
-        on_boost: parts.on_boost.unwrap_or(false),
-        on_stream: parts.on_stream.unwrap_or(false),
-        on_auto: parts.on_auto.unwrap_or(false),
-        on_sent: parts.on_sent.unwrap_or(false),
-        on_invoice: parts.on_invoice.unwrap_or(false),
-        amount: parts.amount.as_ref().and_then(|s| if s.is_empty() { None } else { s.parse::<u64>().ok() }),
-        amount_equality: parts.amount_equality.filter(|equality| !equality.is_empty()),
-        sender: parts.sender.filter(|sender| !sender.is_empty()),
-        sender_equality: parts.sender_equality.filter(|equality| !equality.is_empty()),
-        app: parts.app.filter(|app| !app.is_empty()),
-        app_equality: parts.app_equality.filter(|equality| !equality.is_empty()),
-        podcast: parts.podcast.filter(|podcast| !podcast.is_empty()),
-        podcast_equality: parts.podcast_equality.filter(|equality| !equality.is_empty()),
-        webhook_url: parts.webhook_url.filter(|url| !url.is_empty()),
-        webhook_token: parts.webhook_token.clone().filter(|token| !token.is_empty()),
-        webhook_successful: None,
-        webhook_timestamp: None,
-        osc_address: parts.osc_address.filter(|address| !address.is_empty()),
-        osc_port: parts.osc_port.as_ref().and_then(|s| s.parse::<u16>().ok()),
-        osc_path: parts.osc_path.filter(|path| !path.is_empty()),
-        osc_args: parts.osc_args.filter(|args| !args.is_empty()),
-        osc_successful: None,
-        osc_timestamp: None,
-        midi_note: parts.midi_note.as_ref().and_then(|s| if s.is_empty() { None } else { s.parse::<u8>().ok() }),
-        midi_velocity: parts.midi_velocity.as_ref().and_then(|s| if s.is_empty() { None } else { s.parse::<u8>().ok() }),
-        midi_channel: parts.midi_channel.as_ref().and_then(|s| if s.is_empty() { None } else { s.parse::<u8>().ok() }),
-        midi_duration: parts.midi_duration.as_ref().and_then(|s| if s.is_empty() { None } else { s.parse::<u16>().ok() }),
-        sound_file: None,
-        sound_name: None,
-    };
-
-    if let Some(0) = trigger.amount {
-        trigger.amount = None;
-    }
-
-    if trigger.amount.is_none() {
-        trigger.amount_equality = None;
-    }
-
-    if trigger.sender.is_none() {
-        trigger.sender_equality = None;
-    }
-
-    if trigger.app.is_none() {
-        trigger.app_equality = None;
-    }
-
-    if trigger.podcast.is_none() {
-        trigger.podcast_equality = None;
-    }
-
-    if index > 0 {
-        let existing = match dbif::load_trigger_from_db(&db_filepath, index) {
-            Ok(exist) => exist,
-            Err(e) => {
-                eprintln!("** Error loading trigger: {}.\n", e);
-                return (StatusCode::INTERNAL_SERVER_ERROR, "** Error loading trigger.".to_string()).into_response();
-            }
+    let (result, first) = dbif::run_blocking(move || {
+        let user = if index > 0 {
+            dbif::get_user_from_db(&db_filepath, index).ok().flatten()
+        } else {
+            None
         };
 
-        trigger.position = existing.position;
+        //The first account has to be an admin
+        Ok((user, !dbif::users_exist_in_db(&db_filepath).unwrap_or(true)))
+    }).await.unwrap();
 
-        trigger.sound_file = existing.sound_file;
-        trigger.sound_name = existing.sound_name;
+    let role = result.as_ref().map(|user| user.role).unwrap_or(if first { UserRole::Admin } else { UserRole::Viewer });
 
-        trigger.webhook_successful = existing.webhook_successful;
-        trigger.webhook_timestamp = existing.webhook_timestamp;
+    HtmlTemplate("webroot/template/user-edit.hbs", json!({
+        "user": result,
+        "first": first,
+        "role": json!({
+            "viewer": role == UserRole::Viewer,
+            "operator": role == UserRole::Operator,
+            "admin": role == UserRole::Admin,
+        }),
+    }))
+}
 
-        trigger.osc_successful = existing.osc_successful;
-        trigger.osc_timestamp = existing.osc_timestamp;
-    };
+#[derive(Debug, TryFromMultipart)]
+pub struct UserMultipart {
+    username: Option<String>,
+    password: Option<String>,
+    role: String,
+}
 
-    let existing_filename = trigger.sound_file.clone();
+pub async fn user_settings_save(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
+    TypedMultipart(parts): TypedMultipart<UserMultipart>,
+) -> Response {
+    let db_filepath = state.helipad_config.database_file_path;
 
-    let new_filename = match existing_filename.clone() {
-        Some(filename) => filename,
-        None => format!("{}.mp3", Uuid::new_v4()),
+    let index = match idx.as_str() {
+        "add" => 0,
+        idx => idx.parse().unwrap(),
     };
 
-    let new_path = format!("{}/{}", state.helipad_config.sound_path, new_filename);
+    let role = match UserRole::from_name(&parts.role) {
+        Some(role) => role,
+        None => return (StatusCode::BAD_REQUEST, "Unknown role.").into_response(),
+    };
 
-    if let Some(field) = parts.sound_file {
-        // Upload new file
-        let bytes = std::fs::copy(field.contents.path(), &new_path).unwrap_or(0);
+    let filepath = db_filepath.clone();
 
-        trigger.sound_file = if bytes > 0 {
-            println!("** Wrote sound file to: {}", new_path);
-            Some(new_filename)
+    let result = dbif::run_blocking(move || {
+        if index == 0 {
+            users::add_user(&filepath, &parts.username.unwrap_or_default(), &parts.password.unwrap_or_default(), role)?;
         } else {
-            None
-        };
-
-        trigger.sound_name = field.metadata.file_name.or(trigger.sound_file.clone());
-
-    } else if parts.sound_file_existing.is_none() {
-        // Remove existing file
-        if let Some(filename) = existing_filename {
-            let full_path = format!("{}/{}", state.helipad_config.sound_path, filename);
-
-            match std::fs::remove_file(&full_path) {
-                Ok(_) => println!("** Removed sound file: {}", full_path),
-                Err(e) => eprintln!("** Error removing sound file: {}.", e),
-            };
+            users::update_user(&filepath, index, Some(role), parts.password.as_deref())?;
         }
 
-        trigger.sound_file = None;
-        trigger.sound_name = None;
-    }
+        Ok(())
+    }).await;
 
-    let idx = match dbif::save_trigger_to_db(&db_filepath, &trigger) {
-        Ok(idx) => idx,
-        Err(e) => {
-            eprintln!("** Error saving trigger: {}.\n", e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error saving trigger.").into_response();
-        }
-    };
+    if let Err(e) = result {
+        eprintln!("** Error saving user: {}.\n", e);
+        return (StatusCode::BAD_REQUEST, format!("Unable to save user: {}", e)).into_response();
+    }
 
-    println!("** trigger_settings_save({})", idx);
+    println!("** user_settings_save({})", idx);
 
-    triggers_list(&db_filepath).into_response()
+    users_list(&db_filepath).await.into_response()
 }
 
-pub async fn trigger_settings_delete(
+pub async fn user_settings_delete(
     State(state): State<AppState>,
     Path(idx): Path<String>
-) -> impl IntoResponse {
+) -> Response {
 
     let index = idx.parse().unwrap();
 
-    if let Err(e) = dbif::delete_trigger_from_db(&state.helipad_config.database_file_path, index) {
-        eprintln!("** Error deleting trigger: {}.\n", e);
-        return (StatusCode::INTERNAL_SERVER_ERROR, "** Error deleting trigger.");
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    if let Err(e) = dbif::run_blocking(move || dbif::delete_user_from_db(&db_filepath, index)).await {
+        eprintln!("** Error deleting user: {}.\n", e);
+        return (StatusCode::BAD_REQUEST, format!("Unable to delete user: {}", e)).into_response();
     }
 
-    println!("** trigger_settings_delete({})", index);
+    println!("** user_settings_delete({})", index);
 
-    (StatusCode::OK, "")
+    (StatusCode::OK, "").into_response()
 }
 
-pub async fn trigger_settings_test(
+#[derive(Debug, Deserialize)]
+pub struct SupporterSettingsParams {
+    #[serde(default)]
+    q: Option<String>,
+}
+
+pub async fn supporters_list(db_filepath: &str, search: Option<String>) -> impl IntoResponse {
+    let query = dbif::SupporterQuery {
+        search,
+        sort: dbif::SupporterSort::Total,
+        limit: 200,
+        ..Default::default()
+    };
+
+    let db_filepath = db_filepath.to_string();
+    let supporters = dbif::run_blocking(move || dbif::get_supporters_from_db(&db_filepath, &query)).await.unwrap();
+
+    let rows: Vec<_> = supporters.iter().map(|supporter| json!({
+        "supporter": supporter,
+        "sats": supporter.value_msat_total / 1000,
+        "last_seen": supporter.last_seen
+            .and_then(|time| DateTime::from_timestamp(time, 0))
+            .map(|dt| dt.format("%Y-%m-%d").to_string()),
+        "identities": supporter.identities.iter()
+            .filter(|identity| identity.kind != "sender")
+            .map(|identity| identity.kind.clone())
+            .collect::<Vec<String>>()
+            .join(", "),
+    })).collect();
+
+    HtmlTemplate("webroot/template/supporter-list.hbs", json!({"supporters": rows}))
+}
+
+pub async fn supporter_settings_list(
     State(state): State<AppState>,
-    Path(idx): Path<String>
+    Query(params): Query<SupporterSettingsParams>,
+) -> impl IntoResponse {
+    supporters_list(&state.helipad_config.database_file_path, params.q).await
+}
+
+pub async fn supporter_settings_load(
+    State(state): State<AppState>,
+    Path(idx): Path<String>,
 ) -> Response {
-    let index: u64 = match idx.parse() {
-        Ok(i) => i,
-        Err(e) => {
-            eprintln!("** Invalid trigger index: {}.\n", e);
-            return (StatusCode::BAD_REQUEST, "Invalid trigger index").into_response();
-        }
-    };
+    let db_filepath = state.helipad_config.database_file_path.clone();
+    let index = idx.parse().unwrap();
 
-    let trigger = match dbif::load_trigger_from_db(&state.helipad_config.database_file_path, index) {
-        Ok(trg) => trg,
-        Err(e) => {
-            eprintln!("** Error loading trigger: {}.\n", e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, "Error loading trigger").into_response();
-        }
+    //Everyone else can be merged in
+    let query = dbif::SupporterQuery {
+        sort: dbif::SupporterSort::Name,
+        ..Default::default()
     };
 
-    println!("** Test trigger {}: Executing actions...", index);
+    let result = dbif::run_blocking(move || {
+        Ok((dbif::get_supporter_from_db(&db_filepath, index)?, dbif::get_supporters_from_db(&db_filepath, &query)?))
+    }).await;
 
-    let boost_with_effects = match triggers::test_trigger(&state.helipad_config.database_file_path, &trigger).await {
-        Ok(boost_with_effects) => boost_with_effects,
+    let (supporter, mut others) = match result {
+        Ok((Some(supporter), others)) => (supporter, others),
+        Ok((None, _)) => return (StatusCode::NOT_FOUND, "** Supporter not found.").into_response(),
         Err(e) => {
-            eprintln!("** Error testing trigger: {}.\n", e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, "Error testing trigger").into_response();
+            eprintln!("** Error getting supporter: {}.\n", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting supporter.").into_response();
         }
     };
 
-    match state.ws_tx.send(WebSocketEvent(
-        "boost".to_string(),
-        serde_json::to_value(&boost_with_effects).unwrap()
-    )) {
-        Ok(_) => println!("WebSocket event sent."),
-        Err(e) => eprintln!("Error sending WebSocket event: {:#?}", e)
-    }
+    others.retain(|other| other.index != index);
 
-    triggers_list(&state.helipad_config.database_file_path).into_response()
+    HtmlTemplate("webroot/template/supporter-edit.hbs", json!({
+        "supporter": supporter,
+        "others": others,
+    })).into_response()
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct TriggerPatchForm {
-    position: u64,
+#[derive(Debug, TryFromMultipart)]
+pub struct SupporterMultipart {
+    name: String,
+    note: String,
+    thanked: Option<bool>,
+    merge: Vec<u64>,
 }
 
-pub async fn trigger_settings_patch(
+pub async fn supporter_settings_save(
     State(state): State<AppState>,
     Path(idx): Path<String>,
-    Form(params): Form<TriggerPatchForm>,
-) -> Result<impl IntoResponse, (StatusCode, &'static str)> {
+    TypedMultipart(parts): TypedMultipart<SupporterMultipart>,
+) -> Response {
     let db_filepath = state.helipad_config.database_file_path;
-
     let index = idx.parse().unwrap();
 
-    let mut trigger = match dbif::load_trigger_from_db(&db_filepath, index) {
-        Ok(num) => num,
-        Err(e) => {
-            eprintln!("** Error loading trigger item: {}.\n", e);
-            return Err((StatusCode::INTERNAL_SERVER_ERROR, "** Error loading trigger item."));
-        }
+    let update = dbif::SupporterUpdate {
+        name: Some(parts.name),
+        note: Some(parts.note),
+        thanked: Some(parts.thanked.unwrap_or(false)),
     };
 
-        trigger.position = params.position;
+    let filepath = db_filepath.clone();
 
-    match dbif::save_trigger_to_db(&db_filepath, &trigger) {
-        Ok(num) => num,
-        Err(e) => {
-            eprintln!("** Error saving trigger item: {}.\n", e);
-            return Err((StatusCode::INTERNAL_SERVER_ERROR, "** Error saving trigger item."));
+    if let Err(e) = dbif::run_blocking(move || dbif::update_supporter_in_db(&filepath, index, &update)).await {
+        eprintln!("** Error saving supporter: {}.\n", e);
+        return (StatusCode::BAD_REQUEST, format!("Unable to save supporter: {}", e)).into_response();
+    }
+
+    if !parts.merge.is_empty() {
+        let (filepath, merge) = (db_filepath.clone(), parts.merge);
+
+        if let Err(e) = dbif::run_blocking(move || dbif::merge_supporters_in_db(&filepath, index, &merge)).await {
+            eprintln!("** Error merging supporters: {}.\n", e);
+            return (StatusCode::BAD_REQUEST, format!("Unable to merge supporters: {}", e)).into_response();
         }
-    };
+    }
 
-    println!("** trigger_settings_patch({})", index);
+    println!("** supporter_settings_save({})", index);
 
-    Ok(triggers_list(&db_filepath))
+    supporters_list(&db_filepath, None).await.into_response()
 }
 
-
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ReportGenerateForm {
     list_boosts: Option<bool>,
@@ -1503,6 +5148,9 @@ pub async fn report_generate(
         filters.podcast = Some(form.podcast);
     }
 
+    //Hidden boosts still count towards reports
+    filters.include_hidden = true;
+
     if let Some(val) = form.start_date {
         filters.start_date = Some(val);
     }
@@ -1531,7 +5179,7 @@ pub async fn report_generate(
     let mut csv = String::new();
 
     //CSV column name header
-    let mut headers = "index,type,time,timezone,value_sat,value_sat_total,value_msat,value_msat_total,action,action_name,sender,app,message,podcast,episode,remote_podcast,remote_episode,custom_key,custom_value,memo".to_string();
+    let mut headers = "index,type,time,timezone,value_sat,value_sat_total,value_msat,value_msat_total,action,action_name,sender,app,message,podcast,episode,remote_podcast,remote_episode,custom_key,custom_value,memo,message_resolved,numerology".to_string();
 
     if btc_prices.is_some() {
         headers.push_str(",btc_close,value_usd,value_usd_total");
@@ -1542,29 +5190,43 @@ pub async fn report_generate(
 
     for list in lists {
         let results;
+        let (db_filepath, filters) = (state.helipad_config.database_file_path.clone(), filters.clone());
 
         if list == "boost" {
-            results = dbif::get_boosts_from_db(&state.helipad_config.database_file_path, index, boostcount, direction, false, filters.clone());
+            results = dbif::run_blocking(move || dbif::get_boosts_from_db(&db_filepath, index, boostcount, direction, false, filters)).await.map_err(|e| e.to_string());
         }
         else if list == "stream" {
-            results = dbif::get_streams_from_db(&state.helipad_config.database_file_path, index, boostcount, direction, false, filters.clone());
+            //Streams removed by the retention policy are listed as one per day, podcast, episode, sender and app
+            results = dbif::run_blocking(move || {
+                let mut streams = dbif::get_streams_from_db(&db_filepath, index, boostcount, direction, false, filters.clone())?;
+                let rollups = dbif::get_stream_rollups_from_db(&db_filepath, &filters)?;
+                streams.extend(rollups.iter().map(dbif::StreamRollupRecord::to_boost));
+                Ok(streams)
+            }).await.map_err(|e| e.to_string());
         }
         else if list == "sent" {
-            results = dbif::get_payments_from_db(&state.helipad_config.database_file_path, index, boostcount, direction, false, filters.clone());
+            results = dbif::run_blocking(move || dbif::get_payments_from_db(&db_filepath, index, boostcount, direction, false, filters)).await.map_err(|e| e.to_string());
         }
         else {
             continue;
         }
 
-        if let Err(e) = results {
-            eprintln!("** Error getting boosts: {}.\n", e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting boosts.").into_response();
-        }
+        let mut boosts = match results {
+            Ok(boosts) => boosts,
+            Err(e) => {
+                eprintln!("** Error getting boosts: {}.\n", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting boosts.").into_response();
+            }
+        };
 
-        let boosts = results.unwrap();
+        numerology::attach_numerology(&state.helipad_config.database_file_path, &mut boosts).await;
+        resolve_nostr_refs(&state, &mut boosts).await;
 
         //Iterate the boost set
         for boost in boosts {
+            let message_resolved = boost.resolved_message();
+            let numerology = boost.numerology_emojis();
+
             //Parse out a friendly date
             let dt = DateTime::from_timestamp(boost.time, 0).unwrap_or_else(|| panic!("Unable to parse boost time: {}", boost.time));
             let boost_time = dt.format("%Y-%m-%d %H:%M:%S").to_string();
@@ -1584,7 +5246,7 @@ pub async fn report_generate(
             //The main export data formatting
             csv.push_str(
                 format!(
-                    "{},{},\"{}\",\"{}\",\"{}\",{},{},{},{},{},\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\"",
+                    "{},{},\"{}\",\"{}\",\"{}\",{},{},{},{},{},\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\"",
                     boost.index,
                     list,
                     boost_time,
@@ -1604,7 +5266,9 @@ pub async fn report_generate(
                     BoostRecord::escape_for_csv(boost.remote_episode.unwrap_or("".to_string())),
                     BoostRecord::escape_for_csv(boost.custom_key.map(|k| k.to_string()).unwrap_or_default()),
                     BoostRecord::escape_for_csv(boost.custom_value.unwrap_or("".to_string())),
-                    BoostRecord::escape_for_csv(boost.memo.unwrap_or("".to_string()))
+                    BoostRecord::escape_for_csv(boost.memo.unwrap_or("".to_string())),
+                    BoostRecord::escape_for_csv(message_resolved),
+                    BoostRecord::escape_for_csv(numerology)
                 ).as_str()
             );
 
@@ -1639,7 +5303,9 @@ pub async fn report_generate(
 }
 
 pub async fn report_podcasts_list(State(state): State<AppState>) -> impl IntoResponse {
-    match dbif::get_podcasts_from_db(&state.helipad_config.database_file_path) {
+    let db_filepath = state.helipad_config.database_file_path.clone();
+
+    match dbif::run_blocking(move || dbif::get_podcasts_from_db(&db_filepath)).await {
         Ok(podcasts) => {
              HtmlTemplate("webroot/template/report-podcasts-list.hbs", json!({"podcasts": podcasts}))
         },
@@ -1697,27 +5363,34 @@ pub async fn csv_export_boosts(
     //Get the boosts/streams/sent from db for returning
     let results;
     let filters = BoostFilters::new();
+    let db_filepath = state.helipad_config.database_file_path.clone();
 
     if list == "streams" {
-        results = dbif::get_streams_from_db(&state.helipad_config.database_file_path, index, boostcount, old, false, filters);
+        results = dbif::run_blocking(move || dbif::get_streams_from_db(&db_filepath, index, boostcount, old, false, filters)).await.map_err(|e| e.to_string());
     }
     else if list == "sent" {
-        results = dbif::get_payments_from_db(&state.helipad_config.database_file_path, index, boostcount, old, false, filters);
+        results = dbif::run_blocking(move || dbif::get_payments_from_db(&db_filepath, index, boostcount, old, false, filters)).await.map_err(|e| e.to_string());
     }
     else { // boosts
-        results = dbif::get_boosts_from_db(&state.helipad_config.database_file_path, index, boostcount, old, false, filters);
+        results = dbif::run_blocking(move || dbif::get_boosts_from_db(&db_filepath, index, boostcount, old, false, filters)).await.map_err(|e| e.to_string());
     }
 
     match results {
-        Ok(boosts) => {
+        Ok(mut boosts) => {
+            numerology::attach_numerology(&state.helipad_config.database_file_path, &mut boosts).await;
+            resolve_nostr_refs(&state, &mut boosts).await;
+
             let mut csv = String::new();
 
             //CSV column name header
-            csv.push_str("count,index,time,timezone,value_sat,value_sat_total,value_msat,value_msat_total,action,action_name,sender,app,message,podcast,episode,remote_podcast,remote_episode,custom_key,custom_value,memo\n");
+            csv.push_str("count,index,time,timezone,value_sat,value_sat_total,value_msat,value_msat_total,action,action_name,sender,app,message,podcast,episode,remote_podcast,remote_episode,custom_key,custom_value,memo,message_resolved,numerology\n");
 
             //Iterate the boost set
             let mut count: u64 = 1;
             for boost in boosts {
+                let message_resolved = boost.resolved_message();
+                let numerology = boost.numerology_emojis();
+
                 //Parse out a friendly date
                 let dt = DateTime::from_timestamp(boost.time, 0).unwrap_or_else(|| panic!("Unable to parse boost time: {}", boost.time));
                 let boost_time = dt.format("%Y-%m-%d %H:%M:%S").to_string();
@@ -1736,7 +5409,7 @@ pub async fn csv_export_boosts(
                 //The main export data formatting
                 csv.push_str(
                     format!(
-                        "{},{},\"{}\",\"{}\",{},{},{},{},{},\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\"\n",
+                        "{},{},\"{}\",\"{}\",{},{},{},{},{},\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",\"{}\"\n",
                         count,
                         boost.index,
                         boost_time,
@@ -1756,7 +5429,9 @@ pub async fn csv_export_boosts(
                         BoostRecord::escape_for_csv(boost.remote_episode.unwrap_or("".to_string())),
                         BoostRecord::escape_for_csv(boost.custom_key.map(|k| k.to_string()).unwrap_or_default()),
                         BoostRecord::escape_for_csv(boost.custom_value.unwrap_or("".to_string())),
-                        BoostRecord::escape_for_csv(boost.memo.unwrap_or("".to_string()))
+                        BoostRecord::escape_for_csv(boost.memo.unwrap_or("".to_string())),
+                        BoostRecord::escape_for_csv(message_resolved),
+                        BoostRecord::escape_for_csv(numerology)
                     ).as_str()
                 );
 
@@ -1783,4 +5458,417 @@ pub async fn csv_export_boosts(
             (StatusCode::INTERNAL_SERVER_ERROR, "** Error getting boosts.").into_response()
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HelipadConfig, password::AuthSecrets, trusted_proxy::TrustedProxy};
+    use axum::{middleware, routing::{self, any, post}, Router};
+    use std::sync::Arc;
+    use tokio::sync::{broadcast, Mutex, RwLock};
+    use tower::Service;
+
+    // a stand-in for the login proxy: requests from its address carry the Remote-User header
+    const PROXY_ADDR: &str = "127.0.0.1:40000";
+
+    // where logins come from when there's no proxy
+    const CLIENT_ADDR: &str = "10.0.0.1:40000";
+
+    const PASSWORD: &str = "correct horse";
+
+    async fn whoami(user: Option<Extension<SessionUser>>) -> String {
+        match user {
+            Some(Extension(user)) => format!("{} {}", user.username.as_deref().unwrap_or("-"), user.role.name()),
+            None => "token".to_string(),
+        }
+    }
+
+    struct TestApp {
+        router: Router,
+        db: String,
+        _dir: tempfile::TempDir,
+    }
+
+    //Helipad with a temporary database holding alice (admin), bob (operator) and vic (viewer), behind a login
+    //proxy if one is given. Every other path has a route, so a 403 or 404 can only come from the middleware.
+    fn test_app(trusted_proxy: Option<TrustedProxy>) -> TestApp {
+        let dir = tempfile::tempdir().unwrap();
+        let database_file_path = dir.path().join("database.db").to_string_lossy().to_string();
+
+        dbif::create_database(&database_file_path).unwrap();
+
+        for (username, role) in [("alice", UserRole::Admin), ("bob", UserRole::Operator), ("vic", UserRole::Viewer)] {
+            users::add_user(&database_file_path, username, PASSWORD, role).unwrap();
+        }
+
+        let settings = dbif::load_settings_from_db(&database_file_path).unwrap();
+
+        let state = AppState {
+            helipad_config: HelipadConfig {
+                database_file_path: database_file_path.clone(),
+                sound_path: "".to_string(),
+                listen_port: "".to_string(),
+                macaroon_path: "".to_string(),
+                cert_path: "".to_string(),
+                node_address: "".to_string(),
+                backup_dir: "".to_string(),
+                backup_interval: 0,
+                backup_keep: 0,
+                backup_sounds: false,
+                stream_retention_days: 0,
+                secure_cookies: false,
+                trusted_proxy,
+            },
+            version: "test".to_string(),
+            ws_tx: Arc::new(broadcast::Sender::new(1)),
+            settings: Arc::new(RwLock::new(settings)),
+            auth: Arc::new(RwLock::new(AuthSecrets { password_hash: None, jwt_secret: "secret".to_string() })),
+            login_throttle: Arc::new(Mutex::new(login_throttle::LoginThrottle::default())),
+            metadata_providers: Arc::new(metadata::ProviderCache::default()),
+        };
+
+        let authed_routes = Router::new()
+            .route("/", any(whoami))
+            .route("/{*path}", any(whoami))
+            .route("/api/v1/password", post(api_v1_password))
+            .route_layer(middleware::from_fn_with_state(state.clone(), auth_middleware));
+
+        let router = Router::new()
+            .merge(authed_routes)
+            .route("/login", routing::get(login).post(handle_login))
+            .route("/api/v1/login", post(api_v1_login))
+            .route("/share/{token}/boosts", routing::get(share_boosts))
+            .with_state(state);
+
+        TestApp { router, db: database_file_path, _dir: dir }
+    }
+
+    fn proxied_app(default_role: Option<&str>) -> TestApp {
+        test_app(TrustedProxy::from_config(Some("Remote-User"), Some("127.0.0.1"), default_role).unwrap())
+    }
+
+    struct TestResponse {
+        status: StatusCode,
+        headers: HeaderMap,
+        body: String,
+    }
+
+    impl TestResponse {
+        fn json(&self) -> serde_json::Value {
+            serde_json::from_str(&self.body).unwrap()
+        }
+
+        //A cookie the response sets
+        fn cookie(&self, name: &str) -> Option<String> {
+            self.headers.get_all(header::SET_COOKIE).iter()
+                .filter_map(|val| val.to_str().ok())
+                .filter_map(|val| val.split(';').next())
+                .filter_map(|val| val.split_once('='))
+                .find(|(cookie, _)| *cookie == name)
+                .map(|(_, val)| val.to_string())
+        }
+    }
+
+    async fn send(app: &mut TestApp, from: &str, req: axum::http::request::Builder, body: Body) -> TestResponse {
+        let mut req = req.body(body).unwrap();
+        req.extensions_mut().insert(ConnectInfo(from.parse::<SocketAddr>().unwrap()));
+
+        let resp = app.router.call(req).await.unwrap();
+        let (status, headers) = (resp.status(), resp.headers().clone());
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+
+        TestResponse { status, headers, body: String::from_utf8_lossy(&body).to_string() }
+    }
+
+    async fn get(app: &mut TestApp, from: &str, path: &str, username: Option<&str>) -> (StatusCode, String) {
+        let mut req = Request::builder().uri(path);
+
+        if let Some(username) = username {
+            req = req.header("Remote-User", username);
+        }
+
+        let resp = send(app, from, req, Body::empty()).await;
+        (resp.status, resp.body)
+    }
+
+    async fn with_bearer(app: &mut TestApp, method: Method, path: &str, token: &str) -> StatusCode {
+        let req = Request::builder()
+            .method(method)
+            .uri(path)
+            .header(header::AUTHORIZATION, format!("Bearer {}", token));
+
+        send(app, CLIENT_ADDR, req, Body::empty()).await.status
+    }
+
+    async fn api_login(app: &mut TestApp, from: &str, username: &str, password: &str) -> TestResponse {
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/api/v1/login")
+            .header(header::CONTENT_TYPE, "application/json");
+
+        send(app, from, req, Body::from(json!({"username": username, "password": password}).to_string())).await
+    }
+
+    //Logs in through the API, returning the JWT
+    async fn login_token(app: &mut TestApp, username: &str) -> String {
+        let resp = api_login(app, CLIENT_ADDR, username, PASSWORD).await;
+        assert_eq!(resp.status, StatusCode::OK, "{}", resp.body);
+
+        resp.json()["token"].as_str().unwrap().to_string()
+    }
+
+    async fn form_login(app: &mut TestApp, form: &str) -> TestResponse {
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/login")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded");
+
+        send(app, CLIENT_ADDR, req, Body::from(form.to_string())).await
+    }
+
+    #[tokio::test]
+    async fn viewers_and_operators_only_get_their_routes() {
+        let mut app = test_app(None);
+        let vic = login_token(&mut app, "vic").await;
+        let bob = login_token(&mut app, "bob").await;
+
+        assert_eq!(with_bearer(&mut app, Method::GET, "/api/v1/boosts", &vic).await, StatusCode::OK);
+        assert_eq!(with_bearer(&mut app, Method::POST, "/api/v1/boosts/1/tags", &vic).await, StatusCode::FORBIDDEN);
+        assert_eq!(with_bearer(&mut app, Method::GET, "/api/v1/users", &vic).await, StatusCode::FORBIDDEN);
+        assert_eq!(with_bearer(&mut app, Method::GET, "/settings", &vic).await, StatusCode::FORBIDDEN);
+
+        assert_eq!(with_bearer(&mut app, Method::POST, "/api/v1/boosts/1/tags", &bob).await, StatusCode::OK);
+        assert_eq!(with_bearer(&mut app, Method::GET, "/api/v1/users", &bob).await, StatusCode::FORBIDDEN);
+        assert_eq!(with_bearer(&mut app, Method::POST, "/api/v1/rescan", &bob).await, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn token_scopes_limit_what_it_can_do() {
+        let mut app = test_app(None);
+        let (read, _) = api_tokens::create_token(&app.db, "Reader", &[dbif::ApiScope::Read], None).unwrap();
+        let (settings, _) = api_tokens::create_token(&app.db, "Admin bot", &[dbif::ApiScope::Settings], None).unwrap();
+
+        assert_eq!(with_bearer(&mut app, Method::GET, "/api/v1/boosts", &read).await, StatusCode::OK);
+        assert_eq!(with_bearer(&mut app, Method::POST, "/api/v1/reply", &read).await, StatusCode::FORBIDDEN);
+        assert_eq!(with_bearer(&mut app, Method::POST, "/api/v1/boosts/1/tags", &read).await, StatusCode::FORBIDDEN);
+        assert_eq!(with_bearer(&mut app, Method::GET, "/api/v1/users", &read).await, StatusCode::FORBIDDEN);
+        assert_eq!(with_bearer(&mut app, Method::GET, "/api/v1/users", &settings).await, StatusCode::OK);
+
+        // tokens aren't a login, so whatever their scope they can't manage one
+        for token in [&read, &settings] {
+            assert_eq!(with_bearer(&mut app, Method::POST, "/api/v1/password", token).await, StatusCode::FORBIDDEN);
+            assert_eq!(with_bearer(&mut app, Method::GET, "/api/v1/2fa", token).await, StatusCode::FORBIDDEN);
+            assert_eq!(with_bearer(&mut app, Method::DELETE, "/api/v1/sessions", token).await, StatusCode::FORBIDDEN);
+        }
+    }
+
+    #[tokio::test]
+    async fn revoked_and_expired_tokens_are_refused() {
+        let mut app = test_app(None);
+        let (token, record) = api_tokens::create_token(&app.db, "Reader", &[dbif::ApiScope::Read], Some(1)).unwrap();
+
+        assert_eq!(with_bearer(&mut app, Method::GET, "/api/v1/boosts", &token).await, StatusCode::OK);
+
+        dbif::delete_api_token_from_db(&app.db, record.index).unwrap();
+        assert_eq!(with_bearer(&mut app, Method::GET, "/api/v1/boosts", &token).await, StatusCode::FORBIDDEN);
+
+        let expired = format!("{}expired", api_tokens::TOKEN_PREFIX);
+        let expires = Utc::now().timestamp() - 60;
+        dbif::add_api_token_to_db(&app.db, "Old", &api_tokens::hash_token(&expired), &[dbif::ApiScope::Read], Some(expires)).unwrap();
+        assert_eq!(with_bearer(&mut app, Method::GET, "/api/v1/boosts", &expired).await, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn failed_logins_lock_out_the_address() {
+        let mut app = test_app(None);
+
+        for _ in 0..5 {
+            assert_eq!(api_login(&mut app, CLIENT_ADDR, "alice", "wrong password").await.status, StatusCode::UNAUTHORIZED);
+        }
+
+        // locked out, even with the right password
+        let resp = api_login(&mut app, CLIENT_ADDR, "alice", PASSWORD).await;
+        assert_eq!(resp.status, StatusCode::TOO_MANY_REQUESTS);
+        assert!(resp.headers.contains_key(header::RETRY_AFTER));
+
+        assert_eq!(api_login(&mut app, "10.0.0.2:40000", "alice", PASSWORD).await.status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn two_factor_code_comes_before_the_session_cookie() {
+        let mut app = test_app(None);
+        let enrolment = two_factor::start_enrolment(&app.db, "alice").unwrap();
+        let secret = data_encoding::BASE32_NOPAD.decode(enrolment.secret.as_bytes()).unwrap();
+        let code = totp_rs::TOTP::new(totp_rs::Algorithm::SHA1, 6, 1, 30, secret, None, "alice".to_string()).unwrap().generate_current().unwrap();
+        let recovery_codes = two_factor::confirm_enrolment(&app.db, "alice", &code).unwrap();
+
+        let resp = form_login(&mut app, "username=alice&password=correct+horse").await;
+        assert_eq!(resp.status, StatusCode::OK);
+        assert_eq!(resp.cookie("HELIPAD_JWT"), None);
+
+        let challenge = regex::Regex::new(r#"name="challenge" value="([^"]+)""#).unwrap()
+            .captures(&resp.body).expect("a two-factor challenge")[1].to_string();
+
+        let resp = form_login(&mut app, &format!("challenge={}&code=aaaa-bbbb", challenge)).await;
+        assert_eq!(resp.cookie("HELIPAD_JWT"), None);
+
+        let resp = form_login(&mut app, &format!("challenge={}&code={}", challenge, recovery_codes[0])).await;
+        assert_eq!(resp.status, StatusCode::SEE_OTHER);
+        assert!(resp.cookie("HELIPAD_JWT").is_some());
+
+        // the API doesn't give out a token without a code either
+        let resp = api_login(&mut app, CLIENT_ADDR, "alice", PASSWORD).await;
+        assert_eq!(resp.status, StatusCode::UNAUTHORIZED);
+        assert_eq!(resp.json()["two_factor_required"], true);
+        assert!(resp.json()["token"].is_null());
+    }
+
+    #[tokio::test]
+    async fn revoked_and_expired_share_links_are_gone() {
+        let mut app = test_app(None);
+        let (token, link) = share_links::create_link(&app.db, "Overlay", dbif::ShareView::Boosts, None, None, Some(1)).unwrap();
+        let path = format!("/share/{}/boosts", token);
+
+        assert_eq!(get(&mut app, CLIENT_ADDR, &path, None).await.0, StatusCode::OK);
+
+        dbif::delete_share_link_from_db(&app.db, link.index).unwrap();
+        assert_eq!(get(&mut app, CLIENT_ADDR, &path, None).await.0, StatusCode::NOT_FOUND);
+
+        let expires = Utc::now().timestamp() - 60;
+        let link = dbif::add_share_link_to_db(&app.db, "Old", dbif::ShareView::Boosts, None, None, expires).unwrap();
+        let token = share_links::link_token(&share_links::secret(&app.db).unwrap(), &link).unwrap();
+        assert_eq!(get(&mut app, CLIENT_ADDR, &format!("/share/{}/boosts", token), None).await.0, StatusCode::NOT_FOUND);
+
+        // a link for another view doesn't work here
+        let (token, _) = share_links::create_link(&app.db, "Live", dbif::ShareView::Websocket, None, None, Some(1)).unwrap();
+        assert_eq!(get(&mut app, CLIENT_ADDR, &format!("/share/{}/boosts", token), None).await.0, StatusCode::NOT_FOUND);
+    }
+
+    async fn cookie_post(app: &mut TestApp, jwt: &str, csrf_cookie: &str, sent: Option<&str>) -> StatusCode {
+        let mut req = Request::builder()
+            .method(Method::POST)
+            .uri("/api/v1/boosts/1/tags")
+            .header(header::COOKIE, format!("HELIPAD_JWT={}; {}={}", jwt, csrf::COOKIE_NAME, csrf_cookie));
+
+        if let Some(sent) = sent {
+            req = req.header(csrf::HEADER_NAME, sent);
+        }
+
+        send(app, CLIENT_ADDR, req, Body::empty()).await.status
+    }
+
+    #[tokio::test]
+    async fn cookie_logins_need_their_own_csrf_token() {
+        let mut app = test_app(None);
+
+        let alice = form_login(&mut app, "username=alice&password=correct+horse").await;
+        let (jwt, csrf) = (alice.cookie("HELIPAD_JWT").unwrap(), alice.cookie(csrf::COOKIE_NAME).unwrap());
+
+        let other = form_login(&mut app, "username=alice&password=correct+horse").await;
+        let other_csrf = other.cookie(csrf::COOKIE_NAME).unwrap();
+
+        assert_eq!(cookie_post(&mut app, &jwt, &csrf, None).await, StatusCode::FORBIDDEN);
+        assert_eq!(cookie_post(&mut app, &jwt, &csrf, Some("nonce.signature")).await, StatusCode::FORBIDDEN);
+        assert_eq!(cookie_post(&mut app, &jwt, &csrf, Some(&other_csrf)).await, StatusCode::FORBIDDEN);
+
+        // another session's token doesn't work with this session's cookie, even in both places
+        assert_eq!(cookie_post(&mut app, &jwt, &other_csrf, Some(&other_csrf)).await, StatusCode::FORBIDDEN);
+
+        assert_eq!(cookie_post(&mut app, &jwt, &csrf, Some(&csrf)).await, StatusCode::OK);
+
+        // plain HTML forms send it as a field
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/api/v1/boosts/1/tags")
+            .header(header::COOKIE, format!("HELIPAD_JWT={}; {}={}", jwt, csrf::COOKIE_NAME, csrf))
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded");
+        let body = Body::from(format!("{}={}", csrf::FORM_FIELD, csrf));
+        assert_eq!(send(&mut app, CLIENT_ADDR, req, body).await.status, StatusCode::OK);
+    }
+
+    //The session id in a JWT
+    fn jwt_session(jwt: &str) -> String {
+        let claims = data_encoding::BASE64URL_NOPAD.decode(jwt.split('.').nth(1).unwrap().as_bytes()).unwrap();
+        serde_json::from_slice::<serde_json::Value>(&claims).unwrap()["jti"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn logged_out_sessions_stop_working() {
+        let mut app = test_app(None);
+        let first = login_token(&mut app, "alice").await;
+        let second = login_token(&mut app, "alice").await;
+
+        assert_eq!(with_bearer(&mut app, Method::GET, "/api/v1/boosts", &first).await, StatusCode::OK);
+
+        let jti = jwt_session(&first);
+        let session = dbif::get_sessions_from_db(&app.db, "alice").unwrap().into_iter().find(|session| session.jti == jti).unwrap();
+        assert!(sessions::end_session(&app.db, "alice", session.index).unwrap());
+
+        assert_eq!(with_bearer(&mut app, Method::GET, "/api/v1/boosts", &first).await, StatusCode::FORBIDDEN);
+        assert_eq!(with_bearer(&mut app, Method::GET, "/api/v1/boosts", &second).await, StatusCode::OK);
+
+        sessions::end_sessions(&app.db, "alice", None).unwrap();
+        assert_eq!(with_bearer(&mut app, Method::GET, "/api/v1/boosts", &second).await, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn proxy_header_logs_in_account() {
+        let mut app = proxied_app(None);
+
+        assert_eq!(get(&mut app, PROXY_ADDR, "/", Some("alice")).await, (StatusCode::OK, "alice admin".to_string()));
+        assert_eq!(get(&mut app, PROXY_ADDR, "/", Some("BOB")).await, (StatusCode::OK, "bob operator".to_string()));
+        assert_eq!(get(&mut app, PROXY_ADDR, "/", None).await.0, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn proxy_header_from_elsewhere_is_ignored() {
+        let mut app = proxied_app(Some("viewer"));
+
+        assert_eq!(get(&mut app, "10.0.0.5:40000", "/", Some("alice")).await.0, StatusCode::FORBIDDEN);
+        assert_eq!(get(&mut app, "[::ffff:10.0.0.5]:40000", "/", Some("alice")).await.0, StatusCode::FORBIDDEN);
+        assert_eq!(get(&mut app, "[::1]:40000", "/", Some("alice")).await.0, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn proxy_ipv4_mapped_address_is_trusted() {
+        let mut app = proxied_app(None);
+
+        assert_eq!(get(&mut app, "[::ffff:127.0.0.1]:40000", "/", Some("alice")).await, (StatusCode::OK, "alice admin".to_string()));
+    }
+
+    #[tokio::test]
+    async fn proxy_user_without_account_gets_default_role() {
+        let mut app = proxied_app(Some("viewer"));
+
+        assert_eq!(get(&mut app, PROXY_ADDR, "/", Some("carol")).await, (StatusCode::OK, "carol viewer".to_string()));
+        assert_eq!(get(&mut app, PROXY_ADDR, "/", Some("bob")).await, (StatusCode::OK, "bob operator".to_string())); // account role wins
+        assert_eq!(get(&mut app, PROXY_ADDR, "/settings/users", Some("carol")).await.0, StatusCode::FORBIDDEN);
+
+        let mut app = proxied_app(None);
+
+        assert_eq!(get(&mut app, PROXY_ADDR, "/", Some("carol")).await.0, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn proxy_turns_off_password_two_factor_and_session_pages() {
+        let mut app = proxied_app(None);
+
+        for path in ["/password", "/api/v1/password", "/settings/2fa", "/settings/2fa/setup", "/api/v1/2fa", "/settings/sessions",
+            "/settings/sessions/3", "/api/v1/sessions"] {
+            assert_eq!(get(&mut app, PROXY_ADDR, path, Some("alice")).await.0, StatusCode::NOT_FOUND, "{}", path);
+        }
+
+        assert_eq!(get(&mut app, PROXY_ADDR, "/settings/users", Some("alice")).await.0, StatusCode::OK);
+        assert_eq!(get(&mut app, PROXY_ADDR, "/passwords", Some("alice")).await.0, StatusCode::OK);
+    }
+
+    #[test]
+    fn own_login_paths() {
+        assert!(manages_own_login("/password"));
+        assert!(manages_own_login("/api/v1/sessions/7"));
+        assert!(manages_own_login("/settings/2fa"));
+        assert!(!manages_own_login("/settings/2factor"));
+        assert!(!manages_own_login("/login"));
+        assert!(!manages_own_login("/settings/users"));
+    }
+}
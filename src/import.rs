@@ -0,0 +1,264 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, NaiveDateTime};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use dbif::{ActionType, BoostRecord, ImportBatch, ListType, PaymentRecord};
+
+/// Where the boosts being imported come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Helipad, // another Helipad database
+    Csv, // Helipad's CSV export, or any CSV with a column mapping
+    Json, // an array of objects, with a column mapping if needed
+}
+
+impl ImportFormat {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "helipad" | "db" | "sqlite" => Ok(ImportFormat::Helipad),
+            "csv" => Ok(ImportFormat::Csv),
+            "json" => Ok(ImportFormat::Json),
+            _ => bail!("Unknown import format: {}", name),
+        }
+    }
+
+    /// Guesses the format from the file name, defaulting to a Helipad database
+    pub fn from_filename(filename: &str) -> Self {
+        match Path::new(filename).extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_lowercase()) {
+            Some(ext) if ext == "csv" => ImportFormat::Csv,
+            Some(ext) if ext == "json" => ImportFormat::Json,
+            _ => ImportFormat::Helipad,
+        }
+    }
+}
+
+/// Which source columns hold which boost fields. Fields that aren't mapped are read from the column
+/// of the same name, so Helipad's own CSV export needs no mapping at all.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ImportMapping {
+    #[serde(default)]
+    pub columns: HashMap<String, String>, // boost field => source column
+    #[serde(default)]
+    pub list: Option<String>, // "boost", "stream" or "sent" for rows without a type column
+}
+
+impl ImportMapping {
+    /// Reads a mapping given either as JSON or as the path to a JSON file
+    pub fn load(mapping: &str) -> Result<Self> {
+        let text = if mapping.trim_start().starts_with('{') {
+            mapping.to_string()
+        } else {
+            std::fs::read_to_string(mapping).with_context(|| format!("Unable to read column mapping {}", mapping))?
+        };
+
+        serde_json::from_str(&text).context("Invalid column mapping")
+    }
+
+    fn column<'a>(&'a self, field: &'a str) -> &'a str {
+        self.columns.get(field).map(|column| column.as_str()).unwrap_or(field)
+    }
+}
+
+/// Reads the boosts to import out of a file
+pub fn read_import_file(path: &str, format: ImportFormat, mapping: &ImportMapping) -> Result<ImportBatch> {
+    match format {
+        ImportFormat::Helipad => dbif::read_helipad_database(path).map_err(|e| anyhow!("Unable to read Helipad database: {}", e)),
+        ImportFormat::Csv => read_csv(path, mapping),
+        ImportFormat::Json => read_json(path, mapping),
+    }
+}
+
+fn read_csv(path: &str, mapping: &ImportMapping) -> Result<ImportBatch> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_path(path)
+        .with_context(|| format!("Unable to open {}", path))?;
+
+    let headers = reader.headers().context("Unable to read CSV header")?.clone();
+    let mut batch = ImportBatch::default();
+
+    for (line, record) in reader.records().enumerate() {
+        let record = record.with_context(|| format!("Unable to read CSV row {}", line + 1))?;
+
+        let row: HashMap<&str, String> = headers.iter()
+            .zip(record.iter())
+            .map(|(header, value)| (header.trim(), value.to_string()))
+            .collect();
+
+        let boost = boost_from_fields(&row, mapping).with_context(|| format!("CSV row {}", line + 1))?;
+        batch.boosts.push(boost);
+    }
+
+    Ok(batch)
+}
+
+fn read_json(path: &str, mapping: &ImportMapping) -> Result<ImportBatch> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("Unable to open {}", path))?;
+    let json: Value = serde_json::from_str(&text).context("Invalid JSON")?;
+
+    //Either a plain array or an object wrapping one, like the query API returns
+    let items = match &json {
+        Value::Array(items) => items,
+        Value::Object(object) => match object.values().find_map(|value| value.as_array()) {
+            Some(items) => items,
+            None => bail!("JSON does not contain a list of boosts"),
+        },
+        _ => bail!("JSON does not contain a list of boosts"),
+    };
+
+    let mut batch = ImportBatch::default();
+
+    for (number, item) in items.iter().enumerate() {
+        let object = item.as_object().ok_or_else(|| anyhow!("JSON item {} is not an object", number + 1))?;
+
+        let row: HashMap<&str, String> = object.iter()
+            .filter_map(|(key, value)| {
+                let value = match value {
+                    Value::Null => return None,
+                    Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                };
+                Some((key.as_str(), value))
+            })
+            .collect();
+
+        let boost = boost_from_fields(&row, mapping).with_context(|| format!("JSON item {}", number + 1))?;
+        batch.boosts.push(boost);
+    }
+
+    Ok(batch)
+}
+
+/// Accepts unix seconds or milliseconds, RFC 3339, or the UTC time format of Helipad's CSV export
+fn parse_time(value: &str) -> Result<i64> {
+    if let Ok(number) = value.parse::<i64>() {
+        return Ok(if number > 100_000_000_000 { number / 1000 } else { number });
+    }
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.timestamp());
+    }
+
+    match NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S") {
+        Ok(time) => Ok(time.and_utc().timestamp()),
+        Err(_) => bail!("Unrecognized time: {}", value),
+    }
+}
+
+fn parse_msat(row: &HashMap<&str, String>, mapping: &ImportMapping, msat_field: &str, sat_field: &str) -> Result<Option<i64>> {
+    let field = |name| row.get(mapping.column(name)).map(|value| value.trim()).filter(|value| !value.is_empty());
+
+    if let Some(msat) = field(msat_field) {
+        return Ok(Some(msat.parse::<f64>().with_context(|| format!("Invalid {}: {}", msat_field, msat))? as i64));
+    }
+
+    if let Some(sat) = field(sat_field) {
+        return Ok(Some((sat.parse::<f64>().with_context(|| format!("Invalid {}: {}", sat_field, sat))? * 1000.0) as i64));
+    }
+
+    Ok(None)
+}
+
+fn boost_from_fields(row: &HashMap<&str, String>, mapping: &ImportMapping) -> Result<BoostRecord> {
+    let field = |name| row.get(mapping.column(name)).map(|value| value.trim().to_string()).filter(|value| !value.is_empty());
+    let text = |name| field(name).unwrap_or_default();
+
+    let time = parse_time(&field("time").ok_or_else(|| anyhow!("Missing time"))?)?;
+
+    let value_msat = parse_msat(row, mapping, "value_msat", "value_sat")?;
+    let value_msat_total = parse_msat(row, mapping, "value_msat_total", "value_sat_total")?;
+
+    let (value_msat, value_msat_total) = match (value_msat, value_msat_total) {
+        (Some(value), Some(total)) => (value, total),
+        (Some(value), None) => (value, value),
+        (None, Some(total)) => (total, total),
+        (None, None) => bail!("Missing value"),
+    };
+
+    let action = match field("action") {
+        Some(action) => match action.parse::<u8>() {
+            Ok(number) => ActionType::from_u8(number),
+            Err(_) => ActionType::from_str(&action),
+        },
+        None => ActionType::Boost,
+    };
+
+    let list_type = match field("type").or_else(|| mapping.list.clone()) {
+        Some(list) => match list.to_lowercase().as_str() {
+            "boost" | "boosts" => ListType::Boost,
+            "stream" | "streams" => ListType::Stream,
+            "sent" => ListType::Sent,
+            _ => bail!("Unknown type: {}", list),
+        },
+        None if action == ActionType::Stream => ListType::Stream,
+        None => ListType::Boost,
+    };
+
+    //Boosts with a uuid are matched on it when checking for duplicates
+    let tlv = match (field("tlv"), field("uuid")) {
+        (Some(tlv), _) => tlv,
+        (None, Some(uuid)) => serde_json::json!({ "uuid": uuid }).to_string(),
+        (None, None) => "".to_string(),
+    };
+
+    let custom_key = match field("custom_key") {
+        Some(key) => Some(key.parse::<u64>().with_context(|| format!("Invalid custom_key: {}", key))?),
+        None => None,
+    };
+
+    let payment_info = (list_type == ListType::Sent).then(|| PaymentRecord {
+        payment_hash: text("payment_hash"),
+        pubkey: text("pubkey"),
+        custom_key: custom_key.unwrap_or_default(),
+        custom_value: text("custom_value"),
+        fee_msat: field("fee_msat").and_then(|fee| fee.parse().ok()).unwrap_or_default(),
+        reply_to_idx: None,
+    });
+
+    Ok(BoostRecord {
+        index: field("index").and_then(|index| index.parse().ok()).unwrap_or_default(),
+        time,
+        value_msat,
+        value_msat_total,
+        action,
+        list_type,
+        sender: text("sender"),
+        app: text("app"),
+        message: text("message"),
+        podcast: text("podcast"),
+        episode: text("episode"),
+        tlv,
+        remote_podcast: field("remote_podcast"),
+        remote_episode: field("remote_episode"),
+        reply_sent: false,
+        custom_key,
+        custom_value: field("custom_value"),
+        memo: field("memo"),
+        payment_info,
+        hidden: false,
+        quarantined: false,
+        imported: false,
+        nostr_refs: Vec::new(),
+        numerology: Vec::new(),
+        tags: Vec::new(),
+        note: None,
+        pinned: false,
+        supporter: None,
+    })
+}
+
+/// Prints an import report for the command line
+pub fn print_report(report: &dbif::ImportReport) {
+    println!("\nImport {}:", if report.dry_run { "dry run complete, nothing was changed" } else { "complete" });
+    println!(" - Boosts added: {}", report.boosts_added);
+    println!(" - Streams added: {}", report.streams_added);
+    println!(" - Sent boosts added: {}", report.sent_boosts_added);
+    println!(" - Stream rollups added: {}", report.rollups_added);
+    println!(" - Duplicates skipped: {}", report.duplicates);
+
+    for boost in &report.preview {
+        println!("   {} {}: {} msat, [{}] from [{}]: {}", boost.list_type, boost.time, boost.value_msat_total, boost.podcast, boost.sender, boost.message);
+    }
+}
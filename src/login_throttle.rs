@@ -0,0 +1,134 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Failed logins from one address before it's locked out
+const IP_MAX_FAILURES: u32 = 5;
+
+/// Failed logins from everywhere before all logins are locked out
+const GLOBAL_MAX_FAILURES: u32 = 50;
+
+/// First lockout lengths in seconds. Each lockout in a row doubles them.
+const IP_LOCKOUT_SECS: i64 = 30;
+const GLOBAL_LOCKOUT_SECS: i64 = 60;
+
+/// Longest a lockout can be
+const MAX_LOCKOUT_SECS: i64 = 3600;
+
+/// Failures and lockouts are forgotten after this long without any
+const FAILURE_WINDOW_SECS: i64 = 900;
+
+/// Addresses kept before forgetting the stale ones
+const MAX_TRACKED_IPS: usize = 1000;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LockoutScope {
+    Ip,
+    Global,
+}
+
+/// A lockout that's just started. Sent to lockout triggers as is.
+#[derive(Serialize, Clone, Debug)]
+pub struct Lockout {
+    pub event: &'static str,
+    pub scope: LockoutScope,
+    pub ip: String,
+    pub username: Option<String>,
+    pub lockout_number: u32,
+    pub locked_seconds: i64,
+    pub locked_until: i64,
+}
+
+#[derive(Default, Debug)]
+struct Counter {
+    failures: u32,
+    lockouts: u32,
+    last_failure: i64,
+    locked_until: i64,
+}
+
+impl Counter {
+    fn retry_after(&self, now: i64) -> Option<i64> {
+        (self.locked_until > now).then(|| self.locked_until - now)
+    }
+
+    fn is_stale(&self, now: i64) -> bool {
+        now - self.last_failure.max(self.locked_until) > FAILURE_WINDOW_SECS
+    }
+
+    /// Counts a failure, returning how long to lock out for if that was one too many
+    fn fail(&mut self, now: i64, max_failures: u32, lockout_secs: i64) -> Option<i64> {
+        if self.is_stale(now) {
+            *self = Counter::default();
+        }
+
+        self.last_failure = now;
+        self.failures += 1;
+
+        if self.failures < max_failures {
+            return None;
+        }
+
+        let secs = lockout_secs.saturating_mul(1 << self.lockouts.min(16)).min(MAX_LOCKOUT_SECS);
+
+        self.failures = 0;
+        self.lockouts += 1;
+        self.locked_until = now + secs;
+
+        Some(secs)
+    }
+}
+
+/// Counts failed logins per address and overall, locking logins out for longer each time there are too many
+#[derive(Default, Debug)]
+pub struct LoginThrottle {
+    ips: HashMap<String, Counter>,
+    global: Counter,
+}
+
+impl LoginThrottle {
+    /// Seconds until logins from this address are allowed again, if they're locked out
+    pub fn retry_after(&self, ip: &str, now: i64) -> Option<i64> {
+        let ip_retry = self.ips.get(ip).and_then(|counter| counter.retry_after(now));
+        let global_retry = self.global.retry_after(now);
+
+        ip_retry.max(global_retry)
+    }
+
+    /// Counts a failed login, returning any lockouts it started
+    pub fn fail(&mut self, ip: &str, username: Option<&str>, now: i64) -> Vec<Lockout> {
+        if self.ips.len() >= MAX_TRACKED_IPS {
+            self.ips.retain(|_, counter| !counter.is_stale(now));
+        }
+
+        let ip_counter = self.ips.entry(ip.to_string()).or_default();
+
+        let mut lockouts = Vec::new();
+
+        let started = [
+            (LockoutScope::Ip, ip_counter.fail(now, IP_MAX_FAILURES, IP_LOCKOUT_SECS), ip_counter.lockouts),
+            (LockoutScope::Global, self.global.fail(now, GLOBAL_MAX_FAILURES, GLOBAL_LOCKOUT_SECS), self.global.lockouts),
+        ];
+
+        for (scope, secs, lockout_number) in started.iter() {
+            if let Some(secs) = secs {
+                lockouts.push(Lockout {
+                    event: "lockout",
+                    scope: *scope,
+                    ip: ip.to_string(),
+                    username: username.map(|username| username.to_string()),
+                    lockout_number: *lockout_number,
+                    locked_seconds: *secs,
+                    locked_until: now + secs,
+                });
+            }
+        }
+
+        lockouts
+    }
+
+    /// Forgets the failures from an address after it logs in
+    pub fn succeed(&mut self, ip: &str) {
+        self.ips.remove(ip);
+    }
+}
@@ -5,7 +5,7 @@ use axum::{
     middleware,
     routing::{get, post, delete, patch, any},
     Router,
-    extract::{State, ws::WebSocket, ws::WebSocketUpgrade},
+    extract::{DefaultBodyLimit, State, ws::WebSocket, ws::WebSocketUpgrade},
     response::Response,
 };
 
@@ -20,9 +20,10 @@ use serde::Serialize;
 use std::env;
 use std::fs;
 use std::path::Path;
+use std::net::SocketAddr;
 
 use std::sync::Arc;
-use tokio::sync::{broadcast, RwLock};
+use tokio::sync::{broadcast, Mutex, RwLock};
 
 #[macro_use]
 extern crate configure_me;
@@ -35,15 +36,35 @@ mod lightning;
 mod podcastindex;
 mod lnaddress;
 mod metadata;
+mod moderation;
+mod nostr;
+mod numerology;
+mod backup;
 mod boost;
 mod deserializers;
 mod triggers;
+mod users;
+mod api_tokens;
+mod password;
+mod login_throttle;
+mod share_links;
+mod two_factor;
+mod csrf;
+mod trusted_proxy;
+mod sessions;
 mod poller;
+mod import;
+mod rescan;
+mod retention;
+mod shows;
 
 const HELIPAD_CONFIG_FILE: &str = "./helipad.conf";
 const HELIPAD_DATABASE_DIR: &str = "database.db";
 const HELIPAD_SOUND_DIR: &str = "./sounds";
 const HELIPAD_STANDARD_PORT: &str = "2112";
+const HELIPAD_BACKUP_INTERVAL: u64 = 24;
+const HELIPAD_BACKUP_KEEP: usize = 7;
+const IMPORT_UPLOAD_LIMIT: usize = 512 * 1024 * 1024;
 
 const LND_STANDARD_GRPC_URL: &str = "https://127.0.0.1:10009";
 const LND_STANDARD_MACAROON_LOCATION: &str = "/lnd/data/chain/bitcoin/mainnet/admin.macaroon";
@@ -63,6 +84,9 @@ pub struct AppState {
     pub version: String,
     pub ws_tx: Arc<broadcast::Sender<WebSocketEvent>>,
     pub settings: Arc<RwLock<dbif::SettingsRecord>>,
+    pub auth: Arc<RwLock<password::AuthSecrets>>,
+    pub login_throttle: Arc<Mutex<login_throttle::LoginThrottle>>,
+    pub metadata_providers: Arc<metadata::ProviderCache>,
 }
 
 #[derive(Clone, Debug)]
@@ -73,8 +97,13 @@ pub struct HelipadConfig {
     pub macaroon_path: String,
     pub cert_path: String,
     pub node_address: String,
-    pub password: String,
-    pub secret: String,
+    pub backup_dir: String,
+    pub backup_interval: u64,
+    pub backup_keep: usize,
+    pub backup_sounds: bool,
+    pub stream_retention_days: u64,
+    pub secure_cookies: bool,
+    pub trusted_proxy: Option<trusted_proxy::TrustedProxy>,
 }
 
 
@@ -98,8 +127,13 @@ async fn main() {
         macaroon_path: "".to_string(),
         cert_path: "".to_string(),
         node_address: "".to_string(),
-        password: "".to_string(),
-        secret: "".to_string(),
+        backup_dir: "".to_string(),
+        backup_interval: HELIPAD_BACKUP_INTERVAL,
+        backup_keep: HELIPAD_BACKUP_KEEP,
+        backup_sounds: false,
+        stream_retention_days: 0,
+        secure_cookies: false,
+        trusted_proxy: None,
     };
 
     //Bring in the configuration info
@@ -116,6 +150,7 @@ async fn main() {
     println!("\nDiscovering listen port...");
     let mut listen_port = String::from(HELIPAD_STANDARD_PORT);
     let args: Vec<String> = env::args().collect();
+    let reset_password = args.get(1).is_some_and(|arg| arg == "reset-password");
     let env_listen_port = std::env::var("HELIPAD_LISTEN_PORT");
     //First try from the environment
     if env_listen_port.is_ok() {
@@ -125,7 +160,7 @@ async fn main() {
         //If that fails, try from the config file
         listen_port = server_config.listen_port.unwrap().to_string();
         println!(" - Using config file({}): [{}]", HELIPAD_CONFIG_FILE, listen_port);
-    } else if let Some(arg_port) = args.get(1) {
+    } else if let Some(arg_port) = args.get(1).filter(|arg| !arg.starts_with('-') && !reset_password) {
         //If that fails, try from the command line
         arg_port.clone_into(&mut listen_port);
         println!(" - Using arg from command line: [{}]", listen_port);
@@ -153,6 +188,22 @@ async fn main() {
             println!(" - Nothing else found. Using default: [{}]", helipad_config.database_file_path);
         }
     }
+    //Only report on pending database migrations if asked to
+    if server_config.migrate_dry_run {
+        match dbif::migrate_database(&helipad_config.database_file_path, true) {
+            Ok(pending) => {
+                for migration in pending {
+                    println!(" - Pending migration {}: {}", migration.version, migration.description);
+                }
+                std::process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("Database error: {:#?}", e);
+                std::process::exit(3);
+            }
+        }
+    }
+
     //Create the database file
     match dbif::create_database(&helipad_config.database_file_path) {
         Ok(_) => {
@@ -164,6 +215,19 @@ async fn main() {
         }
     }
 
+    //Set a new password instead of starting up if asked to
+    if reset_password {
+        println!("\nResetting password...");
+
+        match password::reset_password(&helipad_config.database_file_path, args.get(2).map(|arg| arg.as_str())) {
+            Ok(_) => std::process::exit(0),
+            Err(e) => {
+                eprintln!("Reset password error: {:#}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     //SOUND DIR
     //Get the directory to store boost sounds in
     println!("\nDiscovering sound directory...");
@@ -186,27 +250,139 @@ async fn main() {
         }
     }
 
+    //Restore a backup instead of starting up if asked to
+    if let Some(restore_path) = &server_config.restore {
+        println!("\nRestoring backup: [{}]", restore_path);
+
+        match backup::restore_backup(restore_path, &helipad_config.database_file_path, &helipad_config.sound_path) {
+            Ok(_) => {
+                println!("Restore complete.");
+                std::process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("Restore error: {:#}", e);
+                std::process::exit(3);
+            }
+        }
+    }
+
+    //Import boosts from another source instead of starting up if asked to
+    if let Some(import_path) = &server_config.import {
+        println!("\nImporting boosts from: [{}]", import_path);
+
+        let (import_format, import_mapping) = (&server_config.import_format, &server_config.import_mapping);
+        let dry_run = server_config.import_dry_run;
+
+        let result = (|| {
+            let format = match import_format {
+                Some(format) => import::ImportFormat::from_name(format)?,
+                None => import::ImportFormat::from_filename(import_path),
+            };
+
+            let mapping = match import_mapping {
+                Some(mapping) => import::ImportMapping::load(mapping)?,
+                None => import::ImportMapping::default(),
+            };
+
+            let batch = import::read_import_file(import_path, format, &mapping)?;
+
+            dbif::import_boosts_to_db(&helipad_config.database_file_path, batch, dry_run)
+                .map_err(|e| anyhow::anyhow!("Unable to save imported boosts: {}", e))
+        })();
+
+        match result {
+            Ok(report) => {
+                import::print_report(&report);
+                std::process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("Import error: {:#}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    //BACKUPS -----
+    //Get where and how often to take scheduled backups
+    if let Ok(backup_dir) = std::env::var("HELIPAD_BACKUP_DIR") {
+        helipad_config.backup_dir = backup_dir;
+        println!("Using backup directory from environment var(HELIPAD_BACKUP_DIR): [{}]", helipad_config.backup_dir);
+    }
+    else if let Some(backup_dir) = &server_config.backup_dir {
+        helipad_config.backup_dir.clone_from(backup_dir);
+        println!("Using backup directory from config file({}): [{}]", HELIPAD_CONFIG_FILE, helipad_config.backup_dir);
+    }
+
+    if let Some(interval) = std::env::var("HELIPAD_BACKUP_INTERVAL").ok().and_then(|val| val.parse().ok()).or(server_config.backup_interval) {
+        helipad_config.backup_interval = interval;
+    }
+
+    if let Some(keep) = std::env::var("HELIPAD_BACKUP_KEEP").ok().and_then(|val| val.parse().ok()).or(server_config.backup_keep) {
+        helipad_config.backup_keep = keep;
+    }
+
+    helipad_config.backup_sounds = server_config.backup_sounds || std::env::var("HELIPAD_BACKUP_SOUNDS").is_ok_and(|val| val == "true");
+
+    //RETENTION -----
+    //Get how long to keep individual streams for. Zero keeps them forever.
+    if let Some(days) = std::env::var("HELIPAD_STREAM_RETENTION_DAYS").ok().and_then(|val| val.parse().ok()).or(server_config.stream_retention_days) {
+        helipad_config.stream_retention_days = days;
+    }
+
+    //COOKIES -----
+    //Only send login cookies over HTTPS if Helipad is served over TLS
+    helipad_config.secure_cookies = server_config.secure_cookies || std::env::var("HELIPAD_SECURE_COOKIES").is_ok_and(|val| val == "true");
+
+    if helipad_config.secure_cookies {
+        println!("Login cookies will only be sent over HTTPS");
+    }
+
+    //TRUSTED PROXY -----
+    //Let an authenticating proxy in front of Helipad say who is logged in
+    let proxy_header = std::env::var("HELIPAD_TRUSTED_PROXY_HEADER").ok().or(server_config.trusted_proxy_header.clone());
+    let proxy_ips = std::env::var("HELIPAD_TRUSTED_PROXY_IPS").ok().or(server_config.trusted_proxy_ips.clone());
+    let proxy_role = std::env::var("HELIPAD_TRUSTED_PROXY_ROLE").ok().or(server_config.trusted_proxy_role.clone());
+
+    match trusted_proxy::TrustedProxy::from_config(proxy_header.as_deref(), proxy_ips.as_deref(), proxy_role.as_deref()) {
+        Ok(Some(proxy)) => {
+            println!("Trusting the {} header from proxy: {:?}. Password logins are off.", proxy.header, proxy.ips);
+            helipad_config.trusted_proxy = Some(proxy);
+        }
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("Trusted proxy error: {:#}", e);
+            std::process::exit(1);
+        }
+    }
+
     //PASSWORD -----
-    //Get the configured password for Helipad
+    //Get the configured password for Helipad. Only a hash of it is kept.
+    let mut configured_password = None;
     let env_password = std::env::var("HELIPAD_PASSWORD");
     if env_password.is_ok() {
-        helipad_config.password = env_password.unwrap();
+        configured_password = env_password.ok();
         println!("Found password in environment var(HELIPAD_PASSWORD)");
     } else if server_config.password.is_some() {
-        helipad_config.password = server_config.password.unwrap();
+        configured_password = server_config.password.clone();
         println!("Found password in config file({})", HELIPAD_CONFIG_FILE);
     }
 
-    //Get or generate secret for JWT if password set
-    if !helipad_config.password.is_empty() {
-        helipad_config.secret = match dbif::get_or_create_jwt_secret(&helipad_config.database_file_path) {
-            Ok(secret) => secret,
-            Err(e) => {
-                eprintln!("Warning: Failed to get JWT secret from database: {}", e);
-                return;
-            }
-        };
-    }
+    let password_hash = match password::load_password(&helipad_config.database_file_path, configured_password.as_deref()) {
+        Ok(hash) => hash,
+        Err(e) => {
+            eprintln!("Warning: Failed to load password: {:#}", e);
+            return;
+        }
+    };
+
+    //Get or generate secret for JWT. User accounts can turn on logins even without a password.
+    let jwt_secret = match dbif::get_or_create_jwt_secret(&helipad_config.database_file_path) {
+        Ok(secret) => secret,
+        Err(e) => {
+            eprintln!("Warning: Failed to get JWT secret from database: {}", e);
+            return;
+        }
+    };
 
     //Get the macaroon and cert files.  Look in the local directory first as an override.
     //If the files are not found in the currect working directory, look for them at their
@@ -275,9 +451,38 @@ async fn main() {
                 show_lightning_invoices: true,
                 fetch_metadata: true,
                 metadata_whitelist: "".to_string(),
+                nostr_relays: dbif::DEFAULT_NOSTR_RELAYS.to_string(),
             }
         }
     };
+
+    let metadata_providers = Arc::new(metadata::ProviderCache::default());
+
+    //Rescan LND instead of starting up if asked to
+    if server_config.rescan || server_config.rescan_dry_run {
+        let options = rescan::RescanOptions {
+            from_index: server_config.rescan_from.unwrap_or(0),
+            to_index: server_config.rescan_to,
+            invoices: true,
+            payments: true,
+            dry_run: server_config.rescan_dry_run,
+        };
+
+        println!("\nRescanning LND from index {}...", options.from_index);
+        let mut lightning = lightning::connect_lnd_or_exit(&helipad_config.node_address, &helipad_config.cert_path, &helipad_config.macaroon_path).await;
+
+        match rescan::rescan(&mut lightning, &helipad_config.database_file_path, &initial_settings, &metadata_providers, &options).await {
+            Ok(report) => {
+                rescan::print_report(&report);
+                std::process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("Rescan error: {:#}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
     let shared_settings = Arc::new(RwLock::new(initial_settings));
 
     //App State
@@ -286,12 +491,21 @@ async fn main() {
         version: version.to_string(),
         ws_tx: Arc::new(broadcast::Sender::new(100)),
         settings: shared_settings.clone(),
+        auth: Arc::new(RwLock::new(password::AuthSecrets { password_hash, jwt_secret })),
+        login_throttle: Arc::new(Mutex::new(login_throttle::LoginThrottle::default())),
+        metadata_providers: metadata_providers.clone(),
     };
 
     //Start the LND polling thread.  This thread will poll LND every few seconds to
     //get the latest invoices and store them in the database.
-    tokio::spawn(poller::lnd_poller(helipad_config.clone(), state.ws_tx.clone()));
-    tokio::spawn(poller::lnd_subscribe_invoices(helipad_config.clone(), state.ws_tx.clone(), shared_settings.clone()));
+    tokio::spawn(poller::lnd_poller(helipad_config.clone(), state.ws_tx.clone(), shared_settings.clone()));
+    tokio::spawn(poller::lnd_subscribe_invoices(helipad_config.clone(), state.ws_tx.clone(), shared_settings.clone(), metadata_providers));
+
+    //Start the scheduled backups if a backup directory is set
+    tokio::spawn(backup::backup_scheduler(helipad_config.clone()));
+
+    //Start collapsing old streams if a retention period is set
+    tokio::spawn(retention::retention_scheduler(helipad_config.clone()));
 
     // Api routes
 
@@ -302,6 +516,7 @@ async fn main() {
         .route("/streams", get(handler::streams))
         .route("/sent", get(handler::sent))
         .route("/settings", get(handler::settings))
+        .route("/password", get(handler::password).post(handler::handle_password))
         .route("/numerology.json", get(handler::numerology_json))
 
         .route("/settings/general", get(handler::general_settings_load))
@@ -322,6 +537,49 @@ async fn main() {
         .route("/settings/triggers/{idx}", delete(handler::trigger_settings_delete))
         .route("/settings/triggers/{idx}/test", post(handler::trigger_settings_test))
 
+        .route("/settings/metadata", get(handler::metadata_provider_settings_list))
+        .route("/settings/metadata/{idx}", get(handler::metadata_provider_settings_load))
+        .route("/settings/metadata/{idx}", post(handler::metadata_provider_settings_save))
+        .route("/settings/metadata/{idx}", delete(handler::metadata_provider_settings_delete))
+
+        .route("/settings/moderation", get(handler::moderation_settings_list))
+        .route("/settings/moderation/hidden", get(handler::moderation_hidden_list))
+        .route("/settings/moderation/hidden/{idx}", delete(handler::moderation_hidden_unhide))
+        .route("/settings/moderation/{idx}", get(handler::moderation_settings_load))
+        .route("/settings/moderation/{idx}", post(handler::moderation_settings_save))
+        .route("/settings/moderation/{idx}", delete(handler::moderation_settings_delete))
+
+        .route("/settings/supporters", get(handler::supporter_settings_list))
+        .route("/settings/supporters/{idx}", get(handler::supporter_settings_load))
+        .route("/settings/supporters/{idx}", post(handler::supporter_settings_save))
+
+        .route("/settings/users", get(handler::user_settings_list))
+        .route("/settings/users/{idx}", get(handler::user_settings_load))
+        .route("/settings/users/{idx}", post(handler::user_settings_save))
+        .route("/settings/users/{idx}", delete(handler::user_settings_delete))
+
+        .route("/settings/logins", get(handler::login_settings_list))
+
+        .route("/settings/tokens", get(handler::api_token_settings_list))
+        .route("/settings/tokens/add", get(handler::api_token_settings_load))
+        .route("/settings/tokens/add", post(handler::api_token_settings_save))
+        .route("/settings/tokens/{idx}", delete(handler::api_token_settings_delete))
+
+        .route("/settings/share_links", get(handler::share_link_settings_list))
+        .route("/settings/share_links/add", get(handler::share_link_settings_load))
+        .route("/settings/share_links/add", post(handler::share_link_settings_save))
+        .route("/settings/share_links/{idx}", delete(handler::share_link_settings_delete))
+
+        .route("/settings/2fa", get(handler::two_factor_settings))
+        .route("/settings/2fa/start", post(handler::two_factor_settings_start))
+        .route("/settings/2fa/confirm", post(handler::two_factor_settings_confirm))
+        .route("/settings/2fa/recovery", post(handler::two_factor_settings_recovery))
+        .route("/settings/2fa/disable", post(handler::two_factor_settings_disable))
+
+        .route("/settings/sessions", get(handler::session_settings))
+        .route("/settings/sessions", delete(handler::session_settings_delete_all))
+        .route("/settings/sessions/{idx}", delete(handler::session_settings_delete))
+
         .route("/settings/report/podcasts", get(handler::report_podcasts_list))
         .route("/settings/report/generate", post(handler::report_generate))
 
@@ -339,6 +597,18 @@ async fn main() {
             .route("/sent_index", get(handler::api_v1_sent_index))
             .route("/podcasts", get(handler::api_v1_podcasts))
             .route("/sent_podcasts", get(handler::api_v1_sent_podcasts))
+            .route("/search", get(handler::api_v1_search))
+            .route("/query", get(handler::api_v1_query))
+            .route("/stats", get(handler::api_v1_stats))
+            .route("/tags", get(handler::api_v1_tags))
+            .route("/annotations/{list}/{idx}", get(handler::api_v1_annotation))
+            .route("/shows", get(handler::api_v1_shows))
+            .route("/shows/active", get(handler::api_v1_show_active))
+            .route("/shows/{idx}", get(handler::api_v1_show))
+            .route("/shows/{idx}/summary", get(handler::api_v1_show_summary))
+            .route("/supporters", get(handler::api_v1_supporters))
+            .route("/supporters/{idx}", get(handler::api_v1_supporter))
+            .route("/leaderboard", get(handler::api_v1_leaderboard))
             .route("/ws", any(websocket_handler))
 
             // allow all origins to GET from public api
@@ -349,6 +619,41 @@ async fn main() {
         .route("/api/v1/reply", post(handler::api_v1_reply))
         .route("/api/v1/mark_replied", post(handler::api_v1_mark_replied))
         .route("/api/v1/fetch_metadata/{idx}", post(handler::api_v1_fetch_metadata))
+        .route("/api/v1/hide/{idx}", post(handler::api_v1_hide))
+        .route("/api/v1/unhide/{idx}", post(handler::api_v1_unhide))
+        .route("/api/v1/annotations/{list}/{idx}", patch(handler::api_v1_annotation_update))
+        .route("/api/v1/annotations/{list}/{idx}", delete(handler::api_v1_annotation_delete))
+        .route("/api/v1/annotations/{list}/{idx}/tags/{tag}", post(handler::api_v1_tag_add))
+        .route("/api/v1/annotations/{list}/{idx}/tags/{tag}", delete(handler::api_v1_tag_remove))
+        .route("/api/v1/shows", post(handler::api_v1_show_start))
+        .route("/api/v1/shows/{idx}", delete(handler::api_v1_show_delete))
+        .route("/api/v1/shows/{idx}/stop", post(handler::api_v1_show_stop))
+        .route("/api/v1/shows/{idx}/items/{boost}", patch(handler::api_v1_show_item_update))
+        .route("/api/v1/supporters/{idx}", patch(handler::api_v1_supporter_update))
+        .route("/api/v1/supporters/{idx}/merge", post(handler::api_v1_supporter_merge))
+        .route("/api/v1/users", get(handler::api_v1_users))
+        .route("/api/v1/users", post(handler::api_v1_user_add))
+        .route("/api/v1/users/{idx}", patch(handler::api_v1_user_update))
+        .route("/api/v1/users/{idx}", delete(handler::api_v1_user_delete))
+        .route("/api/v1/password", post(handler::api_v1_password))
+        .route("/api/v1/2fa", get(handler::api_v1_two_factor))
+        .route("/api/v1/2fa/start", post(handler::api_v1_two_factor_start))
+        .route("/api/v1/2fa/confirm", post(handler::api_v1_two_factor_confirm))
+        .route("/api/v1/2fa/recovery_codes", post(handler::api_v1_two_factor_recovery))
+        .route("/api/v1/2fa/disable", post(handler::api_v1_two_factor_disable))
+        .route("/api/v1/sessions", get(handler::api_v1_sessions))
+        .route("/api/v1/sessions", delete(handler::api_v1_sessions_delete))
+        .route("/api/v1/sessions/{idx}", delete(handler::api_v1_session_delete))
+        .route("/api/v1/logins", get(handler::api_v1_logins))
+        .route("/api/v1/tokens", get(handler::api_v1_tokens))
+        .route("/api/v1/tokens", post(handler::api_v1_token_add))
+        .route("/api/v1/tokens/{idx}", delete(handler::api_v1_token_delete))
+        .route("/api/v1/share_links", get(handler::api_v1_share_links))
+        .route("/api/v1/share_links", post(handler::api_v1_share_link_add))
+        .route("/api/v1/share_links/{idx}", delete(handler::api_v1_share_link_delete))
+        .route("/api/v1/backup", get(handler::api_v1_backup))
+        .route("/api/v1/rescan", post(handler::api_v1_rescan))
+        .route("/api/v1/import", post(handler::api_v1_import).layer(DefaultBodyLimit::max(IMPORT_UPLOAD_LIMIT)))
 
         // require auth for above routes
         .route_layer(middleware::from_fn_with_state(state.clone(), handler::auth_middleware));
@@ -362,6 +667,12 @@ async fn main() {
         // api login endpoint
         .route("/api/v1/login", post(handler::api_v1_login))
 
+        // read only share links, which don't need a login
+        .route("/share/{token}", get(handler::share_page))
+        .route("/share/{token}/boosts", get(handler::share_boosts))
+        .route("/share/{token}/goal", get(handler::share_goal))
+        .route("/share/{token}/ws", any(share_websocket_handler))
+
         // static assets
         .nest_service("/image", ServeDir::new(WEBROOT_PATH_IMAGE))
         .nest_service("/script", ServeDir::new(WEBROOT_PATH_SCRIPT))
@@ -405,7 +716,7 @@ async fn main() {
     }
 
     println!("\nHelipad is listening on http://{}", binding);
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
 }
 
 
@@ -428,6 +739,64 @@ async fn handle_websocket(mut socket: WebSocket, state: AppState) {
     }
 }
 
+//Live boosts for a share link. Other events, like balances and shows, aren't sent.
+async fn share_websocket_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    axum::extract::Path(token): axum::extract::Path<String>,
+) -> Response {
+    match handler::check_share_link(&state, &token, Some(dbif::ShareView::Websocket)).await {
+        Ok(link) => ws.on_upgrade(move |socket| handle_share_websocket(socket, state, token, link)),
+        Err(resp) => resp,
+    }
+}
+
+async fn handle_share_websocket(mut socket: WebSocket, state: AppState, token: String, link: dbif::ShareLinkRecord) {
+    let mut rx = state.ws_tx.subscribe();
+
+    //Check the link now and then so revoking it closes the socket
+    let mut recheck = tokio::time::interval(std::time::Duration::from_secs(60));
+    recheck.tick().await;
+
+    loop {
+        let msg = tokio::select! {
+            msg = rx.recv() => match msg {
+                Ok(msg) => msg,
+                Err(_) => break,
+            },
+            _ = recheck.tick() => {
+                if handler::check_share_link(&state, &token, Some(dbif::ShareView::Websocket)).await.is_err() {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        if msg.0 != "boost" && msg.0 != "stream" {
+            continue;
+        }
+
+        if link.podcast.is_some() && msg.1.get("podcast").and_then(|podcast| podcast.as_str()) != link.podcast.as_deref() {
+            continue;
+        }
+
+        //Only the public parts of the boost, without its effects
+        let boost = match serde_json::from_value::<share_links::SharedBoost>(msg.1) {
+            Ok(boost) => boost,
+            Err(e) => {
+                eprintln!("Unable to read boost for share link WebSocket: {}", e);
+                continue;
+            }
+        };
+
+        let json = serde_json::to_string(&WebSocketEvent(msg.0, serde_json::to_value(boost).unwrap())).unwrap();
+        if let Err(e) = socket.send(json.into()).await {
+            eprintln!("Error sending message to share link WebSocket: {}", e);
+            break;
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct WebSocketEvent(
     pub String,
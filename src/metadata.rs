@@ -1,11 +1,37 @@
 use std::error::Error;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
 use reqwest;
 use urlencoding::decode as url_decode;
 use regex::Regex;
 use serde::Deserialize;
+use serde_json::Value;
+use tokio::sync::Mutex;
 use crate::boost::RawBoost;
 use crate::deserializers::{d_blank, d_zero, de_optional_string_or_number};
 
+// Domains trusted for automatic rss::payment metadata fetching
+const RSS_PAYMENT_DOMAINS: [&str; 3] = ["fountain.fm", "castamatic.com", "tardbox.com"];
+
+// RawBoost fields that a generic provider may map JSON values to
+pub const RAWBOOST_FIELDS: [&str; 10] = [
+    "action",
+    "app_name",
+    "message",
+    "sender_name",
+    "podcast",
+    "episode",
+    "value_msat",
+    "value_msat_total",
+    "remote_feed_guid",
+    "remote_item_guid",
+];
+
+pub type MetadataResult = Result<Option<RawBoost>, Box<dyn Error>>;
+pub type MetadataFuture<'a> = Pin<Box<dyn Future<Output = MetadataResult> + Send + 'a>>;
+
 #[derive(Debug)]
 pub struct MetadataError(String);
 
@@ -59,25 +85,259 @@ pub struct PodcastGuruPayment {
     metadata_payload: Option<String>,
 }
 
-pub async fn fetch_payment_metadata(comment: &str) -> Result<Option<RawBoost>, Box<dyn Error>> {
-    let rss_payment_regex = Regex::new(r"rss::payment::\w+ (https:\/\/[^\s]+)")?;
+/// A source of payment metadata referenced from a lightning invoice memo
+pub trait MetadataProvider: Send + Sync {
+    /// Name of the provider for logging
+    fn name(&self) -> &str;
+
+    /// Returns the metadata URL if the memo belongs to this provider
+    fn match_memo(&self, memo: &str) -> Option<String>;
+
+    /// Returns true if metadata may be fetched from the URL without user interaction
+    fn is_trusted(&self, _url: &str) -> bool {
+        false
+    }
+
+    /// Fetches the metadata from the URL and maps it to a RawBoost
+    fn fetch<'a>(&'a self, url: &'a str) -> MetadataFuture<'a>;
+}
+
+/// rss::payment memos (Fountain, Castamatic, etc) with metadata in the X-RSS-Payment header
+pub struct RssPaymentProvider {
+    pattern: Regex,
+    domains: Vec<String>,
+}
 
-    if let Some(captures) = rss_payment_regex.captures(comment) {
-        if let Some(url) = captures.get(1) {
-            println!("Found RSS Payment URL: {}", url.as_str());
-            return fetch_rss_payment(url.as_str()).await;
+impl RssPaymentProvider {
+    pub fn new(additional_domains: &str) -> Self {
+        let mut domains: Vec<String> = RSS_PAYMENT_DOMAINS.iter().map(|d| d.to_string()).collect();
+        domains.extend(parse_domains(additional_domains));
+
+        Self {
+            pattern: Regex::new(r"rss::payment::\w+ (https:\/\/[^\s]+)").unwrap(),
+            domains,
         }
     }
+}
+
+impl MetadataProvider for RssPaymentProvider {
+    fn name(&self) -> &str {
+        "RSS Payment"
+    }
+
+    fn match_memo(&self, memo: &str) -> Option<String> {
+        capture_url(&self.pattern, memo)
+    }
+
+    fn is_trusted(&self, url: &str) -> bool {
+        is_on_domains(url, &self.domains)
+    }
+
+    fn fetch<'a>(&'a self, url: &'a str) -> MetadataFuture<'a> {
+        Box::pin(fetch_rss_payment(url))
+    }
+}
+
+/// Podcast Guru "V4V:" memos with the boost TLV in a JSON metadata payload
+pub struct PodcastGuruProvider {
+    pattern: Regex,
+}
+
+impl PodcastGuruProvider {
+    pub fn new() -> Self {
+        Self {
+            pattern: Regex::new(r"V4V: (https:\/\/boost\.podcastguru\.io\/[^\s]+)").unwrap(),
+        }
+    }
+}
+
+impl MetadataProvider for PodcastGuruProvider {
+    fn name(&self) -> &str {
+        "Podcast Guru"
+    }
+
+    fn match_memo(&self, memo: &str) -> Option<String> {
+        capture_url(&self.pattern, memo)
+    }
 
-    let podcast_guru_regex = Regex::new(r"V4V: (https:\/\/boost\.podcastguru\.io\/[^\s]+)")?;
+    //The pattern only matches boost.podcastguru.io URLs
+    fn is_trusted(&self, _url: &str) -> bool {
+        true
+    }
+
+    fn fetch<'a>(&'a self, url: &'a str) -> MetadataFuture<'a> {
+        Box::pin(fetch_podcast_guru_payment(url))
+    }
+}
+
+/// User configured provider: the memo pattern locates the URL and the field map
+/// translates the fetched JSON into boost fields. Anyone sending a boost chooses the memo, so
+/// its URLs are only fetched automatically from the domains in the metadata whitelist.
+pub struct GenericProvider {
+    name: String,
+    pattern: Regex,
+    payload_path: Option<String>,
+    field_map: HashMap<String, String>,
+    domains: Vec<String>,
+}
+
+impl GenericProvider {
+    pub fn from_record(record: &dbif::MetadataProviderRecord, trusted_domains: &str) -> Result<Self, Box<dyn Error>> {
+        let pattern = Regex::new(&record.memo_pattern)?;
+        let field_map = parse_field_map(record.field_map.as_deref().unwrap_or_default())?;
+
+        Ok(Self {
+            name: record.name.clone(),
+            pattern,
+            payload_path: record.payload_path.clone().filter(|p| !p.is_empty()),
+            field_map,
+            domains: parse_domains(trusted_domains).collect(),
+        })
+    }
+}
+
+impl MetadataProvider for GenericProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn match_memo(&self, memo: &str) -> Option<String> {
+        capture_url(&self.pattern, memo)
+    }
+
+    fn is_trusted(&self, url: &str) -> bool {
+        is_on_domains(url, &self.domains)
+    }
 
-    if let Some(captures) = podcast_guru_regex.captures(comment) {
-        if let Some(url) = captures.get(1) {
-            println!("Found Podcast Guru URL: {}", url.as_str());
-            return fetch_podcast_guru_payment(url.as_str()).await;
+    fn fetch<'a>(&'a self, url: &'a str) -> MetadataFuture<'a> {
+        Box::pin(fetch_generic_payment(url, self.payload_path.as_deref(), &self.field_map))
+    }
+}
+
+//Returns the first capture group (or the whole match) of the pattern as the metadata URL
+fn capture_url(pattern: &Regex, memo: &str) -> Option<String> {
+    let captures = pattern.captures(memo)?;
+    let url = captures.get(1).or_else(|| captures.get(0))?;
+    Some(url.as_str().to_string())
+}
+
+//Splits a comma separated list of domains
+fn parse_domains(domains: &str) -> impl Iterator<Item = String> + '_ {
+    domains
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+//Whether the URL is an https URL on one of the domains
+fn is_on_domains(url: &str, domains: &[String]) -> bool {
+    domains.iter().any(|d| url.starts_with(&format!("https://{}/", d)))
+}
+
+/// Parses a JSON object mapping RawBoost fields to JSON pointers in the fetched payload
+pub fn parse_field_map(json: &str) -> Result<HashMap<String, String>, Box<dyn Error>> {
+    if json.trim().is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let field_map: HashMap<String, String> = serde_json::from_str(json)?;
+
+    for field in field_map.keys() {
+        if !RAWBOOST_FIELDS.contains(&field.as_str()) {
+            return Err(Box::new(MetadataError(format!("Unknown boost field in field map: {}", field))));
         }
     }
 
+    Ok(field_map)
+}
+
+/// Builds the metadata providers: the built-in ones followed by the enabled ones configured in the database
+pub fn load_providers(db_filepath: &str, additional_domains: &str) -> Result<Vec<Box<dyn MetadataProvider>>, Box<dyn Error>> {
+    let mut providers = built_in_providers(additional_domains);
+    let records = dbif::get_metadata_providers_from_db(db_filepath)?;
+
+    for record in records.iter().filter(|r| r.enabled) {
+        match GenericProvider::from_record(record, additional_domains) {
+            Ok(provider) => providers.push(Box::new(provider)),
+            Err(e) => eprintln!("** Error in metadata provider {}: {}", record.name, e),
+        }
+    }
+
+    Ok(providers)
+}
+
+fn built_in_providers(additional_domains: &str) -> Vec<Box<dyn MetadataProvider>> {
+    vec![
+        Box::new(RssPaymentProvider::new(additional_domains)),
+        Box::new(PodcastGuruProvider::new()),
+    ]
+}
+
+pub type Providers = Arc<Vec<Box<dyn MetadataProvider>>>;
+
+/// The metadata providers, built once so their patterns aren't recompiled for every invoice. They're built again
+/// after the configured providers change or when the whitelist they were built with isn't the current one.
+#[derive(Default)]
+pub struct ProviderCache {
+    providers: Mutex<Option<(String, Providers)>>, // whitelist they were built with
+}
+
+impl ProviderCache {
+    pub async fn get(&self, db_filepath: &str, additional_domains: &str) -> Providers {
+        let mut cached = self.providers.lock().await;
+
+        if let Some((domains, providers)) = cached.as_ref() {
+            if domains == additional_domains {
+                return providers.clone();
+            }
+        }
+
+        let (filepath, domains) = (db_filepath.to_string(), additional_domains.to_string());
+
+        match dbif::run_blocking(move || load_providers(&filepath, &domains)).await {
+            Ok(providers) => {
+                let providers = Arc::new(providers);
+                *cached = Some((additional_domains.to_string(), providers.clone()));
+                providers
+            }
+            Err(e) => {
+                eprintln!("** Error loading metadata providers: {}", e);
+                Arc::new(built_in_providers(additional_domains)) // the configured ones are tried again next time
+            }
+        }
+    }
+
+    /// Builds the providers again next time they're needed
+    pub async fn invalidate(&self) {
+        *self.providers.lock().await = None;
+    }
+}
+
+impl std::fmt::Debug for ProviderCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("ProviderCache").finish_non_exhaustive()
+    }
+}
+
+/// Fetches metadata from the first provider that handles the memo.  When `trusted_only` is set,
+/// providers that don't trust the URL are skipped.
+pub async fn fetch_payment_metadata(comment: &str, providers: &[Box<dyn MetadataProvider>], trusted_only: bool) -> MetadataResult {
+    for provider in providers {
+        let url = match provider.match_memo(comment) {
+            Some(url) => url,
+            None => continue,
+        };
+
+        if trusted_only && !provider.is_trusted(&url) {
+            println!("Skipping untrusted {} URL: {}", provider.name(), url);
+            continue;
+        }
+
+        println!("Found {} URL: {}", provider.name(), url);
+        return provider.fetch(&url).await;
+    }
+
     Ok(None)
 }
 
@@ -163,4 +423,62 @@ async fn fetch_podcast_guru_payment(url: &str) -> Result<Option<RawBoost>, Box<d
         },
         None => Ok(None),
     }
-}
\ No newline at end of file
+}
+/**
+ * Fetch payment metadata for a generic provider via HTTP GET request and map its fields
+ */
+async fn fetch_generic_payment(url: &str, payload_path: Option<&str>, field_map: &HashMap<String, String>) -> MetadataResult {
+    let app_version = env!("CARGO_PKG_VERSION");
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .user_agent(format!("Helipad/{}", app_version))
+        .build()?;
+
+    let response = client
+        .get(url)
+        .send()
+        .await?;
+
+    let body = response.text().await?;
+    println!("Generic Payment Body: {}", body);
+
+    let mut payload: Value = serde_json::from_str(&body)?;
+
+    if let Some(path) = payload_path {
+        payload = match payload.pointer(&json_pointer(path)) {
+            Some(Value::String(json)) => serde_json::from_str(json)?, // payload embedded as a JSON string
+            Some(value) => value.clone(),
+            None => return Ok(None),
+        };
+    }
+
+    let mapped = if field_map.is_empty() {
+        payload.clone()
+    } else {
+        let mut fields = serde_json::Map::new();
+
+        for (field, path) in field_map {
+            if let Some(value) = payload.pointer(&json_pointer(path)) {
+                fields.insert(field.clone(), value.clone());
+            }
+        }
+
+        Value::Object(fields)
+    };
+
+    let mut rawboost: RawBoost = serde_json::from_value(mapped)
+        .map_err(|e| MetadataError(format!("Error mapping generic payment: {}", e)))?;
+
+    rawboost.tlv = Some(payload.to_string());
+
+    Ok(Some(rawboost))
+}
+
+//Allows plain keys ("sender") as well as JSON pointers ("/sender/name")
+fn json_pointer(path: &str) -> String {
+    if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("/{}", path.replace('.', "/"))
+    }
+}
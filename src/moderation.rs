@@ -0,0 +1,99 @@
+use regex::RegexBuilder;
+
+// Fields a moderation rule can match against
+pub const MODERATION_FIELDS: [&str; 4] = ["sender", "app", "pubkey", "message"];
+
+// Actions a moderation rule can take
+pub const MODERATION_ACTIONS: [&str; 2] = ["hide", "quarantine"];
+
+// Validates a moderation rule before it's saved
+pub fn validate_rule(rule: &dbif::ModerationRuleRecord) -> Result<(), String> {
+    if !MODERATION_FIELDS.contains(&rule.field.as_str()) {
+        return Err(format!("Unknown field: {}", rule.field));
+    }
+
+    if !MODERATION_ACTIONS.contains(&rule.action.as_str()) {
+        return Err(format!("Unknown action: {}", rule.action));
+    }
+
+    if rule.pattern.is_empty() {
+        return Err("Pattern is required".to_string());
+    }
+
+    if rule.is_regex {
+        if let Err(e) = RegexBuilder::new(&rule.pattern).case_insensitive(true).build() {
+            return Err(format!("Invalid regex: {}", e));
+        }
+    }
+
+    Ok(())
+}
+
+// Returns the value of the boost field the rule applies to
+fn field_value(boost: &dbif::BoostRecord, field: &str) -> String {
+    match field {
+        "sender" => boost.sender.clone(),
+        "app" => boost.app.clone(),
+        "message" => boost.message.clone(),
+        "pubkey" => match boost.parse_tlv() {
+            Ok(tlv) => tlv["reply_address"].as_str().unwrap_or_default().to_string(),
+            Err(_) => "".to_string(),
+        },
+        _ => "".to_string(),
+    }
+}
+
+// Checks a single rule against the boost. Plain message patterns are keywords,
+// everything else is a blocklist entry that has to match the whole value.
+pub fn matches_rule(rule: &dbif::ModerationRuleRecord, boost: &dbif::BoostRecord) -> bool {
+    let value = field_value(boost, &rule.field);
+
+    if value.is_empty() {
+        return false;
+    }
+
+    if rule.is_regex {
+        return match RegexBuilder::new(&rule.pattern).case_insensitive(true).build() {
+            Ok(re) => re.is_match(&value),
+            Err(e) => {
+                eprintln!("** Invalid moderation regex {}: {}", rule.pattern, e);
+                false
+            }
+        };
+    }
+
+    let value = value.to_lowercase();
+    let pattern = rule.pattern.to_lowercase();
+
+    if rule.field == "message" {
+        value.contains(&pattern)
+    } else {
+        value.trim() == pattern.trim()
+    }
+}
+
+// Applies the moderation rules to a received boost, hiding or quarantining it if any rule matches
+pub fn moderate_boost(rules: &[dbif::ModerationRuleRecord], boost: &mut dbif::BoostRecord) {
+    for rule in rules.iter().filter(|rule| rule.enabled) {
+        if !matches_rule(rule, boost) {
+            continue;
+        }
+
+        println!("** Boost {} matched moderation rule {} ({})", boost.index, rule.index, rule.action);
+
+        boost.hidden = true;
+
+        if rule.action == "quarantine" {
+            boost.quarantined = true;
+            break; // quarantine is the strongest action
+        }
+    }
+}
+
+// Loads the moderation rules and applies them to the boost
+pub fn moderate_boost_from_db(db_filepath: &str, boost: &mut dbif::BoostRecord) {
+    match dbif::get_moderation_rules_from_db(db_filepath) {
+        Ok(rules) => moderate_boost(&rules, boost),
+        Err(e) => eprintln!("** Error getting moderation rules: {}", e),
+    }
+}
@@ -0,0 +1,476 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use chrono::Utc;
+use data_encoding::HEXLOWER;
+use futures::{SinkExt, StreamExt};
+use regex::Regex;
+use serde_json::{json, Value};
+use tokio::time::timeout;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+// Matches nostr: references (NIP-21) to profiles and notes in boost messages
+static NOSTR_REF_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"nostr:((?:npub|nprofile|note|nevent)1[qpzry9x8gf2tvdw0s3jn54khce6mua7l]+)").unwrap()
+});
+
+// References being looked up on the relays in the background, so each is only fetched once at a time
+static REFRESHING: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+// How long a fetched profile or note stays fresh in the cache (seconds)
+const CACHE_TTL: i64 = 24 * 60 * 60;
+
+// How long to wait before retrying a lookup that returned nothing (seconds)
+const MISSING_CACHE_TTL: i64 = 60 * 60;
+
+// How long to wait on a relay before giving up on it
+const RELAY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub struct DecodedReference {
+    pub kind: String,
+    pub pubkey: Option<String>,
+    pub note_id: Option<String>,
+    pub relays: Vec<String>,
+}
+
+// Splits the comma separated relay setting into a list of relay urls
+pub fn parse_relays(relays: &str) -> Vec<String> {
+    relays.split(',')
+        .map(|relay| relay.trim().to_string())
+        .filter(|relay| relay.starts_with("wss://") || relay.starts_with("ws://"))
+        .collect()
+}
+
+// Returns the full reference text and bech32 entity of every nostr: reference in the message
+pub fn find_references(message: &str) -> Vec<(String, String)> {
+    let mut refs: Vec<(String, String)> = Vec::new();
+
+    for caps in NOSTR_REF_RE.captures_iter(message) {
+        let text = caps[0].to_string();
+
+        if !refs.iter().any(|(existing, _)| *existing == text) {
+            refs.push((text, caps[1].to_string()));
+        }
+    }
+
+    refs
+}
+
+// Decodes an npub, nprofile, note or nevent entity (NIP-19)
+pub fn decode_reference(entity: &str) -> Option<DecodedReference> {
+    let (hrp, data) = bech32::decode(entity).ok()?;
+
+    let mut decoded = DecodedReference {
+        kind: "profile".to_string(),
+        pubkey: None,
+        note_id: None,
+        relays: Vec::new(),
+    };
+
+    match hrp.as_str() {
+        "npub" if data.len() == 32 => {
+            decoded.pubkey = Some(HEXLOWER.encode(&data));
+        }
+        "note" if data.len() == 32 => {
+            decoded.kind = "note".to_string();
+            decoded.note_id = Some(HEXLOWER.encode(&data));
+        }
+        "nprofile" | "nevent" => {
+            let is_event = hrp.as_str() == "nevent";
+
+            if is_event {
+                decoded.kind = "note".to_string();
+            }
+
+            //Walk the TLV entries: 0 = pubkey/event id, 1 = relay, 2 = author
+            let mut pos = 0;
+
+            while pos + 2 <= data.len() {
+                let tlv_type = data[pos];
+                let tlv_len = data[pos + 1] as usize;
+                let start = pos + 2;
+                let end = start + tlv_len;
+
+                if end > data.len() {
+                    break;
+                }
+
+                let value = &data[start..end];
+
+                match tlv_type {
+                    0 if tlv_len == 32 && is_event => decoded.note_id = Some(HEXLOWER.encode(value)),
+                    0 if tlv_len == 32 => decoded.pubkey = Some(HEXLOWER.encode(value)),
+                    1 => {
+                        if let Ok(relay) = std::str::from_utf8(value) {
+                            decoded.relays.push(relay.to_string());
+                        }
+                    }
+                    2 if tlv_len == 32 && is_event => decoded.pubkey = Some(HEXLOWER.encode(value)),
+                    _ => {}
+                }
+
+                pos = end;
+            }
+
+            if decoded.pubkey.is_none() && decoded.note_id.is_none() {
+                return None;
+            }
+        }
+        _ => {
+            return None;
+        }
+    }
+
+    Some(decoded)
+}
+
+// Sends a subscription to a single relay and returns the events it has for the filter
+async fn query_relay(relay: &str, filter: &Value) -> Result<Vec<Value>, Box<dyn std::error::Error + Send + Sync>> {
+    let (mut socket, _) = timeout(RELAY_TIMEOUT, connect_async(relay)).await??;
+
+    let sub_id = format!("helipad-{}", uuid::Uuid::new_v4().simple());
+    let request = json!(["REQ", sub_id, filter]);
+
+    socket.send(Message::text(request.to_string())).await?;
+
+    let mut events: Vec<Value> = Vec::new();
+
+    while let Ok(Some(msg)) = timeout(RELAY_TIMEOUT, socket.next()).await {
+        let msg = msg?;
+
+        if !msg.is_text() {
+            continue;
+        }
+
+        let parsed: Value = match serde_json::from_str(msg.to_text()?) {
+            Ok(val) => val,
+            Err(_) => continue,
+        };
+
+        match (parsed[0].as_str(), parsed[1].as_str()) {
+            (Some("EVENT"), Some(id)) if id == sub_id => events.push(parsed[2].clone()),
+            (Some("EOSE"), Some(id)) if id == sub_id => break,
+            (Some("CLOSED"), Some(id)) if id == sub_id => break,
+            _ => {}
+        }
+    }
+
+    let _ = socket.send(Message::text(json!(["CLOSE", sub_id]).to_string())).await;
+    let _ = socket.close(None).await;
+
+    Ok(events)
+}
+
+// Queries all of the relays at once and returns the newest matching event
+async fn query_relays(relays: &[String], filter: Value) -> Option<Value> {
+    let queries = relays.iter().map(|relay| query_relay(relay, &filter));
+    let results = futures::future::join_all(queries).await;
+
+    let mut newest: Option<Value> = None;
+
+    for (relay, result) in relays.iter().zip(results) {
+        match result {
+            Ok(events) => {
+                for event in events {
+                    let created_at = event["created_at"].as_i64().unwrap_or(0);
+                    let newest_at = newest.as_ref().and_then(|ev| ev["created_at"].as_i64()).unwrap_or(-1);
+
+                    if created_at > newest_at {
+                        newest = Some(event);
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("** Unable to query nostr relay {}: {}", relay, e);
+            }
+        }
+    }
+
+    newest
+}
+
+fn is_fresh(fetched_at: i64, has_data: bool) -> bool {
+    let ttl = if has_data { CACHE_TTL } else { MISSING_CACHE_TTL };
+    Utc::now().timestamp() - fetched_at < ttl
+}
+
+// Loads a profile from the cache
+async fn cached_profile(db_filepath: &str, pubkey: &str) -> Option<dbif::NostrProfileRecord> {
+    let (filepath, pubkey) = (db_filepath.to_string(), pubkey.to_string());
+
+    dbif::run_blocking(move || dbif::get_nostr_profile_from_db(&filepath, &pubkey)).await.unwrap_or_else(|e| {
+        eprintln!("** Error loading nostr profile from cache: {}", e);
+        None
+    })
+}
+
+async fn save_profile(db_filepath: &str, profile: &dbif::NostrProfileRecord) {
+    let (filepath, profile) = (db_filepath.to_string(), profile.clone());
+
+    if let Err(e) = dbif::run_blocking(move || dbif::save_nostr_profile_to_db(&filepath, &profile)).await {
+        eprintln!("** Error saving nostr profile to cache: {}", e);
+    }
+}
+
+// Loads a note from the cache
+async fn cached_note(db_filepath: &str, note_id: &str) -> Option<dbif::NostrNoteRecord> {
+    let (filepath, note_id) = (db_filepath.to_string(), note_id.to_string());
+
+    dbif::run_blocking(move || dbif::get_nostr_note_from_db(&filepath, &note_id)).await.unwrap_or_else(|e| {
+        eprintln!("** Error loading nostr note from cache: {}", e);
+        None
+    })
+}
+
+async fn save_note(db_filepath: &str, note: &dbif::NostrNoteRecord) {
+    let (filepath, note) = (db_filepath.to_string(), note.clone());
+
+    if let Err(e) = dbif::run_blocking(move || dbif::save_nostr_note_to_db(&filepath, &note)).await {
+        eprintln!("** Error saving nostr note to cache: {}", e);
+    }
+}
+
+// Returns a profile from the cache, fetching it from the relays if it's missing or stale
+pub async fn resolve_profile(db_filepath: &str, relays: &[String], pubkey: &str) -> Option<dbif::NostrProfileRecord> {
+    let cached = cached_profile(db_filepath, pubkey).await;
+
+    if let Some(profile) = &cached {
+        if is_fresh(profile.fetched_at, profile.best_name().is_some()) {
+            return cached;
+        }
+    }
+
+    let filter = json!({"kinds": [0], "authors": [pubkey], "limit": 1});
+
+    let event = match query_relays(relays, filter).await {
+        Some(event) => event,
+        None => {
+            //Keep serving the stale entry if the relays came back empty
+            if cached.is_some() {
+                return cached;
+            }
+
+            let missing = dbif::NostrProfileRecord {
+                pubkey: pubkey.to_string(),
+                name: None,
+                display_name: None,
+                nip05: None,
+                picture: None,
+                fetched_at: Utc::now().timestamp(),
+            };
+
+            save_profile(db_filepath, &missing).await;
+
+            return Some(missing);
+        }
+    };
+
+    let content: Value = serde_json::from_str(event["content"].as_str().unwrap_or("{}")).unwrap_or_default();
+    let field = |key: &str| content[key].as_str().map(|val| val.to_string());
+
+    let profile = dbif::NostrProfileRecord {
+        pubkey: pubkey.to_string(),
+        name: field("name"),
+        display_name: field("display_name").or_else(|| field("displayName")),
+        nip05: field("nip05"),
+        picture: field("picture"),
+        fetched_at: Utc::now().timestamp(),
+    };
+
+    save_profile(db_filepath, &profile).await;
+
+    Some(profile)
+}
+
+// Returns a note from the cache, fetching it from the relays if it's missing or stale
+pub async fn resolve_note(db_filepath: &str, relays: &[String], note_id: &str) -> Option<dbif::NostrNoteRecord> {
+    let cached = cached_note(db_filepath, note_id).await;
+
+    if let Some(note) = &cached {
+        if is_fresh(note.fetched_at, note.pubkey.is_some()) {
+            return cached;
+        }
+    }
+
+    let filter = json!({"ids": [note_id], "limit": 1});
+    let event = query_relays(relays, filter).await;
+
+    if event.is_none() && cached.is_some() {
+        return cached;
+    }
+
+    let note = dbif::NostrNoteRecord {
+        note_id: note_id.to_string(),
+        pubkey: event.as_ref().and_then(|ev| ev["pubkey"].as_str()).map(|val| val.to_string()),
+        content: event.as_ref().and_then(|ev| ev["content"].as_str()).map(|val| val.to_string()),
+        fetched_at: Utc::now().timestamp(),
+    };
+
+    save_note(db_filepath, &note).await;
+
+    Some(note)
+}
+
+// Resolves every nostr: reference in the message to the name of the profile (or note author)
+pub async fn resolve_references(db_filepath: &str, relays: &[String], message: &str) -> Vec<dbif::NostrReference> {
+    let mut refs: Vec<dbif::NostrReference> = Vec::new();
+
+    for (text, entity) in find_references(message) {
+        let decoded = match decode_reference(&entity) {
+            Some(decoded) => decoded,
+            None => continue,
+        };
+
+        //Prefer the relay hints embedded in the reference
+        let mut ref_relays = decoded.relays.clone();
+        ref_relays.extend(relays.iter().filter(|relay| !decoded.relays.contains(relay)).cloned());
+
+        let mut pubkey = decoded.pubkey.clone();
+
+        if let Some(note_id) = &decoded.note_id {
+            if let Some(note) = resolve_note(db_filepath, &ref_relays, note_id).await {
+                pubkey = pubkey.or(note.pubkey);
+            }
+        }
+
+        let name = match &pubkey {
+            Some(pubkey) => resolve_profile(db_filepath, &ref_relays, pubkey).await.and_then(|profile| profile.best_name()),
+            None => None,
+        };
+
+        refs.push(dbif::NostrReference {
+            text,
+            kind: decoded.kind,
+            pubkey,
+            note_id: decoded.note_id,
+            name,
+        });
+    }
+
+    refs
+}
+
+// References found in the cache by their text, and the texts of the ones that are missing or stale
+type CachedReferences = (HashMap<String, dbif::NostrReference>, Vec<String>);
+
+// Resolves references from the cache alone, without asking the relays
+fn cached_references(db_filepath: &str, texts: &[String]) -> Result<CachedReferences, Box<dyn std::error::Error>> {
+    let mut refs: HashMap<String, dbif::NostrReference> = HashMap::new();
+    let mut stale: Vec<String> = Vec::new();
+
+    for text in texts {
+        let decoded = match find_references(text).first().and_then(|(_, entity)| decode_reference(entity)) {
+            Some(decoded) => decoded,
+            None => continue,
+        };
+
+        let mut pubkey = decoded.pubkey.clone();
+        let mut fresh = true;
+
+        if let Some(note_id) = &decoded.note_id {
+            match dbif::get_nostr_note_from_db(db_filepath, note_id)? {
+                Some(note) => {
+                    fresh &= is_fresh(note.fetched_at, note.pubkey.is_some());
+                    pubkey = pubkey.or(note.pubkey);
+                }
+                None => fresh = false,
+            }
+        }
+
+        let mut name = None;
+
+        if let Some(pubkey) = &pubkey {
+            match dbif::get_nostr_profile_from_db(db_filepath, pubkey)? {
+                Some(profile) => {
+                    fresh &= is_fresh(profile.fetched_at, profile.best_name().is_some());
+                    name = profile.best_name();
+                }
+                None => fresh = false,
+            }
+        }
+
+        if !fresh {
+            stale.push(text.clone());
+        }
+
+        refs.insert(text.clone(), dbif::NostrReference {
+            text: text.clone(),
+            kind: decoded.kind,
+            pubkey,
+            note_id: decoded.note_id,
+            name,
+        });
+    }
+
+    Ok((refs, stale))
+}
+
+// Looks up references on the relays in the background, filling the cache for next time
+fn refresh_references(db_filepath: &str, relays: Vec<String>, texts: Vec<String>) {
+    let texts: Vec<String> = {
+        let mut refreshing = REFRESHING.lock().unwrap();
+        texts.into_iter().filter(|text| refreshing.insert(text.clone())).collect()
+    };
+
+    if texts.is_empty() {
+        return;
+    }
+
+    let db_filepath = db_filepath.to_string();
+
+    tokio::spawn(async move {
+        for text in &texts {
+            resolve_references(&db_filepath, &relays, text).await;
+        }
+
+        let mut refreshing = REFRESHING.lock().unwrap();
+
+        for text in &texts {
+            refreshing.remove(text);
+        }
+    });
+}
+
+// Attaches nostr references to each of the boosts from the cache. Ones that aren't cached yet (or are stale)
+// are looked up on the relays in the background, so the relays never hold up a boost.
+pub async fn attach_boost_refs(db_filepath: &str, relays: &str, boosts: &mut [dbif::BoostRecord]) {
+    let relays = parse_relays(relays);
+
+    if relays.is_empty() {
+        return;
+    }
+
+    let mut texts: Vec<String> = Vec::new();
+
+    for boost in boosts.iter().filter(|boost| boost.message.contains("nostr:")) {
+        for (text, _) in find_references(&boost.message) {
+            if !texts.contains(&text) {
+                texts.push(text);
+            }
+        }
+    }
+
+    if texts.is_empty() {
+        return;
+    }
+
+    let (filepath, lookup) = (db_filepath.to_string(), texts.clone());
+
+    let (cached, stale) = match dbif::run_blocking(move || cached_references(&filepath, &lookup)).await {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("** Error loading nostr references from cache: {}", e);
+            return;
+        }
+    };
+
+    for boost in boosts.iter_mut().filter(|boost| boost.message.contains("nostr:")) {
+        boost.nostr_refs = find_references(&boost.message)
+            .into_iter()
+            .filter_map(|(text, _)| cached.get(&text).cloned())
+            .collect();
+    }
+
+    refresh_references(db_filepath, relays, stale);
+}
@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+// Returns the sat amount numerology is evaluated against (total amount, falling back to the amount received)
+pub fn boost_sats(boost: &dbif::BoostRecord) -> u64 {
+    match boost.value_msat_total / 1000 {
+        0 => (boost.value_msat.max(0) / 1000) as u64,
+        sats => sats.max(0) as u64,
+    }
+}
+
+// Determines any meaning behind the sat value using the numerology rules in position order
+pub fn evaluate_numerology(rules: &[dbif::NumerologyRecord], value: u64) -> Vec<dbif::NumerologyMatch> {
+    let mut matches: Vec<Option<&dbif::NumerologyRecord>> = Vec::new();
+    let mut substitutions: BTreeMap<usize, &dbif::NumerologyRecord> = BTreeMap::new();
+
+    let mut text_value = value.to_string();
+
+    for rule in rules {
+        match rule.equality.as_str() {
+            "<" if value < rule.amount => {
+                matches.push(Some(rule));
+            }
+            ">=" if value >= rule.amount => {
+                matches.push(Some(rule));
+            }
+            "=" if value == rule.amount => {
+                matches.push(Some(rule));
+                text_value = "".to_string();
+            }
+            "=~" => {
+                let pattern = rule.amount.to_string();
+                let found: Vec<usize> = text_value.match_indices(&pattern).map(|(idx, _)| idx).collect();
+
+                for idx in found {
+                    //Replace the match with X's to prevent further matches
+                    text_value.replace_range(idx..idx + pattern.len(), &"X".repeat(pattern.len()));
+
+                    //Keep track of where this was matched in the value
+                    substitutions.insert(idx, rule);
+
+                    //Add a gap to fill in later
+                    matches.push(None);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    //Fill in substitutions in order of where they appear in the value
+    let mut subs = substitutions.into_values();
+
+    matches.into_iter()
+        .filter_map(|rule| rule.or_else(|| subs.next()))
+        .map(|rule| dbif::NumerologyMatch {
+            emoji: rule.emoji.clone(),
+            description: rule.description.clone(),
+        })
+        .collect()
+}
+
+// Attaches the matching numerology to each of the boosts
+pub async fn attach_numerology(db_filepath: &str, boosts: &mut [dbif::BoostRecord]) {
+    let db_filepath = db_filepath.to_string();
+
+    let rules = match dbif::run_blocking(move || dbif::get_numerology_from_db(&db_filepath)).await {
+        Ok(rules) => rules,
+        Err(e) => {
+            eprintln!("** Error getting numerology: {}", e);
+            return;
+        }
+    };
+
+    for boost in boosts.iter_mut() {
+        boost.numerology = evaluate_numerology(&rules, boost_sats(boost));
+    }
+}
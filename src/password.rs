@@ -0,0 +1,143 @@
+use anyhow::{anyhow, Result};
+
+use crate::{two_factor, users};
+
+/// The shared password hash and the JWT secret. They change together, so changing the password logs everyone out.
+#[derive(Clone, Debug)]
+pub struct AuthSecrets {
+    pub password_hash: Option<String>, // no shared password if none
+    pub jwt_secret: String,
+}
+
+/// Gets the shared password hash, saving a hash of the password from the config file or environment if it's new.
+/// Once the password has been changed in Helipad or with `reset-password`, the configured one is ignored.
+pub fn load_password(db_filepath: &str, configured: Option<&str>) -> Result<Option<String>> {
+    let saved = dbif::get_password_from_db(db_filepath).map_err(|e| anyhow!("Unable to load password: {}", e))?;
+    let configured = configured.filter(|password| !password.is_empty());
+
+    match (saved, configured) {
+        (Some(saved), configured) if !saved.from_config => {
+            if configured.is_some_and(|password| !users::verify_password(&saved.hash, password)) {
+                println!("The configured password is being ignored because it was changed in Helipad. Use `helipad reset-password` to set it again.");
+            }
+
+            Ok(Some(saved.hash))
+        }
+        (Some(saved), Some(password)) if users::verify_password(&saved.hash, password) => {
+            Ok(Some(saved.hash))
+        }
+        (_, Some(password)) => {
+            let hash = users::hash_password(password)?;
+            dbif::set_password_in_db(db_filepath, &hash, true).map_err(|e| anyhow!("Unable to save password: {}", e))?;
+            println!("Saved a hash of the configured password.");
+
+            Ok(Some(hash))
+        }
+        (Some(_), None) => {
+            dbif::delete_password_from_db(db_filepath).map_err(|e| anyhow!("Unable to remove password: {}", e))?;
+            println!("The configured password was removed, so no password is needed.");
+
+            Ok(None)
+        }
+        (None, None) => Ok(None),
+    }
+}
+
+/// Changes a user's own password after checking their current one, or the shared password if there's no username.
+/// Changing the shared password rotates the JWT secret so every existing login stops working, and returns the new
+/// secrets. An account's other sessions are for the caller to log out. Hashing is slow on purpose, so call this from
+/// a blocking task.
+pub fn change_password(db_filepath: &str, username: Option<&str>, current: &str, new: &str) -> Result<Option<AuthSecrets>> {
+    users::validate_password(new)?;
+
+    let saved = dbif::get_password_from_db(db_filepath)
+        .map_err(|e| anyhow!("Unable to load password: {}", e))?
+        .map(|saved| saved.hash);
+
+    let username = match username {
+        Some(username) => username,
+        None => {
+            match saved {
+                Some(hash) if users::verify_password(&hash, current) => {}
+                Some(_) => return Err(anyhow!("The current password is wrong")),
+                None if dbif::users_exist_in_db(db_filepath).map_err(|e| anyhow!("{}", e))? => {
+                    return Err(anyhow!("There's no shared password to change. Log in with an account to change its password."));
+                }
+                None => {} // the first password, set before there's any login
+            }
+
+            return set_shared_password(db_filepath, new).map(Some);
+        }
+    };
+
+    let user = dbif::get_user_by_name_from_db(db_filepath, username).map_err(|e| anyhow!("{}", e))?;
+
+    match user {
+        Some(user) if users::verify_password(&user.password_hash, current) => {
+            users::update_user(db_filepath, user.index, None, Some(new))?;
+        }
+        _ => return Err(anyhow!("The current password is wrong")),
+    }
+
+    Ok(None)
+}
+
+/// Saves a new shared password and rotates the JWT secret
+fn set_shared_password(db_filepath: &str, password: &str) -> Result<AuthSecrets> {
+    let hash = users::hash_password(password)?;
+    dbif::set_password_in_db(db_filepath, &hash, false).map_err(|e| anyhow!("Unable to save password: {}", e))?;
+
+    Ok(AuthSecrets {
+        password_hash: Some(hash),
+        jwt_secret: rotate_secret(db_filepath)?,
+    })
+}
+
+/// Makes a new JWT secret, which logs everyone out
+pub fn rotate_secret(db_filepath: &str) -> Result<String> {
+    dbif::rotate_jwt_secret(db_filepath).map_err(|e| anyhow!("Unable to rotate JWT secret: {}", e))
+}
+
+/// The `reset-password` command. Prompts for a new password for the named account, or the shared password if there's
+/// no username, turns off its two-factor authentication and logs everyone out.
+pub fn reset_password(db_filepath: &str, username: Option<&str>) -> Result<()> {
+    let user = match username {
+        Some(username) => match dbif::get_user_by_name_from_db(db_filepath, username).map_err(|e| anyhow!("{}", e))? {
+            Some(user) => Some(user),
+            None => return Err(anyhow!("There's no user named {}", username)),
+        },
+        None => None,
+    };
+
+    let password = rpassword::prompt_password("New password: ")?;
+
+    if rpassword::prompt_password("Again: ")? != password {
+        return Err(anyhow!("The passwords don't match"));
+    }
+
+    users::validate_password(&password)?;
+
+    match user {
+        Some(user) => {
+            users::update_user(db_filepath, user.index, None, Some(&password))?;
+            rotate_secret(db_filepath)?;
+            println!("Changed the password for {}.", user.username);
+        }
+        None => {
+            set_shared_password(db_filepath, &password)?;
+            println!("Changed the shared password.");
+        }
+    }
+
+    let key = two_factor::login_key(username);
+
+    if dbif::delete_two_factor_from_db(db_filepath, key).map_err(|e| anyhow!("{}", e))? {
+        println!("Two-factor authentication was turned off. Set it up again after logging in.");
+    }
+
+    dbif::delete_all_sessions_from_db(db_filepath, None).map_err(|e| anyhow!("Unable to log out sessions: {}", e))?;
+
+    println!("Everyone has been logged out. Restart Helipad if it's running.");
+
+    Ok(())
+}
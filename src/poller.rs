@@ -1,6 +1,11 @@
 use crate::HelipadConfig;
 use crate::podcastindex;
 use crate::boost;
+use crate::metadata;
+use crate::moderation;
+use crate::nostr;
+use crate::numerology;
+use crate::shows;
 use data_encoding::HEXLOWER;
 use dbif;
 use lnd::lnrpc::lnrpc::invoice::InvoiceState;
@@ -27,11 +32,14 @@ async fn poll_node_info(lightning: &mut lnd::Lnd, db_filepath: &str) -> Result<(
 
     println!("LND node info: {:#?}", node_info);
 
-    if let Err(e) = dbif::add_node_info_to_db(db_filepath, dbif::NodeInfoRecord {
+    let filepath = db_filepath.to_string();
+    let info = dbif::NodeInfoRecord {
         lnd_alias: node_info.alias,
         node_pubkey: node_info.identity_pubkey,
         node_version: node_info.version,
-    }) {
+    };
+
+    if let Err(e) = dbif::run_blocking(move || dbif::add_node_info_to_db(&filepath, info)).await {
         eprintln!("Error adding node info to database: {:#?}", e);
     }
 
@@ -57,12 +65,14 @@ async fn poll_balance(
         .map(|bal| { println!("LND node local balance: {:#?}", bal.sat); bal.sat as i64 })
         .unwrap_or(0);
 
-    if dbif::add_wallet_balance_to_db(db_filepath, new_balance).is_err() {
+    let filepath = db_filepath.to_string();
+
+    if dbif::run_blocking(move || dbif::add_wallet_balance_to_db(&filepath, new_balance)).await.is_err() {
         println!("Error adding wallet balance to the database.");
     }
 
     if *current_balance != new_balance {
-        if let Err(e) = ws_tx.send(WebSocketEvent("balance".to_string(), serde_json::to_value(&new_balance).unwrap())) {
+        if let Err(e) = ws_tx.send(WebSocketEvent("balance".to_string(), serde_json::to_value(new_balance).unwrap())) {
             eprintln!("Error sending WebSocket event: {:#?}", e);
         } else {
             println!("WebSocket event sent.");
@@ -79,6 +89,7 @@ async fn poll_invoices(
     current_index: &mut u64,
     remote_cache: &mut podcastindex::GuidCache,
     ws_tx: &Arc<broadcast::Sender<WebSocketEvent>>,
+    settings: &Arc<RwLock<dbif::SettingsRecord>>,
 ) {
     loop {
         let response = match lnd::Lnd::list_invoices(lightning, false, *current_index, 1000, false, 0, 0).await {
@@ -92,9 +103,9 @@ async fn poll_invoices(
 
             println!("Invoice: {}, state: {}, hash: {}", invoice.add_index, invoice.state, hash);
 
-            if let Some(boost) = boost::parse_boost_from_invoice(invoice.clone(), remote_cache, false, "").await {
+            if let Some(boost) = boost::parse_boost_from_invoice(invoice.clone(), remote_cache, &[]).await {
                 println!("Boost: {:#?}", &boost);
-                handle_boost(&boost, &db_filepath, &ws_tx, true).await;
+                handle_boost(&boost, db_filepath, ws_tx, settings, true).await;
             }
             else if invoice.state == InvoiceState::Settled as i32 {
                 println!("No boost found for invoice: {:#?}", &invoice);
@@ -116,6 +127,7 @@ async fn poll_payments(
     current_index: &mut u64,
     remote_cache: &mut podcastindex::GuidCache,
     ws_tx: &Arc<broadcast::Sender<WebSocketEvent>>,
+    settings: &Arc<RwLock<dbif::SettingsRecord>>,
     catchup: bool,
 ) -> bool {
     let response = match lnd::Lnd::list_payments(lightning, false, *current_index, 500, false, false, 0, 0).await {
@@ -129,7 +141,7 @@ async fn poll_payments(
 
         if let Some(boost) = boost::parse_boost_from_payment(payment.clone(), remote_cache).await {
             println!("Sent Boost: {:#?}", boost);
-            handle_boost(&boost, db_filepath, ws_tx, !catchup).await;
+            handle_boost(&boost, db_filepath, ws_tx, settings, !catchup).await;
         }
 
         *current_index = payment.payment_index;
@@ -142,8 +154,11 @@ async fn handle_boost(
     boost: &dbif::BoostRecord,
     db_filepath: &str,
     ws_tx: &Arc<broadcast::Sender<WebSocketEvent>>,
+    settings: &Arc<RwLock<dbif::SettingsRecord>>,
     add_triggers: bool,
 ) {
+    let mut boost = boost.clone();
+
     let ws_type = if boost.payment_info.is_some() {
         "payment".to_string()
     } else {
@@ -151,17 +166,59 @@ async fn handle_boost(
     };
 
     if ws_type == "payment" {
-        match dbif::add_payment_to_db(db_filepath, boost) {
+        let (filepath, payment) = (db_filepath.to_string(), boost.clone());
+
+        match dbif::run_blocking(move || dbif::add_payment_to_db(&filepath, &payment)).await {
             Ok(_) => println!("New payment added."),
             Err(e) => eprintln!("Error adding payment: {:#?}", e),
         }
     } else {
-        match dbif::add_invoice_to_db(db_filepath, boost) {
+        //Apply moderation rules to received boosts before they're stored
+        let (filepath, mut moderating) = (db_filepath.to_string(), boost.clone());
+
+        let moderated = dbif::run_blocking(move || {
+            moderation::moderate_boost_from_db(&filepath, &mut moderating);
+            Ok(moderating)
+        }).await;
+
+        match moderated {
+            Ok(moderated) => boost = moderated,
+            Err(e) => eprintln!("Error moderating boost: {:#?}", e),
+        }
+
+        let (filepath, invoice) = (db_filepath.to_string(), boost.clone());
+
+        match dbif::run_blocking(move || dbif::add_invoice_to_db(&filepath, &invoice)).await {
             Ok(_) => println!("New invoice added."),
             Err(e) => eprintln!("Error adding invoice: {:#?}", e),
         }
+
+        //Link the boost to its sender's supporter profile so live views can link to it
+        let (filepath, invoice) = (db_filepath.to_string(), boost.clone());
+
+        match dbif::run_blocking(move || dbif::link_boost_to_supporter_in_db(&filepath, &invoice)).await {
+            Ok(supporter) => boost.supporter = supporter,
+            Err(e) => eprintln!("Error linking supporter: {:#?}", e),
+        }
+    }
+
+    //Quarantined boosts don't set off any triggers
+    let add_triggers = add_triggers && !boost.quarantined;
+
+    //Attach cached nostr references to live boosts so clients don't have to resolve them
+    let settings_snapshot = settings.read().await;
+    let resolve_nostr_refs = settings_snapshot.resolve_nostr_refs;
+    let nostr_relays = settings_snapshot.nostr_relays.clone();
+    drop(settings_snapshot);
+
+    numerology::attach_numerology(db_filepath, std::slice::from_mut(&mut boost)).await;
+
+    if add_triggers && resolve_nostr_refs {
+        nostr::attach_boost_refs(db_filepath, &nostr_relays, std::slice::from_mut(&mut boost)).await;
     }
 
+    let boost = &boost;
+
     let boost_with_effects = if add_triggers {
         triggers::process_triggers(db_filepath, boost).await.unwrap_or_else(|e| {
             eprintln!("Error processing triggers: {:#?}", e);
@@ -171,26 +228,39 @@ async fn handle_boost(
         triggers::BoostWithEffects { boost: boost.clone(), effects: Vec::new(), server_effects: Vec::new() }
     };
 
+    //Hidden boosts stay out of the live views
+    if boost.hidden {
+        println!("Boost {} is hidden, skipping WebSocket event.", boost.index);
+        return;
+    }
+
     match ws_tx.send(WebSocketEvent(ws_type, serde_json::to_value(&boost_with_effects).unwrap())) {
         Ok(_) => println!("WebSocket event sent."),
         Err(e) => eprintln!("Error sending WebSocket event: {:#?}", e),
     }
+
+    //Live boosts also go in the queue of a running show
+    if add_triggers {
+        shows::queue_boost(db_filepath, ws_tx, boost).await;
+    }
 }
 
 pub async fn lnd_subscribe_invoices(
     helipad_config: HelipadConfig,
     ws_tx: Arc<broadcast::Sender<WebSocketEvent>>,
     settings: Arc<RwLock<dbif::SettingsRecord>>,
+    metadata_providers: Arc<metadata::ProviderCache>,
 ) {
     let db_filepath = helipad_config.database_file_path.clone();
 
     println!("\nConnecting to LND node address...");
     let mut lightning = lightning::connect_lnd_or_exit(&helipad_config.node_address, &helipad_config.cert_path, &helipad_config.macaroon_path).await;
     let mut remote_cache = podcastindex::GuidCache::new(REMOTE_GUID_CACHE_SIZE);
-    let mut current_index = dbif::get_last_boost_index_from_db(&db_filepath).unwrap();
+    let filepath = db_filepath.clone();
+    let mut current_index = dbif::run_blocking(move || dbif::get_last_boost_index_from_db(&filepath)).await.unwrap();
 
     println!("Getting existing invoices from LND...");
-    poll_invoices(&mut lightning, &db_filepath, &mut current_index, &mut remote_cache, &ws_tx).await;
+    poll_invoices(&mut lightning, &db_filepath, &mut current_index, &mut remote_cache, &ws_tx, &settings).await;
 
     println!("Current invoice index: {}", current_index);
 
@@ -217,9 +287,15 @@ pub async fn lnd_subscribe_invoices(
             let metadata_whitelist = settings_snapshot.metadata_whitelist.clone();
             drop(settings_snapshot);
 
-            if let Some(boost) = boost::parse_boost_from_invoice(invoice.clone(), &mut remote_cache, fetch_metadata, &metadata_whitelist).await {
+            let providers = if fetch_metadata {
+                metadata_providers.get(&db_filepath, &metadata_whitelist).await
+            } else {
+                metadata::Providers::default()
+            };
+
+            if let Some(boost) = boost::parse_boost_from_invoice(invoice.clone(), &mut remote_cache, &providers).await {
                 println!("Boost: {:#?}", &boost);
-                handle_boost(&boost, &db_filepath, &ws_tx, true).await;
+                handle_boost(&boost, &db_filepath, &ws_tx, &settings, true).await;
             }
             else if invoice.state == InvoiceState::Settled as i32 {
                 println!("No boost found for invoice: {:#?}", &invoice);
@@ -234,7 +310,11 @@ pub async fn lnd_subscribe_invoices(
     }
 }
 
-pub async fn lnd_poller(helipad_config: HelipadConfig, ws_tx: Arc<broadcast::Sender<WebSocketEvent>>) {
+pub async fn lnd_poller(
+    helipad_config: HelipadConfig,
+    ws_tx: Arc<broadcast::Sender<WebSocketEvent>>,
+    settings: Arc<RwLock<dbif::SettingsRecord>>,
+) {
     let db_filepath = helipad_config.database_file_path.clone();
 
     println!("\nConnecting to LND node address...");
@@ -245,7 +325,8 @@ pub async fn lnd_poller(helipad_config: HelipadConfig, ws_tx: Arc<broadcast::Sen
     }
 
     let mut remote_cache = podcastindex::GuidCache::new(REMOTE_GUID_CACHE_SIZE);
-    let mut current_payment = dbif::get_last_payment_index_from_db(&db_filepath).unwrap();
+    let filepath = db_filepath.clone();
+    let mut current_payment = dbif::run_blocking(move || dbif::get_last_payment_index_from_db(&filepath)).await.unwrap();
     let mut current_balance = 0i64;
     let mut catchup = true;
 
@@ -262,7 +343,7 @@ pub async fn lnd_poller(helipad_config: HelipadConfig, ws_tx: Arc<broadcast::Sen
             continue;
         }
 
-        let updated = poll_payments(&mut lightning, &db_filepath, &mut current_payment, &mut remote_cache, &ws_tx, catchup).await;
+        let updated = poll_payments(&mut lightning, &db_filepath, &mut current_payment, &mut remote_cache, &ws_tx, &settings, catchup).await;
         println!("Current payment: {}", current_payment);
 
         if !updated {
@@ -0,0 +1,267 @@
+use anyhow::{anyhow, Result};
+use lnd::lnrpc::lnrpc::invoice::InvoiceState;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::{boost, metadata, moderation, podcastindex};
+
+use crate::REMOTE_GUID_CACHE_SIZE;
+
+/// Number of invoices or payments asked for from LND at a time
+const RESCAN_PAGE_SIZE: u64 = 1000;
+
+static RESCAN_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Only one rescan runs at a time. The guard lets the next one start when it's dropped.
+pub struct RescanGuard;
+
+impl RescanGuard {
+    pub fn acquire() -> Option<Self> {
+        match RESCAN_RUNNING.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => Some(RescanGuard),
+            Err(_) => None,
+        }
+    }
+}
+
+impl Drop for RescanGuard {
+    fn drop(&mut self) {
+        RESCAN_RUNNING.store(false, Ordering::Release);
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// What to rescan. Indexes are LND's invoice add index and payment index.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RescanOptions {
+    #[serde(default)]
+    pub from_index: u64,
+    #[serde(default)]
+    pub to_index: Option<u64>,
+    #[serde(default = "default_true")]
+    pub invoices: bool,
+    #[serde(default = "default_true")]
+    pub payments: bool,
+    #[serde(default)]
+    pub dry_run: bool, // only report, don't save anything
+}
+
+/// A boost or sent boost LND has that Helipad never recorded
+#[derive(Debug, Clone, Serialize)]
+pub struct RescanGap {
+    pub list: String, // "invoice" or "payment"
+    pub index: u64,
+    pub time: i64,
+    pub value_msat: i64,
+    pub podcast: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RescanReport {
+    pub dry_run: bool,
+    pub invoices_scanned: u64,
+    pub payments_scanned: u64,
+    pub boosts_updated: u64,
+    pub sent_boosts_updated: u64,
+    pub rolled_up_skipped: u64, // streams already counted in the stream rollups
+    pub gaps: Vec<RescanGap>, // added to the database unless this is a dry run
+}
+
+enum Saved {
+    Added,
+    Updated,
+    RolledUp,
+}
+
+/// Walks LND's invoices and payments and re-parses them with the current settings, refreshing the stored
+/// boosts and adding any that are missing. Triggers aren't fired and no websocket events are sent.
+pub async fn rescan(
+    lightning: &mut lnd::Lnd,
+    db_filepath: &str,
+    settings: &dbif::SettingsRecord,
+    providers: &metadata::ProviderCache,
+    options: &RescanOptions,
+) -> Result<RescanReport> {
+    let mut report = RescanReport {
+        dry_run: options.dry_run,
+        ..Default::default()
+    };
+
+    let mut remote_cache = podcastindex::GuidCache::new(REMOTE_GUID_CACHE_SIZE);
+
+    if options.invoices {
+        rescan_invoices(lightning, db_filepath, settings, providers, options, &mut remote_cache, &mut report).await?;
+    }
+
+    if options.payments {
+        rescan_payments(lightning, db_filepath, options, &mut remote_cache, &mut report).await?;
+    }
+
+    Ok(report)
+}
+
+async fn rescan_invoices(
+    lightning: &mut lnd::Lnd,
+    db_filepath: &str,
+    settings: &dbif::SettingsRecord,
+    providers: &metadata::ProviderCache,
+    options: &RescanOptions,
+    remote_cache: &mut podcastindex::GuidCache,
+    report: &mut RescanReport,
+) -> Result<()> {
+    let metadata_providers = if settings.fetch_metadata {
+        providers.get(db_filepath, &settings.metadata_whitelist).await
+    } else {
+        metadata::Providers::default()
+    };
+
+    let filepath = db_filepath.to_string();
+    let rollups_end = dbif::run_blocking(move || dbif::get_stream_rollups_end_from_db(&filepath)).await
+        .map_err(|e| anyhow!("Unable to check stream rollups: {}", e))?;
+
+    //LND returns invoices after the offset
+    let mut offset = options.from_index.saturating_sub(1);
+
+    loop {
+        let response = lnd::Lnd::list_invoices(lightning, false, offset, RESCAN_PAGE_SIZE, false, 0, 0).await
+            .map_err(|e| anyhow!("lnd::Lnd::list_invoices failed: {}", e))?;
+
+        if response.invoices.is_empty() {
+            return Ok(());
+        }
+
+        for invoice in response.invoices {
+            if options.to_index.is_some_and(|to_index| invoice.add_index > to_index) {
+                return Ok(());
+            }
+
+            offset = invoice.add_index;
+            report.invoices_scanned += 1;
+
+            if invoice.state != InvoiceState::Settled as i32 {
+                continue;
+            }
+
+            let boost = match boost::parse_boost_from_invoice(invoice, remote_cache, &metadata_providers).await {
+                Some(boost) => boost,
+                None => continue,
+            };
+
+            let (filepath, saving, dry_run) = (db_filepath.to_string(), boost.clone(), options.dry_run);
+
+            let saved = dbif::run_blocking(move || save_invoice(&filepath, saving, rollups_end, dry_run)).await
+                .map_err(|e| anyhow!("Unable to save boost {}: {}", boost.index, e))?;
+
+            match saved {
+                Saved::Added => report.gaps.push(RescanGap {
+                    list: "invoice".to_string(),
+                    index: boost.index,
+                    time: boost.time,
+                    value_msat: boost.value_msat_total,
+                    podcast: boost.podcast,
+                }),
+                Saved::Updated => report.boosts_updated += 1,
+                Saved::RolledUp => report.rolled_up_skipped += 1,
+            }
+        }
+    }
+}
+
+fn save_invoice(db_filepath: &str, mut boost: dbif::BoostRecord, rollups_end: Option<i64>, dry_run: bool) -> Result<Saved, Box<dyn Error>> {
+    if dbif::invoice_exists_in_db(db_filepath, boost.index)? {
+        if !dry_run {
+            dbif::upsert_invoice_in_db(db_filepath, &boost)?;
+        }
+
+        return Ok(Saved::Updated);
+    }
+
+    //Old streams missing from the boosts table were most likely collapsed by the retention policy
+    if boost.list_type == dbif::ListType::Stream && rollups_end.is_some_and(|end| boost.time < end) {
+        return Ok(Saved::RolledUp);
+    }
+
+    if !dry_run {
+        moderation::moderate_boost_from_db(db_filepath, &mut boost);
+        dbif::upsert_invoice_in_db(db_filepath, &boost)?;
+        dbif::link_boost_to_supporter_in_db(db_filepath, &boost)?;
+    }
+
+    Ok(Saved::Added)
+}
+
+async fn rescan_payments(
+    lightning: &mut lnd::Lnd,
+    db_filepath: &str,
+    options: &RescanOptions,
+    remote_cache: &mut podcastindex::GuidCache,
+    report: &mut RescanReport,
+) -> Result<()> {
+    //LND returns payments after the offset
+    let mut offset = options.from_index.saturating_sub(1);
+
+    loop {
+        let response = lnd::Lnd::list_payments(lightning, false, offset, RESCAN_PAGE_SIZE, false, false, 0, 0).await
+            .map_err(|e| anyhow!("lnd::Lnd::list_payments failed: {}", e))?;
+
+        if response.payments.is_empty() {
+            return Ok(());
+        }
+
+        for payment in response.payments {
+            if options.to_index.is_some_and(|to_index| payment.payment_index > to_index) {
+                return Ok(());
+            }
+
+            offset = payment.payment_index;
+            report.payments_scanned += 1;
+
+            let boost = match boost::parse_boost_from_payment(payment, remote_cache).await {
+                Some(boost) => boost,
+                None => continue,
+            };
+
+            let (filepath, saving, dry_run) = (db_filepath.to_string(), boost.clone(), options.dry_run);
+
+            let added = dbif::run_blocking(move || {
+                let exists = dbif::payment_exists_in_db(&filepath, saving.index)?;
+
+                if !dry_run {
+                    dbif::upsert_payment_in_db(&filepath, &saving)?;
+                }
+
+                Ok(!exists)
+            }).await.map_err(|e| anyhow!("Unable to save sent boost {}: {}", boost.index, e))?;
+
+            if added {
+                report.gaps.push(RescanGap {
+                    list: "payment".to_string(),
+                    index: boost.index,
+                    time: boost.time,
+                    value_msat: boost.value_msat_total,
+                    podcast: boost.podcast,
+                });
+            } else {
+                report.sent_boosts_updated += 1;
+            }
+        }
+    }
+}
+
+/// Prints a rescan report for the command line
+pub fn print_report(report: &RescanReport) {
+    println!("\nRescan {}:", if report.dry_run { "dry run complete, nothing was changed" } else { "complete" });
+    println!(" - Invoices scanned: {}", report.invoices_scanned);
+    println!(" - Payments scanned: {}", report.payments_scanned);
+    println!(" - Boosts and streams refreshed: {}", report.boosts_updated);
+    println!(" - Sent boosts refreshed: {}", report.sent_boosts_updated);
+    println!(" - Streams already in rollups: {}", report.rolled_up_skipped);
+    println!(" - Missing from Helipad{}: {}", if report.dry_run { "" } else { " (now added)" }, report.gaps.len());
+
+    for gap in &report.gaps {
+        println!("   {} {}: time {}, {} msat, podcast [{}]", gap.list, gap.index, gap.time, gap.value_msat, gap.podcast);
+    }
+}
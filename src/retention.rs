@@ -0,0 +1,34 @@
+use chrono::Utc;
+use std::time::Duration;
+use crate::HelipadConfig;
+
+/// How often old streams are checked for
+const RETENTION_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+
+/// Collapses streams older than the retention period into hourly rollups once a day
+pub async fn retention_scheduler(helipad_config: HelipadConfig) {
+    if helipad_config.stream_retention_days == 0 {
+        return;
+    }
+
+    println!("Keeping individual streams for {} day(s).", helipad_config.stream_retention_days);
+
+    let mut interval = tokio::time::interval(RETENTION_CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let db_filepath = helipad_config.database_file_path.clone();
+        let older_than = Utc::now().timestamp() - (helipad_config.stream_retention_days as i64 * 86400);
+
+        match dbif::run_blocking(move || dbif::rollup_streams_in_db(&db_filepath, older_than)).await {
+            Ok(result) if result.streams > 0 => {
+                println!("Collapsed {} old stream(s) into {} hourly rollup(s).", result.streams, result.rollups);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("** Error collapsing old streams: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use anyhow::{anyhow, Result};
+
+/// How often a session's last seen time is saved, so every request doesn't write to the database
+const LAST_SEEN_RESOLUTION: i64 = 60;
+
+/// Longest user agent kept for a session
+const MAX_USER_AGENT_LENGTH: usize = 200;
+
+/// Saves a new session for a login, returning the id that goes in its JWT
+pub fn start_session(
+    db_filepath: &str,
+    login_key: &str,
+    user_agent: Option<&str>,
+    ip: &str,
+    long_lived: bool,
+    expires: i64,
+) -> Result<String> {
+    let jti = uuid::Uuid::new_v4().to_string();
+    let user_agent = user_agent.map(|agent| agent.chars().take(MAX_USER_AGENT_LENGTH).collect::<String>());
+
+    dbif::add_session_to_db(db_filepath, &jti, login_key, user_agent.as_deref(), Some(ip), long_lived, expires)
+        .map_err(|e| anyhow!("Unable to save session: {}", e))?;
+
+    Ok(jti)
+}
+
+/// Looks up the session a JWT belongs to, returning it if it hasn't been logged out and belongs to the same login.
+/// Saves when it was last seen, and from where, along with when it now expires.
+pub fn check_session(db_filepath: &str, jti: &str, login_key: &str, ip: &str, expires: i64) -> Result<Option<dbif::SessionRecord>> {
+    let session = match dbif::get_session_from_db(db_filepath, jti).map_err(|e| anyhow!("Unable to look up session: {}", e))? {
+        Some(session) => session,
+        None => return Ok(None), // logged out
+    };
+
+    if !session.username.eq_ignore_ascii_case(login_key) {
+        return Ok(None);
+    }
+
+    let now = chrono::Utc::now().timestamp();
+
+    if now - session.last_seen >= LAST_SEEN_RESOLUTION || session.ip.as_deref() != Some(ip) {
+        if let Err(e) = dbif::set_session_seen_in_db(db_filepath, session.index, Some(ip), now, expires) {
+            eprintln!("** Unable to save session last seen time: {}", e);
+        }
+    }
+
+    Ok(Some(session))
+}
+
+/// Logs out all of a login's sessions, except the one with the `keep` id if given
+pub fn end_sessions(db_filepath: &str, login_key: &str, keep: Option<&str>) -> Result<u64> {
+    dbif::delete_sessions_from_db(db_filepath, login_key, keep).map_err(|e| anyhow!("Unable to log out sessions: {}", e))
+}
+
+/// Logs out one of a login's sessions. Other logins' sessions aren't found.
+pub fn end_session(db_filepath: &str, login_key: &str, index: u64) -> Result<bool> {
+    dbif::delete_session_from_db(db_filepath, login_key, index).map_err(|e| anyhow!("Unable to log out session: {}", e))
+}
@@ -0,0 +1,154 @@
+use anyhow::{anyhow, Result};
+use data_encoding::BASE64URL_NOPAD;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+/// Longest name a link can have
+const MAX_NAME_LENGTH: usize = 100;
+
+/// Days a link lasts if none are given, and the most it can
+const DEFAULT_EXPIRES_DAYS: u64 = 30;
+const MAX_EXPIRES_DAYS: u64 = 365;
+
+/// How often the last used time is saved, so overlays polling every few seconds don't write to the database each time
+const LAST_USED_RESOLUTION: i64 = 60;
+
+/// Boosts a boost feed link can get at once
+pub const MAX_BOOSTS: u64 = 100;
+
+/// What a share link shows of a boost. The TLV, memo, payment details, supporter, tags and notes stay private.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SharedBoost {
+    pub index: u64,
+    pub time: i64,
+    pub value_msat: i64,
+    pub value_msat_total: i64,
+    pub action: u8,
+    pub sender: String,
+    pub app: String,
+    pub message: String,
+    pub podcast: String,
+    pub episode: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub numerology: Vec<dbif::NumerologyMatch>,
+}
+
+impl From<dbif::BoostRecord> for SharedBoost {
+    fn from(boost: dbif::BoostRecord) -> Self {
+        Self {
+            index: boost.index,
+            time: boost.time,
+            value_msat: boost.value_msat,
+            value_msat_total: boost.value_msat_total,
+            action: boost.action as u8,
+            sender: boost.sender,
+            app: boost.app,
+            message: boost.message,
+            podcast: boost.podcast,
+            episode: boost.episode,
+            numerology: boost.numerology,
+        }
+    }
+}
+
+/// Signs everything a link can see, so changing any of it in the database stops old links from working
+fn mac(secret: &str, link: &dbif::ShareLinkRecord) -> Result<Hmac<Sha256>> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(|e| anyhow!("Invalid share link secret: {}", e))?;
+
+    mac.update(format!(
+        "{}|{}|{}|{}|{}",
+        link.index,
+        link.view.name(),
+        link.podcast.as_deref().unwrap_or(""),
+        link.goal_sats.unwrap_or(0),
+        link.expires,
+    ).as_bytes());
+
+    Ok(mac)
+}
+
+/// The secret links are signed with
+pub fn secret(db_filepath: &str) -> Result<String> {
+    dbif::get_or_create_share_link_secret(db_filepath).map_err(|e| anyhow!("Unable to load share link secret: {}", e))
+}
+
+/// The token that goes in a link's URL: its index and signature
+pub fn link_token(secret: &str, link: &dbif::ShareLinkRecord) -> Result<String> {
+    let signature = mac(secret, link)?.finalize().into_bytes();
+    Ok(format!("{}.{}", link.index, BASE64URL_NOPAD.encode(&signature)))
+}
+
+/// Makes a new share link, returning its token with the saved link
+pub fn create_link(
+    db_filepath: &str,
+    name: &str,
+    view: dbif::ShareView,
+    podcast: Option<&str>,
+    goal_sats: Option<u64>,
+    expires_days: Option<u64>,
+) -> Result<(String, dbif::ShareLinkRecord)> {
+    let name = name.trim();
+
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err(anyhow!("Link names have to be 1 to {} characters long", MAX_NAME_LENGTH));
+    }
+
+    let goal_sats = match view {
+        dbif::ShareView::Goal => match goal_sats.filter(|sats| *sats > 0) {
+            Some(sats) => Some(sats),
+            None => return Err(anyhow!("Goal links need a goal in sats")),
+        },
+        _ => None,
+    };
+
+    let expires_days = expires_days.unwrap_or(DEFAULT_EXPIRES_DAYS);
+
+    if expires_days == 0 || expires_days > MAX_EXPIRES_DAYS {
+        return Err(anyhow!("Links have to expire in 1 to {} days", MAX_EXPIRES_DAYS));
+    }
+
+    let podcast = podcast.map(|podcast| podcast.trim()).filter(|podcast| !podcast.is_empty());
+    let expires = chrono::Utc::now().timestamp() + expires_days as i64 * 86400;
+
+    let link = dbif::add_share_link_to_db(db_filepath, name, view, podcast, goal_sats, expires)
+        .map_err(|e| anyhow!("Unable to save share link: {}", e))?;
+
+    Ok((link_token(&secret(db_filepath)?, &link)?, link))
+}
+
+/// Looks up a link from its token, returning it if the signature matches and it hasn't expired or been revoked
+pub fn check_link(db_filepath: &str, token: &str) -> Result<Option<dbif::ShareLinkRecord>> {
+    let (index, signature) = match token.split_once('.') {
+        Some((index, signature)) => (index, signature),
+        None => return Ok(None),
+    };
+
+    let (index, signature) = match (index.parse::<u64>(), BASE64URL_NOPAD.decode(signature.as_bytes())) {
+        (Ok(index), Ok(signature)) => (index, signature),
+        _ => return Ok(None),
+    };
+
+    let link = match dbif::get_share_link_from_db(db_filepath, index).map_err(|e| anyhow!("Unable to look up share link: {}", e))? {
+        Some(link) => link,
+        None => return Ok(None), // revoked
+    };
+
+    if mac(&secret(db_filepath)?, &link)?.verify_slice(&signature).is_err() {
+        return Ok(None);
+    }
+
+    let now = chrono::Utc::now().timestamp();
+
+    if link.is_expired(now) {
+        return Ok(None);
+    }
+
+    if link.last_used.is_none_or(|last_used| now - last_used >= LAST_USED_RESOLUTION) {
+        if let Err(e) = dbif::set_share_link_used_in_db(db_filepath, link.index, now) {
+            eprintln!("** Unable to save share link last used time: {}", e);
+        }
+    }
+
+    Ok(Some(link))
+}
@@ -0,0 +1,151 @@
+use anyhow::{anyhow, Result};
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use crate::{numerology, WebSocketEvent};
+
+/// How many of the biggest boosters go in a session summary
+const TOP_BOOSTERS: usize = 10;
+
+/// A sender's boosts during a show
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ShowBooster {
+    pub sender: String,
+    pub count: u64,
+    pub value_msat_total: i64,
+}
+
+/// End of show totals
+#[derive(Serialize, Debug, Clone)]
+pub struct ShowSummary {
+    pub session: dbif::ShowSessionRecord,
+    pub boosts: u64,
+    pub boost_value_msat: i64,
+    pub boost_value_msat_total: i64,
+    pub streams: u64,
+    pub stream_value_msat: i64,
+    pub read: u64,
+    pub skipped: u64,
+    pub unread: u64,
+    pub top_boosters: Vec<ShowBooster>,
+    pub numerology: Vec<dbif::BoostRecord>, // boosts whose amounts meant something
+}
+
+/// Fills in the numerology of the boosts in a show's queue
+pub fn attach_numerology(db_filepath: &str, items: &mut [dbif::ShowItem]) {
+    let rules = match dbif::get_numerology_from_db(db_filepath) {
+        Ok(rules) => rules,
+        Err(e) => {
+            eprintln!("** Error getting numerology: {}", e);
+            return;
+        }
+    };
+
+    for item in items.iter_mut() {
+        item.boost.numerology = numerology::evaluate_numerology(&rules, numerology::boost_sats(&item.boost));
+    }
+}
+
+/// Totals up a show session from its queue, plus the streams that came in while it ran
+pub fn session_summary(db_filepath: &str, session: &dbif::ShowSessionRecord) -> Result<ShowSummary> {
+    let mut items = dbif::get_show_items_from_db(db_filepath, session, None, true)
+        .map_err(|e| anyhow!("Unable to get the show queue: {}", e))?;
+
+    attach_numerology(db_filepath, &mut items);
+
+    //Streams removed by the retention policy are still counted through their rollups
+    let streams = dbif::get_stats_from_db(db_filepath, Some(dbif::ListType::Stream), dbif::StatsGroup::Podcast, Tz::UTC, &session.filters())
+        .map_err(|e| anyhow!("Unable to total the show streams: {}", e))?
+        .total;
+
+    let mut summary = ShowSummary {
+        session: session.clone(),
+        boosts: items.len() as u64,
+        boost_value_msat: 0,
+        boost_value_msat_total: 0,
+        streams: streams.count,
+        stream_value_msat: streams.value_msat,
+        read: 0,
+        skipped: 0,
+        unread: 0,
+        top_boosters: Vec::new(),
+        numerology: Vec::new(),
+    };
+
+    //Senders are grouped without regard to case since apps don't all agree on it
+    let mut boosters: HashMap<String, ShowBooster> = HashMap::new();
+
+    for item in items {
+        summary.boost_value_msat += item.boost.value_msat;
+        summary.boost_value_msat_total += item.boost.value_msat_total;
+
+        match item.state {
+            dbif::ShowItemState::Read => summary.read += 1,
+            dbif::ShowItemState::Skipped => summary.skipped += 1,
+            dbif::ShowItemState::Unread => summary.unread += 1,
+        }
+
+        let booster = boosters.entry(item.boost.sender.to_lowercase()).or_insert_with(|| ShowBooster {
+            sender: item.boost.sender.clone(),
+            ..Default::default()
+        });
+
+        booster.count += 1;
+        booster.value_msat_total += item.boost.value_msat_total;
+
+        if !item.boost.numerology.is_empty() {
+            summary.numerology.push(item.boost);
+        }
+    }
+
+    let mut boosters: Vec<ShowBooster> = boosters.into_values().collect();
+    boosters.sort_by(|a, b| b.value_msat_total.cmp(&a.value_msat_total).then(b.count.cmp(&a.count)).then(a.sender.cmp(&b.sender)));
+    boosters.truncate(TOP_BOOSTERS);
+
+    summary.top_boosters = boosters;
+
+    Ok(summary)
+}
+
+/// Lets every open browser know a show session started or stopped
+pub fn send_session(ws_tx: &Arc<broadcast::Sender<WebSocketEvent>>, session: &dbif::ShowSessionRecord) {
+    if let Err(e) = ws_tx.send(WebSocketEvent("show".to_string(), serde_json::to_value(session).unwrap())) {
+        eprintln!("Error sending WebSocket event: {:#?}", e);
+    }
+}
+
+/// Lets every open browser know a boost joined a show's queue or changed state
+pub fn send_item(ws_tx: &Arc<broadcast::Sender<WebSocketEvent>>, item: &dbif::ShowItem) {
+    if let Err(e) = ws_tx.send(WebSocketEvent("show_item".to_string(), serde_json::to_value(item).unwrap())) {
+        eprintln!("Error sending WebSocket event: {:#?}", e);
+    }
+}
+
+/// Adds a newly received boost to the running show's queue if it belongs there
+pub async fn queue_boost(db_filepath: &str, ws_tx: &Arc<broadcast::Sender<WebSocketEvent>>, boost: &dbif::BoostRecord) {
+    if boost.list_type != dbif::ListType::Boost || boost.hidden || boost.quarantined {
+        return;
+    }
+
+    let (filepath, index) = (db_filepath.to_string(), boost.index);
+
+    let result = dbif::run_blocking(move || {
+        match dbif::get_active_show_session_from_db(&filepath)? {
+            Some(session) => dbif::get_show_item_from_db(&filepath, &session, index, true),
+            None => Ok(None),
+        }
+    }).await;
+
+    match result {
+        Ok(Some(mut item)) => {
+            item.boost.numerology = boost.numerology.clone();
+            send_item(ws_tx, &item);
+        }
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("** Error checking the show queue: {}", e);
+        }
+    }
+}
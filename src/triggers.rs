@@ -6,6 +6,7 @@ use rosc::{encoder, OscMessage, OscPacket, OscType};
 use serde::Serialize;
 use std::net::UdpSocket;
 use serde_json::json;
+use crate::numerology;
 
 /// Represents a boost record with associated client and server effects
 #[derive(Serialize, Clone, Debug)]
@@ -142,6 +143,15 @@ pub async fn test_trigger(db_filepath: &str, trigger: &dbif::TriggerRecord) -> R
         custom_value: None,
         memo: None,
         payment_info: None,
+        hidden: false,
+        quarantined: false,
+        imported: false,
+        nostr_refs: Vec::new(),
+        numerology: Vec::new(),
+        tags: Vec::new(),
+        note: None,
+        pinned: false,
+        supporter: None,
     };
 
     let server_effects = match get_server_effect(&trigger) {
@@ -168,12 +178,20 @@ pub async fn test_trigger(db_filepath: &str, trigger: &dbif::TriggerRecord) -> R
 /// Gets multiple boosts with their associated trigger effects
 pub async fn get_boosts_with_triggers(
     db_filepath: &str,
-    boosts: Vec<dbif::BoostRecord>,
+    mut boosts: Vec<dbif::BoostRecord>,
 ) -> Result<Vec<BoostWithEffects>> {
-    let triggers = dbif::get_triggers_from_db(db_filepath)
-        .map_err(|e| anyhow::anyhow!("Unable to get triggers from database: {}", e))?;
+    let filepath = db_filepath.to_string();
 
-    let default_sound = get_default_sound_effect(db_filepath)?;
+    let (triggers, default_sound) = dbif::run_blocking(move || {
+        let triggers = dbif::get_triggers_from_db(&filepath)?;
+        let default_sound = get_default_sound_effect(&filepath)?;
+        Ok((triggers, default_sound))
+    })
+    .await
+    .map_err(|e| anyhow::anyhow!("Unable to get triggers from database: {}", e))?;
+
+    // Numerology is needed to match numerology filters
+    numerology::attach_numerology(db_filepath, &mut boosts).await;
 
     let boosts_with_triggers = boosts
         .into_iter()
@@ -268,6 +286,26 @@ fn matches_filter_conditions(trigger: &dbif::TriggerRecord, boost: &dbif::BoostR
         && matches_filter_condition(&trigger.sender_equality, &trigger.sender, &boost.sender)
         && matches_filter_condition(&trigger.app_equality, &trigger.app, &boost.app)
         && matches_filter_condition(&trigger.podcast_equality, &trigger.podcast, &boost.podcast)
+        && matches_numerology_condition(trigger, boost)
+}
+
+/// Checks the trigger's numerology filter against the emojis and descriptions matched by the boost
+fn matches_numerology_condition(trigger: &dbif::TriggerRecord, boost: &dbif::BoostRecord) -> bool {
+    if trigger.numerology_equality.is_none() || trigger.numerology.is_none() {
+        return true;
+    }
+
+    let values: Vec<String> = boost.numerology.iter()
+        .flat_map(|num| vec![num.emoji.clone(), num.description.clone()])
+        .flatten()
+        .collect();
+
+    if trigger.numerology_equality.as_deref() == Some("!=") {
+        // None of the matched numerology may equal the filter
+        values.iter().all(|value| matches_filter_condition(&trigger.numerology_equality, &trigger.numerology, value))
+    } else {
+        values.iter().any(|value| matches_filter_condition(&trigger.numerology_equality, &trigger.numerology, value))
+    }
 }
 
 /// Matches a single filter condition against a value
@@ -373,9 +411,46 @@ pub async fn run_server_effects(
     db_filepath: &str,
     boost_with_effects: &BoostWithEffects,
 ) -> Result<()> {
+    let direction = if boost_with_effects.boost.payment_info.is_some() {
+        "outgoing"
+    } else {
+        "incoming"
+    };
+
+    let payload = WebhookPayload {
+        direction: direction.to_string(),
+        boost: boost_with_effects.boost.clone(),
+    };
+
     for effect in &boost_with_effects.server_effects {
         if let Some(webhook) = &effect.webhook {
-            handle_webhook_effect(db_filepath, webhook, boost_with_effects).await;
+            handle_webhook_effect(db_filepath, webhook, &payload).await;
+        }
+
+        if let Some(osc) = &effect.osc {
+            handle_osc_effect(db_filepath, osc).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the webhooks and OSC messages of the enabled lockout triggers, sending them the lockout
+pub async fn process_lockout_triggers<T: Serialize>(db_filepath: &str, lockout: &T) -> Result<()> {
+    let filepath = db_filepath.to_string();
+
+    let triggers = dbif::run_blocking(move || dbif::get_triggers_from_db(&filepath))
+        .await
+        .map_err(|e| anyhow::anyhow!("Unable to load triggers from database: {}", e))?;
+
+    for trigger in triggers.iter().filter(|trigger| trigger.enabled && trigger.on_lockout) {
+        let effect = match get_server_effect(trigger) {
+            Some(effect) => effect,
+            None => continue,
+        };
+
+        if let Some(webhook) = &effect.webhook {
+            handle_webhook_effect(db_filepath, webhook, lockout).await;
         }
 
         if let Some(osc) = &effect.osc {
@@ -387,17 +462,19 @@ pub async fn run_server_effects(
 }
 
 /// Handles sending a webhook and updating its status
-async fn handle_webhook_effect(
+async fn handle_webhook_effect<T: Serialize>(
     db_filepath: &str,
     webhook: &WebhookEffect,
-    boost_with_effects: &BoostWithEffects,
+    payload: &T,
 ) {
     let timestamp = Utc::now().timestamp();
 
-    match send_webhook(webhook, boost_with_effects).await {
+    match send_webhook(webhook, payload).await {
         Ok(successful) => {
+            let (filepath, index) = (db_filepath.to_string(), webhook.index);
+
             if let Err(e) =
-                dbif::set_trigger_webhook_last_request(db_filepath, webhook.index, successful, timestamp)
+                dbif::run_blocking(move || dbif::set_trigger_webhook_last_request(&filepath, index, successful, timestamp)).await
             {
                 eprintln!("Error setting trigger webhook last request status: {}", e);
             }
@@ -414,7 +491,9 @@ async fn handle_osc_effect(db_filepath: &str, osc: &OscEffect) {
 
     match send_osc(osc).await {
         Ok(successful) => {
-            if let Err(e) = dbif::set_trigger_osc_last_request(db_filepath, osc.index, successful, timestamp) {
+            let (filepath, index) = (db_filepath.to_string(), osc.index);
+
+            if let Err(e) = dbif::run_blocking(move || dbif::set_trigger_osc_last_request(&filepath, index, successful, timestamp)).await {
                 eprintln!("Error setting trigger OSC last request status: {}", e);
             }
         }
@@ -452,10 +531,10 @@ fn get_sound(trigger: &dbif::TriggerRecord) -> Option<SoundEffect> {
     })
 }
 
-/// Sends an HTTP webhook with boost data
-async fn send_webhook(
+/// Sends an HTTP webhook with boost or lockout data
+async fn send_webhook<T: Serialize>(
     effect: &WebhookEffect,
-    boost_with_effects: &BoostWithEffects,
+    payload: &T,
 ) -> Result<bool> {
     let mut headers = HeaderMap::new();
 
@@ -483,18 +562,7 @@ async fn send_webhook(
         .build()
         .context("Unable to build reqwest client")?;
 
-    let direction = if boost_with_effects.boost.payment_info.is_some() {
-        "outgoing"
-    } else {
-        "incoming"
-    };
-
-    let payload = WebhookPayload {
-        direction: direction.to_string(),
-        boost: boost_with_effects.boost.clone(),
-    };
-
-    let json = serde_json::to_string_pretty(&payload)
+    let json = serde_json::to_string_pretty(payload)
         .context("Unable to encode webhook payload as JSON")?;
 
     let res = client
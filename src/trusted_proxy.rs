@@ -0,0 +1,124 @@
+use anyhow::{anyhow, Result};
+use axum::http::{HeaderMap, HeaderName};
+use dbif::UserRole;
+use std::net::IpAddr;
+
+/// An authenticating proxy (Authelia, oauth2-proxy and so on) in front of Helipad. Its header says who is logged in,
+/// and Helipad's own password logins are turned off.
+#[derive(Clone, Debug)]
+pub struct TrustedProxy {
+    pub header: HeaderName,
+    pub ips: Vec<IpAddr>,
+    pub default_role: Option<UserRole>, // for proxy users without a Helipad account
+}
+
+impl TrustedProxy {
+    /// Reads the proxy options. There's no proxy unless a header is given, and then it needs at least one address.
+    pub fn from_config(header: Option<&str>, ips: Option<&str>, default_role: Option<&str>) -> Result<Option<Self>> {
+        let header = match header.map(str::trim).filter(|header| !header.is_empty()) {
+            Some(header) => HeaderName::from_bytes(header.as_bytes()).map_err(|_| anyhow!("Invalid trusted proxy header: {}", header))?,
+            None => return Ok(None),
+        };
+
+        let ips = ips.unwrap_or("")
+            .split(',')
+            .map(str::trim)
+            .filter(|ip| !ip.is_empty())
+            .map(|ip| ip.parse().map_err(|_| anyhow!("Invalid trusted proxy address: {}", ip)))
+            .collect::<Result<Vec<IpAddr>>>()?;
+
+        if ips.is_empty() {
+            return Err(anyhow!("trusted_proxy_ips is needed with trusted_proxy_header"));
+        }
+
+        let default_role = match default_role.map(str::trim).filter(|role| !role.is_empty()) {
+            Some(role) => Some(UserRole::from_name(role).ok_or_else(|| anyhow!("Invalid trusted proxy role: {}", role))?),
+            None => None,
+        };
+
+        Ok(Some(Self { header, ips, default_role }))
+    }
+
+    /// The username the proxy sent, if the request came from the proxy. Anyone else could set the header themselves.
+    pub fn username(&self, addr: IpAddr, headers: &HeaderMap) -> Option<String> {
+        if !self.ips.contains(&addr.to_canonical()) {
+            return None;
+        }
+
+        headers.get(&self.header)
+            .and_then(|val| val.to_str().ok())
+            .map(str::trim)
+            .filter(|username| !username.is_empty())
+            .map(str::to_string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn proxy() -> TrustedProxy {
+        TrustedProxy::from_config(Some("Remote-User"), Some("127.0.0.1, ::1"), Some("viewer"))
+            .expect("valid config")
+            .expect("proxy configured")
+    }
+
+    fn headers(username: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("remote-user", HeaderValue::from_str(username).unwrap());
+        headers
+    }
+
+    #[test]
+    fn no_header_means_no_proxy() {
+        assert!(TrustedProxy::from_config(None, Some("127.0.0.1"), None).unwrap().is_none());
+        assert!(TrustedProxy::from_config(Some("  "), Some("127.0.0.1"), None).unwrap().is_none());
+    }
+
+    #[test]
+    fn config_needs_valid_addresses_and_role() {
+        assert!(TrustedProxy::from_config(Some("Remote-User"), None, None).is_err());
+        assert!(TrustedProxy::from_config(Some("Remote-User"), Some(" , "), None).is_err());
+        assert!(TrustedProxy::from_config(Some("Remote-User"), Some("proxy.local"), None).is_err());
+        assert!(TrustedProxy::from_config(Some("Remote-User"), Some("127.0.0.1"), Some("owner")).is_err());
+        assert!(TrustedProxy::from_config(Some("Remote User"), Some("127.0.0.1"), None).is_err());
+
+        let proxy = proxy();
+        assert_eq!(proxy.header.as_str(), "remote-user");
+        assert_eq!(proxy.ips.len(), 2);
+        assert_eq!(proxy.default_role, Some(UserRole::Viewer));
+    }
+
+    #[test]
+    fn header_from_proxy_is_used() {
+        let proxy = proxy();
+
+        assert_eq!(proxy.username("127.0.0.1".parse().unwrap(), &headers(" alice ")).as_deref(), Some("alice"));
+        assert_eq!(proxy.username("::1".parse().unwrap(), &headers("alice")).as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn header_from_elsewhere_is_ignored() {
+        let proxy = proxy();
+
+        assert_eq!(proxy.username("10.0.0.5".parse().unwrap(), &headers("alice")), None);
+        assert_eq!(proxy.username("::ffff:10.0.0.5".parse().unwrap(), &headers("alice")), None);
+    }
+
+    #[test]
+    fn ipv4_mapped_proxy_address_is_trusted() {
+        let proxy = proxy();
+
+        // a dual-stack listener sees IPv4 clients like this
+        assert_eq!(proxy.username("::ffff:127.0.0.1".parse().unwrap(), &headers("alice")).as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn missing_or_empty_header_is_no_user() {
+        let proxy = proxy();
+
+        assert_eq!(proxy.username("127.0.0.1".parse().unwrap(), &HeaderMap::new()), None);
+        assert_eq!(proxy.username("127.0.0.1".parse().unwrap(), &headers("  ")), None);
+    }
+}
@@ -0,0 +1,159 @@
+use anyhow::{anyhow, Result};
+use data_encoding::{BASE32_NOPAD, HEXLOWER};
+use qrcode::render::svg;
+use qrcode::QrCode;
+use rand::RngExt;
+use sha2::{Digest, Sha256};
+use totp_rs::{Algorithm, TOTP};
+
+/// Name shown in authenticator apps
+const ISSUER: &str = "Helipad";
+
+/// Seconds each code lasts. Codes from one step either side are accepted too.
+const TOTP_STEP: u64 = 30;
+
+/// Recovery codes made at a time
+const RECOVERY_CODE_COUNT: usize = 10;
+
+/// A new secret shown as a QR code and as text for apps that can't scan one
+pub struct Enrolment {
+    pub secret: String,
+    pub url: String,
+    pub qr_svg: String,
+}
+
+/// Two-factor settings are kept by username, with an empty one for the shared password login
+pub fn login_key(username: Option<&str>) -> &str {
+    username.unwrap_or("")
+}
+
+fn totp(secret: &str, username: &str) -> Result<TOTP> {
+    let bytes = BASE32_NOPAD.decode(secret.as_bytes()).map_err(|e| anyhow!("Invalid two-factor secret: {}", e))?;
+    let account = if username.is_empty() { ISSUER } else { username };
+
+    TOTP::new(Algorithm::SHA1, 6, 1, TOTP_STEP, bytes, Some(ISSUER.to_string()), account.to_string())
+        .map_err(|e| anyhow!("Unable to set up two-factor: {}", e))
+}
+
+/// Finds the time step a code is for, if it's valid now
+fn check_totp(totp: &TOTP, code: &str) -> Option<i64> {
+    let now = chrono::Utc::now().timestamp() as u64;
+    let step = now / TOTP_STEP;
+
+    [step, step - 1, step + 1].iter()
+        .find(|step| totp.generate(*step * TOTP_STEP) == code)
+        .map(|step| *step as i64)
+}
+
+fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase();
+    HEXLOWER.encode(&Sha256::digest(normalized.as_bytes()))
+}
+
+/// Makes a fresh set of recovery codes, returning them with their hashes
+fn new_recovery_codes() -> (Vec<String>, Vec<String>) {
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT).map(|_| {
+        let mut bytes = [0u8; 5];
+        rand::rng().fill(&mut bytes);
+
+        let code = BASE32_NOPAD.encode(&bytes).to_lowercase();
+        format!("{}-{}", &code[..4], &code[4..])
+    }).collect();
+
+    let hashes = codes.iter().map(|code| hash_recovery_code(code)).collect();
+
+    (codes, hashes)
+}
+
+pub fn is_enabled(db_filepath: &str, username: &str) -> Result<bool> {
+    let two_factor = dbif::get_two_factor_from_db(db_filepath, username).map_err(|e| anyhow!("{}", e))?;
+    Ok(two_factor.is_some_and(|two_factor| two_factor.enabled))
+}
+
+/// Makes a new secret for a login. Two-factor isn't on until a code from it is confirmed.
+pub fn start_enrolment(db_filepath: &str, username: &str) -> Result<Enrolment> {
+    let mut bytes = [0u8; 20];
+    rand::rng().fill(&mut bytes);
+
+    let secret = BASE32_NOPAD.encode(&bytes);
+    let url = totp(&secret, username)?.get_url();
+
+    let qr_svg = QrCode::new(url.as_bytes())
+        .map_err(|e| anyhow!("Unable to make QR code: {}", e))?
+        .render::<svg::Color>()
+        .min_dimensions(200, 200)
+        .quiet_zone(true)
+        .build();
+
+    dbif::start_two_factor_in_db(db_filepath, username, &secret).map_err(|e| anyhow!("{}", e))?;
+
+    Ok(Enrolment { secret, url, qr_svg })
+}
+
+/// Turns two-factor on once a code from the new secret checks out, returning the recovery codes
+pub fn confirm_enrolment(db_filepath: &str, username: &str, code: &str) -> Result<Vec<String>> {
+    let two_factor = match dbif::get_two_factor_from_db(db_filepath, username).map_err(|e| anyhow!("{}", e))? {
+        Some(two_factor) if !two_factor.enabled => two_factor,
+        Some(_) => return Err(anyhow!("Two-factor authentication is already on")),
+        None => return Err(anyhow!("Start setting up two-factor authentication first")),
+    };
+
+    let step = match check_totp(&totp(&two_factor.secret, username)?, code.trim()) {
+        Some(step) => step,
+        None => return Err(anyhow!("That code isn't right. Check the time on your device and try again")),
+    };
+
+    let (codes, hashes) = new_recovery_codes();
+    dbif::enable_two_factor_in_db(db_filepath, username, step, &hashes).map_err(|e| anyhow!("{}", e))?;
+
+    Ok(codes)
+}
+
+/// Checks a code from an authenticator app, or a recovery code, for a login with two-factor on. Each code only
+/// works once.
+pub fn verify_code(db_filepath: &str, username: &str, code: &str) -> Result<bool> {
+    let two_factor = match dbif::get_two_factor_from_db(db_filepath, username).map_err(|e| anyhow!("{}", e))? {
+        Some(two_factor) if two_factor.enabled => two_factor,
+        _ => return Ok(false),
+    };
+
+    let code = code.trim();
+
+    if code.len() == 6 && code.chars().all(|c| c.is_ascii_digit()) {
+        return match check_totp(&totp(&two_factor.secret, username)?, code) {
+            Some(step) => dbif::use_two_factor_step_in_db(db_filepath, username, step).map_err(|e| anyhow!("{}", e)),
+            None => Ok(false),
+        };
+    }
+
+    let used = dbif::use_recovery_code_in_db(db_filepath, username, &hash_recovery_code(code)).map_err(|e| anyhow!("{}", e))?;
+
+    if used {
+        println!("** Two-factor recovery code used for {}", if username.is_empty() { "the shared login" } else { username });
+    }
+
+    Ok(used)
+}
+
+/// Replaces the recovery codes after checking a code
+pub fn regenerate_recovery_codes(db_filepath: &str, username: &str, code: &str) -> Result<Vec<String>> {
+    if !verify_code(db_filepath, username, code)? {
+        return Err(anyhow!("That code isn't right"));
+    }
+
+    let (codes, hashes) = new_recovery_codes();
+    dbif::set_recovery_codes_in_db(db_filepath, username, &hashes).map_err(|e| anyhow!("{}", e))?;
+
+    Ok(codes)
+}
+
+/// Turns two-factor off after checking a code. A setup that was never confirmed can be cancelled without one.
+pub fn disable(db_filepath: &str, username: &str, code: &str) -> Result<()> {
+    if is_enabled(db_filepath, username)? && !verify_code(db_filepath, username, code)? {
+        return Err(anyhow!("That code isn't right"));
+    }
+
+    dbif::delete_two_factor_from_db(db_filepath, username).map_err(|e| anyhow!("{}", e))?;
+
+    Ok(())
+}
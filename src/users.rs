@@ -0,0 +1,110 @@
+use anyhow::{anyhow, Result};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use rand::RngExt;
+
+/// Shortest password an account can have
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// Longest username an account can have
+const MAX_USERNAME_LENGTH: usize = 50;
+
+/// Hashes a password with Argon2 and a random salt, in the PHC string format
+pub fn hash_password(password: &str) -> Result<String> {
+    let mut salt = [0u8; 16];
+    rand::rng().fill(&mut salt);
+
+    let salt = SaltString::encode_b64(&salt).map_err(|e| anyhow!("Unable to encode salt: {}", e))?;
+
+    let hash = Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| anyhow!("Unable to hash password: {}", e))?;
+
+    Ok(hash.to_string())
+}
+
+/// Checks a password against a hash made by `hash_password`
+pub fn verify_password(hash: &str, password: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(hash) => Argon2::default().verify_password(password.as_bytes(), &hash).is_ok(),
+        Err(e) => {
+            eprintln!("** Invalid password hash: {}", e);
+            false
+        }
+    }
+}
+
+/// Usernames are letters, numbers and `.`, `_` or `-`
+pub fn validate_username(username: &str) -> Result<()> {
+    if username.is_empty() || username.chars().count() > MAX_USERNAME_LENGTH {
+        return Err(anyhow!("Usernames have to be 1 to {} characters long", MAX_USERNAME_LENGTH));
+    }
+
+    if !username.chars().all(|c| c.is_alphanumeric() || c == '.' || c == '_' || c == '-') {
+        return Err(anyhow!("Usernames can only have letters, numbers, dots, dashes and underscores"));
+    }
+
+    Ok(())
+}
+
+pub fn validate_password(password: &str) -> Result<()> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(anyhow!("Passwords have to be at least {} characters long", MIN_PASSWORD_LENGTH));
+    }
+
+    Ok(())
+}
+
+/// Checks a username and password, returning the account if they match. Hashing is slow on purpose, so
+/// call this from a blocking task.
+pub fn authenticate(db_filepath: &str, username: &str, password: &str) -> Result<Option<dbif::UserRecord>> {
+    let user = dbif::get_user_by_name_from_db(db_filepath, username)
+        .map_err(|e| anyhow!("Unable to look up user: {}", e))?;
+
+    let user = match user {
+        Some(user) if verify_password(&user.password_hash, password) => user,
+        _ => return Ok(None),
+    };
+
+    if let Err(e) = dbif::set_user_last_login_in_db(db_filepath, user.index) {
+        eprintln!("** Unable to save last login: {}", e);
+    }
+
+    Ok(Some(user))
+}
+
+/// Adds an account after checking the username and password
+pub fn add_user(db_filepath: &str, username: &str, password: &str, role: dbif::UserRole) -> Result<u64> {
+    let username = username.trim();
+
+    validate_username(username)?;
+    validate_password(password)?;
+
+    let hash = hash_password(password)?;
+
+    dbif::add_user_to_db(db_filepath, username, &hash, role).map_err(|e| anyhow!("{}", e))
+}
+
+/// Changes an account's role and, if one is given, its password. Returns false if there's no such account.
+pub fn update_user(db_filepath: &str, index: u64, role: Option<dbif::UserRole>, password: Option<&str>) -> Result<bool> {
+    let password = password.filter(|password| !password.is_empty());
+
+    if let Some(password) = password {
+        validate_password(password)?;
+    }
+
+    if dbif::get_user_from_db(db_filepath, index).map_err(|e| anyhow!("{}", e))?.is_none() {
+        return Ok(false);
+    }
+
+    if let Some(role) = role {
+        dbif::set_user_role_in_db(db_filepath, index, role).map_err(|e| anyhow!("{}", e))?;
+    }
+
+    if let Some(password) = password {
+        let hash = hash_password(password)?;
+        dbif::set_user_password_in_db(db_filepath, index, &hash).map_err(|e| anyhow!("{}", e))?;
+    }
+
+    Ok(true)
+}
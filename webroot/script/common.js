@@ -158,7 +158,7 @@ class BalanceTracker {
             contentType: "application/json; charset=utf-8",
             dataType: "json",
             error: (xhr) => {
-                if (xhr.status === 403) {
+                if (notLoggedIn(xhr)) {
                     window.location.href = "/login";
                 }
             },
@@ -3,16 +3,16 @@ $(document).ready(function () {
     let inbox = messages.find('div.msg_history');
     let appIconUrlBase = 'image/';
     let appList = {};
-    let numerologyList = [];
     let messageIds = [];
+    let liveMessageIds = []; // received through this node, leaving out imported boosts
     let currentInvoiceIndex = null;
     let nodeInfo = null;
     let settings = null;
     let filters = {};
-    let nostrPool = null;
-    let nostrRelays = ["wss://relay.damus.io", "wss://nos.lol", "wss://relay.nostr.band"];
     let balanceTracker = null;
     let triggers = null;
+    let showSession = null;
+    let showItems = {};
 
     let config = {
         'listUrl': '/api/v1/boosts',
@@ -40,6 +40,11 @@ $(document).ready(function () {
             messageIds.push($(this).data('msgid'));
         });
 
+        liveMessageIds = [];
+        $('div.outgoing_msg:not(.imported)').map(function () {
+            liveMessageIds.push($(this).data('msgid'));
+        });
+
         //Override shouldPew for receiving our first boost
         if ($('div.nodata').length && startIndex === 1) {
             shouldPew = true;
@@ -72,7 +77,7 @@ $(document).ready(function () {
             contentType: "application/json; charset=utf-8",
             dataType: "json",
             error: function (xhr) {
-                if (xhr.status === 403) {
+                if (notLoggedIn(xhr)) {
                     window.location.href = "/login";
                 }
             },
@@ -80,7 +85,8 @@ $(document).ready(function () {
                 renderBoosts(data, max, scrollToTop, shouldPew);
 
                 if (data.length > 0) {
-                    currentInvoiceIndex = Math.max(currentInvoiceIndex, ...data.map(a => a.index));
+                    //Imported boosts are numbered apart from LND's indexes, so they don't move the live position
+                    currentInvoiceIndex = Math.max(currentInvoiceIndex, ...data.filter(a => !a.imported).map(a => a.index));
                 }
             }
         });
@@ -92,8 +98,6 @@ $(document).ready(function () {
                 return; // already in the list
             }
 
-            let displayedMessageCount = $('div.outgoing_msg').length;
-
             let boostMessage = element.message || "";
             let boostSats = Math.trunc(element.value_msat_total / 1000) || Math.trunc(element.value_msat / 1000);
             let boostActualSats = Math.trunc(element.value_msat / 1000) || 0;
@@ -137,6 +141,9 @@ $(document).ready(function () {
             if (config.pluralName == 'sent boosts' && boostTlv.name) {
                 boostPerson = `to ${boostTlv.name}`;
             }
+            else if (element.sender.trim() != "" && element.supporter) {
+                boostPerson = `from <a href="#" class="supporter-link" data-supporter="${element.supporter}">${element.sender}</a>`;
+            }
             else if (element.sender.trim() != "") {
                 boostPerson = `from ${element.sender}`;
             }
@@ -191,8 +198,8 @@ $(document).ready(function () {
                 </span>` + boostDisplayAmount;
             }
 
-            //Determine the numerology behind the sat amount
-            boostNumerology = gatherNumerology(boostSats);
+            //Show the numerology Helipad matched for the sat amount
+            boostNumerology = gatherNumerology(element.numerology || []);
 
             //Generate remote item and link to podcastindex website if one exists
             let boostRemoteInfo = '';
@@ -230,7 +237,7 @@ $(document).ready(function () {
 
             //Build the message element
             elMessage = `
-            <div class="outgoing_msg message" data-msgid="${boostIndex}" style="width: 100%">
+            <div class="outgoing_msg message${element.imported ? ' imported' : ''}" data-msgid="${boostIndex}" style="width: 100%">
             <div class="sent_msg">
                 <div class="sent_withd_msg">
                 <span class="app">
@@ -253,12 +260,22 @@ $(document).ready(function () {
                 <div class="boost-message" style="clear: both">
                     ${boostMessage}
                 </div>
+                <div class="boost-annotations"></div>
+                <div class="boost-show"></div>
                 </div>
             </div>
             </div>`;
 
-            //Insert the message in the right spot
-            if (displayedMessageCount == 0) {
+            //Insert the message in the right spot. Imported boosts come after this node's own, in the order they're listed.
+            if (element.imported) {
+                const $loadMore = inbox.find('div.loadmore');
+
+                if ($loadMore.length) {
+                    $loadMore.before(elMessage);
+                } else {
+                    inbox.append(elMessage);
+                }
+            } else if (liveMessageIds.length == 0) {
                 inbox.prepend(elMessage);
                 //Scroll the list back up if necessary
                 if (scrollToTop) {
@@ -267,7 +284,7 @@ $(document).ready(function () {
             } else {
                 //Get the closest matching id
                 var prepend = false;
-                let closestId = closest(messageIds, boostIndex);
+                let closestId = closest(liveMessageIds, boostIndex);
                 if (boostIndex < closestId) {
                     prepend = true;
                 }
@@ -309,17 +326,32 @@ $(document).ready(function () {
                 renderFetchMetadataButton(boostIndex);
             }
 
-            // Replace Nostr npub references with names
-            if (boostMessage.indexOf('nostr:') !== -1) {
-                replaceNostrReferences(boostMessage).then((message) => {
-                    $('div.outgoing_msg[data-msgid=' + boostIndex + '] .boost-message').html(message);
-                });
+            // Show hide button for received boosts/streams
+            if (config.pluralName == 'boosts' || config.pluralName == 'streams') {
+                renderHideButton(boostIndex);
+            }
+
+            // Show tags, notes and pins, and the button to change them
+            renderAnnotation(boostIndex, element);
+            renderAnnotateButton(boostIndex);
+
+            // Show whether the boost has been read out during the running show
+            renderShowItem(boostIndex);
+
+            // Replace Nostr references with the names resolved by Helipad
+            if (element.nostr_refs && element.nostr_refs.length) {
+                const $message = $('div.outgoing_msg[data-msgid=' + boostIndex + '] .boost-message');
+                $message.html(replaceNostrReferences($message.html(), element.nostr_refs));
             }
 
             //Update the tracking array
             messageIds.push(boostIndex);
             messageIds = messageIds.sort((a, b) => a - b);
 
+            if (!element.imported) {
+                liveMessageIds.push(boostIndex);
+            }
+
             let now = new Date();
 
             if (shouldPew && config.effects && (now - timestamp) < 600000) { // if enabled/shouldPew and received within past 10 mins
@@ -366,7 +398,8 @@ $(document).ready(function () {
         //Update the csv export link
         const bcount = $('div.outgoing_msg').length;
         const csvindex = $('div.outgoing_msg:first').data('msgid') ?? currentInvoiceIndex;
-        const endex = $('div.outgoing_msg:last').data('msgid') ?? 1;
+        const $lastMessage = $('div.outgoing_msg:last');
+        const endex = $lastMessage.hasClass('imported') ? 0 : ($lastMessage.data('msgid') ?? 1);
 
         $('span.csv a').attr('href', '/csv?list=' + config.listType + '&index=' + csvindex + '&count=' + bcount + '&old=true' + '&end=' + endex);
 
@@ -377,55 +410,8 @@ $(document).ready(function () {
         }
     }
 
-    //Determine any meaning behind this sat value
-    function parseNumerology(value) {
-        const numerology = []
-        const substitutions = []
-
-        let textValue = value.toString()
-
-        // loop through numerology and capture matches
-        numerologyList.forEach(item => {
-            if (item.equality == '<' && value < item.amount) {
-                numerology.push(item)
-            }
-            else if (item.equality == '>=' && value >= item.amount) {
-                numerology.push(item)
-            }
-            else if (item.equality == '=' && value == item.amount) {
-                numerology.push(item)
-                textValue = ''
-            }
-            else if (item.equality == '=~') {
-                const regex = new RegExp(item.amount, 'g')
-
-                for (let match of textValue.matchAll(regex)) {
-                    // replace match with X's to prevent further matches
-                    const replace = match[0].replaceAll(new RegExp('[0-9]', 'g'), 'X')
-                    textValue = textValue.replace(match[0], replace)
-
-                    // keep track of where this was matched in the value
-                    substitutions[match.index] = item
-
-                    // add gap to fill in later
-                    numerology.push(null)
-                }
-            }
-        })
-
-        // fill in substitutions in order of where they appear in the value
-        let subs = Object.values(substitutions)
-
-        numerology.forEach((item, idx) => {
-            numerology[idx] = item || subs.shift()
-        })
-
-        return numerology
-    };
-
     //Get the emojis that correspond to the donation amount
-    function gatherNumerology(value) {
-        const matches = parseNumerology(value);
+    function gatherNumerology(matches) {
         const emojis = matches.map(num => num.emoji);
         const descriptions = matches.map(num => num.description);
 
@@ -464,47 +450,11 @@ $(document).ready(function () {
         });
     }
 
-    //Set up nostr simplepool if resolve_nostr_refs enabled
-    function initNostr() {
-        if (settings.resolve_nostr_refs) {
-            nostrPool = new NostrTools.SimplePool();
-        }
-    }
-
-    //Resolve a nostr profile reference into a name
-    async function resolveNostrName(ref) {
-        if (!nostrPool || !ref.profile) return null;
-
-        const cache = JSON.parse(localStorage.getItem("nostrNames")) || {};
-
-        if (!cache[ref.profile.pubkey]) {
-            const useRelays = ref.profile.relays.length ? ref.profile.relays : nostrRelays;
-            const results = await nostrPool.querySync(useRelays, {
-                authors: [ref.profile.pubkey],
-                kinds: [0]
-            });
-            const event = results.find(result => result.pubkey === ref.profile.pubkey);
-
-            if (!event) return null;
-
-            const { display_name, name } = JSON.parse(event.content);
-
-            cache[ref.profile.pubkey] = display_name || name;
-
-            localStorage.setItem("nostrNames", JSON.stringify(cache));
-        }
-
-        return cache[ref.profile.pubkey]
-    }
-
     //Replace nostr npubs with an njump link or actual name
-    async function replaceNostrReferences(message) {
-        const refs = NostrTools.parseReferences({content: message});
-
+    function replaceNostrReferences(message, refs) {
         for (let ref of refs) {
-            const name = await resolveNostrName(ref);
-            const displayName = name || ref.text;
-            message = message.replace(ref.text, `<a href="https://njump.me/${escapeHTML(ref.text)}" target="_blank">${escapeHTML(displayName)}</a>`);
+            const displayName = ref.name ? `@${ref.name}` : ref.text;
+            message = message.replaceAll(ref.text, `<a href="https://njump.me/${escapeHTML(ref.text.replace('nostr:', ''))}" target="_blank">${escapeHTML(displayName)}</a>`);
         }
 
         return message;
@@ -525,6 +475,15 @@ $(document).ready(function () {
             ) {
                 renderBoosts([args[0]], 0, true, true);
             }
+            else if (event == "annotation" && args[0].sent == (config.listType == 'sent')) {
+                renderAnnotation(args[0].index, args[0]);
+            }
+            else if (event == "show" && config.listType == 'boosts') {
+                showSessionChanged(args[0]);
+            }
+            else if (event == "show_item" && config.listType == 'boosts') {
+                showItemChanged(args[0]);
+            }
         });
     }
 
@@ -537,7 +496,7 @@ $(document).ready(function () {
             contentType: "application/json; charset=utf-8",
             dataType: "json",
             error: function (xhr) {
-                if (xhr.status === 403) {
+                if (notLoggedIn(xhr)) {
                     window.location.href = "/login";
                 }
             },
@@ -570,18 +529,6 @@ $(document).ready(function () {
         return appList;
     }
 
-    //Get the defined numerology
-    async function getNumerologyList() {
-        numerologyList = await $.ajax({
-            url: "/numerology.json",
-            type: "GET",
-            contentType: "application/json; charset=utf-8",
-            dataType: "json"
-        });
-
-        return numerologyList;
-    }
-
     //Render the boost info modal
     function renderBoostInfo() {
         if ($('#boostInfo').length) {
@@ -935,7 +882,7 @@ $(document).ready(function () {
                 type: 'POST',
                 contentType: 'application/json; charset=utf-8',
                 error: function(xhr) {
-                    if (xhr.status === 403) {
+                    if (notLoggedIn(xhr)) {
                         window.location.href = "/login";
                         return;
                     }
@@ -970,6 +917,512 @@ $(document).ready(function () {
         });
     }
 
+    function renderHideButton(index) {
+        const icon = `<svg class="mr-1" xmlns="http://www.w3.org/2000/svg" height="1em" viewBox="0 0 640 512" fill="currentColor"><!--!Font Awesome Free 6.5.1 by @fontawesome - https://fontawesome.com License - https://fontawesome.com/license/free Copyright 2024 Fonticons, Inc.--><path d="M38.8 5.1C28.4-3.1 13.3-1.2 5.1 9.2S-1.2 34.7 9.2 42.9l592 464c10.4 8.2 25.5 6.3 33.7-4.1s6.3-25.5-4.1-33.7L525.6 386.7c39.6-40.6 66.4-86.1 79.9-118.4c3.3-7.9 3.3-16.7 0-24.6c-14.9-35.7-46.2-87.7-93-131.1C465.5 68.8 400.8 32 320 32c-68.2 0-125 26.3-169.3 60.8L38.8 5.1zM223.1 149.5C248.6 126.2 282.7 112 320 112c79.5 0 144 64.5 144 144c0 24.9-6.3 48.3-17.4 68.7L408 294.5c8.4-19.3 10.6-41.4 4.8-63.3c-11.1-41.5-47.8-69.4-88.6-71.1c-5.8-.2-9.2 6.1-7.4 11.7c2.1 6.4 3.3 13.2 3.3 20.3c0 10.2-2.4 19.8-6.6 28.3l-90.3-70.8zM373 389.9c-16.4 6.5-34.3 10.1-53 10.1c-79.5 0-144-64.5-144-144c0-6.9 .5-13.6 1.4-20.2L83.1 161.5C60.3 191.2 44 220.8 34.5 243.7c-3.3 7.9-3.3 16.7 0 24.6c14.9 35.7 46.2 87.7 93 131.1C174.5 443.2 239.2 480 320 480c47.8 0 89.9-12.9 126.2-32.5L373 389.9z"/></svg>`;
+        const $div = $('div.outgoing_msg[data-msgid=' + index + '] .reply-to-boost-div');
+
+        $div.append(`
+            <a
+                href="#"
+                class="hide-boost btn btn-sm btn-outline-secondary position-relative d-inline-flex align-items-center ml-2"
+                title="Hide this boost"
+            >
+                ${icon} Hide
+            </a>
+        `);
+
+        // Attach click handler
+        $div.find('.hide-boost').click(function(e) {
+            e.preventDefault();
+
+            if (!confirm('Hide this boost? It can be restored from the Moderation settings.')) {
+                return;
+            }
+
+            $.ajax({
+                url: '/api/v1/hide/' + index,
+                type: 'POST',
+                contentType: 'application/json; charset=utf-8',
+                error: function(xhr) {
+                    if (notLoggedIn(xhr)) {
+                        window.location.href = "/login";
+                        return;
+                    }
+
+                    alert('Failed to hide boost');
+                },
+                success: function() {
+                    $('div.outgoing_msg[data-msgid=' + index + ']').remove();
+
+                    const idx = messageIds.indexOf(index);
+                    if (idx > -1) {
+                        messageIds.splice(idx, 1);
+                    }
+                }
+            });
+        });
+    }
+
+    //Annotations are changed for received boosts and streams together, and sent boosts on their own
+    function annotationUrl(index) {
+        return `/api/v1/annotations/${config.listType == 'sent' ? 'sent' : 'received'}/${index}`;
+    }
+
+    //Show the tags, note and pin of a boost. Tags and notes come html escaped from the server.
+    function renderAnnotation(index, annotation) {
+        const $msg = $('div.outgoing_msg[data-msgid=' + index + ']');
+        const tags = annotation.tags || [];
+
+        $msg.toggleClass('pinned', !!annotation.pinned);
+
+        let html = '';
+
+        if (annotation.pinned) {
+            html += '<span class="badge badge-warning mr-1" title="Pinned">&#128204; Pinned</span>';
+        }
+
+        html += tags.map(tag => `<span class="badge badge-info mr-1">${tag}</span>`).join('');
+
+        if (annotation.note) {
+            html += `<p class="boost-note text-muted mt-1 mb-0"><small>${annotation.note}</small></p>`;
+        }
+
+        $msg.find('.boost-annotations').html(html);
+    }
+
+    function renderAnnotateButton(index) {
+        const $div = $('div.outgoing_msg[data-msgid=' + index + '] .reply-to-boost-div');
+
+        $div.append(`
+            <a
+                href="#"
+                class="annotate-boost btn btn-sm btn-outline-secondary position-relative d-inline-flex align-items-center ml-2"
+                title="Tags, note and pin"
+                data-toggle="modal"
+                data-target="#annotateModal"
+            >
+                &#127991;&#65039; Tag
+            </a>
+        `);
+    }
+
+    //Render the tags and note modal
+    function renderAnnotateModal() {
+        if ($('#annotateModal').length) {
+            return; // already added
+        }
+
+        const $dialog = $(`
+        <div id="annotateModal" class="modal" tabindex="-1">
+          <div class="modal-dialog modal-dialog-centered">
+            <div class="modal-content">
+              <form id="annotate-form">
+                <div class="modal-header">
+                  <h5 class="modal-title">Tags and Note</h5>
+                  <button type="button" class="close" data-dismiss="modal" aria-label="Close">
+                    <span aria-hidden="true">&times;</span>
+                  </button>
+                </div>
+                <div class="modal-body">
+                  <div class="form-group">
+                    <label for="annotate-tags" class="col-form-label">Tags:</label>
+                    <input id="annotate-tags" type="text" class="form-control" placeholder="read on air, follow up, merch" list="annotate-tag-list">
+                    <datalist id="annotate-tag-list"></datalist>
+                    <small class="text-black-50">Separate tags with commas.</small>
+                  </div>
+                  <div class="form-group">
+                    <label for="annotate-note" class="col-form-label">Note:</label>
+                    <textarea id="annotate-note" class="form-control" style="height: 6em;" maxlength="4000"></textarea>
+                  </div>
+                  <div class="form-check">
+                    <input id="annotate-pinned" class="form-check-input" type="checkbox">
+                    <label for="annotate-pinned" class="form-check-label">Pinned</label>
+                  </div>
+                </div>
+                <div class="modal-footer">
+                  <input id="annotate-index" type="hidden" value="">
+                  <button type="button" class="btn btn-secondary" data-dismiss="modal">Close</button>
+                  <button type="submit" class="btn btn-primary">Save</button>
+                </div>
+              </form>
+            </div>
+          </div>
+        </div>`).appendTo('body');
+
+        $dialog.on('show.bs.modal', async function (ev) {
+            const index = $(ev.relatedTarget).closest('div[data-msgid]').data('msgid');
+            $('#annotate-index').val(index);
+            $('#annotate-tags, #annotate-note').val('');
+            $('#annotate-pinned').prop('checked', false);
+
+            const [annotation, tags] = await Promise.all([
+                $.get(annotationUrl(index)),
+                $.get('/api/v1/tags'),
+            ]);
+
+            $('#annotate-tags').val(annotation.tags.join(', '));
+            $('#annotate-note').val(annotation.note || '');
+            $('#annotate-pinned').prop('checked', annotation.pinned);
+            $('#annotate-tag-list').empty().append(tags.map(tag => $('<option>').val(tag.tag)));
+        });
+
+        $('#annotate-form').submit(function (ev) {
+            ev.preventDefault();
+
+            const index = $('#annotate-index').val();
+            const update = {
+                tags: $('#annotate-tags').val().split(',').map(tag => tag.trim()).filter(tag => tag),
+                note: $('#annotate-note').val(),
+                pinned: $('#annotate-pinned').prop('checked'),
+            };
+
+            // the page is updated by the websocket event sent to every browser
+            $.ajax({
+                url: annotationUrl(index),
+                type: 'PATCH',
+                data: JSON.stringify(update),
+                contentType: 'application/json; charset=utf-8',
+                error: function (xhr) {
+                    if (notLoggedIn(xhr)) {
+                        window.location.href = "/login";
+                        return;
+                    }
+
+                    alert(xhr.responseText || 'Failed to save tags');
+                },
+                success: function () {
+                    $dialog.modal('hide');
+                }
+            });
+        });
+    }
+
+    //Load the running show session and the state of its queue
+    async function getShowSession() {
+        showSession = await $.get('/api/v1/shows/active');
+        showItems = {};
+
+        if (showSession) {
+            const queue = await $.get(`/api/v1/shows/${showSession.index}`);
+            queue.items.forEach(item => showItems[item.boost.index] = item.state);
+        }
+
+        renderShowBar();
+        $('div.outgoing_msg').each((_, el) => renderShowItem($(el).data('msgid')));
+    }
+
+    //A show session started or stopped in this or another browser
+    async function showSessionChanged(session) {
+        if (session.ended) {
+            if (showSession && showSession.index == session.index) {
+                showSession = null;
+                showItems = {};
+                renderShowBar();
+                $('div.outgoing_msg').each((_, el) => renderShowItem($(el).data('msgid')));
+                renderShowSummary(await $.get(`/api/v1/shows/${session.index}/summary`));
+            }
+            return;
+        }
+
+        getShowSession();
+    }
+
+    //A boost joined the show queue or was marked in this or another browser
+    function showItemChanged(item) {
+        if (!showSession || showSession.index != item.session) {
+            return;
+        }
+
+        showItems[item.boost.index] = item.state;
+        renderShowItem(item.boost.index);
+        renderShowBar();
+    }
+
+    function setShowItemState(index, state) {
+        // the page is updated by the websocket event sent to every browser
+        $.ajax({
+            url: `/api/v1/shows/${showSession.index}/items/${index}`,
+            type: 'PATCH',
+            data: JSON.stringify({state: state}),
+            contentType: 'application/json; charset=utf-8',
+            error: function (xhr) {
+                if (notLoggedIn(xhr)) {
+                    window.location.href = "/login";
+                    return;
+                }
+
+                alert(xhr.responseText || 'Failed to update the show queue');
+            },
+        });
+    }
+
+    //Show the running show above the boosts, or the button to start one
+    function renderShowBar() {
+        let $bar = $('#show-bar');
+
+        if (!$bar.length) {
+            $bar = $('<div id="show-bar" class="show-bar"></div>').insertBefore(messages);
+        }
+
+        if (!showSession) {
+            $bar.html(`
+                <button class="btn btn-sm btn-outline-light" data-toggle="modal" data-target="#showModal">
+                    &#127908; Start show
+                </button>
+            `);
+            return;
+        }
+
+        const title = [showSession.podcast || 'Any podcast', showSession.episode].filter(part => part).join(' - ');
+        const unread = Object.values(showItems).filter(state => state == 'unread').length;
+
+        $bar.html(`
+            <span class="badge badge-danger mr-2">LIVE</span>
+            <span class="show-title mr-2">${escapeHTML(title)}</span>
+            <span class="show-unread mr-2">${numberFormat(unread)} unread</span>
+            <a href="#" class="show-next mr-2">Next unread</a>
+            <button class="show-stop btn btn-sm btn-outline-light">&#9632; Stop show</button>
+        `);
+    }
+
+    //Show the read, skipped or unread state of a boost in the show queue
+    function renderShowItem(index) {
+        const $msg = $('div.outgoing_msg[data-msgid=' + index + ']');
+        const state = showSession ? showItems[index] : undefined;
+
+        $msg.toggleClass('show-read', state == 'read');
+        $msg.toggleClass('show-skipped', state == 'skipped');
+
+        if (!state) {
+            $msg.find('.boost-show').empty();
+            return;
+        }
+
+        const labels = {unread: 'Unread', read: 'Read on air', skipped: 'Skipped'};
+        const buttons = (state == 'unread')
+            ? `<a href="#" class="show-mark btn btn-sm btn-outline-success ml-2" data-state="read">&#10004; Read</a>
+               <a href="#" class="show-mark btn btn-sm btn-outline-secondary ml-1" data-state="skipped">Skip</a>`
+            : `<a href="#" class="show-mark btn btn-sm btn-outline-secondary ml-2" data-state="unread">Mark unread</a>`;
+
+        $msg.find('.boost-show').html(`
+            <span class="badge badge-${state == 'unread' ? 'primary' : 'secondary'}">${labels[state]}</span>
+            ${buttons}
+        `);
+    }
+
+    //Render the modal that starts a show session
+    function renderShowModal() {
+        if ($('#showModal').length) {
+            return; // already added
+        }
+
+        const $dialog = $(`
+        <div id="showModal" class="modal" tabindex="-1">
+          <div class="modal-dialog modal-dialog-centered">
+            <div class="modal-content">
+              <form id="show-form">
+                <div class="modal-header">
+                  <h5 class="modal-title">Start a Show</h5>
+                  <button type="button" class="close" data-dismiss="modal" aria-label="Close">
+                    <span aria-hidden="true">&times;</span>
+                  </button>
+                </div>
+                <div class="modal-body">
+                  <p class="text-black-50">Boosts for the podcast and episode that come in during the show are queued up for reading on air.</p>
+                  <div class="form-group">
+                    <label for="show-podcast" class="col-form-label">Podcast:</label>
+                    <select id="show-podcast" class="form-control"></select>
+                  </div>
+                  <div class="form-group">
+                    <label for="show-episode" class="col-form-label">Episode:</label>
+                    <input id="show-episode" type="text" class="form-control" placeholder="Any episode">
+                  </div>
+                </div>
+                <div class="modal-footer">
+                  <button type="button" class="btn btn-secondary" data-dismiss="modal">Close</button>
+                  <button type="submit" class="btn btn-primary">Start</button>
+                </div>
+              </form>
+            </div>
+          </div>
+        </div>`).appendTo('body');
+
+        $dialog.on('show.bs.modal', async function () {
+            const podcasts = await getPodcasts();
+
+            $('#show-episode').val('');
+            $('#show-podcast').empty().append(
+                $('<option>').val('').text('Any podcast'),
+                podcasts.map(name => $('<option>').val(name).text(name))
+            ).val(filters.podcast || '');
+        });
+
+        $('#show-form').submit(function (ev) {
+            ev.preventDefault();
+
+            $.ajax({
+                url: '/api/v1/shows',
+                type: 'POST',
+                data: JSON.stringify({
+                    podcast: $('#show-podcast').val(),
+                    episode: $('#show-episode').val(),
+                }),
+                contentType: 'application/json; charset=utf-8',
+                error: function (xhr) {
+                    if (notLoggedIn(xhr)) {
+                        window.location.href = "/login";
+                        return;
+                    }
+
+                    alert(xhr.responseText || 'Failed to start the show');
+                },
+                success: function () {
+                    $dialog.modal('hide');
+                }
+            });
+        });
+    }
+
+    //Show the end of show summary. Senders and boosts come html escaped from the server.
+    function renderShowSummary(summary) {
+        $('#showSummaryModal').remove();
+
+        const title = [summary.session.podcast || 'Any podcast', summary.session.episode].filter(part => part).join(' - ');
+        const sats = (msat) => numberFormat(Math.trunc(msat / 1000));
+
+        const boosters = summary.top_boosters.map(booster => `
+            <tr>
+                <td>${booster.sender || 'Anonymous'}</td>
+                <td class="text-right">${numberFormat(booster.count)}</td>
+                <td class="text-right">${sats(booster.value_msat_total)}</td>
+            </tr>
+        `).join('');
+
+        const numerology = summary.numerology.map(boost => `
+            <li>${gatherNumerology(boost.numerology)} ${sats(boost.value_msat_total)} sats from ${boost.sender || 'Anonymous'}</li>
+        `).join('');
+
+        $(`
+        <div id="showSummaryModal" class="modal" tabindex="-1">
+          <div class="modal-dialog modal-dialog-centered modal-lg">
+            <div class="modal-content">
+              <div class="modal-header">
+                <h5 class="modal-title">Show Summary: ${escapeHTML(title)}</h5>
+                <button type="button" class="close" data-dismiss="modal" aria-label="Close">
+                  <span aria-hidden="true">&times;</span>
+                </button>
+              </div>
+              <div class="modal-body">
+                <p>
+                  <b>${numberFormat(summary.boosts)}</b> boosts for <b>${sats(summary.boost_value_msat_total)}</b> sats
+                  and <b>${numberFormat(summary.streams)}</b> streams for <b>${sats(summary.stream_value_msat)}</b> sats.<br>
+                  ${numberFormat(summary.read)} read on air, ${numberFormat(summary.skipped)} skipped and ${numberFormat(summary.unread)} unread.
+                </p>
+                <h6>Top boosters</h6>
+                <table class="table table-sm">
+                  <thead><tr><th>Sender</th><th class="text-right">Boosts</th><th class="text-right">Sats</th></tr></thead>
+                  <tbody>${boosters || '<tr><td colspan="3">No boosts</td></tr>'}</tbody>
+                </table>
+                <h6>Numerology highlights</h6>
+                <ul>${numerology || '<li>None this time</li>'}</ul>
+              </div>
+              <div class="modal-footer">
+                <button type="button" class="btn btn-secondary" data-dismiss="modal">Close</button>
+              </div>
+            </div>
+          </div>
+        </div>`).appendTo('body').modal('show');
+    }
+
+    //Show a supporter's profile. Supporters come from the server unescaped.
+    async function renderSupporterModal(index) {
+        let supporter;
+
+        try {
+            supporter = await $.get(`/api/v1/supporters/${index}`);
+        }
+        catch {
+            return alert('Failed to load the supporter');
+        }
+
+        $('#supporterModal').remove();
+
+        const sats = (msat) => numberFormat(Math.trunc(msat / 1000));
+        const date = (time) => time ? new Date(time * 1000).toLocaleDateString() : 'never';
+
+        const identities = supporter.identities.map(identity => `
+            <li><small><span class="badge badge-secondary">${escapeHTML(identity.kind)}</span> <code>${escapeHTML(identity.value)}</code></small></li>
+        `).join('');
+
+        const $dialog = $(`
+        <div id="supporterModal" class="modal" tabindex="-1">
+          <div class="modal-dialog modal-dialog-centered">
+            <div class="modal-content">
+              <form id="supporter-form">
+                <div class="modal-header">
+                  <h5 class="modal-title">${escapeHTML(supporter.name)}</h5>
+                  <button type="button" class="close" data-dismiss="modal" aria-label="Close">
+                    <span aria-hidden="true">&times;</span>
+                  </button>
+                </div>
+                <div class="modal-body">
+                  <p>
+                    <b>${numberFormat(supporter.boosts)}</b> boosts and <b>${numberFormat(supporter.streams)}</b> streams
+                    for <b>${sats(supporter.value_msat_total)}</b> sats.<br>
+                    First seen ${date(supporter.first_seen)}, last seen ${date(supporter.last_seen)}.<br>
+                    Favourite app: ${escapeHTML(supporter.favourite_app || 'unknown')}.
+                    Replied to ${numberFormat(supporter.replied)} of their boosts.
+                  </p>
+                  <ul class="list-unstyled">${identities}</ul>
+                  <div class="form-group">
+                    <label for="supporter-note" class="col-form-label">Note:</label>
+                    <textarea id="supporter-note" class="form-control" rows="3" maxlength="4000"></textarea>
+                  </div>
+                  <div class="form-check">
+                    <input id="supporter-thanked" type="checkbox" class="form-check-input">
+                    <label for="supporter-thanked" class="form-check-label">Thanked</label>
+                  </div>
+                </div>
+                <div class="modal-footer">
+                  <button type="button" class="btn btn-secondary" data-dismiss="modal">Close</button>
+                  <button type="submit" class="btn btn-primary">Save</button>
+                </div>
+              </form>
+            </div>
+          </div>
+        </div>`).appendTo('body');
+
+        $('#supporter-note').val(supporter.note || '');
+        $('#supporter-thanked').prop('checked', supporter.thanked);
+
+        $('#supporter-form').submit(function (ev) {
+            ev.preventDefault();
+
+            $.ajax({
+                url: `/api/v1/supporters/${supporter.index}`,
+                type: 'PATCH',
+                data: JSON.stringify({
+                    note: $('#supporter-note').val(),
+                    thanked: $('#supporter-thanked').prop('checked'),
+                }),
+                contentType: 'application/json; charset=utf-8',
+                error: function (xhr) {
+                    if (notLoggedIn(xhr)) {
+                        window.location.href = "/login";
+                        return;
+                    }
+
+                    alert(xhr.responseText || 'Failed to save the supporter');
+                },
+                success: function () {
+                    $dialog.modal('hide');
+                }
+            });
+        });
+
+        $dialog.modal('show');
+    }
+
     function renderFilters() {
         try {
             filters = JSON.parse(sessionStorage.getItem(config.filterName)) || {};
@@ -1044,16 +1497,21 @@ $(document).ready(function () {
     async function initPage() {
         setConfig();
         renderReplyModal();
+        renderAnnotateModal();
         //Get starting balance and index number
         await getNodeInfo();
         await getSettings();
         await getAppList();
-        await getNumerologyList();
         renderBoostInfo();
         renderFilters();
         initWebsocket();
-        initNostr();
         getIndex();
+
+        //Live shows are run from the boosts page
+        if (config.listType == 'boosts') {
+            renderShowModal();
+            getShowSession();
+        }
     }
 
     function setConfig() {
@@ -1115,6 +1573,42 @@ $(document).ready(function () {
         }
     }, 7000);
 
+    //Show queue buttons
+    $(document).on('click', '.show-mark', function (ev) {
+        ev.preventDefault();
+        setShowItemState($(this).closest('div[data-msgid]').data('msgid'), $(this).data('state'));
+    });
+
+    $(document).on('click', '.show-next', function (ev) {
+        ev.preventDefault();
+
+        const unread = Object.keys(showItems).filter(index => showItems[index] == 'unread').map(Number);
+        const $msg = $('div.outgoing_msg[data-msgid=' + Math.min(...unread) + ']');
+
+        if ($msg.length) {
+            $msg[0].scrollIntoView({behavior: 'smooth', block: 'center'});
+        }
+    });
+
+    $(document).on('click', '.show-stop', function (ev) {
+        ev.preventDefault();
+
+        if (!showSession || !confirm('Stop the show? Every open Helipad will get the summary.')) {
+            return;
+        }
+
+        // the summary is shown by the websocket event sent to every browser
+        $.post(`/api/v1/shows/${showSession.index}/stop`).fail(function (xhr) {
+            alert(xhr.responseText || 'Failed to stop the show');
+        });
+    });
+
+    //Supporter profiles
+    $(document).on('click', '.supporter-link', function (ev) {
+        ev.preventDefault();
+        renderSupporterModal($(this).data('supporter'));
+    });
+
     //Timestamp refresher
     setInterval(function () {
         updateTimestamps();
@@ -0,0 +1,87 @@
+// Read only share link pages: a boost feed, live boosts or a goal tracker
+
+const SHARE_REFRESH_MS = 15000;
+const SHARE_LIVE_BOOSTS = 10;
+
+function shareSats(msat) {
+    return Math.trunc((msat || 0) / 1000).toLocaleString();
+}
+
+// Text comes escaped from the boost feed and raw from the WebSocket. A textarea decodes it without running anything.
+function shareText(value, escaped) {
+    return escaped ? $('<textarea></textarea>').html(value).text() : value;
+}
+
+function shareBoost(boost, escaped) {
+    const $boost = $('<div class="share-boost p-3 mb-2"></div>');
+
+    $('<div></div>')
+        .append($('<b></b>').text(shareSats(boost.value_msat_total || boost.value_msat) + ' sats'))
+        .append(document.createTextNode(' from '))
+        .append($('<b></b>').text(shareText(boost.sender, escaped) || 'Anonymous'))
+        .appendTo($boost);
+
+    if (boost.message) {
+        $('<div class="mt-1"></div>').text(shareText(boost.message, escaped)).appendTo($boost);
+    }
+
+    if (boost.podcast) {
+        $('<div class="small mt-1"></div>').text(shareText(boost.podcast + (boost.episode ? ' - ' + boost.episode : ''), escaped)).appendTo($boost);
+    }
+
+    return $boost;
+}
+
+function shareBoostFeed(path) {
+    $.getJSON(path + '/boosts', (boosts) => {
+        $('.share-boosts').empty().append(boosts.map((boost) => shareBoost(boost, true)));
+    });
+}
+
+function shareLiveBoosts(path) {
+    const scheme = window.location.protocol == 'https:' ? 'wss:' : 'ws:';
+    const ws = new WebSocket(scheme + '//' + window.location.host + path + '/ws');
+
+    ws.onmessage = ({ data }) => {
+        const [event, boost] = JSON.parse(data);
+
+        if (event == 'boost') {
+            $('.share-boosts').prepend(shareBoost(boost, false));
+            $('.share-boost').slice(SHARE_LIVE_BOOSTS).remove();
+        }
+    };
+
+    // reconnect after restarts, but not once the link stops working
+    ws.onclose = () => {
+        $.get(path).done(() => setTimeout(() => shareLiveBoosts(path), 5000));
+    };
+}
+
+function shareGoal(path) {
+    $.getJSON(path + '/goal', (goal) => {
+        $('.share-goal').show();
+        $('.share-goal .progress-bar').css('width', goal.percent + '%');
+        $('.share-goal-sats').text(goal.sats.toLocaleString());
+        $('.share-goal-target').text(goal.goal_sats.toLocaleString());
+        $('.share-goal-count').text(goal.count.toLocaleString());
+    });
+}
+
+$(function () {
+    const $share = $('#share');
+    const path = $share.data('path');
+
+    switch ($share.data('view')) {
+        case 'boosts':
+            shareBoostFeed(path);
+            setInterval(() => shareBoostFeed(path), SHARE_REFRESH_MS);
+            break;
+        case 'websocket':
+            shareLiveBoosts(path);
+            break;
+        case 'goal':
+            shareGoal(path);
+            setInterval(() => shareGoal(path), SHARE_REFRESH_MS);
+            break;
+    }
+});
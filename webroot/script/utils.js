@@ -186,6 +186,11 @@ const escapeHTML = (text) => {
     return div.innerHTML;
 }
 
+// Whether a request failed because the session is missing or expired, rather than the account's role
+const notLoggedIn = (xhr) => {
+    return xhr.status === 403 && xhr.responseText === 'Not logged in';
+}
+
 // Capitalize the first letter of each word in a string
 const ucWords = (text) => {
     if (!text) return text;
@@ -256,4 +261,26 @@ function showToast(message, type = 'info') {
         toast.style.animation = 'slideOut 0.3s ease'
         setTimeout(() => toast.remove(), 300)
     }, 5000)
-}
\ No newline at end of file
+}
+
+// CSRF token from the HELIPAD_CSRF cookie. Changes made with a login cookie have to send it back, which other sites can't do.
+function csrfToken() {
+    const cookie = document.cookie.split('; ').find(row => row.startsWith('HELIPAD_CSRF='))
+    return cookie ? decodeURIComponent(cookie.split('=')[1]) : ''
+}
+
+// jQuery requests
+$(document).ajaxSend((event, xhr, settings) => {
+    xhr.setRequestHeader('X-CSRF-Token', csrfToken())
+})
+
+// htmx requests
+document.addEventListener('htmx:configRequest', (event) => {
+    event.detail.headers['X-CSRF-Token'] = csrfToken()
+})
+
+// Plain HTML forms
+$(document).on('submit', 'form[method="POST"]', function () {
+    $(this).find('input[name="csrf_token"]').remove()
+    $('<input type="hidden" name="csrf_token">').val(csrfToken()).appendTo(this)
+})